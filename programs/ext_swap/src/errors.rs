@@ -14,4 +14,8 @@ pub enum SwapError {
     NotAuthorized,
     #[msg("Invalid amount")]
     InvalidAmount,
+    #[msg("Invalid account")]
+    InvalidAccount,
+    #[msg("Global account is already migrated")]
+    AlreadyMigrated,
 }
@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
 
-use crate::state::{SwapGlobal, GLOBAL_SEED};
+use crate::state::{SwapGlobal, GLOBAL_SEED, SWAP_GLOBAL_VERSION};
 
 #[derive(Accounts)]
 pub struct InitializeGlobal<'info> {
@@ -22,6 +22,7 @@ pub struct InitializeGlobal<'info> {
 impl InitializeGlobal<'_> {
     pub fn handler(ctx: Context<Self>) -> Result<()> {
         ctx.accounts.swap_global.set_inner(SwapGlobal {
+            version: SWAP_GLOBAL_VERSION,
             bump: ctx.bumps.swap_global,
             admin: ctx.accounts.admin.key(),
             whitelisted_unwrappers: vec![],
@@ -0,0 +1,115 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+    Discriminator,
+};
+
+use crate::{
+    errors::SwapError,
+    state::{SwapGlobal, WhitelistedExtension, GLOBAL_SEED, SWAP_GLOBAL_VERSION},
+};
+
+// Layout of the global account before it was versioned, extensions were
+// whitelisted by program since each program hosted a single extension
+#[derive(AnchorDeserialize)]
+struct LegacySwapGlobal {
+    bump: u8,
+    admin: Pubkey,
+    whitelisted_unwrappers: Vec<Pubkey>,
+    whitelisted_extensions: Vec<Pubkey>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateGlobal<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [GLOBAL_SEED],
+        bump,
+    )]
+    /// CHECK: decoded manually since the legacy layout can't be deserialized as SwapGlobal
+    pub swap_global: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl MigrateGlobal<'_> {
+    // This instruction allows the admin to upgrade the global account from the legacy layout.
+    // Each legacy whitelisted program is paired with the mint of the extension it hosts, passed
+    // at the same index in ext_mints. Legacy extensions use fixed seeds, so none is namespaced.
+    // The account is resized to fit the current layout and the admin pays any extra rent.
+
+    fn decode(&self) -> Result<LegacySwapGlobal> {
+        let data = self.swap_global.try_borrow_data()?;
+
+        if data.len() < 8 + 1 + 32 || data[..8] != *SwapGlobal::DISCRIMINATOR {
+            return err!(SwapError::InvalidAccount);
+        }
+
+        // Legacy accounts have the admin right after the bump, versioned accounts
+        // have the version first and the admin right after the version and the bump
+        if data[9..41] != self.admin.key().to_bytes() {
+            if data.len() >= 8 + 2 + 32 && data[10..42] == self.admin.key().to_bytes() {
+                return err!(SwapError::AlreadyMigrated);
+            }
+
+            return err!(SwapError::NotAuthorized);
+        }
+
+        Ok(LegacySwapGlobal::deserialize(&mut &data[8..])?)
+    }
+
+    pub fn handler(ctx: Context<Self>, ext_mints: Vec<Pubkey>) -> Result<()> {
+        let legacy = ctx.accounts.decode()?;
+
+        if ext_mints.len() != legacy.whitelisted_extensions.len() {
+            return err!(SwapError::InvalidIndex);
+        }
+
+        let global = SwapGlobal {
+            version: SWAP_GLOBAL_VERSION,
+            bump: legacy.bump,
+            admin: legacy.admin,
+            whitelisted_unwrappers: legacy.whitelisted_unwrappers,
+            whitelisted_extensions: legacy
+                .whitelisted_extensions
+                .into_iter()
+                .zip(ext_mints)
+                .map(|(program_id, ext_mint)| WhitelistedExtension {
+                    program_id,
+                    ext_mint,
+                    namespaced: false,
+                })
+                .collect(),
+        };
+
+        // Resize the account to the current layout and top up rent
+        let global_info = ctx.accounts.swap_global.to_account_info();
+        let new_size = SwapGlobal::size(
+            global.whitelisted_unwrappers.len(),
+            global.whitelisted_extensions.len(),
+        );
+        let rent = Rent::get()?.minimum_balance(new_size);
+
+        if rent > global_info.lamports() {
+            transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.admin.to_account_info(),
+                        to: global_info.clone(),
+                    },
+                ),
+                rent - global_info.lamports(),
+            )?;
+        }
+
+        global_info.resize(new_size)?;
+        global.try_serialize(&mut &mut global_info.try_borrow_mut_data()?[..])?;
+
+        Ok(())
+    }
+}
@@ -1,10 +1,12 @@
 pub mod initialize;
+pub mod migrate_global;
 pub mod swap;
 pub mod unwrap;
 pub mod whitelist;
 pub mod wrap;
 
 pub use initialize::*;
+pub use migrate_global::*;
 pub use swap::*;
 pub use unwrap::*;
 pub use whitelist::*;
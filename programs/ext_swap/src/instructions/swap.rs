@@ -29,7 +29,7 @@ pub struct Swap<'info> {
     pub swap_global: Box<Account<'info, SwapGlobal>>,
     #[account(
         mut,
-        seeds = [EXT_GLOBAL_SEED],
+        seeds = [EXT_GLOBAL_SEED, SwapGlobal::ext_seed_namespace(&swap_global, &from_ext_program.key(), &from_mint.key())?],
         seeds::program = from_ext_program.key(),
         bump,
     )]
@@ -37,7 +37,7 @@ pub struct Swap<'info> {
     pub from_global: AccountInfo<'info>,
     #[account(
         mut,
-        seeds = [EXT_GLOBAL_SEED],
+        seeds = [EXT_GLOBAL_SEED, SwapGlobal::ext_seed_namespace(&swap_global, &to_ext_program.key(), &to_mint.key())?],
         seeds::program = to_ext_program.key(),
         bump,
     )]
@@ -56,7 +56,8 @@ pub struct Swap<'info> {
     #[account(mut)]
     /// Validated by unwrap on the extension program
     pub from_mint: Box<InterfaceAccount<'info, Mint>>,
-    #[account(mut)]
+    // Extensions are identified by their mint, so both can be hosted by the same program
+    #[account(mut, constraint = to_mint.key() != from_mint.key())]
     /// Validated by wrap on the extension program
     pub to_mint: Box<InterfaceAccount<'info, Mint>>,
     #[account(
@@ -95,28 +96,28 @@ pub struct Swap<'info> {
      * Authorities
      */
     #[account(
-        seeds = [M_VAULT_SEED],
+        seeds = [M_VAULT_SEED, SwapGlobal::ext_seed_namespace(&swap_global, &from_ext_program.key(), &from_mint.key())?],
         seeds::program = from_ext_program.key(),
         bump,
     )]
     /// CHECK: account does not hold data
     pub from_m_vault_auth: AccountInfo<'info>,
     #[account(
-        seeds = [M_VAULT_SEED],
+        seeds = [M_VAULT_SEED, SwapGlobal::ext_seed_namespace(&swap_global, &to_ext_program.key(), &to_mint.key())?],
         seeds::program = to_ext_program.key(),
         bump,
     )]
     /// CHECK: account does not hold data
     pub to_m_vault_auth: AccountInfo<'info>,
     #[account(
-        seeds = [MINT_AUTHORITY_SEED],
+        seeds = [MINT_AUTHORITY_SEED, SwapGlobal::ext_seed_namespace(&swap_global, &from_ext_program.key(), &from_mint.key())?],
         seeds::program = from_ext_program.key(),
         bump,
     )]
     /// CHECK: account does not hold data
     pub from_mint_authority: AccountInfo<'info>,
     #[account(
-        seeds = [MINT_AUTHORITY_SEED],
+        seeds = [MINT_AUTHORITY_SEED, SwapGlobal::ext_seed_namespace(&swap_global, &to_ext_program.key(), &to_mint.key())?],
         seeds::program = to_ext_program.key(),
         bump,
     )]
@@ -129,6 +130,30 @@ pub struct Swap<'info> {
     /// CHECK: This is validated by the CPI to the to_ext_program
     pub to_m_earner_account: UncheckedAccount<'info>,
 
+    /// CHECK: This is validated by the CPI to the from_ext_program
+    pub from_blocklist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: This is validated by the CPI to the to_ext_program
+    pub to_blocklist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: This is validated by the CPI to the from_ext_program
+    #[account(mut)]
+    pub from_rate_limit: UncheckedAccount<'info>,
+
+    // Not required if the extension is open to anyone
+    /// CHECK: This is validated by the CPI to the from_ext_program
+    #[account(mut)]
+    pub from_wrap_authority_account: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: This is validated by the CPI to the to_ext_program
+    #[account(mut)]
+    pub to_rate_limit: UncheckedAccount<'info>,
+
+    // Not required if the extension is open to anyone
+    /// CHECK: This is validated by the CPI to the to_ext_program
+    #[account(mut)]
+    pub to_wrap_authority_account: Option<UncheckedAccount<'info>>,
+
     /*
      * Vaults
      */
@@ -160,7 +185,6 @@ pub struct Swap<'info> {
     /// CHECK: checked against whitelisted extensions
     pub from_ext_program: UncheckedAccount<'info>,
     /// CHECK: checked against whitelisted extensions
-    #[account(constraint = to_ext_program.key() != from_ext_program.key())]
     pub to_ext_program: UncheckedAccount<'info>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
@@ -173,11 +197,13 @@ impl<'info> Swap<'info> {
         remaining_accounts: &[AccountInfo<'_>],
         remaining_accounts_split_idx: usize,
     ) -> Result<()> {
-        for ext_program in [&self.from_ext_program, &self.to_ext_program] {
+        for (ext_program, ext_mint) in [
+            (&self.from_ext_program, &self.from_mint),
+            (&self.to_ext_program, &self.to_mint),
+        ] {
             if !self
                 .swap_global
-                .whitelisted_extensions
-                .contains(ext_program.key)
+                .is_whitelisted(ext_program.key, &ext_mint.key())
             {
                 return err!(SwapError::InvalidExtension);
             }
@@ -223,12 +249,19 @@ impl<'info> Swap<'info> {
                     m_mint: ctx.accounts.m_mint.to_account_info(),
                     ext_mint: ctx.accounts.from_mint.to_account_info(),
                     global_account: ctx.accounts.from_global.to_account_info(),
+                    wrap_authority_account: ctx
+                        .accounts
+                        .from_wrap_authority_account
+                        .as_ref()
+                        .map(|account| account.to_account_info()),
                     m_earn_global_account: ctx.accounts.m_global.to_account_info(),
                     m_vault: ctx.accounts.from_m_vault_auth.to_account_info(),
                     ext_mint_authority: ctx.accounts.from_mint_authority.to_account_info(),
                     to_m_token_account: ctx.accounts.intermediate_m_account.to_account_info(),
                     vault_m_token_account: ctx.accounts.from_m_vault.to_account_info(),
                     from_ext_token_account: ctx.accounts.from_token_account.to_account_info(),
+                    from_blocklist_entry: ctx.accounts.from_blocklist_entry.to_account_info(),
+                    rate_limit: ctx.accounts.from_rate_limit.to_account_info(),
                     m_earner_account: ctx.accounts.from_m_earner_account.to_account_info(),
                     m_token_program: ctx.accounts.m_token_program.to_account_info(),
                     ext_token_program: ctx.accounts.from_token_program.to_account_info(),
@@ -258,12 +291,19 @@ impl<'info> Swap<'info> {
                     m_mint: ctx.accounts.m_mint.to_account_info(),
                     ext_mint: ctx.accounts.to_mint.to_account_info(),
                     global_account: ctx.accounts.to_global.to_account_info(),
+                    wrap_authority_account: ctx
+                        .accounts
+                        .to_wrap_authority_account
+                        .as_ref()
+                        .map(|account| account.to_account_info()),
                     m_earn_global_account: ctx.accounts.m_global.to_account_info(),
                     m_vault: ctx.accounts.to_m_vault_auth.to_account_info(),
                     ext_mint_authority: ctx.accounts.to_mint_authority.to_account_info(),
                     from_m_token_account: ctx.accounts.intermediate_m_account.to_account_info(),
                     vault_m_token_account: ctx.accounts.to_m_vault.to_account_info(),
                     to_ext_token_account: ctx.accounts.to_token_account.to_account_info(),
+                    to_blocklist_entry: ctx.accounts.to_blocklist_entry.to_account_info(),
+                    rate_limit: ctx.accounts.to_rate_limit.to_account_info(),
                     m_earner_account: ctx.accounts.to_m_earner_account.to_account_info(),
                     m_token_program: ctx.accounts.m_token_program.to_account_info(),
                     ext_token_program: ctx.accounts.to_token_program.to_account_info(),
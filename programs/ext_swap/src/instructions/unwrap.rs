@@ -27,7 +27,7 @@ pub struct Unwrap<'info> {
     pub swap_global: Box<Account<'info, SwapGlobal>>,
     #[account(
         mut,
-        seeds = [EXT_GLOBAL_SEED],
+        seeds = [EXT_GLOBAL_SEED, SwapGlobal::ext_seed_namespace(&swap_global, &from_ext_program.key(), &from_mint.key())?],
         seeds::program = from_ext_program.key(),
         bump,
     )]
@@ -76,14 +76,14 @@ pub struct Unwrap<'info> {
      * Authorities
      */
     #[account(
-        seeds = [M_VAULT_SEED],
+        seeds = [M_VAULT_SEED, SwapGlobal::ext_seed_namespace(&swap_global, &from_ext_program.key(), &from_mint.key())?],
         seeds::program = from_ext_program.key(),
         bump,
     )]
     /// CHECK: account does not hold data
     pub from_m_vault_auth: AccountInfo<'info>,
     #[account(
-        seeds = [MINT_AUTHORITY_SEED],
+        seeds = [MINT_AUTHORITY_SEED, SwapGlobal::ext_seed_namespace(&swap_global, &from_ext_program.key(), &from_mint.key())?],
         seeds::program = from_ext_program.key(),
         bump,
     )]
@@ -104,6 +104,18 @@ pub struct Unwrap<'info> {
     /// CHECK: This is validated by the CPI to the to_ext_program
     pub from_m_earner_account: UncheckedAccount<'info>,
 
+    /// CHECK: This is validated by the CPI to the from_ext_program
+    pub from_blocklist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: This is validated by the CPI to the from_ext_program
+    #[account(mut)]
+    pub from_rate_limit: UncheckedAccount<'info>,
+
+    // Not required if the extension is open to anyone
+    /// CHECK: This is validated by the CPI to the from_ext_program
+    #[account(mut)]
+    pub from_wrap_authority_account: Option<UncheckedAccount<'info>>,
+
     /*
      * Token Programs
      */
@@ -123,8 +135,7 @@ impl<'info> Unwrap<'info> {
     fn validate(&self, amount: u64) -> Result<()> {
         if !self
             .swap_global
-            .whitelisted_extensions
-            .contains(self.from_ext_program.key)
+            .is_whitelisted(self.from_ext_program.key, &self.from_mint.key())
         {
             return err!(SwapError::InvalidExtension);
         }
@@ -166,12 +177,19 @@ impl<'info> Unwrap<'info> {
                     m_mint: ctx.accounts.m_mint.to_account_info(),
                     ext_mint: ctx.accounts.from_mint.to_account_info(),
                     global_account: ctx.accounts.from_global.to_account_info(),
+                    wrap_authority_account: ctx
+                        .accounts
+                        .from_wrap_authority_account
+                        .as_ref()
+                        .map(|account| account.to_account_info()),
                     m_earn_global_account: ctx.accounts.m_global.to_account_info(),
                     m_vault: ctx.accounts.from_m_vault_auth.to_account_info(),
                     ext_mint_authority: ctx.accounts.from_mint_authority.to_account_info(),
                     to_m_token_account: ctx.accounts.m_token_account.to_account_info(),
                     vault_m_token_account: ctx.accounts.from_m_vault.to_account_info(),
                     from_ext_token_account: ctx.accounts.from_token_account.to_account_info(),
+                    from_blocklist_entry: ctx.accounts.from_blocklist_entry.to_account_info(),
+                    rate_limit: ctx.accounts.from_rate_limit.to_account_info(),
                     m_earner_account: ctx.accounts.from_m_earner_account.to_account_info(),
                     m_token_program: ctx.accounts.m_token_program.to_account_info(),
                     ext_token_program: ctx.accounts.from_token_program.to_account_info(),
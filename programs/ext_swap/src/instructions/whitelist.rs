@@ -1,8 +1,9 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
 
 use crate::{
     errors::SwapError,
-    state::{SwapGlobal, GLOBAL_SEED},
+    state::{SwapGlobal, WhitelistedExtension, GLOBAL_SEED},
 };
 
 #[derive(Accounts)]
@@ -28,15 +29,16 @@ pub struct WhitelistExt<'info> {
 
     /// CHECK: This account is validated in the `validate` function
     pub ext_program: AccountInfo<'info>,
+
+    pub ext_mint: InterfaceAccount<'info, Mint>,
 }
 
 impl WhitelistExt<'_> {
     fn validate(&self) -> Result<()> {
-        // Check if the extension program is already whitelisted
+        // Check if the extension is already whitelisted
         if self
             .swap_global
-            .whitelisted_extensions
-            .contains(self.ext_program.key)
+            .is_whitelisted(self.ext_program.key, &self.ext_mint.key())
         {
             return err!(SwapError::AlreadyWhitelisted);
         }
@@ -49,12 +51,18 @@ impl WhitelistExt<'_> {
         Ok(())
     }
 
+    // The namespaced flag must match the one the extension was initialized with,
+    // since wrap, unwrap and swap derive the PDAs of the extension from it
     #[access_control(ctx.accounts.validate())]
-    pub fn handler(ctx: Context<Self>) -> Result<()> {
+    pub fn handler(ctx: Context<Self>, namespaced: bool) -> Result<()> {
         ctx.accounts
             .swap_global
             .whitelisted_extensions
-            .push(*ctx.accounts.ext_program.key);
+            .push(WhitelistedExtension {
+                program_id: *ctx.accounts.ext_program.key,
+                ext_mint: ctx.accounts.ext_mint.key(),
+                namespaced,
+            });
 
         Ok(())
     }
@@ -117,24 +125,20 @@ pub struct RemoveWhitelistedExt<'info> {
 }
 
 impl RemoveWhitelistedExt<'_> {
-    fn validate(&self, ext_program: &Pubkey) -> Result<()> {
-        if !self
-            .swap_global
-            .whitelisted_extensions
-            .contains(ext_program)
-        {
+    fn validate(&self, ext_program: &Pubkey, ext_mint: &Pubkey) -> Result<()> {
+        if !self.swap_global.is_whitelisted(ext_program, ext_mint) {
             return err!(SwapError::InvalidExtension);
         }
 
         Ok(())
     }
 
-    #[access_control(ctx.accounts.validate(&ext_program))]
-    pub fn handler(ctx: Context<Self>, ext_program: Pubkey) -> Result<()> {
+    #[access_control(ctx.accounts.validate(&ext_program, &ext_mint))]
+    pub fn handler(ctx: Context<Self>, ext_program: Pubkey, ext_mint: Pubkey) -> Result<()> {
         ctx.accounts
             .swap_global
             .whitelisted_extensions
-            .retain(|&x| !x.eq(&ext_program));
+            .retain(|x| !(x.program_id.eq(&ext_program) && x.ext_mint.eq(&ext_mint)));
 
         Ok(())
     }
@@ -26,7 +26,7 @@ pub struct Wrap<'info> {
     pub swap_global: Box<Account<'info, SwapGlobal>>,
     #[account(
         mut,
-        seeds = [EXT_GLOBAL_SEED],
+        seeds = [EXT_GLOBAL_SEED, SwapGlobal::ext_seed_namespace(&swap_global, &to_ext_program.key(), &to_mint.key())?],
         seeds::program = to_ext_program.key(),
         bump,
     )]
@@ -74,14 +74,14 @@ pub struct Wrap<'info> {
      * Authorities
      */
     #[account(
-        seeds = [M_VAULT_SEED],
+        seeds = [M_VAULT_SEED, SwapGlobal::ext_seed_namespace(&swap_global, &to_ext_program.key(), &to_mint.key())?],
         seeds::program = to_ext_program.key(),
         bump,
     )]
     /// CHECK: account does not hold data
     pub to_m_vault_auth: AccountInfo<'info>,
     #[account(
-        seeds = [MINT_AUTHORITY_SEED],
+        seeds = [MINT_AUTHORITY_SEED, SwapGlobal::ext_seed_namespace(&swap_global, &to_ext_program.key(), &to_mint.key())?],
         seeds::program = to_ext_program.key(),
         bump,
     )]
@@ -102,6 +102,18 @@ pub struct Wrap<'info> {
     /// CHECK: This is validated by the CPI to the to_ext_program
     pub to_m_earner_account: UncheckedAccount<'info>,
 
+    /// CHECK: This is validated by the CPI to the to_ext_program
+    pub to_blocklist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: This is validated by the CPI to the to_ext_program
+    #[account(mut)]
+    pub to_rate_limit: UncheckedAccount<'info>,
+
+    // Not required if the extension is open to anyone
+    /// CHECK: This is validated by the CPI to the to_ext_program
+    #[account(mut)]
+    pub to_wrap_authority_account: Option<UncheckedAccount<'info>>,
+
     /*
      * Token Programs
      */
@@ -121,8 +133,7 @@ impl<'info> Wrap<'info> {
     fn validate(&self, amount: u64) -> Result<()> {
         if !self
             .swap_global
-            .whitelisted_extensions
-            .contains(self.to_ext_program.key)
+            .is_whitelisted(self.to_ext_program.key, &self.to_mint.key())
         {
             return err!(SwapError::InvalidExtension);
         }
@@ -151,12 +162,19 @@ impl<'info> Wrap<'info> {
                     m_mint: ctx.accounts.m_mint.to_account_info(),
                     ext_mint: ctx.accounts.to_mint.to_account_info(),
                     global_account: ctx.accounts.to_global.to_account_info(),
+                    wrap_authority_account: ctx
+                        .accounts
+                        .to_wrap_authority_account
+                        .as_ref()
+                        .map(|account| account.to_account_info()),
                     m_earn_global_account: ctx.accounts.m_global.to_account_info(),
                     m_vault: ctx.accounts.to_m_vault_auth.to_account_info(),
                     ext_mint_authority: ctx.accounts.to_mint_authority.to_account_info(),
                     from_m_token_account: ctx.accounts.m_token_account.to_account_info(),
                     vault_m_token_account: ctx.accounts.to_m_vault.to_account_info(),
                     to_ext_token_account: ctx.accounts.to_token_account.to_account_info(),
+                    to_blocklist_entry: ctx.accounts.to_blocklist_entry.to_account_info(),
+                    rate_limit: ctx.accounts.to_rate_limit.to_account_info(),
                     m_earner_account: ctx.accounts.to_m_earner_account.to_account_info(),
                     m_token_program: ctx.accounts.m_token_program.to_account_info(),
                     ext_token_program: ctx.accounts.to_token_program.to_account_info(),
@@ -28,15 +28,23 @@ pub mod ext_swap {
         InitializeGlobal::handler(ctx)
     }
 
-    pub fn whitelist_extension<'info>(ctx: Context<WhitelistExt>) -> Result<()> {
-        WhitelistExt::handler(ctx)
+    pub fn migrate_global<'info>(
+        ctx: Context<MigrateGlobal>,
+        ext_mints: Vec<Pubkey>,
+    ) -> Result<()> {
+        MigrateGlobal::handler(ctx, ext_mints)
+    }
+
+    pub fn whitelist_extension<'info>(ctx: Context<WhitelistExt>, namespaced: bool) -> Result<()> {
+        WhitelistExt::handler(ctx, namespaced)
     }
 
     pub fn remove_whitelisted_extension<'info>(
         ctx: Context<RemoveWhitelistedExt>,
         ext_program: Pubkey,
+        ext_mint: Pubkey,
     ) -> Result<()> {
-        RemoveWhitelistedExt::handler(ctx, ext_program)
+        RemoveWhitelistedExt::handler(ctx, ext_program, ext_mint)
     }
 
     pub fn whitelist_unwrapper<'info>(
@@ -1,24 +1,71 @@
 use anchor_lang::prelude::*;
 
+use crate::errors::SwapError;
+
 #[constant]
 pub const GLOBAL_SEED: &[u8] = b"global";
 
+// Version of the layout of the global account. Accounts created before it was versioned
+// start with the bump and whitelist extensions by program, they are upgraded with migrate_global.
+#[constant]
+pub const SWAP_GLOBAL_VERSION: u8 = 1;
+
 #[account]
 pub struct SwapGlobal {
+    pub version: u8,
     pub bump: u8,
     pub admin: Pubkey,
     pub whitelisted_unwrappers: Vec<Pubkey>,
-    pub whitelisted_extensions: Vec<Pubkey>,
+    pub whitelisted_extensions: Vec<WhitelistedExtension>,
+}
+
+// One program can host several extensions namespaced by their mint,
+// so an extension is identified by the pair rather than the program alone
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct WhitelistedExtension {
+    pub program_id: Pubkey,
+    pub ext_mint: Pubkey,
+    pub namespaced: bool, // PDAs of the extension have the ext mint as an extra seed
 }
 
 impl SwapGlobal {
     pub fn size(unwrappers: usize, extensions: usize) -> usize {
         8 + // discriminator
+        1 + // version
         1 + // bump
         32 + // admin
         4 + // length of whitelisted_unwrappers vector
         unwrappers * 32 + // each Pubkey is 32 bytes
         4 + // length of whitelisted_extensions vector
-        extensions * 32 // each Pubkey is 32 bytes
+        extensions * 65 // program id, ext mint and namespaced flag
+    }
+
+    pub fn find_extension(
+        &self,
+        program_id: &Pubkey,
+        ext_mint: &Pubkey,
+    ) -> Option<&WhitelistedExtension> {
+        self.whitelisted_extensions
+            .iter()
+            .find(|x| x.program_id == *program_id && x.ext_mint == *ext_mint)
+    }
+
+    pub fn is_whitelisted(&self, program_id: &Pubkey, ext_mint: &Pubkey) -> bool {
+        self.find_extension(program_id, ext_mint).is_some()
+    }
+
+    // Seed added to the PDAs of a whitelisted extension, empty if its program hosts a single
+    // extension. Seeds constraints call it as `SwapGlobal::ext_seed_namespace(&swap_global, ..)`
+    // so the IDL doesn't mistake it for a seed of the swap global account.
+    pub fn ext_seed_namespace(&self, program_id: &Pubkey, ext_mint: &Pubkey) -> Result<&[u8]> {
+        let extension = self
+            .find_extension(program_id, ext_mint)
+            .ok_or(SwapError::InvalidExtension)?;
+
+        Ok(if extension.namespaced {
+            extension.ext_mint.as_ref()
+        } else {
+            &[]
+        })
     }
 }
@@ -5,3 +5,23 @@ pub const INDEX_SCALE_U64: u64 = 1_000_000_000_000u64;
 
 pub const ONE_HUNDRED_PERCENT_U64: u64 = 100_00u64;
 pub const ONE_HUNDRED_PERCENT_F64: f64 = 1e4f64;
+
+// Fixed-point representation used for deterministic exponentiation, 63 fractional bits
+// so that squaring a value in [1, 2) fits in a u128
+pub const FIXED_POINT_BITS: u32 = 63;
+pub const FIXED_POINT_ONE: u128 = 1u128 << FIXED_POINT_BITS;
+pub const LN_2_FIXED: u128 = 6_393_154_322_601_327_829u128; // floor(ln(2) * 2^63)
+
+pub const SECONDS_PER_YEAR: u64 = 31_556_736u64; // 365.24 days, same as Token2022's interest bearing extension
+
+pub const MAX_BENEFICIARIES: usize = 10;
+
+pub const MAX_MULTISIG_SIGNERS: usize = 10;
+pub const MAX_PROPOSAL_ACCOUNTS: usize = 24;
+
+// Queued actions can only be executed within this many seconds after their eta
+pub const GRACE_PERIOD: i64 = 14 * 24 * 60 * 60;
+
+pub const MAX_RATE_LIMIT_WINDOWS: usize = 4;
+
+pub const MAX_WRAP_AUTHORITY_LABEL_LEN: usize = 32;
@@ -26,4 +26,46 @@ pub enum ExtError {
     InvalidInput,
     #[msg("Invalid amount")]
     InvalidAmount,
+    #[msg("Invalid merkle proof.")]
+    InvalidProof,
+    #[msg("Already claimed.")]
+    AlreadyClaimed,
+    #[msg("Instruction not supported by the yield mode.")]
+    UnsupportedYieldMode,
+    #[msg("Account is already at the latest version.")]
+    AlreadyMigrated,
+    #[msg("Not enough approvals.")]
+    InsufficientApprovals,
+    #[msg("Already executed.")]
+    AlreadyExecuted,
+    #[msg("Instruction is paused.")]
+    Paused,
+    #[msg("Wallet is blocklisted.")]
+    Blocklisted,
+    #[msg("Supply cap exceeded.")]
+    SupplyCapExceeded,
+    #[msg("Wrap authority quota exceeded.")]
+    QuotaExceeded,
+    #[msg("Rate limit exceeded.")]
+    RateLimitExceeded,
+    #[msg("Wrap authority has expired.")]
+    Expired,
+    #[msg("Voucher has expired.")]
+    VoucherExpired,
+    #[msg("Invalid voucher signature.")]
+    InvalidSignature,
+    #[msg("Extension is winding down.")]
+    WindingDown,
+    #[msg("Extension is not winding down.")]
+    NotWindingDown,
+    #[msg("Ext tokens are still outstanding.")]
+    OutstandingSupply,
+    #[msg("Queued action has expired.")]
+    QueuedActionExpired,
+    #[msg("Token account was frozen by the compliance officer.")]
+    FrozenByComplianceOfficer,
+    #[msg("M owed to holders is still outstanding.")]
+    OutstandingClaims,
+    #[msg("Extension is decommissioned.")]
+    Decommissioned,
 }
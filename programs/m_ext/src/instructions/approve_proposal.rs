@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::ExtError,
+    state::{ExtGlobal, Multisig, Proposal, EXT_GLOBAL_SEED, MULTISIG_SEED, PROPOSAL_SEED},
+};
+
+#[derive(Accounts)]
+pub struct ApproveProposal<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    #[account(
+        seeds = [MULTISIG_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump = multisig.bump,
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        seeds = [PROPOSAL_SEED, ExtGlobal::seed_namespace(&global_account), proposal.index.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+impl ApproveProposal<'_> {
+    fn validate(&self) -> Result<()> {
+        if !self.multisig.signers.contains(self.signer.key) {
+            return err!(ExtError::NotAuthorized);
+        }
+
+        if self.proposal.executed {
+            return err!(ExtError::AlreadyExecuted);
+        }
+
+        if Clock::get()?.unix_timestamp > self.proposal.expires_at {
+            return err!(ExtError::NotActive);
+        }
+
+        if self.proposal.approvals.contains(self.signer.key) {
+            return err!(ExtError::InvalidParam);
+        }
+
+        Ok(())
+    }
+
+    // This instruction allows a multisig signer to approve a pending proposal.
+    #[access_control(ctx.accounts.validate())]
+    pub fn handler(ctx: Context<Self>) -> Result<()> {
+        let signer = ctx.accounts.signer.key();
+
+        // Drop approvals of removed signers so there is always room for the current ones
+        let signers = &ctx.accounts.multisig.signers;
+        let proposal = &mut ctx.accounts.proposal;
+        proposal
+            .approvals
+            .retain(|approver| signers.contains(approver));
+        proposal.approvals.push(signer);
+
+        emit!(ProposalApproved {
+            index: proposal.index,
+            signer,
+            approvals: proposal.approvals.len() as u8,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct ProposalApproved {
+    pub index: u64,
+    pub signer: Pubkey,
+    pub approvals: u8,
+}
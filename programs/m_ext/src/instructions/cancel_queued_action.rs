@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::ExtError,
+    state::{ExtGlobal, QueuedAction, EXT_GLOBAL_SEED, QUEUED_ACTION_SEED},
+};
+
+#[derive(Accounts)]
+pub struct CancelQueuedAction<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        has_one = admin @ ExtError::NotAuthorized,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    #[account(
+        mut,
+        close = queued_by,
+        has_one = queued_by @ ExtError::InvalidAccount,
+        seeds = [QUEUED_ACTION_SEED, ExtGlobal::seed_namespace(&global_account), queued_action.index.to_le_bytes().as_ref()],
+        bump = queued_action.bump,
+    )]
+    pub queued_action: Account<'info, QueuedAction>,
+
+    /// CHECK: Receives the rent of the queued action
+    #[account(mut)]
+    pub queued_by: UncheckedAccount<'info>,
+}
+
+impl CancelQueuedAction<'_> {
+    // This instruction allows the admin to cancel a queued change before it is executed.
+
+    pub fn handler(ctx: Context<Self>) -> Result<()> {
+        emit!(QueuedActionCancelled {
+            index: ctx.accounts.queued_action.index,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct QueuedActionCancelled {
+    pub index: u64,
+}
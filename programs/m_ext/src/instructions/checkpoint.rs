@@ -0,0 +1,122 @@
+// external dependencies
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Token2022, TokenAccount};
+use earn::{
+    state::{Global as EarnGlobal, EARNER_SEED},
+    ID as EARN_PROGRAM,
+};
+
+// local dependencies
+use crate::{
+    errors::ExtError,
+    state::{Earner, ExtGlobal, EXT_EARNER_SEED, EXT_GLOBAL_SEED, M_VAULT_SEED},
+    utils::conversion::{amount_to_principal_down, sync_earner_index},
+};
+
+#[derive(Accounts)]
+pub struct Checkpoint<'info> {
+    #[account(
+        mut,
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        has_one = m_earn_global_account @ ExtError::InvalidAccount,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    pub m_earn_global_account: Account<'info, EarnGlobal>,
+
+    /// CHECK: This account is validated by the seed, it stores no data
+    #[account(
+        seeds = [M_VAULT_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump = global_account.m_vault_bump,
+    )]
+    pub m_vault: AccountInfo<'info>,
+
+    #[account(
+        associated_token::mint = global_account.m_mint,
+        associated_token::authority = m_vault,
+        associated_token::token_program = m_token_program,
+    )]
+    pub vault_m_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: We partially validate this account is the correct address
+    /// via the seed, but we delay full validation to the handler
+    /// so we can handle cases where the account has been closed.
+    #[account(
+        seeds = [EARNER_SEED, vault_m_token_account.key().as_ref()],
+        seeds::program = EARN_PROGRAM,
+        bump,
+    )]
+    pub m_earner_account: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [EXT_EARNER_SEED, ExtGlobal::seed_namespace(&global_account), earner_token_account.key().as_ref()],
+        bump = earner_account.bump,
+    )]
+    pub earner_account: Account<'info, Earner>,
+
+    // Mutable so the claim instruction can mint the yield to it
+    #[account(mut, token::mint = global_account.ext_mint)]
+    pub earner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub m_token_program: Program<'info, Token2022>,
+}
+
+impl Checkpoint<'_> {
+    // This instruction records the yield accrued by an ext token account so far and
+    // resets its principal to the current balance. Since balances can change through
+    // regular token transfers, wallets should checkpoint both sides of a transfer
+    // so the yield is accrued on the balance that was actually held.
+    pub fn handler(ctx: Context<Self>) -> Result<()> {
+        ctx.accounts.update()?;
+
+        Ok(())
+    }
+
+    pub fn update(&mut self) -> Result<u64> {
+        // Sync the earner index so all yield up to now is included
+        let index = sync_earner_index(
+            &mut self.global_account,
+            &self.m_earn_global_account,
+            &self.m_earner_account,
+        )?;
+
+        let accrued = self
+            .earner_account
+            .accrued_yield(index, self.earner_token_account.amount)?;
+
+        // Replace the earner's contribution to the totals with its updated principal
+        let old_principal = self.earner_account.principal;
+        let old_basis = self.earner_account.basis()?;
+
+        self.earner_account.principal =
+            amount_to_principal_down(self.earner_token_account.amount, index)?;
+        self.earner_account.last_index = index;
+        self.earner_account.pending_yield = self
+            .earner_account
+            .pending_yield
+            .checked_add(accrued)
+            .ok_or(ExtError::MathOverflow)?;
+
+        let yield_config = self.global_account.yield_config.claimable_yield_mut()?;
+        yield_config.earner_principal = yield_config
+            .earner_principal
+            .checked_sub(old_principal)
+            .ok_or(ExtError::MathUnderflow)?
+            .checked_add(self.earner_account.principal)
+            .ok_or(ExtError::MathOverflow)?;
+        yield_config.earner_basis = yield_config
+            .earner_basis
+            .checked_sub(old_basis)
+            .ok_or(ExtError::MathUnderflow)?
+            .checked_add(self.earner_account.basis()?)
+            .ok_or(ExtError::MathOverflow)?;
+        yield_config.earner_pending_yield = yield_config
+            .earner_pending_yield
+            .checked_add(accrued)
+            .ok_or(ExtError::MathOverflow)?;
+
+        Ok(index)
+    }
+}
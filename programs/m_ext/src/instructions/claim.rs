@@ -0,0 +1,113 @@
+// external dependencies
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022};
+
+// local dependencies
+use crate::{
+    constants::INDEX_SCALE_U64,
+    errors::ExtError,
+    instructions::checkpoint::*,
+    state::{ExtGlobal, MINT_AUTHORITY_SEED, PAUSE_CLAIM},
+    utils::{conversion::get_excess_collateral, token::mint_tokens},
+};
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    pub checkpoint: Checkpoint<'info>,
+
+    #[account(
+        mut,
+        address = checkpoint.global_account.ext_mint @ ExtError::InvalidMint,
+        mint::token_program = ext_token_program,
+    )]
+    pub ext_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This account is validated by the seed, it stores no data
+    #[account(
+        seeds = [MINT_AUTHORITY_SEED, ExtGlobal::seed_namespace(&checkpoint.global_account)],
+        bump = checkpoint.global_account.ext_mint_authority_bump,
+    )]
+    pub ext_mint_authority: AccountInfo<'info>,
+
+    pub ext_token_program: Program<'info, Token2022>,
+}
+
+impl Claim<'_> {
+    fn validate(&self) -> Result<()> {
+        if self.checkpoint.global_account.is_paused(PAUSE_CLAIM) {
+            return err!(ExtError::Paused);
+        }
+
+        Ok(())
+    }
+
+    // This instruction checkpoints the earner and mints its pending yield to the token account.
+    // The yield is always minted to the earner's token account, so anyone can trigger the claim.
+    #[access_control(ctx.accounts.validate())]
+    pub fn handler(ctx: Context<Self>) -> Result<()> {
+        let index = ctx.accounts.checkpoint.update()?;
+
+        let pending_yield = ctx.accounts.checkpoint.earner_account.pending_yield;
+        if pending_yield == 0 {
+            return Ok(());
+        }
+
+        // The yield can never be paid out of the collateral backing the ext supply
+        let (excess, _) = get_excess_collateral(
+            ctx.accounts.checkpoint.vault_m_token_account.amount,
+            ctx.accounts.ext_mint.supply,
+            INDEX_SCALE_U64,
+        )?;
+        if pending_yield > excess {
+            return err!(ExtError::InsufficientCollateral);
+        }
+
+        mint_tokens(
+            &ctx.accounts.checkpoint.earner_token_account,
+            pending_yield,
+            &ctx.accounts.ext_mint,
+            &ctx.accounts.ext_mint_authority,
+            &[&[
+                MINT_AUTHORITY_SEED,
+                ctx.accounts.checkpoint.global_account.seed_namespace(),
+                &[ctx
+                    .accounts
+                    .checkpoint
+                    .global_account
+                    .ext_mint_authority_bump],
+            ]],
+            &ctx.accounts.ext_token_program,
+        )?;
+
+        ctx.accounts.checkpoint.earner_account.pending_yield = 0;
+        let yield_config = ctx
+            .accounts
+            .checkpoint
+            .global_account
+            .yield_config
+            .claimable_yield_mut()?;
+        yield_config.earner_pending_yield = yield_config
+            .earner_pending_yield
+            .checked_sub(pending_yield)
+            .ok_or(ExtError::MathUnderflow)?;
+
+        // Checkpoint again so the claimed yield starts earning
+        ctx.accounts.checkpoint.earner_token_account.reload()?;
+        ctx.accounts.checkpoint.update()?;
+
+        emit!(PendingYieldClaimed {
+            token_account: ctx.accounts.checkpoint.earner_token_account.key(),
+            amount: pending_yield,
+            index,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct PendingYieldClaimed {
+    pub token_account: Pubkey,
+    pub amount: u64,
+    pub index: u64,
+}
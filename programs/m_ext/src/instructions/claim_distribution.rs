@@ -0,0 +1,149 @@
+// external dependencies
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+// local dependencies
+use crate::{
+    errors::ExtError,
+    state::{
+        Distribution, ExtGlobal, DISTRIBUTION_SEED, EXT_GLOBAL_SEED, MINT_AUTHORITY_SEED,
+        PAUSE_CLAIM,
+    },
+    utils::{
+        merkle::{hash_leaf, verify_proof},
+        token::mint_tokens,
+    },
+};
+
+#[derive(Accounts)]
+pub struct ClaimDistribution<'info> {
+    pub claimant: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        has_one = ext_mint @ ExtError::InvalidMint,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    #[account(
+        mut,
+        seeds = [DISTRIBUTION_SEED, ExtGlobal::seed_namespace(&global_account), distribution_account.index.to_le_bytes().as_ref()],
+        bump = distribution_account.bump,
+    )]
+    pub distribution_account: Account<'info, Distribution>,
+
+    #[account(mut, mint::token_program = ext_token_program)]
+    pub ext_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This account is validated by the seed, it stores no data
+    #[account(
+        seeds = [MINT_AUTHORITY_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump = global_account.ext_mint_authority_bump,
+    )]
+    pub ext_mint_authority: AccountInfo<'info>,
+
+    // The claimant signs, so they can choose which token account receives the payout
+    #[account(
+        mut,
+        token::mint = ext_mint,
+        token::token_program = ext_token_program,
+    )]
+    pub recipient_ext_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub ext_token_program: Program<'info, Token2022>,
+}
+
+impl ClaimDistribution<'_> {
+    fn validate(&self, leaf_index: u32, amount: u64, proof: &[[u8; 32]]) -> Result<()> {
+        if self.global_account.is_paused(PAUSE_CLAIM) {
+            return err!(ExtError::Paused);
+        }
+
+        let distribution = &self.distribution_account;
+
+        if Clock::get()?.unix_timestamp > distribution.expires_at {
+            return err!(ExtError::NotActive);
+        }
+
+        if leaf_index >= distribution.num_leaves {
+            return err!(ExtError::InvalidParam);
+        }
+
+        if distribution.is_claimed(leaf_index) {
+            return err!(ExtError::AlreadyClaimed);
+        }
+
+        let leaf = hash_leaf(leaf_index, &self.claimant.key(), amount);
+        if !verify_proof(proof, &distribution.merkle_root, leaf) {
+            return err!(ExtError::InvalidProof);
+        }
+
+        Ok(())
+    }
+
+    #[access_control(ctx.accounts.validate(leaf_index, amount, &proof))]
+    pub fn handler(
+        ctx: Context<Self>,
+        leaf_index: u32,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let distribution = &mut ctx.accounts.distribution_account;
+        distribution.set_claimed(leaf_index);
+
+        // The amounts in the tree can't sum to more than what was locked
+        distribution.claimed_amount = distribution
+            .claimed_amount
+            .checked_add(amount)
+            .ok_or(ExtError::MathOverflow)?;
+        if distribution.claimed_amount > distribution.total_amount {
+            return err!(ExtError::InsufficientCollateral);
+        }
+
+        let yield_config = ctx
+            .accounts
+            .global_account
+            .yield_config
+            .merkle_distribution_mut()?;
+        yield_config.locked_amount = yield_config
+            .locked_amount
+            .checked_sub(amount)
+            .ok_or(ExtError::MathUnderflow)?;
+
+        if amount > 0 {
+            mint_tokens(
+                &ctx.accounts.recipient_ext_token_account,
+                amount,
+                &ctx.accounts.ext_mint,
+                &ctx.accounts.ext_mint_authority,
+                &[&[
+                    MINT_AUTHORITY_SEED,
+                    ctx.accounts.global_account.seed_namespace(),
+                    &[ctx.accounts.global_account.ext_mint_authority_bump],
+                ]],
+                &ctx.accounts.ext_token_program,
+            )?;
+        }
+
+        emit!(DistributionClaimed {
+            index: ctx.accounts.distribution_account.index,
+            leaf_index,
+            claimant: ctx.accounts.claimant.key(),
+            recipient_token_account: ctx.accounts.recipient_ext_token_account.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct DistributionClaimed {
+    pub index: u64,
+    pub leaf_index: u32,
+    pub claimant: Pubkey,
+    pub recipient_token_account: Pubkey,
+    pub amount: u64,
+}
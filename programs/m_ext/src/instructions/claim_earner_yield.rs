@@ -0,0 +1,211 @@
+// external dependencies
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+use earn::{
+    state::{Global as EarnGlobal, EARNER_SEED},
+    ID as EARN_PROGRAM,
+};
+
+// local dependencies
+use crate::{
+    constants::{INDEX_SCALE_U64, ONE_HUNDRED_PERCENT_U64},
+    errors::ExtError,
+    state::{
+        ExtGlobal, ManagedEarner, EXT_EARNER_SEED, EXT_GLOBAL_SEED, MINT_AUTHORITY_SEED,
+        M_VAULT_SEED, PAUSE_CLAIM,
+    },
+    utils::{
+        conversion::{amount_to_principal_down, get_excess_collateral, sync_earner_index},
+        token::mint_tokens,
+    },
+};
+
+#[derive(Accounts)]
+pub struct ClaimEarnerYield<'info> {
+    #[account(
+        mut,
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        has_one = ext_mint @ ExtError::InvalidMint,
+        has_one = m_earn_global_account @ ExtError::InvalidAccount,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    pub m_earn_global_account: Account<'info, EarnGlobal>,
+
+    #[account(mut, mint::token_program = ext_token_program)]
+    pub ext_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This account is validated by the seed, it stores no data
+    #[account(
+        seeds = [MINT_AUTHORITY_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump = global_account.ext_mint_authority_bump,
+    )]
+    pub ext_mint_authority: AccountInfo<'info>,
+
+    /// CHECK: This account is validated by the seed, it stores no data
+    #[account(
+        seeds = [M_VAULT_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump = global_account.m_vault_bump,
+    )]
+    pub m_vault: AccountInfo<'info>,
+
+    #[account(
+        associated_token::mint = global_account.m_mint,
+        associated_token::authority = m_vault,
+        associated_token::token_program = m_token_program,
+    )]
+    pub vault_m_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: We partially validate this account is the correct address
+    /// via the seed, but we delay full validation to the handler
+    /// so we can handle cases where the account has been closed.
+    #[account(
+        seeds = [EARNER_SEED, vault_m_token_account.key().as_ref()],
+        seeds::program = EARN_PROGRAM,
+        bump,
+    )]
+    pub m_earner_account: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [EXT_EARNER_SEED, ExtGlobal::seed_namespace(&global_account), earner_token_account.key().as_ref()],
+        bump = earner_account.bump,
+    )]
+    pub earner_account: Account<'info, ManagedEarner>,
+
+    // The yield is always minted to the earner's token account, so anyone can trigger the claim
+    #[account(
+        mut,
+        token::mint = ext_mint,
+        token::token_program = ext_token_program,
+    )]
+    pub earner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        address = global_account.yield_config.earner_manager()?.fee_recipient @ ExtError::InvalidAccount,
+        token::mint = ext_mint,
+        token::token_program = ext_token_program,
+    )]
+    pub fee_recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub m_token_program: Program<'info, Token2022>,
+    pub ext_token_program: Program<'info, Token2022>,
+}
+
+impl ClaimEarnerYield<'_> {
+    pub fn handler(ctx: Context<Self>) -> Result<()> {
+        ctx.accounts.settle()
+    }
+
+    // Mints the yield accrued by the earner since its last update, net of its fee,
+    // and resets its principal to the current balance of the token account.
+    pub fn settle(&mut self) -> Result<()> {
+        // Checked here so set_earner_fee can't mint yield while claims are paused either
+        if self.global_account.is_paused(PAUSE_CLAIM) {
+            return err!(ExtError::Paused);
+        }
+
+        // Sync the earner index so all yield up to now is included
+        let index = sync_earner_index(
+            &mut self.global_account,
+            &self.m_earn_global_account,
+            &self.m_earner_account,
+        )?;
+
+        let earner = &self.earner_account;
+        let accrued = earner.accrued_yield(index, self.earner_token_account.amount)?;
+
+        // The fee is rounded up so the earner never receives more than its share
+        let fee: u64 = (accrued as u128)
+            .checked_mul(earner.fee_bps as u128)
+            .ok_or(ExtError::MathOverflow)?
+            .checked_add(ONE_HUNDRED_PERCENT_U64 as u128 - 1)
+            .ok_or(ExtError::MathOverflow)?
+            .checked_div(ONE_HUNDRED_PERCENT_U64 as u128)
+            .ok_or(ExtError::MathUnderflow)?
+            .try_into()?;
+        let net = accrued - fee;
+
+        // The yield can never be paid out of the collateral backing the ext supply
+        let (excess, _) = get_excess_collateral(
+            self.vault_m_token_account.amount,
+            self.ext_mint.supply,
+            INDEX_SCALE_U64,
+        )?;
+        if accrued > excess {
+            return err!(ExtError::InsufficientCollateral);
+        }
+
+        let authority_seeds: &[&[&[u8]]] = &[&[
+            MINT_AUTHORITY_SEED,
+            self.global_account.seed_namespace(),
+            &[self.global_account.ext_mint_authority_bump],
+        ]];
+
+        if net > 0 {
+            mint_tokens(
+                &self.earner_token_account,
+                net,
+                &self.ext_mint,
+                &self.ext_mint_authority,
+                authority_seeds,
+                &self.ext_token_program,
+            )?;
+            self.earner_token_account.reload()?;
+        }
+
+        if fee > 0 {
+            mint_tokens(
+                &self.fee_recipient_token_account,
+                fee,
+                &self.ext_mint,
+                &self.ext_mint_authority,
+                authority_seeds,
+                &self.ext_token_program,
+            )?;
+        }
+
+        // Replace the earner's contribution to the totals with its updated principal
+        let old_principal = self.earner_account.principal;
+        let old_basis = self.earner_account.basis()?;
+
+        self.earner_account.principal =
+            amount_to_principal_down(self.earner_token_account.amount, index)?;
+        self.earner_account.last_index = index;
+
+        let yield_config = self.global_account.yield_config.earner_manager_mut()?;
+        yield_config.earner_principal = yield_config
+            .earner_principal
+            .checked_sub(old_principal)
+            .ok_or(ExtError::MathUnderflow)?
+            .checked_add(self.earner_account.principal)
+            .ok_or(ExtError::MathOverflow)?;
+        yield_config.earner_basis = yield_config
+            .earner_basis
+            .checked_sub(old_basis)
+            .ok_or(ExtError::MathUnderflow)?
+            .checked_add(self.earner_account.basis()?)
+            .ok_or(ExtError::MathOverflow)?;
+
+        if accrued > 0 {
+            emit!(EarnerYieldClaimed {
+                token_account: self.earner_token_account.key(),
+                amount: net,
+                fee,
+                index,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct EarnerYieldClaimed {
+    pub token_account: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+    pub index: u64,
+}
@@ -9,21 +9,24 @@ use earn::{
 // local dependencies
 use crate::{
     errors::ExtError,
-    state::{ExtGlobal, EXT_GLOBAL_SEED, MINT_AUTHORITY_SEED, M_VAULT_SEED},
+    state::{
+        ExtGlobal, Role, YieldConfig, EXT_GLOBAL_SEED, MINT_AUTHORITY_SEED, M_VAULT_SEED,
+        PAUSE_CLAIM,
+    },
     utils::{
-        conversion::{amount_to_principal_down, principal_to_amount_up, sync_multiplier},
+        conversion::{get_excess_collateral, sync_multiplier},
         token::mint_tokens,
     },
 };
 
 #[derive(Accounts)]
 pub struct ClaimFees<'info> {
-    pub admin: Signer<'info>,
+    pub fee_claimer: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [EXT_GLOBAL_SEED],
-        has_one = admin @ ExtError::NotAuthorized,
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        constraint = global_account.roles.has(Role::FeeClaimer, fee_claimer.key) @ ExtError::NotAuthorized,
         has_one = m_mint @ ExtError::InvalidMint,
         has_one = ext_mint @ ExtError::InvalidMint,
         has_one = m_earn_global_account @ ExtError::InvalidAccount,
@@ -41,14 +44,14 @@ pub struct ClaimFees<'info> {
 
     /// CHECK: This account is validated by the seed, it stores no data
     #[account(
-        seeds = [MINT_AUTHORITY_SEED],
+        seeds = [MINT_AUTHORITY_SEED, ExtGlobal::seed_namespace(&global_account)],
         bump = global_account.ext_mint_authority_bump,
     )]
     pub ext_mint_authority: AccountInfo<'info>,
 
     /// CHECK: There is no data in this account, it is validated by the seed
     #[account(
-        seeds = [M_VAULT_SEED],
+        seeds = [M_VAULT_SEED, ExtGlobal::seed_namespace(&global_account)],
         bump = global_account.m_vault_bump,
     )]
     pub m_vault: AccountInfo<'info>,
@@ -61,7 +64,7 @@ pub struct ClaimFees<'info> {
     )]
     pub vault_m_token_account: InterfaceAccount<'info, TokenAccount>,
 
-    /// CHECK: Allowing the admin to specify the recipient account is more flexible
+    /// CHECK: Allowing the fee claimer to specify the recipient account is more flexible
     /// so the authority of this token account is not checked
     #[account(
         mut,
@@ -85,32 +88,52 @@ pub struct ClaimFees<'info> {
 }
 
 impl ClaimFees<'_> {
+    fn validate(&self) -> Result<()> {
+        if self.global_account.is_paused(PAUSE_CLAIM) {
+            return err!(ExtError::Paused);
+        }
+
+        // The excess of these modes belongs to the recipients set by the admin
+        if matches!(
+            self.global_account.yield_config,
+            YieldConfig::YieldToOne(_) | YieldConfig::WeightedSplit(_)
+        ) {
+            return err!(ExtError::UnsupportedYieldMode);
+        }
+
+        Ok(())
+    }
+
+    #[access_control(ctx.accounts.validate())]
     pub fn handler(ctx: Context<Self>) -> Result<()> {
         // Sync the multiplier before allowing any collateral withdrawals
         let signer_bump = ctx.accounts.global_account.ext_mint_authority_bump;
+        let namespace = ctx.accounts.global_account.seed_namespace().to_vec();
         let multiplier: u64 = sync_multiplier(
             &mut ctx.accounts.ext_mint,
             &mut ctx.accounts.global_account,
             &ctx.accounts.m_earn_global_account,
             &ctx.accounts.ext_mint_authority,
-            &[&[MINT_AUTHORITY_SEED, &[signer_bump]]],
+            &[&[MINT_AUTHORITY_SEED, &namespace, &[signer_bump]]],
             &ctx.accounts.ext_token_program,
             &ctx.accounts.m_earner_account,
         )?;
 
-        // Calculate the required collateral, rounding up to be conservative
-        // This amount will always be greater than what is required
-        // since it allows a rounding error of up to 2e-6
-        let required_m = principal_to_amount_up(ctx.accounts.ext_mint.supply, multiplier)?;
-
-        // Excess M is the amount of M in the vault above the amount needed to fully collateralize the extension
         let vault_m = ctx.accounts.vault_m_token_account.amount;
 
-        let excess = vault_m
-            .checked_sub(required_m)
-            .ok_or(ExtError::InsufficientCollateral)?; // This shouldn't underflow, but we check for safety
+        // M owed to holders is reserved for them, only the rest is claimed as fees
+        let vault_m = vault_m
+            .checked_sub(
+                ctx.accounts
+                    .global_account
+                    .yield_config
+                    .reserved_collateral()?,
+            )
+            .ok_or(ExtError::InsufficientCollateral)?;
 
-        let excess_principal = amount_to_principal_down(excess, multiplier)?;
+        // Excess M is the amount of M in the vault above the amount needed to fully collateralize the extension
+        let (excess, excess_principal) =
+            get_excess_collateral(vault_m, ctx.accounts.ext_mint.supply, multiplier)?;
 
         // Only transfer a positive amount of excess
         if excess_principal > 0 {
@@ -121,6 +144,7 @@ impl ClaimFees<'_> {
                 &ctx.accounts.ext_mint_authority,
                 &[&[
                     MINT_AUTHORITY_SEED,
+                    &namespace,
                     &[ctx.accounts.global_account.ext_mint_authority_bump],
                 ]],
                 &ctx.accounts.ext_token_program,
@@ -0,0 +1,144 @@
+// external dependencies
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+use earn::{
+    state::{Global as EarnGlobal, EARNER_SEED},
+    ID as EARN_PROGRAM,
+};
+
+// local dependencies
+use crate::{
+    errors::ExtError,
+    state::{ExtGlobal, EXT_GLOBAL_SEED, MINT_AUTHORITY_SEED, M_VAULT_SEED, PAUSE_CLAIM},
+    utils::{
+        conversion::{get_excess_collateral, sync_multiplier},
+        token::mint_tokens,
+    },
+};
+
+#[derive(Accounts)]
+pub struct ClaimYield<'info> {
+    #[account(
+        mut,
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        has_one = m_mint @ ExtError::InvalidMint,
+        has_one = ext_mint @ ExtError::InvalidMint,
+        has_one = m_earn_global_account @ ExtError::InvalidAccount,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    pub m_earn_global_account: Account<'info, EarnGlobal>,
+
+    #[account(mint::token_program = m_token_program)]
+    pub m_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, mint::token_program = ext_token_program)]
+    pub ext_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This account is validated by the seed, it stores no data
+    #[account(
+        seeds = [MINT_AUTHORITY_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump = global_account.ext_mint_authority_bump,
+    )]
+    pub ext_mint_authority: AccountInfo<'info>,
+
+    /// CHECK: There is no data in this account, it is validated by the seed
+    #[account(
+        seeds = [M_VAULT_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump = global_account.m_vault_bump,
+    )]
+    pub m_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = m_mint,
+        associated_token::authority = m_vault,
+        associated_token::token_program = m_token_program,
+    )]
+    pub vault_m_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    // The recipient is fixed in the global account, so anyone can trigger the claim
+    #[account(
+        mut,
+        address = global_account.yield_config.yield_to_one()?.yield_recipient @ ExtError::InvalidAccount,
+        token::mint = ext_mint,
+        token::token_program = ext_token_program,
+    )]
+    pub recipient_ext_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: We partially validate this account is the correct address
+    /// via the seed, but we delay full validation to the handler
+    /// so we can handle cases where the account has been closed.
+    #[account(
+        seeds = [EARNER_SEED, vault_m_token_account.key().as_ref()],
+        seeds::program = EARN_PROGRAM,
+        bump,
+    )]
+    pub m_earner_account: UncheckedAccount<'info>,
+
+    pub m_token_program: Program<'info, Token2022>,
+    pub ext_token_program: Program<'info, Token2022>,
+}
+
+impl ClaimYield<'_> {
+    fn validate(&self) -> Result<()> {
+        if self.global_account.is_paused(PAUSE_CLAIM) {
+            return err!(ExtError::Paused);
+        }
+
+        Ok(())
+    }
+
+    // This instruction mints all excess collateral in the vault to the yield recipient.
+    // It is permissionless since the recipient can only be changed by the admin.
+    #[access_control(ctx.accounts.validate())]
+    pub fn handler(ctx: Context<Self>) -> Result<()> {
+        // Sync the multiplier before allowing any collateral withdrawals
+        let signer_bump = ctx.accounts.global_account.ext_mint_authority_bump;
+        let namespace = ctx.accounts.global_account.seed_namespace().to_vec();
+        let multiplier: u64 = sync_multiplier(
+            &mut ctx.accounts.ext_mint,
+            &mut ctx.accounts.global_account,
+            &ctx.accounts.m_earn_global_account,
+            &ctx.accounts.ext_mint_authority,
+            &[&[MINT_AUTHORITY_SEED, &namespace, &[signer_bump]]],
+            &ctx.accounts.ext_token_program,
+            &ctx.accounts.m_earner_account,
+        )?;
+
+        // Excess M is the amount of M in the vault above the amount needed to fully collateralize the extension
+        let (excess, excess_principal) = get_excess_collateral(
+            ctx.accounts.vault_m_token_account.amount,
+            ctx.accounts.ext_mint.supply,
+            multiplier,
+        )?;
+
+        // Only mint a positive amount of excess
+        if excess_principal > 0 {
+            mint_tokens(
+                &ctx.accounts.recipient_ext_token_account,
+                excess_principal,
+                &ctx.accounts.ext_mint,
+                &ctx.accounts.ext_mint_authority,
+                &[&[MINT_AUTHORITY_SEED, &namespace, &[signer_bump]]],
+                &ctx.accounts.ext_token_program,
+            )?;
+
+            emit!(YieldClaimed {
+                recipient_token_account: ctx.accounts.recipient_ext_token_account.key(),
+                amount: excess,
+                principal: excess_principal,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct YieldClaimed {
+    pub recipient_token_account: Pubkey,
+    pub amount: u64,
+    pub principal: u64,
+}
@@ -0,0 +1,191 @@
+// external dependencies
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+use earn::{
+    state::{Global as EarnGlobal, EARNER_SEED},
+    ID as EARN_PROGRAM,
+};
+
+// local dependencies
+use crate::{
+    errors::ExtError,
+    state::{
+        Distribution, ExtGlobal, DISTRIBUTION_SEED, EXT_GLOBAL_SEED, MINT_AUTHORITY_SEED,
+        M_VAULT_SEED,
+    },
+    utils::conversion::{get_excess_collateral, sync_multiplier},
+};
+
+#[derive(Accounts)]
+#[instruction(merkle_root: [u8; 32], total_amount: u64, num_leaves: u32)]
+pub struct CreateDistribution<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        has_one = admin @ ExtError::NotAuthorized,
+        has_one = m_mint @ ExtError::InvalidMint,
+        has_one = ext_mint @ ExtError::InvalidMint,
+        has_one = m_earn_global_account @ ExtError::InvalidAccount,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    pub m_earn_global_account: Account<'info, EarnGlobal>,
+
+    #[account(mint::token_program = m_token_program)]
+    pub m_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, mint::token_program = ext_token_program)]
+    pub ext_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This account is validated by the seed, it stores no data
+    #[account(
+        seeds = [MINT_AUTHORITY_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump = global_account.ext_mint_authority_bump,
+    )]
+    pub ext_mint_authority: AccountInfo<'info>,
+
+    /// CHECK: There is no data in this account, it is validated by the seed
+    #[account(
+        seeds = [M_VAULT_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump = global_account.m_vault_bump,
+    )]
+    pub m_vault: AccountInfo<'info>,
+
+    #[account(
+        associated_token::mint = m_mint,
+        associated_token::authority = m_vault,
+        associated_token::token_program = m_token_program,
+    )]
+    pub vault_m_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: We partially validate this account is the correct address
+    /// via the seed, but we delay full validation to the handler
+    /// so we can handle cases where the account has been closed.
+    #[account(
+        seeds = [EARNER_SEED, vault_m_token_account.key().as_ref()],
+        seeds::program = EARN_PROGRAM,
+        bump,
+    )]
+    pub m_earner_account: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = Distribution::size(num_leaves),
+        seeds = [DISTRIBUTION_SEED, ExtGlobal::seed_namespace(&global_account), global_account.yield_config.merkle_distribution()?.distribution_count.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub distribution_account: Account<'info, Distribution>,
+
+    pub m_token_program: Program<'info, Token2022>,
+    pub ext_token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+impl CreateDistribution<'_> {
+    fn validate(&self, total_amount: u64, num_leaves: u32, expires_at: i64) -> Result<()> {
+        if total_amount == 0 || num_leaves == 0 {
+            return err!(ExtError::InvalidParam);
+        }
+
+        if expires_at <= Clock::get()?.unix_timestamp {
+            return err!(ExtError::InvalidParam);
+        }
+
+        Ok(())
+    }
+
+    // This instruction locks excess M in the vault for a distribution computed off-chain.
+    // The merkle root commits to the (leaf index, claimant, amount) of each payout
+    // and the amounts in the tree must sum to the total amount.
+    #[access_control(ctx.accounts.validate(total_amount, num_leaves, expires_at))]
+    pub fn handler(
+        ctx: Context<Self>,
+        merkle_root: [u8; 32],
+        total_amount: u64,
+        num_leaves: u32,
+        expires_at: i64,
+    ) -> Result<()> {
+        // Sync the multiplier before locking any collateral
+        let signer_bump = ctx.accounts.global_account.ext_mint_authority_bump;
+        let namespace = ctx.accounts.global_account.seed_namespace().to_vec();
+        let multiplier: u64 = sync_multiplier(
+            &mut ctx.accounts.ext_mint,
+            &mut ctx.accounts.global_account,
+            &ctx.accounts.m_earn_global_account,
+            &ctx.accounts.ext_mint_authority,
+            &[&[MINT_AUTHORITY_SEED, &namespace, &[signer_bump]]],
+            &ctx.accounts.ext_token_program,
+            &ctx.accounts.m_earner_account,
+        )?;
+
+        // M already locked in other distributions can't be locked again
+        let vault_m = ctx
+            .accounts
+            .vault_m_token_account
+            .amount
+            .checked_sub(
+                ctx.accounts
+                    .global_account
+                    .yield_config
+                    .merkle_distribution()?
+                    .locked_amount,
+            )
+            .ok_or(ExtError::InsufficientCollateral)?;
+
+        // Only excess M can be distributed
+        let (_, excess_principal) =
+            get_excess_collateral(vault_m, ctx.accounts.ext_mint.supply, multiplier)?;
+
+        if total_amount > excess_principal {
+            return err!(ExtError::InsufficientCollateral);
+        }
+
+        let yield_config = ctx
+            .accounts
+            .global_account
+            .yield_config
+            .merkle_distribution_mut()?;
+        let index = yield_config.distribution_count;
+
+        ctx.accounts.distribution_account.set_inner(Distribution {
+            bump: ctx.bumps.distribution_account,
+            index,
+            merkle_root,
+            total_amount,
+            claimed_amount: 0,
+            expires_at,
+            num_leaves,
+            claimed: vec![0; Distribution::bitmap_len(num_leaves)],
+        });
+
+        yield_config.distribution_count = index.checked_add(1).ok_or(ExtError::MathOverflow)?;
+        yield_config.locked_amount = yield_config
+            .locked_amount
+            .checked_add(total_amount)
+            .ok_or(ExtError::MathOverflow)?;
+
+        emit!(DistributionCreated {
+            index,
+            merkle_root,
+            total_amount,
+            num_leaves,
+            expires_at,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct DistributionCreated {
+    pub index: u64,
+    pub merkle_root: [u8; 32],
+    pub total_amount: u64,
+    pub num_leaves: u32,
+    pub expires_at: i64,
+}
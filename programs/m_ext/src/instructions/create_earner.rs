@@ -0,0 +1,111 @@
+// external dependencies
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Token2022, TokenAccount};
+use earn::{
+    state::{Global as EarnGlobal, EARNER_SEED},
+    ID as EARN_PROGRAM,
+};
+
+// local dependencies
+use crate::{
+    errors::ExtError,
+    state::{Earner, ExtGlobal, EXT_EARNER_SEED, EXT_GLOBAL_SEED, M_VAULT_SEED},
+    utils::conversion::{amount_to_principal_down, sync_earner_index},
+};
+
+#[derive(Accounts)]
+pub struct CreateEarner<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        has_one = m_earn_global_account @ ExtError::InvalidAccount,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    pub m_earn_global_account: Account<'info, EarnGlobal>,
+
+    /// CHECK: This account is validated by the seed, it stores no data
+    #[account(
+        seeds = [M_VAULT_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump = global_account.m_vault_bump,
+    )]
+    pub m_vault: AccountInfo<'info>,
+
+    #[account(
+        associated_token::mint = global_account.m_mint,
+        associated_token::authority = m_vault,
+        associated_token::token_program = m_token_program,
+    )]
+    pub vault_m_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: We partially validate this account is the correct address
+    /// via the seed, but we delay full validation to the handler
+    /// so we can handle cases where the account has been closed.
+    #[account(
+        seeds = [EARNER_SEED, vault_m_token_account.key().as_ref()],
+        seeds::program = EARN_PROGRAM,
+        bump,
+    )]
+    pub m_earner_account: UncheckedAccount<'info>,
+
+    // Anyone can pay to create the earner account for any ext token account
+    #[account(token::mint = global_account.ext_mint)]
+    pub earner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = Earner::size(),
+        seeds = [EXT_EARNER_SEED, ExtGlobal::seed_namespace(&global_account), earner_token_account.key().as_ref()],
+        bump,
+    )]
+    pub earner_account: Account<'info, Earner>,
+
+    pub m_token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+impl CreateEarner<'_> {
+    // This instruction creates the account that tracks the yield of an ext token account.
+    // Yield accrues on the current balance of the token account from the current index onwards.
+    pub fn handler(ctx: Context<Self>) -> Result<()> {
+        // Sync the earner index so the earner doesn't receive yield from before it was created
+        let index = sync_earner_index(
+            &mut ctx.accounts.global_account,
+            &ctx.accounts.m_earn_global_account,
+            &ctx.accounts.m_earner_account,
+        )?;
+
+        ctx.accounts.earner_account.set_inner(Earner {
+            bump: ctx.bumps.earner_account,
+            token_account: ctx.accounts.earner_token_account.key(),
+            principal: amount_to_principal_down(ctx.accounts.earner_token_account.amount, index)?,
+            last_index: index,
+            pending_yield: 0,
+        });
+
+        // Add the earner to the totals
+        let principal = ctx.accounts.earner_account.principal;
+        let basis = ctx.accounts.earner_account.basis()?;
+
+        let yield_config = ctx
+            .accounts
+            .global_account
+            .yield_config
+            .claimable_yield_mut()?;
+        yield_config.earner_principal = yield_config
+            .earner_principal
+            .checked_add(principal)
+            .ok_or(ExtError::MathOverflow)?;
+        yield_config.earner_basis = yield_config
+            .earner_basis
+            .checked_add(basis)
+            .ok_or(ExtError::MathOverflow)?;
+
+        Ok(())
+    }
+}
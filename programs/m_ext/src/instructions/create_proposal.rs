@@ -0,0 +1,97 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::MAX_PROPOSAL_ACCOUNTS,
+    errors::ExtError,
+    state::{
+        ExtGlobal, Multisig, MultisigAction, Proposal, EXT_GLOBAL_SEED, MULTISIG_SEED,
+        PROPOSAL_SEED,
+    },
+};
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    #[account(
+        mut,
+        seeds = [MULTISIG_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump = multisig.bump,
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = Proposal::size(MAX_PROPOSAL_ACCOUNTS),
+        seeds = [PROPOSAL_SEED, ExtGlobal::seed_namespace(&global_account), multisig.proposal_count.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl CreateProposal<'_> {
+    fn validate(&self, expires_at: i64, remaining_accounts: &[AccountInfo]) -> Result<()> {
+        if !self.multisig.signers.contains(self.proposer.key) {
+            return err!(ExtError::NotAuthorized);
+        }
+
+        if expires_at <= Clock::get()?.unix_timestamp {
+            return err!(ExtError::InvalidParam);
+        }
+
+        if remaining_accounts.len() > MAX_PROPOSAL_ACCOUNTS {
+            return err!(ExtError::InvalidParam);
+        }
+
+        Ok(())
+    }
+
+    // This instruction allows a multisig signer to propose an admin action, which counts as their approval.
+    // The remaining accounts are the accounts of the action's instruction, in order,
+    // and they are stored so the action can only be executed with the approved accounts.
+    #[access_control(ctx.accounts.validate(expires_at, ctx.remaining_accounts))]
+    pub fn handler(ctx: Context<Self>, action: MultisigAction, expires_at: i64) -> Result<()> {
+        let index = ctx.accounts.multisig.proposal_count;
+        let proposer = ctx.accounts.proposer.key();
+
+        ctx.accounts.proposal.set_inner(Proposal {
+            bump: ctx.bumps.proposal,
+            index,
+            proposer,
+            action: action.clone(),
+            accounts: ctx.remaining_accounts.iter().map(|a| a.key()).collect(),
+            approvals: vec![proposer],
+            expires_at,
+            executed: false,
+        });
+
+        ctx.accounts.multisig.proposal_count += 1;
+
+        emit!(ProposalCreated {
+            index,
+            proposer,
+            action,
+            expires_at,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct ProposalCreated {
+    pub index: u64,
+    pub proposer: Pubkey,
+    pub action: MultisigAction,
+    pub expires_at: i64,
+}
@@ -0,0 +1,168 @@
+// external dependencies
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+use earn::{
+    state::{Global as EarnGlobal, EARNER_SEED},
+    ID as EARN_PROGRAM,
+};
+
+// local dependencies
+use crate::{
+    constants::ONE_HUNDRED_PERCENT_U64,
+    errors::ExtError,
+    state::{ExtGlobal, EXT_GLOBAL_SEED, MINT_AUTHORITY_SEED, M_VAULT_SEED, PAUSE_CLAIM},
+    utils::{
+        conversion::{get_excess_collateral, sync_multiplier},
+        token::mint_tokens,
+    },
+};
+
+#[derive(Accounts)]
+pub struct Distribute<'info> {
+    #[account(
+        mut,
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        has_one = m_mint @ ExtError::InvalidMint,
+        has_one = ext_mint @ ExtError::InvalidMint,
+        has_one = m_earn_global_account @ ExtError::InvalidAccount,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    pub m_earn_global_account: Account<'info, EarnGlobal>,
+
+    #[account(mint::token_program = m_token_program)]
+    pub m_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, mint::token_program = ext_token_program)]
+    pub ext_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This account is validated by the seed, it stores no data
+    #[account(
+        seeds = [MINT_AUTHORITY_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump = global_account.ext_mint_authority_bump,
+    )]
+    pub ext_mint_authority: AccountInfo<'info>,
+
+    /// CHECK: There is no data in this account, it is validated by the seed
+    #[account(
+        seeds = [M_VAULT_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump = global_account.m_vault_bump,
+    )]
+    pub m_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = m_mint,
+        associated_token::authority = m_vault,
+        associated_token::token_program = m_token_program,
+    )]
+    pub vault_m_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: We partially validate this account is the correct address
+    /// via the seed, but we delay full validation to the handler
+    /// so we can handle cases where the account has been closed.
+    #[account(
+        seeds = [EARNER_SEED, vault_m_token_account.key().as_ref()],
+        seeds::program = EARN_PROGRAM,
+        bump,
+    )]
+    pub m_earner_account: UncheckedAccount<'info>,
+
+    pub m_token_program: Program<'info, Token2022>,
+    pub ext_token_program: Program<'info, Token2022>,
+}
+
+impl<'info> Distribute<'info> {
+    // Mints the excess collateral to the beneficiaries according to their weights.
+    // The beneficiary token accounts are passed in the same order as they are stored in the yield config.
+    pub fn distribute(&mut self, beneficiary_accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+        // Checked here so set_beneficiaries can't mint yield while claims are paused either
+        if self.global_account.is_paused(PAUSE_CLAIM) {
+            return err!(ExtError::Paused);
+        }
+
+        let beneficiaries = self
+            .global_account
+            .yield_config
+            .weighted_split()?
+            .beneficiaries
+            .clone();
+        if beneficiary_accounts.len() != beneficiaries.len() {
+            return err!(ExtError::InvalidAccount);
+        }
+
+        // Sync the multiplier before allowing any collateral withdrawals
+        let signer_bump = self.global_account.ext_mint_authority_bump;
+        let namespace = self.global_account.seed_namespace().to_vec();
+        let multiplier: u64 = sync_multiplier(
+            &mut self.ext_mint,
+            &mut self.global_account,
+            &self.m_earn_global_account,
+            &self.ext_mint_authority,
+            &[&[MINT_AUTHORITY_SEED, &namespace, &[signer_bump]]],
+            &self.ext_token_program,
+            &self.m_earner_account,
+        )?;
+
+        // Excess M is the amount of M in the vault above the amount needed to fully collateralize the extension
+        let (_, excess_principal) = get_excess_collateral(
+            self.vault_m_token_account.amount,
+            self.ext_mint.supply,
+            multiplier,
+        )?;
+
+        for (beneficiary, account) in beneficiaries.iter().zip(beneficiary_accounts) {
+            if account.key() != beneficiary.token_account {
+                return err!(ExtError::InvalidAccount);
+            }
+
+            // Shares are rounded down, the remainder stays in the vault for the next distribution
+            let share: u64 = (excess_principal as u128)
+                .checked_mul(beneficiary.weight_bps as u128)
+                .ok_or(ExtError::MathOverflow)?
+                .checked_div(ONE_HUNDRED_PERCENT_U64 as u128)
+                .ok_or(ExtError::MathUnderflow)?
+                .try_into()
+                .map_err(|_| ExtError::TypeConversionError)?;
+
+            if share == 0 {
+                continue;
+            }
+
+            // If a beneficiary token account has been closed, its share stays in the vault
+            // so the other beneficiaries can still be paid and the admin can replace it
+            let token_account = match InterfaceAccount::<TokenAccount>::try_from(account) {
+                Ok(token_account) if token_account.mint == self.ext_mint.key() => token_account,
+                _ => continue,
+            };
+
+            mint_tokens(
+                &token_account,
+                share,
+                &self.ext_mint,
+                &self.ext_mint_authority,
+                &[&[MINT_AUTHORITY_SEED, &namespace, &[signer_bump]]],
+                &self.ext_token_program,
+            )?;
+
+            emit!(YieldDistributed {
+                recipient_token_account: token_account.key(),
+                amount: share,
+            });
+        }
+
+        Ok(())
+    }
+
+    // This instruction is permissionless since the beneficiaries can only be changed by the admin.
+    pub fn handler(ctx: Context<'_, '_, 'info, 'info, Self>) -> Result<()> {
+        ctx.accounts.distribute(ctx.remaining_accounts)
+    }
+}
+
+#[event]
+pub struct YieldDistributed {
+    pub recipient_token_account: Pubkey,
+    pub amount: u64,
+}
@@ -0,0 +1,131 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::invoke_signed,
+    },
+    InstructionData,
+};
+
+use crate::{
+    errors::ExtError,
+    instruction,
+    state::{
+        ExtGlobal, Multisig, MultisigAction, Proposal, EXT_GLOBAL_SEED, MULTISIG_AUTHORITY_SEED,
+        MULTISIG_SEED, PROPOSAL_SEED,
+    },
+};
+
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    // Not mutable so the action can update the global account
+    #[account(
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    #[account(
+        seeds = [MULTISIG_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump = multisig.bump,
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    /// CHECK: This account is validated by the seed, it stores no data
+    #[account(
+        seeds = [MULTISIG_AUTHORITY_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump = multisig.authority_bump,
+    )]
+    pub multisig_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [PROPOSAL_SEED, ExtGlobal::seed_namespace(&global_account), proposal.index.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+impl ExecuteProposal<'_> {
+    fn validate(&self, remaining_accounts: &[AccountInfo]) -> Result<()> {
+        if self.proposal.executed {
+            return err!(ExtError::AlreadyExecuted);
+        }
+
+        if Clock::get()?.unix_timestamp > self.proposal.expires_at {
+            return err!(ExtError::NotActive);
+        }
+
+        if self.multisig.approvals(&self.proposal) < self.multisig.threshold as usize {
+            return err!(ExtError::InsufficientApprovals);
+        }
+
+        // The action must be executed with the accounts that were approved
+        if remaining_accounts.len() != self.proposal.accounts.len()
+            || remaining_accounts
+                .iter()
+                .zip(&self.proposal.accounts)
+                .any(|(account, approved)| account.key != approved)
+        {
+            return err!(ExtError::InvalidAccount);
+        }
+
+        Ok(())
+    }
+
+    // This instruction executes an approved proposal, anyone can call it once the threshold is met.
+    // The action is executed by calling the matching instruction of this program with the
+    // multisig authority as signer, so it goes through the same checks as a direct call.
+    #[access_control(ctx.accounts.validate(ctx.remaining_accounts))]
+    pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, ExecuteProposal<'info>>) -> Result<()> {
+        ctx.accounts.proposal.executed = true;
+
+        let data = match ctx.accounts.proposal.action {
+            MultisigAction::SetFee { fee_bps } => instruction::SetFee { fee_bps }.data(),
+            MultisigAction::AddWrapAuthority { wrap_authority } => instruction::AddWrapAuthority {
+                new_wrap_authority: wrap_authority,
+            }
+            .data(),
+            MultisigAction::RemoveWrapAuthority { wrap_authority } => {
+                instruction::RemoveWrapAuthority { wrap_authority }.data()
+            }
+            MultisigAction::ClaimFees => instruction::ClaimFees {}.data(),
+        };
+
+        let multisig_authority = ctx.accounts.multisig_authority.key();
+        let accounts = ctx
+            .remaining_accounts
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: account.key(),
+                is_signer: account.key() == multisig_authority,
+                is_writable: account.is_writable,
+            })
+            .collect();
+
+        invoke_signed(
+            &Instruction {
+                program_id: crate::ID,
+                accounts,
+                data,
+            },
+            ctx.remaining_accounts,
+            &[&[
+                MULTISIG_AUTHORITY_SEED,
+                ctx.accounts.global_account.seed_namespace(),
+                &[ctx.accounts.multisig.authority_bump],
+            ]],
+        )?;
+
+        emit!(ProposalExecuted {
+            index: ctx.accounts.proposal.index,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct ProposalExecuted {
+    pub index: u64,
+}
@@ -0,0 +1,161 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::invoke_signed,
+    },
+    InstructionData,
+};
+
+use crate::{
+    constants::GRACE_PERIOD,
+    errors::ExtError,
+    instruction,
+    state::{
+        ExtGlobal, QueuedAction, TimelockAction, EXT_GLOBAL_SEED, QUEUED_ACTION_SEED,
+        TIMELOCK_AUTHORITY_SEED,
+    },
+};
+
+#[derive(Accounts)]
+pub struct ExecuteQueuedAction<'info> {
+    // Not mutable so the action can update the global account
+    #[account(
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    /// CHECK: This account is validated by the seed, it stores no data
+    #[account(
+        seeds = [TIMELOCK_AUTHORITY_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump = global_account.timelock_authority_bump,
+    )]
+    pub timelock_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        close = queued_by,
+        has_one = queued_by @ ExtError::InvalidAccount,
+        seeds = [QUEUED_ACTION_SEED, ExtGlobal::seed_namespace(&global_account), queued_action.index.to_le_bytes().as_ref()],
+        bump = queued_action.bump,
+    )]
+    pub queued_action: Account<'info, QueuedAction>,
+
+    /// CHECK: Receives the rent of the queued action
+    #[account(mut)]
+    pub queued_by: UncheckedAccount<'info>,
+}
+
+impl ExecuteQueuedAction<'_> {
+    fn validate(&self) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let eta = self.queued_action.eta;
+
+        if now < eta {
+            return err!(ExtError::Active);
+        }
+
+        // Stale actions have to be queued again, the admin can cancel them to refund the rent
+        if now > eta.saturating_add(GRACE_PERIOD) {
+            return err!(ExtError::QueuedActionExpired);
+        }
+
+        Ok(())
+    }
+
+    // This instruction executes a queued change once its eta has passed and before the grace
+    // period ends, anyone can call it.
+    // The change is executed by calling the matching instruction of this program with the
+    // timelock authority as signer, the remaining accounts are the accounts of that instruction.
+    // All of them are validated by the instruction, so they don't need to be stored when queued.
+    #[access_control(ctx.accounts.validate())]
+    pub fn handler<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteQueuedAction<'info>>,
+    ) -> Result<()> {
+        let action = &ctx.accounts.queued_action.action;
+        let data = match action.clone() {
+            TimelockAction::SetFee { fee_bps } => instruction::SetFee { fee_bps }.data(),
+            TimelockAction::AddWrapAuthority { wrap_authority } => instruction::AddWrapAuthority {
+                new_wrap_authority: wrap_authority,
+            }
+            .data(),
+            TimelockAction::ProposeAdmin { new_admin } => {
+                instruction::ProposeAdmin { new_admin }.data()
+            }
+            TimelockAction::SetEarnerFee { fee_bps, .. } => {
+                instruction::SetEarnerFee { fee_bps }.data()
+            }
+            TimelockAction::SetYieldRecipient { .. } => instruction::SetYieldRecipient {}.data(),
+            TimelockAction::SetBeneficiaries { beneficiaries } => {
+                instruction::SetBeneficiaries { beneficiaries }.data()
+            }
+            TimelockAction::SetWrapAuthorityPermissions {
+                wrap_authority,
+                permissions,
+                expires_at,
+            } => instruction::SetWrapAuthorityPermissions {
+                wrap_authority,
+                permissions,
+                expires_at,
+            }
+            .data(),
+            TimelockAction::SetOpenMode {
+                open_wrap,
+                open_unwrap,
+            } => instruction::SetOpenMode {
+                open_wrap,
+                open_unwrap,
+            }
+            .data(),
+        };
+
+        // The instruction only validates the accounts it is passed, so the account the change
+        // was queued for must be at its position
+        if let Some((position, target)) = action.target_account() {
+            let account = ctx
+                .remaining_accounts
+                .get(position)
+                .ok_or(ExtError::InvalidAccount)?;
+            if account.key() != target {
+                return err!(ExtError::InvalidAccount);
+            }
+        }
+
+        let timelock_authority = ctx.accounts.timelock_authority.key();
+        let accounts = ctx
+            .remaining_accounts
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: account.key(),
+                is_signer: account.key() == timelock_authority,
+                is_writable: account.is_writable,
+            })
+            .collect();
+
+        invoke_signed(
+            &Instruction {
+                program_id: crate::ID,
+                accounts,
+                data,
+            },
+            ctx.remaining_accounts,
+            &[&[
+                TIMELOCK_AUTHORITY_SEED,
+                ctx.accounts.global_account.seed_namespace(),
+                &[ctx.accounts.global_account.timelock_authority_bump],
+            ]],
+        )?;
+
+        emit!(QueuedActionExecuted {
+            index: ctx.accounts.queued_action.index,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct QueuedActionExecuted {
+    pub index: u64,
+}
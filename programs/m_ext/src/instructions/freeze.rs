@@ -0,0 +1,172 @@
+use anchor_lang::{prelude::*, AccountsClose};
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+use crate::{
+    errors::ExtError,
+    state::{
+        ComplianceFreeze, ExtGlobal, Role, COMPLIANCE_FREEZE_SEED, EXT_GLOBAL_SEED,
+        FREEZE_AUTHORITY_SEED,
+    },
+    utils::token::{freeze_token_account, thaw_token_account},
+};
+
+#[derive(Accounts)]
+pub struct FreezeAccount<'info> {
+    #[account(mut)]
+    pub compliance_officer: Signer<'info>,
+
+    #[account(
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        constraint = global_account.roles.has(Role::ComplianceOfficer, compliance_officer.key) @ ExtError::NotAuthorized,
+        has_one = ext_mint @ ExtError::InvalidMint,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    #[account(
+        mint::token_program = ext_token_program,
+        constraint = ext_mint.freeze_authority == Some(freeze_authority.key()).into() @ ExtError::InvalidMint,
+    )]
+    pub ext_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This account is validated by the seed, it stores no data
+    #[account(
+        seeds = [FREEZE_AUTHORITY_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump,
+    )]
+    pub freeze_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        token::mint = ext_mint,
+        token::token_program = ext_token_program,
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = compliance_officer,
+        space = ComplianceFreeze::size(),
+        seeds = [COMPLIANCE_FREEZE_SEED, ExtGlobal::seed_namespace(&global_account), token_account.key().as_ref()],
+        bump,
+    )]
+    pub compliance_freeze: Account<'info, ComplianceFreeze>,
+
+    pub ext_token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+impl FreezeAccount<'_> {
+    // This instruction allows the compliance officer to freeze an ext token account.
+    // The freeze authority of the ext mint must be the freeze authority PDA of the extension.
+    // The freeze is recorded so approve_holder can't thaw the token account.
+
+    pub fn handler(ctx: Context<Self>) -> Result<()> {
+        ctx.accounts.compliance_freeze.set_inner(ComplianceFreeze {
+            bump: ctx.bumps.compliance_freeze,
+            token_account: ctx.accounts.token_account.key(),
+        });
+
+        freeze_token_account(
+            &ctx.accounts.token_account,
+            &ctx.accounts.ext_mint,
+            &ctx.accounts.freeze_authority,
+            &[&[
+                FREEZE_AUTHORITY_SEED,
+                ctx.accounts.global_account.seed_namespace(),
+                &[ctx.bumps.freeze_authority],
+            ]],
+            &ctx.accounts.ext_token_program,
+        )?;
+
+        emit!(AccountFrozen {
+            token_account: ctx.accounts.token_account.key(),
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ThawAccount<'info> {
+    #[account(mut)]
+    pub compliance_officer: Signer<'info>,
+
+    #[account(
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        constraint = global_account.roles.has(Role::ComplianceOfficer, compliance_officer.key) @ ExtError::NotAuthorized,
+        has_one = ext_mint @ ExtError::InvalidMint,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    #[account(
+        mint::token_program = ext_token_program,
+        constraint = ext_mint.freeze_authority == Some(freeze_authority.key()).into() @ ExtError::InvalidMint,
+    )]
+    pub ext_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This account is validated by the seed, it stores no data
+    #[account(
+        seeds = [FREEZE_AUTHORITY_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump,
+    )]
+    pub freeze_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        token::mint = ext_mint,
+        token::token_program = ext_token_program,
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: This account is validated by the seed, it only exists if the compliance officer froze the token account
+    #[account(
+        mut,
+        seeds = [COMPLIANCE_FREEZE_SEED, ExtGlobal::seed_namespace(&global_account), token_account.key().as_ref()],
+        bump,
+    )]
+    pub compliance_freeze: UncheckedAccount<'info>,
+
+    pub ext_token_program: Program<'info, Token2022>,
+}
+
+impl ThawAccount<'_> {
+    // This instruction allows the compliance officer to thaw a frozen ext token account.
+    // It can also thaw token accounts frozen by default, so the freeze record is optional.
+
+    pub fn handler(ctx: Context<Self>) -> Result<()> {
+        if ctx.accounts.compliance_freeze.owner == &crate::ID {
+            Account::<ComplianceFreeze>::try_from(&ctx.accounts.compliance_freeze)?
+                .close(ctx.accounts.compliance_officer.to_account_info())?;
+        }
+
+        thaw_token_account(
+            &ctx.accounts.token_account,
+            &ctx.accounts.ext_mint,
+            &ctx.accounts.freeze_authority,
+            &[&[
+                FREEZE_AUTHORITY_SEED,
+                ctx.accounts.global_account.seed_namespace(),
+                &[ctx.bumps.freeze_authority],
+            ]],
+            &ctx.accounts.ext_token_program,
+        )?;
+
+        emit!(AccountThawed {
+            token_account: ctx.accounts.token_account.key(),
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct AccountFrozen {
+    pub token_account: Pubkey,
+}
+
+#[event]
+pub struct AccountThawed {
+    pub token_account: Pubkey,
+}
@@ -1,33 +1,33 @@
 // external dependencies
 use anchor_lang::prelude::*;
+use anchor_spl::token_2022_extensions::spl_pod::optional_keys::OptionalNonZeroPubkey;
 use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
-use cfg_if::cfg_if;
 use earn::{
     state::{Earner, Global as EarnGlobal, EARNER_SEED, GLOBAL_SEED as EARN_GLOBAL_SEED},
     ID as EARN_PROGRAM,
 };
+use spl_token_2022::{extension::ExtensionType, state::AccountState};
 use std::collections::HashSet;
 
 // local dependencies
 use crate::{
+    constants::{INDEX_SCALE_U64, ONE_HUNDRED_PERCENT_U64},
     errors::ExtError,
-    state::{ExtGlobal, YieldConfig, EXT_GLOBAL_SEED, MINT_AUTHORITY_SEED, M_VAULT_SEED},
+    instructions::create_wrap_authority_accounts,
+    state::{
+        ClaimableYieldConfig, EarnerManagerConfig, ExtGlobal, InterestBearingConfig,
+        MerkleDistributionConfig, Roles, ScaledUiConfig, WeightedSplitConfig, WrapQuota,
+        YieldConfig, YieldMode, YieldToOneConfig, EXT_GLOBAL_SEED, EXT_GLOBAL_VERSION,
+        FREEZE_AUTHORITY_SEED, MINT_AUTHORITY_SEED, M_VAULT_SEED, TOMBSTONE_SEED,
+    },
+    utils::conversion::{
+        get_default_account_state, get_interest_bearing_config, get_mint_extensions,
+        get_scaled_ui_config, sync_multiplier,
+    },
 };
 
-// conditional dependencies
-cfg_if! {
-    if #[cfg(feature = "scaled-ui")] {
-        use anchor_spl::token_2022_extensions::spl_pod::optional_keys::OptionalNonZeroPubkey;
-        use spl_token_2022::extension::ExtensionType;
-        use crate::{
-            constants::{INDEX_SCALE_U64, ONE_HUNDRED_PERCENT_U64},
-            utils::conversion::{sync_multiplier, get_mint_extensions, get_scaled_ui_config},
-        };
-    }
-}
-
 #[derive(Accounts)]
-#[instruction(wrap_authorities: Vec<Pubkey>)]
+#[instruction(wrap_authorities: Vec<Pubkey>, yield_mode: YieldMode, namespaced: bool)]
 pub struct Initialize<'info> {
     #[account(mut)]
     pub admin: Signer<'info>,
@@ -35,14 +35,19 @@ pub struct Initialize<'info> {
     #[account(
         init,
         payer = admin,
-        space = ExtGlobal::size(
-            wrap_authorities.len()
-        ),
-        seeds = [EXT_GLOBAL_SEED],
+        space = ExtGlobal::size(),
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::namespace(namespaced, &ext_mint.key())],
         bump
     )]
     pub global_account: Account<'info, ExtGlobal>,
 
+    /// CHECK: This account is validated by the seed, it only exists if the extension was decommissioned
+    #[account(
+        seeds = [TOMBSTONE_SEED, ExtGlobal::namespace(namespaced, &ext_mint.key())],
+        bump
+    )]
+    pub tombstone: UncheckedAccount<'info>,
+
     #[account(
         mint::token_program = m_token_program,
         address = m_earn_global_account.mint,
@@ -59,14 +64,14 @@ pub struct Initialize<'info> {
 
     /// CHECK: Validated by the seeds, stores no data
     #[account(
-        seeds = [MINT_AUTHORITY_SEED],
+        seeds = [MINT_AUTHORITY_SEED, ExtGlobal::namespace(namespaced, &ext_mint.key())],
         bump
     )]
     pub ext_mint_authority: AccountInfo<'info>,
 
     /// CHECK: Validated by the seeds, stores no data
     #[account(
-        seeds = [M_VAULT_SEED],
+        seeds = [M_VAULT_SEED, ExtGlobal::namespace(namespaced, &ext_mint.key())],
         bump
     )]
     pub m_vault: AccountInfo<'info>,
@@ -103,13 +108,43 @@ pub struct Initialize<'info> {
 }
 
 impl Initialize<'_> {
-    // This instruction initializes the Scaled UI M extension for a given ext mint.
+    // This instruction initializes the M extension for a given ext mint.
     // It sets up the global account, validates the mint and its authority,
-    // and initializes the Scaled UI multiplier to 1.0.
+    // and initializes the state of the chosen yield mode.
     // The ext_mint must have a supply of 0 to start.
-    // The wrap authorities are validated and stored in the global account.
-    // The fee_bps is validated to be within the allowed range.
-    fn validate(&self, _fee_bps: u64) -> Result<()> {
+    // The initial wrap authorities get their own accounts, passed as remaining accounts in the
+    // same order, and more can be added afterwards with add_wrap_authority.
+    // Mode specific parameters, such as the fee_bps, are validated to be within the allowed range.
+    // If namespaced, the PDAs of the extension are derived with the ext mint as an extra seed
+    // so the program can host any number of extensions.
+    // A non-zero timelock delay enables the timelock, so fee, wrap authority and admin changes
+    // must be queued for that many seconds before they can be executed.
+    // If an allowlist is required, new ext token accounts must start frozen and are thawed by
+    // the freeze authority PDA once their owner is allowlisted.
+    // Extensions that were decommissioned can't be initialized again.
+    fn validate(
+        &self,
+        wrap_authorities: &[Pubkey],
+        yield_mode: &YieldMode,
+        namespaced: bool,
+        timelock_delay: i64,
+        require_allowlist: bool,
+    ) -> Result<()> {
+        if timelock_delay < 0 {
+            return err!(ExtError::InvalidParam);
+        }
+
+        // Create hash set from wrap_authorities to ensure uniqueness
+        let wrap_auth_set: HashSet<&Pubkey> = wrap_authorities.iter().collect();
+        if wrap_auth_set.len() < wrap_authorities.len() {
+            return err!(ExtError::InvalidParam);
+        }
+
+        // A decommissioned extension can't be initialized again with the same PDAs
+        if self.tombstone.owner == &crate::ID {
+            return err!(ExtError::Decommissioned);
+        }
+
         // Validate the ext_mint_authority PDA is the mint authority for the ext mint
         let ext_mint_authority = self.ext_mint_authority.key();
         if self.ext_mint.mint_authority.unwrap_or_default() != ext_mint_authority {
@@ -121,8 +156,31 @@ impl Initialize<'_> {
             return err!(ExtError::InvalidMint);
         }
 
-        cfg_if! {
-            if #[cfg(feature = "scaled-ui")] {
+        if require_allowlist {
+            // Validate that new token accounts of the ext mint are frozen by default
+            let extensions = get_mint_extensions(&self.ext_mint)?;
+
+            if !extensions.contains(&ExtensionType::DefaultAccountState)
+                || get_default_account_state(&self.ext_mint)?.state != AccountState::Frozen as u8
+            {
+                return err!(ExtError::InvalidMint);
+            }
+
+            // Validate that the freeze authority PDA can thaw them
+            let (freeze_authority, _) = Pubkey::find_program_address(
+                &[
+                    FREEZE_AUTHORITY_SEED,
+                    ExtGlobal::namespace(namespaced, &self.ext_mint.key()),
+                ],
+                &crate::ID,
+            );
+            if self.ext_mint.freeze_authority != Some(freeze_authority).into() {
+                return err!(ExtError::InvalidMint);
+            }
+        }
+
+        match yield_mode {
+            YieldMode::ScaledUi { fee_bps } => {
                 // Validate that the ext mint has the ScaledUiAmount extension and
                 // that the ext mint authority is the extension authority
                 let extensions = get_mint_extensions(&self.ext_mint)?;
@@ -137,43 +195,108 @@ impl Initialize<'_> {
                 }
 
                 // Validate the fee_bps is within the allowed range
-                if _fee_bps > ONE_HUNDRED_PERCENT_U64 {
+                if *fee_bps > ONE_HUNDRED_PERCENT_U64 {
+                    return err!(ExtError::InvalidParam);
+                }
+            }
+            YieldMode::InterestBearing { fee_bps } => {
+                // Validate that the ext mint has the InterestBearingConfig extension and
+                // that the ext mint authority is the rate authority
+                let extensions = get_mint_extensions(&self.ext_mint)?;
+
+                if !extensions.contains(&ExtensionType::InterestBearingConfig) {
+                    return err!(ExtError::InvalidMint);
+                }
+
+                let interest_bearing_config = get_interest_bearing_config(&self.ext_mint)?;
+                if interest_bearing_config.rate_authority
+                    != OptionalNonZeroPubkey(ext_mint_authority)
+                {
+                    return err!(ExtError::InvalidMint);
+                }
+
+                // Validate that no interest has been applied to the mint yet,
+                // so displayed balances start out equal to the ext index of 1.0
+                if i16::from(interest_bearing_config.current_rate) != 0
+                    || i16::from(interest_bearing_config.pre_update_average_rate) != 0
+                {
+                    return err!(ExtError::InvalidMint);
+                }
+
+                // Validate the fee_bps is within the allowed range
+                if *fee_bps > ONE_HUNDRED_PERCENT_U64 {
                     return err!(ExtError::InvalidParam);
                 }
             }
+            YieldMode::WeightedSplit { beneficiaries } => {
+                WeightedSplitConfig::validate_beneficiaries(beneficiaries)?;
+            }
+            _ => {}
         }
 
         Ok(())
     }
 
-    #[access_control(ctx.accounts.validate(fee_bps))]
-    pub fn handler(
-        ctx: Context<Initialize>,
+    #[access_control(ctx.accounts.validate(&wrap_authorities, &yield_mode, namespaced, timelock_delay, require_allowlist))]
+    pub fn handler<'info>(
+        ctx: Context<'_, '_, '_, 'info, Initialize<'info>>,
         wrap_authorities: Vec<Pubkey>,
-        fee_bps: u64,
+        yield_mode: YieldMode,
+        namespaced: bool,
+        timelock_delay: i64,
+        require_allowlist: bool,
     ) -> Result<()> {
-        // Create hash set from wrap_authorities to ensure uniqueness
-        let wrap_auth_set: HashSet<Pubkey> = wrap_authorities.clone().into_iter().collect();
-        if wrap_auth_set.len() < wrap_authorities.len() {
-            return err!(ExtError::InvalidParam);
-        }
-
-        // Create the yield config
-        let yield_config: YieldConfig;
-        cfg_if! {
-            if #[cfg(feature = "scaled-ui")] {
-                yield_config = YieldConfig {
+        // Create the yield config for the chosen mode
+        let m_index = ctx.accounts.m_earn_global_account.index;
+        let yield_config = match yield_mode {
+            YieldMode::NoYield => YieldConfig::NoYield,
+            YieldMode::ScaledUi { fee_bps } => YieldConfig::ScaledUi(ScaledUiConfig {
+                fee_bps,
+                last_m_index: m_index,
+                last_ext_index: INDEX_SCALE_U64, // we set the extension index to 1.0 initially
+            }),
+            YieldMode::YieldToOne { yield_recipient } => {
+                YieldConfig::YieldToOne(YieldToOneConfig { yield_recipient })
+            }
+            YieldMode::EarnerManager { fee_recipient } => {
+                YieldConfig::EarnerManager(EarnerManagerConfig {
+                    fee_recipient,
+                    last_m_index: m_index,
+                    last_ext_index: INDEX_SCALE_U64, // the earner index starts at 1.0
+                    earner_principal: 0,
+                    earner_basis: 0,
+                })
+            }
+            YieldMode::ClaimableYield => YieldConfig::ClaimableYield(ClaimableYieldConfig {
+                last_m_index: m_index,
+                last_ext_index: INDEX_SCALE_U64, // the earner index starts at 1.0
+                earner_principal: 0,
+                earner_basis: 0,
+                earner_pending_yield: 0,
+            }),
+            YieldMode::InterestBearing { fee_bps } => {
+                YieldConfig::InterestBearing(InterestBearingConfig {
                     fee_bps,
-                    last_m_index: ctx.accounts.m_earn_global_account.index,
+                    last_m_index: m_index,
+                    last_m_timestamp: ctx.accounts.m_earn_global_account.timestamp,
                     last_ext_index: INDEX_SCALE_U64, // we set the extension index to 1.0 initially
-                };
-            } else {
-                yield_config = YieldConfig {};
+                })
             }
-        }
+            YieldMode::MerkleDistribution => {
+                YieldConfig::MerkleDistribution(MerkleDistributionConfig {
+                    distribution_count: 0,
+                    locked_amount: 0,
+                })
+            }
+            YieldMode::WeightedSplit { beneficiaries } => {
+                YieldConfig::WeightedSplit(WeightedSplitConfig { beneficiaries })
+            }
+        };
+        let scaled_ui = matches!(yield_config, YieldConfig::ScaledUi(_));
 
         // Initialize the ExtGlobal account
         ctx.accounts.global_account.set_inner(ExtGlobal {
+            version: EXT_GLOBAL_VERSION,
             admin: ctx.accounts.admin.key(),
             ext_mint: ctx.accounts.ext_mint.key(),
             m_mint: ctx.accounts.m_mint.key(),
@@ -181,24 +304,57 @@ impl Initialize<'_> {
             bump: ctx.bumps.global_account,
             m_vault_bump: ctx.bumps.m_vault,
             ext_mint_authority_bump: ctx.bumps.ext_mint_authority,
+            namespaced,
             yield_config,
-            wrap_authorities,
+            pending_admin: None,
+            roles: Roles::default(),
+            timelock_delay: 0,
+            timelock_authority_bump: 0,
+            queued_action_count: 0,
+            paused: 0,
+            require_allowlist,
+            supply_cap: 0,
+            open_wrap: false,
+            open_unwrap: false,
+            winding_down: false,
         });
+        ctx.accounts
+            .global_account
+            .set_timelock_delay(timelock_delay);
+
+        // Create the accounts of the initial wrap authorities with an unlimited quota
+        let wrap_authorities: Vec<(Pubkey, WrapQuota)> = wrap_authorities
+            .into_iter()
+            .map(|authority| (authority, WrapQuota::default()))
+            .collect();
+        create_wrap_authority_accounts(
+            &ctx.accounts.global_account,
+            &wrap_authorities,
+            ctx.remaining_accounts,
+            &ctx.accounts.admin.to_account_info(),
+            &ctx.accounts.system_program,
+        )?;
 
         // Set the ScaledUi multiplier to 1.0
         // We can do this by calling the sync_multiplier function
         // when the last_m_index equals the index on the m_earn_global_account
         // and having last_ext_index set to 1e12
-        #[cfg(feature = "scaled-ui")]
-        sync_multiplier(
-            &mut ctx.accounts.ext_mint,
-            &mut ctx.accounts.global_account,
-            &ctx.accounts.m_earn_global_account,
-            &ctx.accounts.ext_mint_authority,
-            &[&[MINT_AUTHORITY_SEED, &[ctx.bumps.ext_mint_authority]]],
-            &ctx.accounts.ext_token_program,
-            &ctx.accounts.m_earner_account.to_account_info(),
-        )?;
+        if scaled_ui {
+            let ext_mint = ctx.accounts.ext_mint.key();
+            sync_multiplier(
+                &mut ctx.accounts.ext_mint,
+                &mut ctx.accounts.global_account,
+                &ctx.accounts.m_earn_global_account,
+                &ctx.accounts.ext_mint_authority,
+                &[&[
+                    MINT_AUTHORITY_SEED,
+                    ExtGlobal::namespace(namespaced, &ext_mint),
+                    &[ctx.bumps.ext_mint_authority],
+                ]],
+                &ctx.accounts.ext_token_program,
+                &ctx.accounts.m_earner_account.to_account_info(),
+            )?;
+        }
 
         Ok(())
     }
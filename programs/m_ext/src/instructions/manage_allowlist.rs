@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::ExtError,
+    state::{AllowlistEntry, ExtGlobal, Role, ALLOWLIST_SEED, EXT_GLOBAL_SEED},
+};
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct AddToAllowlist<'info> {
+    #[account(mut)]
+    pub compliance_officer: Signer<'info>,
+
+    #[account(
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        constraint = global_account.roles.has(Role::ComplianceOfficer, compliance_officer.key) @ ExtError::NotAuthorized,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    #[account(
+        init,
+        payer = compliance_officer,
+        space = AllowlistEntry::size(),
+        seeds = [ALLOWLIST_SEED, ExtGlobal::seed_namespace(&global_account), wallet.as_ref()],
+        bump,
+    )]
+    pub allowlist_entry: Account<'info, AllowlistEntry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl AddToAllowlist<'_> {
+    // This instruction allows the compliance officer to allowlist a verified wallet.
+    // If the extension requires an allowlist, token accounts of the wallet can then be thawed with approve_holder.
+
+    pub fn handler(ctx: Context<Self>, wallet: Pubkey) -> Result<()> {
+        ctx.accounts.allowlist_entry.set_inner(AllowlistEntry {
+            bump: ctx.bumps.allowlist_entry,
+            wallet,
+        });
+
+        emit!(AddedToAllowlist { wallet });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct RemoveFromAllowlist<'info> {
+    #[account(mut)]
+    pub compliance_officer: Signer<'info>,
+
+    #[account(
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        constraint = global_account.roles.has(Role::ComplianceOfficer, compliance_officer.key) @ ExtError::NotAuthorized,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    #[account(
+        mut,
+        close = compliance_officer,
+        seeds = [ALLOWLIST_SEED, ExtGlobal::seed_namespace(&global_account), allowlist_entry.wallet.as_ref()],
+        bump = allowlist_entry.bump,
+    )]
+    pub allowlist_entry: Account<'info, AllowlistEntry>,
+}
+
+impl RemoveFromAllowlist<'_> {
+    // This instruction allows the compliance officer to remove a wallet from the allowlist.
+    // Token accounts of the wallet can then be frozen again with revoke_holder.
+
+    pub fn handler(ctx: Context<Self>) -> Result<()> {
+        emit!(RemovedFromAllowlist {
+            wallet: ctx.accounts.allowlist_entry.wallet,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct AddedToAllowlist {
+    pub wallet: Pubkey,
+}
+
+#[event]
+pub struct RemovedFromAllowlist {
+    pub wallet: Pubkey,
+}
@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::ExtError,
+    state::{BlocklistEntry, ExtGlobal, Role, BLOCKLIST_SEED, EXT_GLOBAL_SEED},
+};
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct AddToBlocklist<'info> {
+    #[account(mut)]
+    pub compliance_officer: Signer<'info>,
+
+    #[account(
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        constraint = global_account.roles.has(Role::ComplianceOfficer, compliance_officer.key) @ ExtError::NotAuthorized,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    #[account(
+        init,
+        payer = compliance_officer,
+        space = BlocklistEntry::size(),
+        seeds = [BLOCKLIST_SEED, ExtGlobal::seed_namespace(&global_account), wallet.as_ref()],
+        bump,
+    )]
+    pub blocklist_entry: Account<'info, BlocklistEntry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl AddToBlocklist<'_> {
+    // This instruction allows the compliance officer to blocklist a wallet.
+    // Blocklisted wallets can't receive ext tokens from wrap or unwrap their ext tokens.
+
+    pub fn handler(ctx: Context<Self>, wallet: Pubkey) -> Result<()> {
+        ctx.accounts.blocklist_entry.set_inner(BlocklistEntry {
+            bump: ctx.bumps.blocklist_entry,
+            wallet,
+        });
+
+        emit!(AddedToBlocklist { wallet });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct RemoveFromBlocklist<'info> {
+    #[account(mut)]
+    pub compliance_officer: Signer<'info>,
+
+    #[account(
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        constraint = global_account.roles.has(Role::ComplianceOfficer, compliance_officer.key) @ ExtError::NotAuthorized,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    #[account(
+        mut,
+        close = compliance_officer,
+        seeds = [BLOCKLIST_SEED, ExtGlobal::seed_namespace(&global_account), blocklist_entry.wallet.as_ref()],
+        bump = blocklist_entry.bump,
+    )]
+    pub blocklist_entry: Account<'info, BlocklistEntry>,
+}
+
+impl RemoveFromBlocklist<'_> {
+    // This instruction allows the compliance officer to remove a wallet from the blocklist.
+
+    pub fn handler(ctx: Context<Self>) -> Result<()> {
+        emit!(RemovedFromBlocklist {
+            wallet: ctx.accounts.blocklist_entry.wallet,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct AddedToBlocklist {
+    pub wallet: Pubkey,
+}
+
+#[event]
+pub struct RemovedFromBlocklist {
+    pub wallet: Pubkey,
+}
@@ -0,0 +1,255 @@
+// external dependencies
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+use earn::{
+    state::{Global as EarnGlobal, EARNER_SEED},
+    ID as EARN_PROGRAM,
+};
+
+// local dependencies
+use crate::{
+    constants::ONE_HUNDRED_PERCENT_U64,
+    errors::ExtError,
+    instructions::claim_earner_yield::*,
+    state::{ExtGlobal, ManagedEarner, Role, EXT_EARNER_SEED, EXT_GLOBAL_SEED, M_VAULT_SEED},
+    utils::conversion::{amount_to_principal_down, sync_earner_index},
+};
+
+#[derive(Accounts)]
+pub struct AddEarner<'info> {
+    #[account(mut)]
+    pub fee_manager: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        constraint = global_account.roles.has(Role::FeeManager, fee_manager.key) @ ExtError::NotAuthorized,
+        has_one = ext_mint @ ExtError::InvalidMint,
+        has_one = m_earn_global_account @ ExtError::InvalidAccount,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    pub m_earn_global_account: Account<'info, EarnGlobal>,
+
+    #[account(mint::token_program = ext_token_program)]
+    pub ext_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This account is validated by the seed, it stores no data
+    #[account(
+        seeds = [M_VAULT_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump = global_account.m_vault_bump,
+    )]
+    pub m_vault: AccountInfo<'info>,
+
+    #[account(
+        associated_token::mint = global_account.m_mint,
+        associated_token::authority = m_vault,
+        associated_token::token_program = m_token_program,
+    )]
+    pub vault_m_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: We partially validate this account is the correct address
+    /// via the seed, but we delay full validation to the handler
+    /// so we can handle cases where the account has been closed.
+    #[account(
+        seeds = [EARNER_SEED, vault_m_token_account.key().as_ref()],
+        seeds::program = EARN_PROGRAM,
+        bump,
+    )]
+    pub m_earner_account: UncheckedAccount<'info>,
+
+    #[account(
+        token::mint = ext_mint,
+        token::token_program = ext_token_program,
+    )]
+    pub earner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = fee_manager,
+        space = ManagedEarner::size(),
+        seeds = [EXT_EARNER_SEED, ExtGlobal::seed_namespace(&global_account), earner_token_account.key().as_ref()],
+        bump,
+    )]
+    pub earner_account: Account<'info, ManagedEarner>,
+
+    pub m_token_program: Program<'info, Token2022>,
+    pub ext_token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+impl AddEarner<'_> {
+    // This instruction allows the fee manager to approve a token account to earn yield
+    // at the provided fee rate. Yield accrues from the current index onwards.
+    fn validate(&self, fee_bps: u64) -> Result<()> {
+        // Validate that the fee is between 0 and 10000 bps
+        if fee_bps > ONE_HUNDRED_PERCENT_U64 {
+            return err!(ExtError::InvalidParam);
+        }
+
+        Ok(())
+    }
+
+    #[access_control(ctx.accounts.validate(fee_bps))]
+    pub fn handler(ctx: Context<Self>, fee_bps: u64) -> Result<()> {
+        // Sync the earner index so the earner doesn't receive yield from before it was added
+        let index = sync_earner_index(
+            &mut ctx.accounts.global_account,
+            &ctx.accounts.m_earn_global_account,
+            &ctx.accounts.m_earner_account,
+        )?;
+
+        ctx.accounts.earner_account.set_inner(ManagedEarner {
+            bump: ctx.bumps.earner_account,
+            token_account: ctx.accounts.earner_token_account.key(),
+            fee_bps,
+            principal: amount_to_principal_down(ctx.accounts.earner_token_account.amount, index)?,
+            last_index: index,
+        });
+
+        // Add the earner to the totals
+        let principal = ctx.accounts.earner_account.principal;
+        let basis = ctx.accounts.earner_account.basis()?;
+
+        let yield_config = ctx
+            .accounts
+            .global_account
+            .yield_config
+            .earner_manager_mut()?;
+        yield_config.earner_principal = yield_config
+            .earner_principal
+            .checked_add(principal)
+            .ok_or(ExtError::MathOverflow)?;
+        yield_config.earner_basis = yield_config
+            .earner_basis
+            .checked_add(basis)
+            .ok_or(ExtError::MathOverflow)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct RemoveEarner<'info> {
+    #[account(
+        mut,
+        constraint = claim.global_account.roles.has(Role::FeeManager, fee_manager.key) @ ExtError::NotAuthorized,
+    )]
+    pub fee_manager: Signer<'info>,
+
+    pub claim: ClaimEarnerYield<'info>,
+}
+
+impl RemoveEarner<'_> {
+    // This instruction allows the fee manager to stop a token account from earning yield.
+    // The yield accrued up to now is paid out first, and the rent of the earner account
+    // is refunded to the fee manager.
+    pub fn handler(ctx: Context<Self>) -> Result<()> {
+        // Settle the yield owed to the earner so it isn't forfeited
+        ctx.accounts.claim.settle()?;
+
+        let principal = ctx.accounts.claim.earner_account.principal;
+        let basis = ctx.accounts.claim.earner_account.basis()?;
+
+        // Remove the earner from the totals
+        let yield_config = ctx
+            .accounts
+            .claim
+            .global_account
+            .yield_config
+            .earner_manager_mut()?;
+        yield_config.earner_principal = yield_config
+            .earner_principal
+            .checked_sub(principal)
+            .ok_or(ExtError::MathUnderflow)?;
+        yield_config.earner_basis = yield_config
+            .earner_basis
+            .checked_sub(basis)
+            .ok_or(ExtError::MathUnderflow)?;
+
+        ctx.accounts
+            .claim
+            .earner_account
+            .close(ctx.accounts.fee_manager.to_account_info())
+    }
+}
+
+#[derive(Accounts)]
+pub struct SetEarnerFee<'info> {
+    // The timelock authority when executing a queued change
+    #[account(
+        constraint = claim.global_account.is_timelocked_signer(
+            fee_manager.key,
+            claim.global_account.roles.has(Role::FeeManager, fee_manager.key),
+        ) @ ExtError::NotAuthorized,
+    )]
+    pub fee_manager: Signer<'info>,
+
+    pub claim: ClaimEarnerYield<'info>,
+}
+
+impl SetEarnerFee<'_> {
+    // This instruction allows the fee manager to change the fee rate of an earner.
+    // The fee must be between 0 and 10000 bps (inclusive).
+    fn validate(&self, fee_bps: u64) -> Result<()> {
+        // Validate that the fee is between 0 and 10000 bps
+        if fee_bps > ONE_HUNDRED_PERCENT_U64 {
+            return err!(ExtError::InvalidParam);
+        }
+
+        Ok(())
+    }
+
+    #[access_control(ctx.accounts.validate(fee_bps))]
+    pub fn handler(ctx: Context<Self>, fee_bps: u64) -> Result<()> {
+        // Settle the yield accrued at the old fee before changing it
+        ctx.accounts.claim.settle()?;
+
+        // Set the new fee
+        ctx.accounts.claim.earner_account.fee_bps = fee_bps;
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct SetFeeRecipient<'info> {
+    pub fee_claimer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        constraint = global_account.roles.has(Role::FeeClaimer, fee_claimer.key) @ ExtError::NotAuthorized,
+        has_one = ext_mint @ ExtError::InvalidMint,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    #[account(mint::token_program = ext_token_program)]
+    pub ext_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: The authority of this token account is not checked
+    /// so the fees can be directed to any ext token account
+    #[account(
+        token::mint = ext_mint,
+        token::token_program = ext_token_program,
+    )]
+    pub new_fee_recipient: InterfaceAccount<'info, TokenAccount>,
+
+    pub ext_token_program: Program<'info, Token2022>,
+}
+
+impl SetFeeRecipient<'_> {
+    // This instruction allows the fee claimer to change the ext token account that receives earner fees,
+    // like it chooses the recipient of claim_fees in the other modes.
+    pub fn handler(ctx: Context<Self>) -> Result<()> {
+        ctx.accounts
+            .global_account
+            .yield_config
+            .earner_manager_mut()?
+            .fee_recipient = ctx.accounts.new_fee_recipient.key();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,179 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+use crate::{
+    errors::ExtError,
+    state::{
+        AllowlistEntry, ExtGlobal, ALLOWLIST_SEED, COMPLIANCE_FREEZE_SEED, EXT_GLOBAL_SEED,
+        FREEZE_AUTHORITY_SEED,
+    },
+    utils::token::{freeze_token_account, thaw_token_account},
+};
+
+#[derive(Accounts)]
+pub struct ApproveHolder<'info> {
+    #[account(
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        constraint = global_account.require_allowlist @ ExtError::NotActive,
+        has_one = ext_mint @ ExtError::InvalidMint,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    #[account(
+        mint::token_program = ext_token_program,
+        constraint = ext_mint.freeze_authority == Some(freeze_authority.key()).into() @ ExtError::InvalidMint,
+    )]
+    pub ext_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This account is validated by the seed, it stores no data
+    #[account(
+        seeds = [FREEZE_AUTHORITY_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump,
+    )]
+    pub freeze_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        token::mint = ext_mint,
+        token::token_program = ext_token_program,
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [ALLOWLIST_SEED, ExtGlobal::seed_namespace(&global_account), token_account.owner.as_ref()],
+        bump = allowlist_entry.bump,
+    )]
+    pub allowlist_entry: Account<'info, AllowlistEntry>,
+
+    /// CHECK: This account is validated by the seed, it only exists if the compliance officer froze the token account
+    #[account(
+        seeds = [COMPLIANCE_FREEZE_SEED, ExtGlobal::seed_namespace(&global_account), token_account.key().as_ref()],
+        bump,
+    )]
+    pub compliance_freeze: UncheckedAccount<'info>,
+
+    pub ext_token_program: Program<'info, Token2022>,
+}
+
+impl ApproveHolder<'_> {
+    fn validate(&self) -> Result<()> {
+        // Only the compliance officer can thaw token accounts it froze
+        if self.compliance_freeze.owner == &crate::ID {
+            return err!(ExtError::FrozenByComplianceOfficer);
+        }
+
+        Ok(())
+    }
+
+    // This instruction thaws a token account of an allowlisted wallet, anyone can call it.
+    // Token accounts of the ext mint start frozen when the extension requires an allowlist,
+    // so only allowlisted wallets can hold ext tokens.
+    #[access_control(ctx.accounts.validate())]
+    pub fn handler(ctx: Context<Self>) -> Result<()> {
+        thaw_token_account(
+            &ctx.accounts.token_account,
+            &ctx.accounts.ext_mint,
+            &ctx.accounts.freeze_authority,
+            &[&[
+                FREEZE_AUTHORITY_SEED,
+                ctx.accounts.global_account.seed_namespace(),
+                &[ctx.bumps.freeze_authority],
+            ]],
+            &ctx.accounts.ext_token_program,
+        )?;
+
+        emit!(HolderApproved {
+            token_account: ctx.accounts.token_account.key(),
+            owner: ctx.accounts.token_account.owner,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct RevokeHolder<'info> {
+    #[account(
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        constraint = global_account.require_allowlist @ ExtError::NotActive,
+        has_one = ext_mint @ ExtError::InvalidMint,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    #[account(
+        mint::token_program = ext_token_program,
+        constraint = ext_mint.freeze_authority == Some(freeze_authority.key()).into() @ ExtError::InvalidMint,
+    )]
+    pub ext_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This account is validated by the seed, it stores no data
+    #[account(
+        seeds = [FREEZE_AUTHORITY_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump,
+    )]
+    pub freeze_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        token::mint = ext_mint,
+        token::token_program = ext_token_program,
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: This account is validated by the seed, it only exists if the owner is allowlisted
+    #[account(
+        seeds = [ALLOWLIST_SEED, ExtGlobal::seed_namespace(&global_account), token_account.owner.as_ref()],
+        bump,
+    )]
+    pub allowlist_entry: UncheckedAccount<'info>,
+
+    pub ext_token_program: Program<'info, Token2022>,
+}
+
+impl RevokeHolder<'_> {
+    fn validate(&self) -> Result<()> {
+        // Only token accounts of wallets removed from the allowlist can be frozen again
+        if self.allowlist_entry.owner == &crate::ID {
+            return err!(ExtError::Active);
+        }
+
+        Ok(())
+    }
+
+    // This instruction freezes a token account of a wallet that is not allowlisted, anyone can call it.
+    #[access_control(ctx.accounts.validate())]
+    pub fn handler(ctx: Context<Self>) -> Result<()> {
+        freeze_token_account(
+            &ctx.accounts.token_account,
+            &ctx.accounts.ext_mint,
+            &ctx.accounts.freeze_authority,
+            &[&[
+                FREEZE_AUTHORITY_SEED,
+                ctx.accounts.global_account.seed_namespace(),
+                &[ctx.bumps.freeze_authority],
+            ]],
+            &ctx.accounts.ext_token_program,
+        )?;
+
+        emit!(HolderRevoked {
+            token_account: ctx.accounts.token_account.key(),
+            owner: ctx.accounts.token_account.owner,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct HolderApproved {
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct HolderRevoked {
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+}
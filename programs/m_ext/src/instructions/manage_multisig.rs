@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+use std::collections::HashSet;
+
+use crate::{
+    constants::MAX_MULTISIG_SIGNERS,
+    errors::ExtError,
+    state::{ExtGlobal, Multisig, EXT_GLOBAL_SEED, MULTISIG_AUTHORITY_SEED, MULTISIG_SEED},
+};
+
+fn validate_signers(signers: &[Pubkey], threshold: u8) -> Result<()> {
+    if signers.is_empty() || signers.len() > MAX_MULTISIG_SIGNERS {
+        return err!(ExtError::InvalidParam);
+    }
+
+    if threshold == 0 || threshold as usize > signers.len() {
+        return err!(ExtError::InvalidParam);
+    }
+
+    // Duplicate signers would count twice towards the threshold
+    if signers.iter().collect::<HashSet<_>>().len() != signers.len() {
+        return err!(ExtError::InvalidParam);
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateMultisig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        has_one = admin @ ExtError::NotAuthorized,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = Multisig::size(),
+        seeds = [MULTISIG_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump,
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    /// CHECK: This account is validated by the seed, it stores no data
+    #[account(
+        seeds = [MULTISIG_AUTHORITY_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump,
+    )]
+    pub multisig_authority: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl CreateMultisig<'_> {
+    // This instruction allows the admin to set up M-of-N approval of admin actions.
+    // Actions are only subject to approval once their roles are granted to the multisig authority.
+
+    #[access_control(validate_signers(&signers, threshold))]
+    pub fn handler(ctx: Context<Self>, signers: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        ctx.accounts.multisig.set_inner(Multisig {
+            bump: ctx.bumps.multisig,
+            authority_bump: ctx.bumps.multisig_authority,
+            threshold,
+            proposal_count: 0,
+            signers: signers.clone(),
+        });
+
+        emit!(MultisigUpdated { signers, threshold });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct UpdateMultisig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        has_one = admin @ ExtError::NotAuthorized,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    #[account(
+        mut,
+        seeds = [MULTISIG_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump = multisig.bump,
+    )]
+    pub multisig: Account<'info, Multisig>,
+}
+
+impl UpdateMultisig<'_> {
+    // This instruction allows the admin to replace the signers and threshold of the multisig.
+    // Approvals of pending proposals by removed signers no longer count.
+
+    #[access_control(validate_signers(&signers, threshold))]
+    pub fn handler(ctx: Context<Self>, signers: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        multisig.signers = signers.clone();
+        multisig.threshold = threshold;
+
+        emit!(MultisigUpdated { signers, threshold });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct MultisigUpdated {
+    pub signers: Vec<Pubkey>,
+    pub threshold: u8,
+}
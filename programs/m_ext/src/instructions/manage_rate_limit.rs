@@ -0,0 +1,159 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::MAX_RATE_LIMIT_WINDOWS,
+    errors::ExtError,
+    state::{ExtGlobal, RateLimit, RateLimitWindow, EXT_GLOBAL_SEED, RATE_LIMIT_SEED},
+};
+
+fn validate_windows(
+    wrap_windows: &[RateLimitWindow],
+    unwrap_windows: &[RateLimitWindow],
+) -> Result<()> {
+    for windows in [wrap_windows, unwrap_windows] {
+        if windows.len() > MAX_RATE_LIMIT_WINDOWS {
+            return err!(ExtError::InvalidParam);
+        }
+
+        if windows
+            .iter()
+            .any(|window| window.capacity == 0 || window.duration <= 0)
+        {
+            return err!(ExtError::InvalidParam);
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateRateLimit<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        has_one = admin @ ExtError::NotAuthorized,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = RateLimit::size(),
+        seeds = [RATE_LIMIT_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump,
+    )]
+    pub rate_limit: Account<'info, RateLimit>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl CreateRateLimit<'_> {
+    // This instruction allows the admin to limit the net volume of M that can be wrapped
+    // and unwrapped over rolling windows, for example per hour and per day.
+    // The buckets start full, so the whole capacity of each window is available right away.
+
+    #[access_control(validate_windows(&wrap_windows, &unwrap_windows))]
+    pub fn handler(
+        ctx: Context<Self>,
+        wrap_windows: Vec<RateLimitWindow>,
+        unwrap_windows: Vec<RateLimitWindow>,
+    ) -> Result<()> {
+        let rate_limit = &mut ctx.accounts.rate_limit;
+        rate_limit.bump = ctx.bumps.rate_limit;
+        rate_limit.set_windows(&wrap_windows, &unwrap_windows, Clock::get()?.unix_timestamp);
+
+        emit!(RateLimitUpdated {
+            wrap_windows,
+            unwrap_windows,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct UpdateRateLimit<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        has_one = admin @ ExtError::NotAuthorized,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    #[account(
+        mut,
+        seeds = [RATE_LIMIT_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump = rate_limit.bump,
+    )]
+    pub rate_limit: Account<'info, RateLimit>,
+}
+
+impl UpdateRateLimit<'_> {
+    // This instruction allows the admin to replace the rate limit windows.
+    // Volume already used in a window is kept, so raising a limit doesn't refill its bucket.
+
+    #[access_control(validate_windows(&wrap_windows, &unwrap_windows))]
+    pub fn handler(
+        ctx: Context<Self>,
+        wrap_windows: Vec<RateLimitWindow>,
+        unwrap_windows: Vec<RateLimitWindow>,
+    ) -> Result<()> {
+        ctx.accounts.rate_limit.set_windows(
+            &wrap_windows,
+            &unwrap_windows,
+            Clock::get()?.unix_timestamp,
+        );
+
+        emit!(RateLimitUpdated {
+            wrap_windows,
+            unwrap_windows,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct CloseRateLimit<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        has_one = admin @ ExtError::NotAuthorized,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [RATE_LIMIT_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump = rate_limit.bump,
+    )]
+    pub rate_limit: Account<'info, RateLimit>,
+}
+
+impl CloseRateLimit<'_> {
+    // This instruction allows the admin to remove the rate limits of the extension.
+
+    pub fn handler(_ctx: Context<Self>) -> Result<()> {
+        emit!(RateLimitUpdated {
+            wrap_windows: vec![],
+            unwrap_windows: vec![],
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct RateLimitUpdated {
+    pub wrap_windows: Vec<RateLimitWindow>,
+    pub unwrap_windows: Vec<RateLimitWindow>,
+}
@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::ExtError,
+    state::{ExtGlobal, Role, EXT_GLOBAL_SEED},
+};
+
+#[derive(Accounts)]
+pub struct GrantRole<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        has_one = admin @ ExtError::NotAuthorized,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+}
+
+impl GrantRole<'_> {
+    // This instruction allows the admin to grant a role to an account, replacing the current holder.
+    // Roles can't be granted to the admin, so the admin key only manages who holds each role.
+
+    pub fn validate(&self, account: Pubkey) -> Result<()> {
+        if account == self.admin.key()
+            || self.global_account.pending_admin == Some(account)
+            || account == Pubkey::default()
+        {
+            return err!(ExtError::InvalidParam);
+        }
+
+        Ok(())
+    }
+
+    #[access_control(ctx.accounts.validate(account))]
+    pub fn handler(ctx: Context<Self>, role: Role, account: Pubkey) -> Result<()> {
+        *ctx.accounts.global_account.roles.get_mut(role) = account;
+
+        emit!(RoleGranted { role, account });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct RevokeRole<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        has_one = admin @ ExtError::NotAuthorized,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+}
+
+impl RevokeRole<'_> {
+    // This instruction allows the admin to revoke a role, leaving it unassigned.
+
+    pub fn handler(ctx: Context<Self>, role: Role) -> Result<()> {
+        let holder = ctx.accounts.global_account.roles.get_mut(role);
+
+        if *holder == Pubkey::default() {
+            return err!(ExtError::NotActive);
+        }
+
+        let account = std::mem::take(holder);
+
+        emit!(RoleRevoked { role, account });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct RoleGranted {
+    pub role: Role,
+    pub account: Pubkey,
+}
+
+#[event]
+pub struct RoleRevoked {
+    pub role: Role,
+    pub account: Pubkey,
+}
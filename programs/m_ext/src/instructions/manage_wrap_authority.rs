@@ -1,120 +1,234 @@
 use anchor_lang::prelude::*;
 
 use crate::{
+    constants::MAX_WRAP_AUTHORITY_LABEL_LEN,
     errors::ExtError,
-    state::{ExtGlobal, EXT_GLOBAL_SEED},
+    state::{
+        ExtGlobal, Role, WrapAuthority, WrapQuota, EXT_GLOBAL_SEED, PERMISSION_ALL,
+        WRAP_AUTHORITY_SEED,
+    },
+    utils::account::create_pda_account,
 };
 
 #[derive(Accounts)]
+#[instruction(new_wrap_authority: Pubkey)]
 pub struct AddWrapAuthority<'info> {
+    // The timelock authority when executing a queued change
     #[account(mut)]
-    pub admin: Signer<'info>,
+    pub wrap_authority_manager: Signer<'info>,
 
     #[account(
-        mut,
-        seeds = [EXT_GLOBAL_SEED],
-        has_one = admin @ ExtError::NotAuthorized,
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        constraint = global_account.is_timelocked_signer(
+            wrap_authority_manager.key,
+            global_account.roles.has(Role::WrapAuthorityManager, wrap_authority_manager.key),
+        ) @ ExtError::NotAuthorized,
         bump = global_account.bump,
-        realloc = ExtGlobal::size(global_account.wrap_authorities.len() + 1),
-        realloc::payer = admin,
-        realloc::zero = false,
     )]
     pub global_account: Account<'info, ExtGlobal>,
 
+    #[account(
+        init,
+        payer = wrap_authority_manager,
+        space = WrapAuthority::size(),
+        seeds = [WRAP_AUTHORITY_SEED, ExtGlobal::seed_namespace(&global_account), new_wrap_authority.as_ref()],
+        bump,
+    )]
+    pub wrap_authority_account: Account<'info, WrapAuthority>,
+
     pub system_program: Program<'info, System>,
 }
 
 impl AddWrapAuthority<'_> {
-    // This instruction allows the admin to add a wrap authority to the global account.
-    // The new wrap authority must not already exist in the list.
-
-    pub fn validate(&self, new_wrap_authority: Pubkey) -> Result<()> {
-        // Validate that the new wrap authority is not already in the list
-        if self
-            .global_account
-            .wrap_authorities
-            .contains(&new_wrap_authority)
-        {
-            return err!(ExtError::InvalidParam);
-        }
+    // This instruction allows the wrap authority manager to add a wrap authority.
+    // The wrap authority is stored in its own account, so adding it fails if it already exists.
+    // It starts enabled with an unlimited quota, no label, no expiry and permission to wrap and unwrap.
 
-        Ok(())
-    }
-
-    #[access_control(ctx.accounts.validate(new_wrap_authority))]
     pub fn handler(ctx: Context<Self>, new_wrap_authority: Pubkey) -> Result<()> {
-        // Add the wrap authority to the list
         ctx.accounts
-            .global_account
-            .wrap_authorities
-            .push(new_wrap_authority);
+            .wrap_authority_account
+            .set_inner(WrapAuthority {
+                bump: ctx.bumps.wrap_authority_account,
+                authority: new_wrap_authority,
+                label: String::new(),
+                created_at: Clock::get()?.unix_timestamp,
+                enabled: true,
+                quota: WrapQuota::default(),
+                permissions: PERMISSION_ALL,
+                expires_at: 0,
+            });
+
+        emit!(WrapAuthorityAdded {
+            wrap_authority: new_wrap_authority,
+        });
 
         Ok(())
     }
 }
 
 #[derive(Accounts)]
+#[instruction(wrap_authority: Pubkey)]
 pub struct RemoveWrapAuthority<'info> {
     #[account(mut)]
-    pub admin: Signer<'info>,
+    pub wrap_authority_manager: Signer<'info>,
 
     #[account(
-        mut,
-        seeds = [EXT_GLOBAL_SEED],
-        has_one = admin @ ExtError::NotAuthorized,
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        constraint = global_account.roles.has(Role::WrapAuthorityManager, wrap_authority_manager.key) @ ExtError::NotAuthorized,
         bump = global_account.bump,
     )]
     pub global_account: Account<'info, ExtGlobal>,
 
+    #[account(
+        mut,
+        close = wrap_authority_manager,
+        seeds = [WRAP_AUTHORITY_SEED, ExtGlobal::seed_namespace(&global_account), wrap_authority.as_ref()],
+        bump = wrap_authority_account.bump,
+    )]
+    pub wrap_authority_account: Account<'info, WrapAuthority>,
+
     pub system_program: Program<'info, System>,
 }
 
 impl RemoveWrapAuthority<'_> {
-    // This instruction allows the admin to remove a wrap authority from the global account.
-    // The wrap authority must exist in the list.
-
-    pub fn validate(&self, wrap_authority: Pubkey) -> Result<()> {
-        // Validate that the wrap authority exists in the list
-        if !self
-            .global_account
-            .wrap_authorities
-            .contains(&wrap_authority)
-        {
+    // This instruction allows the wrap authority manager to remove a wrap authority.
+    // The account of the wrap authority is closed and the rent is refunded to the wrap authority manager.
+
+    pub fn handler(_ctx: Context<Self>, wrap_authority: Pubkey) -> Result<()> {
+        emit!(WrapAuthorityRemoved { wrap_authority });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(wrap_authority: Pubkey)]
+pub struct UpdateWrapAuthority<'info> {
+    pub wrap_authority_manager: Signer<'info>,
+
+    #[account(
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        constraint = global_account.roles.has(Role::WrapAuthorityManager, wrap_authority_manager.key) @ ExtError::NotAuthorized,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    #[account(
+        mut,
+        seeds = [WRAP_AUTHORITY_SEED, ExtGlobal::seed_namespace(&global_account), wrap_authority.as_ref()],
+        bump = wrap_authority_account.bump,
+    )]
+    pub wrap_authority_account: Account<'info, WrapAuthority>,
+}
+
+impl UpdateWrapAuthority<'_> {
+    // This instruction allows the wrap authority manager to set the label of a wrap authority
+    // and to enable or disable it without losing its quota.
+    // While the timelock is enabled, a disabled wrap authority can't be enabled again directly,
+    // it has to be removed and added back through the queue.
+
+    pub fn validate(&self, label: &str, enabled: bool) -> Result<()> {
+        if label.len() > MAX_WRAP_AUTHORITY_LABEL_LEN {
             return err!(ExtError::InvalidParam);
         }
 
+        if enabled
+            && !self.wrap_authority_account.enabled
+            && self.global_account.timelock_authority().is_some()
+        {
+            return err!(ExtError::NotAuthorized);
+        }
+
         Ok(())
     }
 
-    #[access_control(ctx.accounts.validate(wrap_authority))]
-    pub fn handler(ctx: Context<Self>, wrap_authority: Pubkey) -> Result<()> {
-        // Remove the specified wrap authority
-        ctx.accounts
-            .global_account
-            .wrap_authorities
-            .retain(|&x| !x.eq(&wrap_authority));
+    #[access_control(ctx.accounts.validate(&label, enabled))]
+    pub fn handler(
+        ctx: Context<Self>,
+        wrap_authority: Pubkey,
+        label: String,
+        enabled: bool,
+    ) -> Result<()> {
+        let account = &mut ctx.accounts.wrap_authority_account;
+        account.label = label.clone();
+        account.enabled = enabled;
+
+        emit!(WrapAuthorityUpdated {
+            wrap_authority,
+            label,
+            enabled,
+        });
 
-        // Reallocate the account to remove the empty space without erasing the other data
-        let new_size = ExtGlobal::size(ctx.accounts.global_account.wrap_authorities.len());
-        ctx.accounts
-            .global_account
-            .to_account_info()
-            .realloc(new_size, false)?;
-
-        // Refund excess lamports to the admin
-        let current_lamports = ctx.accounts.global_account.to_account_info().lamports();
-        let required_lamports = Rent::get()?.minimum_balance(new_size);
-        let excess_lamports = current_lamports.saturating_sub(required_lamports);
-        if excess_lamports > 0 {
-            **ctx
-                .accounts
-                .global_account
-                .to_account_info()
-                .lamports
-                .borrow_mut() -= excess_lamports;
-            **ctx.accounts.admin.to_account_info().lamports.borrow_mut() += excess_lamports;
+        Ok(())
+    }
+}
+
+// Creates the accounts of wrap authorities that are not added with add_wrap_authority,
+// the accounts are passed in the same order as the wrap authorities and paid by the payer
+pub fn create_wrap_authority_accounts<'info>(
+    global_account: &ExtGlobal,
+    wrap_authorities: &[(Pubkey, WrapQuota)],
+    accounts: &[AccountInfo<'info>],
+    payer: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    if accounts.len() != wrap_authorities.len() {
+        return err!(ExtError::InvalidAccount);
+    }
+
+    let created_at = Clock::get()?.unix_timestamp;
+    let namespace = global_account.seed_namespace();
+
+    for ((authority, quota), account) in wrap_authorities.iter().zip(accounts) {
+        let (address, bump) = Pubkey::find_program_address(
+            &[WRAP_AUTHORITY_SEED, namespace, authority.as_ref()],
+            &crate::ID,
+        );
+        if account.key() != address {
+            return err!(ExtError::InvalidAccount);
         }
 
-        Ok(())
+        create_pda_account(
+            payer,
+            account,
+            WrapAuthority::size(),
+            &[&[WRAP_AUTHORITY_SEED, namespace, authority.as_ref(), &[bump]]],
+            system_program,
+        )?;
+
+        WrapAuthority {
+            bump,
+            authority: *authority,
+            label: String::new(),
+            created_at,
+            enabled: true,
+            quota: *quota,
+            permissions: PERMISSION_ALL,
+            expires_at: 0,
+        }
+        .try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+
+        emit!(WrapAuthorityAdded {
+            wrap_authority: *authority,
+        });
     }
+
+    Ok(())
+}
+
+#[event]
+pub struct WrapAuthorityAdded {
+    pub wrap_authority: Pubkey,
+}
+
+#[event]
+pub struct WrapAuthorityRemoved {
+    pub wrap_authority: Pubkey,
+}
+
+#[event]
+pub struct WrapAuthorityUpdated {
+    pub wrap_authority: Pubkey,
+    pub label: String,
+    pub enabled: bool,
 }
@@ -0,0 +1,265 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+    Discriminator,
+};
+
+use crate::{
+    errors::ExtError,
+    instructions::create_wrap_authority_accounts,
+    state::{ExtGlobal, Roles, ScaledUiConfig, WrapQuota, YieldConfig, EXT_GLOBAL_VERSION},
+};
+
+// Wrap authorities kept in the global account before version 10, with their quota
+type LegacyWrapAuthorities = Vec<(Pubkey, WrapQuota)>;
+
+// Layout of the global account before it was versioned, the yield config
+// was either empty (no-yield) or a ScaledUiConfig depending on the build
+#[derive(AnchorDeserialize)]
+struct LegacyExtGlobalHeader {
+    admin: Pubkey,
+    ext_mint: Pubkey,
+    m_mint: Pubkey,
+    m_earn_global_account: Pubkey,
+    bump: u8,
+    m_vault_bump: u8,
+    ext_mint_authority_bump: u8,
+}
+
+const LEGACY_HEADER_SIZE: usize = 8 + 4 * 32 + 3;
+const LEGACY_SCALED_UI_SIZE: usize = 3 * 8;
+
+#[derive(Accounts)]
+pub struct MigrateGlobal<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(mut, owner = crate::ID)]
+    /// CHECK: decoded manually since older layouts can't be deserialized as ExtGlobal
+    pub global_account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl MigrateGlobal<'_> {
+    // This instruction allows the admin to upgrade the global account to the latest layout.
+    // The account is resized to fit the current layout and the admin pays any extra rent.
+    // Wrap authorities stored in the global account by older layouts are moved to their own
+    // accounts with their quota, the accounts are passed as remaining accounts in the same order.
+
+    fn decode(&self) -> Result<(u8, ExtGlobal, LegacyWrapAuthorities)> {
+        let data = self.global_account.try_borrow_data()?;
+
+        if data.len() < LEGACY_HEADER_SIZE || data[..8] != *ExtGlobal::DISCRIMINATOR {
+            return err!(ExtError::InvalidAccount);
+        }
+
+        // Legacy accounts start with the admin, versioned accounts start with the version
+        if data[8..40] == self.admin.key().to_bytes() {
+            let (global, wrap_authorities) = Self::decode_legacy(&data)?;
+            return Ok((0, global, wrap_authorities));
+        }
+
+        // Versioned accounts have the admin right after the version
+        if data[9..41] != self.admin.key().to_bytes() {
+            return err!(ExtError::NotAuthorized);
+        }
+
+        let version = data[8];
+        if version == EXT_GLOBAL_VERSION {
+            return err!(ExtError::AlreadyMigrated);
+        }
+        if version > EXT_GLOBAL_VERSION {
+            return err!(ExtError::InvalidAccount);
+        }
+
+        let (global, wrap_authorities) = Self::decode_versioned(version, &data)?;
+        Ok((version, global, wrap_authorities))
+    }
+
+    fn decode_versioned(version: u8, data: &[u8]) -> Result<(ExtGlobal, LegacyWrapAuthorities)> {
+        // Each version appended fields to the end of the layout, so only the fields
+        // of the stored version are read and the rest keep their defaults. Bytes past
+        // the stored layout are never read since they can be left over from a longer
+        // serialization, e.g. a wrap authority that was removed without shrinking.
+        let buf = &mut &data[8..];
+        let mut global = ExtGlobal {
+            version: u8::deserialize(buf)?,
+            admin: Pubkey::deserialize(buf)?,
+            ext_mint: Pubkey::deserialize(buf)?,
+            m_mint: Pubkey::deserialize(buf)?,
+            m_earn_global_account: Pubkey::deserialize(buf)?,
+            bump: u8::deserialize(buf)?,
+            m_vault_bump: u8::deserialize(buf)?,
+            ext_mint_authority_bump: u8::deserialize(buf)?,
+            namespaced: bool::deserialize(buf)?,
+            yield_config: YieldConfig::deserialize(buf)?,
+            pending_admin: None,
+            roles: Roles::default(),
+            timelock_delay: 0,
+            timelock_authority_bump: 0,
+            queued_action_count: 0,
+            paused: 0,
+            require_allowlist: false,
+            supply_cap: 0,
+            open_wrap: false,
+            open_unwrap: false,
+            winding_down: false,
+        };
+
+        // Versions before 10 kept the legacy wrap authorities after the yield config
+        // and, from version 7, their quotas after the supply cap
+        let wrap_authorities = if version < 10 {
+            Vec::<Pubkey>::deserialize(buf)?
+        } else {
+            vec![]
+        };
+        let mut wrap_quotas = vec![];
+
+        if version >= 2 {
+            global.pending_admin = Option::<Pubkey>::deserialize(buf)?;
+        }
+        if version >= 3 {
+            global.roles = Roles::deserialize(buf)?;
+        }
+        if version >= 4 {
+            global.timelock_delay = i64::deserialize(buf)?;
+            global.timelock_authority_bump = u8::deserialize(buf)?;
+            global.queued_action_count = u64::deserialize(buf)?;
+        }
+        if version >= 5 {
+            global.paused = u8::deserialize(buf)?;
+        }
+        if version >= 6 {
+            global.require_allowlist = bool::deserialize(buf)?;
+        }
+        if version >= 7 {
+            global.supply_cap = u64::deserialize(buf)?;
+        }
+        if (7..10).contains(&version) {
+            wrap_quotas = Vec::<WrapQuota>::deserialize(buf)?;
+        }
+        if version >= 8 {
+            global.open_wrap = bool::deserialize(buf)?;
+            global.open_unwrap = bool::deserialize(buf)?;
+        }
+        if version >= 9 {
+            global.winding_down = bool::deserialize(buf)?;
+        }
+
+        // Wrap authorities added before quotas existed get an unlimited quota
+        wrap_quotas.resize(wrap_authorities.len(), WrapQuota::default());
+
+        Ok((
+            global,
+            wrap_authorities.into_iter().zip(wrap_quotas).collect(),
+        ))
+    }
+
+    fn decode_legacy(data: &[u8]) -> Result<(ExtGlobal, LegacyWrapAuthorities)> {
+        // Accounts were always sized exactly, so the variant is found by
+        // checking which yield config size is consistent with the vector length
+        let wrap_authorities_len = |offset: usize| -> Option<usize> {
+            let len = data.get(offset..offset + 4)?;
+            let len = u32::from_le_bytes(len.try_into().ok()?) as usize;
+            (data.len() == offset + 4 + len * 32).then_some(len)
+        };
+
+        let scaled_ui = if wrap_authorities_len(LEGACY_HEADER_SIZE).is_some() {
+            false
+        } else if wrap_authorities_len(LEGACY_HEADER_SIZE + LEGACY_SCALED_UI_SIZE).is_some() {
+            true
+        } else {
+            return err!(ExtError::InvalidAccount);
+        };
+
+        let buf = &mut &data[8..];
+        let header = LegacyExtGlobalHeader::deserialize(buf)?;
+        let yield_config = if scaled_ui {
+            YieldConfig::ScaledUi(ScaledUiConfig::deserialize(buf)?)
+        } else {
+            YieldConfig::NoYield
+        };
+        let wrap_authorities = Vec::<Pubkey>::deserialize(buf)?;
+
+        let global = ExtGlobal {
+            version: EXT_GLOBAL_VERSION,
+            admin: header.admin,
+            ext_mint: header.ext_mint,
+            m_mint: header.m_mint,
+            m_earn_global_account: header.m_earn_global_account,
+            bump: header.bump,
+            m_vault_bump: header.m_vault_bump,
+            ext_mint_authority_bump: header.ext_mint_authority_bump,
+            namespaced: false,
+            yield_config,
+            pending_admin: None,
+            roles: Roles::default(),
+            timelock_delay: 0,
+            timelock_authority_bump: 0,
+            queued_action_count: 0,
+            paused: 0,
+            require_allowlist: false,
+            supply_cap: 0,
+            open_wrap: false,
+            open_unwrap: false,
+            winding_down: false,
+        };
+
+        // Quotas didn't exist yet, so every wrap authority gets an unlimited quota
+        let wrap_authorities = wrap_authorities
+            .into_iter()
+            .map(|authority| (authority, WrapQuota::default()))
+            .collect();
+
+        Ok((global, wrap_authorities))
+    }
+
+    pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, MigrateGlobal<'info>>) -> Result<()> {
+        let (old_version, mut global, wrap_authorities) = ctx.accounts.decode()?;
+        global.version = EXT_GLOBAL_VERSION;
+
+        // Resize the account to the current layout and top up rent
+        let global_info = ctx.accounts.global_account.to_account_info();
+        let new_size = ExtGlobal::size();
+        let rent = Rent::get()?.minimum_balance(new_size);
+
+        if rent > global_info.lamports() {
+            transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.admin.to_account_info(),
+                        to: global_info.clone(),
+                    },
+                ),
+                rent - global_info.lamports(),
+            )?;
+        }
+
+        global_info.resize(new_size)?;
+        global.try_serialize(&mut &mut global_info.try_borrow_mut_data()?[..])?;
+
+        // Move the legacy wrap authorities to their own accounts
+        create_wrap_authority_accounts(
+            &global,
+            &wrap_authorities,
+            ctx.remaining_accounts,
+            &ctx.accounts.admin.to_account_info(),
+            &ctx.accounts.system_program,
+        )?;
+
+        emit!(GlobalMigrated {
+            old_version,
+            new_version: EXT_GLOBAL_VERSION,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct GlobalMigrated {
+    pub old_version: u8,
+    pub new_version: u8,
+}
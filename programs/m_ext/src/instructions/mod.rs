@@ -1,21 +1,87 @@
+pub mod approve_proposal;
+pub mod cancel_queued_action;
+pub mod checkpoint;
+pub mod claim;
+pub mod claim_distribution;
+pub mod claim_earner_yield;
 pub mod claim_fees;
+pub mod claim_yield;
+pub mod create_distribution;
+pub mod create_earner;
+pub mod create_proposal;
+pub mod distribute;
+pub mod execute_proposal;
+pub mod execute_queued_action;
+pub mod freeze;
 pub mod initialize;
+pub mod manage_allowlist;
+pub mod manage_blocklist;
+pub mod manage_earner;
+pub mod manage_holder;
+pub mod manage_multisig;
+pub mod manage_rate_limit;
+pub mod manage_role;
 pub mod manage_wrap_authority;
+pub mod migrate_global;
+pub mod pause;
+pub mod queue_action;
+pub mod seize;
+pub mod set_beneficiaries;
+pub mod set_fee;
+pub mod set_open_mode;
+pub mod set_supply_cap;
+pub mod set_timelock_delay;
+pub mod set_wrap_authority_permissions;
+pub mod set_wrap_quota;
+pub mod set_yield_recipient;
+pub mod sweep_distribution;
+pub mod sync;
+pub mod transfer_admin;
 pub mod unwrap;
+pub mod wind_down;
 pub mod wrap;
+pub mod wrap_with_voucher;
 
+pub use approve_proposal::*;
+pub use cancel_queued_action::*;
+pub use checkpoint::*;
+pub use claim::*;
+pub use claim_distribution::*;
+pub use claim_earner_yield::*;
 pub use claim_fees::*;
+pub use claim_yield::*;
+pub use create_distribution::*;
+pub use create_earner::*;
+pub use create_proposal::*;
+pub use distribute::*;
+pub use execute_proposal::*;
+pub use execute_queued_action::*;
+pub use freeze::*;
 pub use initialize::*;
+pub use manage_allowlist::*;
+pub use manage_blocklist::*;
+pub use manage_earner::*;
+pub use manage_holder::*;
+pub use manage_multisig::*;
+pub use manage_rate_limit::*;
+pub use manage_role::*;
 pub use manage_wrap_authority::*;
+pub use migrate_global::*;
+pub use pause::*;
+pub use queue_action::*;
+pub use seize::*;
+pub use set_beneficiaries::*;
+pub use set_fee::*;
+pub use set_open_mode::*;
+pub use set_supply_cap::*;
+pub use set_timelock_delay::*;
+pub use set_wrap_authority_permissions::*;
+pub use set_wrap_quota::*;
+pub use set_yield_recipient::*;
+pub use sweep_distribution::*;
+pub use sync::*;
+pub use transfer_admin::*;
 pub use unwrap::*;
+pub use wind_down::*;
 pub use wrap::*;
-
-cfg_if::cfg_if!(
-    if #[cfg(feature = "scaled-ui")] {
-        pub mod set_fee;
-        pub mod sync;
-
-        pub use set_fee::*;
-        pub use sync::*;
-    }
-);
+pub use wrap_with_voucher::*;
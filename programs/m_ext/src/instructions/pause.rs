@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::ExtError,
+    state::{ExtGlobal, Role, EXT_GLOBAL_SEED, PAUSE_ALL},
+};
+
+#[derive(Accounts)]
+pub struct Pause<'info> {
+    pub pauser: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        constraint = global_account.roles.has(Role::Pauser, pauser.key) @ ExtError::NotAuthorized,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+}
+
+impl Pause<'_> {
+    // This instruction allows the pauser to pause wrapping, unwrapping and/or claiming fees
+    // and syncing, so minting can be stopped in a single transaction if a wrap authority is compromised.
+    // Pausing instructions that are already paused is allowed so it never fails in an emergency.
+
+    pub fn validate(&self, flags: u8) -> Result<()> {
+        validate_flags(flags)
+    }
+
+    #[access_control(ctx.accounts.validate(flags))]
+    pub fn handler(ctx: Context<Self>, flags: u8) -> Result<()> {
+        let old_flags = ctx.accounts.global_account.paused;
+        ctx.accounts.global_account.paused |= flags;
+
+        emit!(PauseUpdated {
+            old_flags,
+            new_flags: ctx.accounts.global_account.paused,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Unpause<'info> {
+    pub pauser: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        constraint = global_account.roles.has(Role::Pauser, pauser.key) @ ExtError::NotAuthorized,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+}
+
+impl Unpause<'_> {
+    // This instruction allows the pauser to resume paused instructions.
+
+    pub fn validate(&self, flags: u8) -> Result<()> {
+        validate_flags(flags)
+    }
+
+    #[access_control(ctx.accounts.validate(flags))]
+    pub fn handler(ctx: Context<Self>, flags: u8) -> Result<()> {
+        let old_flags = ctx.accounts.global_account.paused;
+        ctx.accounts.global_account.paused &= !flags;
+
+        emit!(PauseUpdated {
+            old_flags,
+            new_flags: ctx.accounts.global_account.paused,
+        });
+
+        Ok(())
+    }
+}
+
+fn validate_flags(flags: u8) -> Result<()> {
+    if flags == 0 || flags & !PAUSE_ALL != 0 {
+        return err!(ExtError::InvalidParam);
+    }
+
+    Ok(())
+}
+
+#[event]
+pub struct PauseUpdated {
+    pub old_flags: u8,
+    pub new_flags: u8,
+}
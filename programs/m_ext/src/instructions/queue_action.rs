@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::ExtError,
+    state::{ExtGlobal, QueuedAction, Role, TimelockAction, EXT_GLOBAL_SEED, QUEUED_ACTION_SEED},
+};
+
+#[derive(Accounts)]
+pub struct QueueAction<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = QueuedAction::size(),
+        seeds = [QUEUED_ACTION_SEED, ExtGlobal::seed_namespace(&global_account), global_account.queued_action_count.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub queued_action: Account<'info, QueuedAction>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl QueueAction<'_> {
+    fn validate(&self, action: &TimelockAction) -> Result<()> {
+        if self.global_account.timelock_authority().is_none() {
+            return err!(ExtError::NotActive);
+        }
+
+        // Changes are queued by the same key that would make them without the timelock
+        let global = &self.global_account;
+        let authorized = match action {
+            TimelockAction::SetFee { .. } => global.roles.has(Role::FeeManager, self.signer.key),
+            TimelockAction::AddWrapAuthority { .. } => global
+                .roles
+                .has(Role::WrapAuthorityManager, self.signer.key),
+            TimelockAction::ProposeAdmin { .. }
+            | TimelockAction::SetYieldRecipient { .. }
+            | TimelockAction::SetBeneficiaries { .. }
+            | TimelockAction::SetWrapAuthorityPermissions { .. }
+            | TimelockAction::SetOpenMode { .. } => global.admin == self.signer.key(),
+            TimelockAction::SetEarnerFee { .. } => {
+                global.roles.has(Role::FeeManager, self.signer.key)
+            }
+        };
+
+        if !authorized {
+            return err!(ExtError::NotAuthorized);
+        }
+
+        Ok(())
+    }
+
+    // This instruction queues a change while the timelock is enabled.
+    // The change can be executed by anyone once the delay has passed, and the admin can cancel it until then.
+    #[access_control(ctx.accounts.validate(&action))]
+    pub fn handler(ctx: Context<Self>, action: TimelockAction) -> Result<()> {
+        let index = ctx.accounts.global_account.queued_action_count;
+        let eta = Clock::get()?
+            .unix_timestamp
+            .checked_add(ctx.accounts.global_account.timelock_delay)
+            .ok_or(ExtError::MathOverflow)?;
+
+        ctx.accounts.queued_action.set_inner(QueuedAction {
+            bump: ctx.bumps.queued_action,
+            index,
+            queued_by: ctx.accounts.signer.key(),
+            action: action.clone(),
+            eta,
+        });
+
+        ctx.accounts.global_account.queued_action_count += 1;
+
+        emit!(ActionQueued { index, action, eta });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct ActionQueued {
+    pub index: u64,
+    pub action: TimelockAction,
+    pub eta: i64,
+}
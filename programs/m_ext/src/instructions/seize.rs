@@ -0,0 +1,398 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022_extensions::spl_pod::optional_keys::OptionalNonZeroPubkey,
+    token_interface::{Mint, Token2022, TokenAccount},
+};
+use spl_token_2022::extension::ExtensionType;
+
+use crate::{
+    errors::ExtError,
+    state::{
+        ExtGlobal, Role, EXT_GLOBAL_SEED, FREEZE_AUTHORITY_SEED, MINT_AUTHORITY_SEED, M_VAULT_SEED,
+        PERMANENT_DELEGATE_SEED,
+    },
+    utils::{
+        conversion::{
+            amount_to_principal_up, get_mint_extensions, get_permanent_delegate,
+            principal_to_amount_down, sync_multiplier,
+        },
+        token::{
+            burn_tokens_from_program, freeze_token_account, thaw_token_account,
+            transfer_tokens_from_program,
+        },
+    },
+};
+use earn::{
+    state::{Global as EarnGlobal, EARNER_SEED},
+    ID as EARN_PROGRAM,
+};
+
+#[derive(Accounts)]
+pub struct Seize<'info> {
+    pub compliance_officer: Signer<'info>,
+
+    #[account(
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        constraint = global_account.roles.has(Role::ComplianceOfficer, compliance_officer.key) @ ExtError::NotAuthorized,
+        has_one = ext_mint @ ExtError::InvalidMint,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    #[account(mint::token_program = ext_token_program)]
+    pub ext_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This account is validated by the seed, it stores no data
+    #[account(
+        seeds = [PERMANENT_DELEGATE_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump,
+    )]
+    pub permanent_delegate: AccountInfo<'info>,
+
+    /// CHECK: This account is validated by the seed, it stores no data
+    #[account(
+        seeds = [FREEZE_AUTHORITY_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump,
+    )]
+    pub freeze_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        token::mint = ext_mint,
+        token::token_program = ext_token_program,
+    )]
+    pub from_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = ext_mint,
+        token::token_program = ext_token_program,
+    )]
+    pub recovery_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub ext_token_program: Program<'info, Token2022>,
+}
+
+impl Seize<'_> {
+    pub fn validate(&self, amount: u64) -> Result<()> {
+        validate_permanent_delegate(&self.ext_mint, &self.permanent_delegate)?;
+
+        if amount == 0 {
+            return err!(ExtError::InvalidAmount);
+        }
+
+        Ok(())
+    }
+
+    // This instruction allows the compliance officer to move ext tokens out of a sanctioned
+    // token account into a recovery token account, signing as the permanent delegate PDA.
+    // The amount is capped at the balance of the token account so all of it can be seized with u64::MAX.
+    // The reason code is emitted so each seizure can be matched to the order that required it.
+    // A frozen token account is thawed for the transfer and frozen again afterwards.
+
+    #[access_control(ctx.accounts.validate(amount))]
+    pub fn handler(ctx: Context<Self>, amount: u64, reason: u8) -> Result<()> {
+        let amount = amount.min(ctx.accounts.from_token_account.amount);
+        if amount == 0 {
+            return err!(ExtError::InvalidAmount);
+        }
+
+        let namespace = ctx.accounts.global_account.seed_namespace().to_vec();
+        let freeze_authority_seeds: &[&[&[u8]]] = &[&[
+            FREEZE_AUTHORITY_SEED,
+            &namespace,
+            &[ctx.bumps.freeze_authority],
+        ]];
+
+        let frozen = ctx.accounts.from_token_account.is_frozen();
+        if frozen {
+            thaw_token_account(
+                &ctx.accounts.from_token_account,
+                &ctx.accounts.ext_mint,
+                &ctx.accounts.freeze_authority,
+                freeze_authority_seeds,
+                &ctx.accounts.ext_token_program,
+            )?;
+        }
+
+        transfer_tokens_from_program(
+            &ctx.accounts.from_token_account,     // from
+            &ctx.accounts.recovery_token_account, // to
+            amount,                               // amount
+            &ctx.accounts.ext_mint,               // mint
+            &ctx.accounts.permanent_delegate,     // authority
+            &[&[
+                PERMANENT_DELEGATE_SEED,
+                &namespace,
+                &[ctx.bumps.permanent_delegate],
+            ]], // authority seeds
+            &ctx.accounts.ext_token_program,      // token program
+        )?;
+
+        if frozen {
+            freeze_token_account(
+                &ctx.accounts.from_token_account,
+                &ctx.accounts.ext_mint,
+                &ctx.accounts.freeze_authority,
+                freeze_authority_seeds,
+                &ctx.accounts.ext_token_program,
+            )?;
+        }
+
+        emit!(Seized {
+            from_token_account: ctx.accounts.from_token_account.key(),
+            owner: ctx.accounts.from_token_account.owner,
+            recovery_token_account: ctx.accounts.recovery_token_account.key(),
+            amount,
+            reason,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Clawback<'info> {
+    pub compliance_officer: Signer<'info>,
+
+    #[account(mint::token_program = m_token_program)]
+    pub m_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, mint::token_program = ext_token_program)]
+    pub ext_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        constraint = global_account.roles.has(Role::ComplianceOfficer, compliance_officer.key) @ ExtError::NotAuthorized,
+        bump = global_account.bump,
+        has_one = m_mint @ ExtError::InvalidAccount,
+        has_one = ext_mint @ ExtError::InvalidAccount,
+        has_one = m_earn_global_account @ ExtError::InvalidAccount
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    pub m_earn_global_account: Account<'info, EarnGlobal>,
+
+    /// CHECK: This account is validated by the seed, it stores no data
+    #[account(
+        seeds = [M_VAULT_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump = global_account.m_vault_bump,
+    )]
+    pub m_vault: AccountInfo<'info>,
+
+    /// CHECK: This account is validated by the seed, it stores no data
+    #[account(
+        seeds = [MINT_AUTHORITY_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump = global_account.ext_mint_authority_bump,
+    )]
+    pub ext_mint_authority: AccountInfo<'info>,
+
+    /// CHECK: This account is validated by the seed, it stores no data
+    #[account(
+        seeds = [PERMANENT_DELEGATE_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump,
+    )]
+    pub permanent_delegate: AccountInfo<'info>,
+
+    /// CHECK: This account is validated by the seed, it stores no data
+    #[account(
+        seeds = [FREEZE_AUTHORITY_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump,
+    )]
+    pub freeze_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        token::mint = m_mint,
+        token::token_program = m_token_program,
+    )]
+    pub recovery_m_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = m_mint,
+        associated_token::authority = m_vault,
+        associated_token::token_program = m_token_program,
+    )]
+    pub vault_m_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = ext_mint,
+        token::token_program = ext_token_program,
+    )]
+    pub from_ext_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: We partially validate this account is the correct address
+    /// via the seed, but we delay full validation to the handler
+    /// so we can handle cases where the account has been closed.
+    #[account(
+        seeds = [EARNER_SEED, vault_m_token_account.key().as_ref()],
+        seeds::program = EARN_PROGRAM,
+        bump,
+    )]
+    pub m_earner_account: UncheckedAccount<'info>,
+
+    pub m_token_program: Program<'info, Token2022>,
+    pub ext_token_program: Program<'info, Token2022>,
+}
+
+impl Clawback<'_> {
+    pub fn validate(&self, amount: u64) -> Result<()> {
+        validate_permanent_delegate(&self.ext_mint, &self.permanent_delegate)?;
+
+        if amount == 0 {
+            return err!(ExtError::InvalidAmount);
+        }
+
+        Ok(())
+    }
+
+    // This instruction allows the compliance officer to burn ext tokens from a sanctioned
+    // token account and release the backing M to a recovery M token account.
+    // The amount is in M and is converted with the same rounding as unwrap.
+    // A frozen token account is thawed for the burn and frozen again afterwards.
+
+    #[access_control(ctx.accounts.validate(amount))]
+    pub fn handler(ctx: Context<Self>, mut amount: u64, reason: u8) -> Result<()> {
+        let namespace = ctx.accounts.global_account.seed_namespace().to_vec();
+        let authority_seeds: &[&[&[u8]]] = &[&[
+            MINT_AUTHORITY_SEED,
+            &namespace,
+            &[ctx.accounts.global_account.ext_mint_authority_bump],
+        ]];
+
+        // If necessary, sync the multiplier between M and Ext tokens
+        // Return the current value to use for conversions
+        let multiplier: u64 = sync_multiplier(
+            &mut ctx.accounts.ext_mint,
+            &mut ctx.accounts.global_account,
+            &ctx.accounts.m_earn_global_account,
+            &ctx.accounts.ext_mint_authority,
+            authority_seeds,
+            &ctx.accounts.ext_token_program,
+            &ctx.accounts.m_earner_account,
+        )?;
+
+        // Calculate the principal amount of ext tokens to burn
+        // from the amount of m tokens to release
+        let mut principal = amount_to_principal_up(amount, multiplier)?;
+        if principal > ctx.accounts.from_ext_token_account.amount {
+            principal = ctx.accounts.from_ext_token_account.amount;
+            amount = principal_to_amount_down(principal, multiplier)?;
+        }
+
+        // Revert if 0 m tokens will be released or 0 ext tokens burned
+        if amount == 0 || principal == 0 {
+            return err!(ExtError::InvalidAmount);
+        }
+
+        // Thaw the sanctioned token account if the compliance officer has frozen it
+        let freeze_authority_seeds: &[&[&[u8]]] = &[&[
+            FREEZE_AUTHORITY_SEED,
+            &namespace,
+            &[ctx.bumps.freeze_authority],
+        ]];
+
+        let frozen = ctx.accounts.from_ext_token_account.is_frozen();
+        if frozen {
+            thaw_token_account(
+                &ctx.accounts.from_ext_token_account,
+                &ctx.accounts.ext_mint,
+                &ctx.accounts.freeze_authority,
+                freeze_authority_seeds,
+                &ctx.accounts.ext_token_program,
+            )?;
+        }
+
+        // Burn the ext tokens from the sanctioned token account as the permanent delegate
+        burn_tokens_from_program(
+            &ctx.accounts.from_ext_token_account, // from
+            principal,                            // amount
+            &ctx.accounts.ext_mint,               // mint
+            &ctx.accounts.permanent_delegate,     // authority
+            &[&[
+                PERMANENT_DELEGATE_SEED,
+                &namespace,
+                &[ctx.bumps.permanent_delegate],
+            ]], // authority seeds
+            &ctx.accounts.ext_token_program,      // token program
+        )?;
+
+        // Freeze the sanctioned token account again
+        if frozen {
+            freeze_token_account(
+                &ctx.accounts.from_ext_token_account,
+                &ctx.accounts.ext_mint,
+                &ctx.accounts.freeze_authority,
+                freeze_authority_seeds,
+                &ctx.accounts.ext_token_program,
+            )?;
+        }
+
+        // Transfer the amount of m tokens from the m vault to the recovery account
+        transfer_tokens_from_program(
+            &ctx.accounts.vault_m_token_account,    // from
+            &ctx.accounts.recovery_m_token_account, // to
+            amount,                                 // amount
+            &ctx.accounts.m_mint,                   // mint
+            &ctx.accounts.m_vault,                  // authority
+            &[&[
+                M_VAULT_SEED,
+                &namespace,
+                &[ctx.accounts.global_account.m_vault_bump],
+            ]], // authority seeds
+            &ctx.accounts.m_token_program,          // token program
+        )?;
+
+        emit!(ClawedBack {
+            from_token_account: ctx.accounts.from_ext_token_account.key(),
+            owner: ctx.accounts.from_ext_token_account.owner,
+            recovery_m_token_account: ctx.accounts.recovery_m_token_account.key(),
+            principal,
+            amount,
+            reason,
+        });
+
+        Ok(())
+    }
+}
+
+// The ext mint must use the permanent delegate extension with the
+// permanent delegate PDA of the extension as the delegate
+fn validate_permanent_delegate(
+    ext_mint: &InterfaceAccount<'_, Mint>,
+    permanent_delegate: &AccountInfo<'_>,
+) -> Result<()> {
+    let extensions = get_mint_extensions(ext_mint)?;
+    if !extensions.contains(&ExtensionType::PermanentDelegate) {
+        return err!(ExtError::InvalidMint);
+    }
+
+    if get_permanent_delegate(ext_mint)?.delegate != OptionalNonZeroPubkey(permanent_delegate.key())
+    {
+        return err!(ExtError::InvalidMint);
+    }
+
+    Ok(())
+}
+
+#[event]
+pub struct Seized {
+    pub from_token_account: Pubkey,
+    pub owner: Pubkey,
+    pub recovery_token_account: Pubkey,
+    pub amount: u64,
+    pub reason: u8,
+}
+
+#[event]
+pub struct ClawedBack {
+    pub from_token_account: Pubkey,
+    pub owner: Pubkey,
+    pub recovery_m_token_account: Pubkey,
+    pub principal: u64,
+    pub amount: u64,
+    pub reason: u8,
+}
@@ -0,0 +1,56 @@
+// external dependencies
+use anchor_lang::prelude::*;
+
+// local dependencies
+use crate::{
+    errors::ExtError,
+    instructions::distribute::*,
+    state::{Beneficiary, WeightedSplitConfig},
+};
+
+#[derive(Accounts)]
+pub struct SetBeneficiaries<'info> {
+    // The timelock authority when executing a queued change
+    #[account(
+        constraint = distribute.global_account.is_timelocked_signer(
+            admin.key,
+            distribute.global_account.admin == admin.key(),
+        ) @ ExtError::NotAuthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    pub distribute: Distribute<'info>,
+}
+
+impl<'info> SetBeneficiaries<'info> {
+    // This instruction allows the admin to replace the set of beneficiaries.
+    // The weights must sum to 10000 bps and each token account can only appear once.
+    fn validate(&self, beneficiaries: &[Beneficiary]) -> Result<()> {
+        WeightedSplitConfig::validate_beneficiaries(beneficiaries)
+    }
+
+    #[access_control(ctx.accounts.validate(&beneficiaries))]
+    pub fn handler(
+        ctx: Context<'_, '_, 'info, 'info, Self>,
+        beneficiaries: Vec<Beneficiary>,
+    ) -> Result<()> {
+        // Distribute the yield accrued to the old beneficiaries before changing them
+        ctx.accounts.distribute.distribute(ctx.remaining_accounts)?;
+
+        ctx.accounts
+            .distribute
+            .global_account
+            .yield_config
+            .weighted_split_mut()?
+            .beneficiaries = beneficiaries.clone();
+
+        emit!(BeneficiariesUpdated { beneficiaries });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct BeneficiariesUpdated {
+    pub beneficiaries: Vec<Beneficiary>,
+}
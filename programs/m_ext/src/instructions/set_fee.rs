@@ -10,18 +10,22 @@ use earn::{
 use crate::{
     constants::ONE_HUNDRED_PERCENT_U64,
     errors::ExtError,
-    state::{ExtGlobal, EXT_GLOBAL_SEED, MINT_AUTHORITY_SEED, M_VAULT_SEED},
+    state::{ExtGlobal, Role, YieldConfig, EXT_GLOBAL_SEED, MINT_AUTHORITY_SEED, M_VAULT_SEED},
     utils::conversion::sync_multiplier,
 };
 
 #[derive(Accounts)]
 pub struct SetFee<'info> {
-    pub admin: Signer<'info>,
+    // The timelock authority when executing a queued change
+    pub fee_manager: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [EXT_GLOBAL_SEED],
-        has_one = admin @ ExtError::NotAuthorized,
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        constraint = global_account.is_timelocked_signer(
+            fee_manager.key,
+            global_account.roles.has(Role::FeeManager, fee_manager.key),
+        ) @ ExtError::NotAuthorized,
         has_one = ext_mint @ ExtError::InvalidMint,
         has_one = m_earn_global_account @ ExtError::InvalidAccount,
         bump = global_account.bump,
@@ -32,7 +36,7 @@ pub struct SetFee<'info> {
 
     // CHECK: This account is validated by the seed, it stores no data
     #[account(
-        seeds = [M_VAULT_SEED],
+        seeds = [M_VAULT_SEED, ExtGlobal::seed_namespace(&global_account)],
         bump = global_account.m_vault_bump,
     )]
     pub m_vault: UncheckedAccount<'info>,
@@ -62,7 +66,7 @@ pub struct SetFee<'info> {
 
     /// CHECK: This account is validated by the seed, it stores no data
     #[account(
-        seeds = [MINT_AUTHORITY_SEED],
+        seeds = [MINT_AUTHORITY_SEED, ExtGlobal::seed_namespace(&global_account)],
         bump = global_account.ext_mint_authority_bump,
     )]
     pub ext_mint_authority: AccountInfo<'info>,
@@ -73,12 +77,20 @@ pub struct SetFee<'info> {
 }
 
 impl SetFee<'_> {
-    // This instruction allows the admin to set a new fee in basis points (bps).
+    // This instruction allows the fee manager to set a new fee in basis points (bps).
     // The fee must be between 0 and 10000 bps (inclusive).
     // If the fee is set to 0, it effectively disables the fee.
     // If the fee is set to 10000, it means the entire amount is taken as a fee.
     // Any value above 10000 bps will result in an error.
     fn validate(&self, fee_bps: u64) -> Result<()> {
+        // Only the modes that take a fee on the index support this instruction
+        if !matches!(
+            self.global_account.yield_config,
+            YieldConfig::ScaledUi(_) | YieldConfig::InterestBearing(_)
+        ) {
+            return err!(ExtError::UnsupportedYieldMode);
+        }
+
         // Validate that the fee is between 0 and 10000 bps
         if fee_bps > ONE_HUNDRED_PERCENT_U64 {
             return err!(ExtError::InvalidParam);
@@ -93,18 +105,23 @@ impl SetFee<'_> {
         // if it doesn't match the index on m_earn_global_account
         // It also checks that the vault is solvent after the update
         let signer_bump = ctx.accounts.global_account.ext_mint_authority_bump;
+        let namespace = ctx.accounts.global_account.seed_namespace().to_vec();
         sync_multiplier(
             &mut ctx.accounts.ext_mint,
             &mut ctx.accounts.global_account,
             &ctx.accounts.m_earn_global_account,
             &ctx.accounts.ext_mint_authority,
-            &[&[MINT_AUTHORITY_SEED, &[signer_bump]]],
+            &[&[MINT_AUTHORITY_SEED, &namespace, &[signer_bump]]],
             &ctx.accounts.ext_token_program,
             &ctx.accounts.m_earner_account,
         )?;
 
         // Set the new fee
-        ctx.accounts.global_account.yield_config.fee_bps = fee_bps;
+        match &mut ctx.accounts.global_account.yield_config {
+            YieldConfig::ScaledUi(config) => config.fee_bps = fee_bps,
+            YieldConfig::InterestBearing(config) => config.fee_bps = fee_bps,
+            _ => return err!(ExtError::UnsupportedYieldMode),
+        }
 
         Ok(())
     }
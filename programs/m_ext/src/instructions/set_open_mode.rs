@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::ExtError,
+    state::{ExtGlobal, EXT_GLOBAL_SEED},
+};
+
+#[derive(Accounts)]
+pub struct SetOpenMode<'info> {
+    // The timelock authority when executing a queued change
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        constraint = global_account.admin == admin.key()
+            || global_account.is_timelock_authority(admin.key) @ ExtError::NotAuthorized,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+}
+
+impl SetOpenMode<'_> {
+    // This instruction allows the admin to open wrap and unwrap to anyone, so the extension
+    // can be used as an open stablecoin. Each direction is set separately, and closing it
+    // requires a wrap authority again. Pauses, the supply cap and rate limits still apply.
+    // While the timelock is enabled, opening wrap lets anyone mint, so it has to be queued.
+
+    pub fn validate(&self, open_wrap: bool) -> Result<()> {
+        if open_wrap
+            && !self.global_account.open_wrap
+            && self.global_account.timelock_authority().is_some()
+            && !self.global_account.is_timelock_authority(self.admin.key)
+        {
+            return err!(ExtError::NotAuthorized);
+        }
+
+        Ok(())
+    }
+
+    #[access_control(ctx.accounts.validate(open_wrap))]
+    pub fn handler(ctx: Context<Self>, open_wrap: bool, open_unwrap: bool) -> Result<()> {
+        let global_account = &mut ctx.accounts.global_account;
+        global_account.open_wrap = open_wrap;
+        global_account.open_unwrap = open_unwrap;
+
+        emit!(OpenModeUpdated {
+            open_wrap,
+            open_unwrap,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct OpenModeUpdated {
+    pub open_wrap: bool,
+    pub open_unwrap: bool,
+}
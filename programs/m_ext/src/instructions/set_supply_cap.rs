@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::ExtError,
+    state::{ExtGlobal, EXT_GLOBAL_SEED},
+};
+
+#[derive(Accounts)]
+pub struct SetSupplyCap<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        has_one = admin @ ExtError::NotAuthorized,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+}
+
+impl SetSupplyCap<'_> {
+    // This instruction allows the admin to set the max ext supply that wrap can mint up to.
+    // A supply cap of zero removes the cap. The cap can be set below the current supply,
+    // which stops wrapping until enough ext tokens are unwrapped.
+
+    pub fn handler(ctx: Context<Self>, supply_cap: u64) -> Result<()> {
+        let old_supply_cap = ctx.accounts.global_account.supply_cap;
+        ctx.accounts.global_account.supply_cap = supply_cap;
+
+        emit!(SupplyCapUpdated {
+            old_supply_cap,
+            new_supply_cap: supply_cap,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct SupplyCapUpdated {
+    pub old_supply_cap: u64,
+    pub new_supply_cap: u64,
+}
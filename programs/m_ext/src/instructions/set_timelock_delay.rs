@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::ExtError,
+    state::{ExtGlobal, EXT_GLOBAL_SEED},
+};
+
+#[derive(Accounts)]
+pub struct SetTimelockDelay<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        has_one = admin @ ExtError::NotAuthorized,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+}
+
+impl SetTimelockDelay<'_> {
+    // This instruction allows the admin to increase the timelock delay, or to enable the timelock
+    // on an extension that was initialized without it. The delay can never be decreased,
+    // so holders can rely on having at least the current delay to react to queued changes.
+
+    pub fn validate(&self, timelock_delay: i64) -> Result<()> {
+        if timelock_delay <= self.global_account.timelock_delay {
+            return err!(ExtError::InvalidParam);
+        }
+
+        Ok(())
+    }
+
+    #[access_control(ctx.accounts.validate(timelock_delay))]
+    pub fn handler(ctx: Context<Self>, timelock_delay: i64) -> Result<()> {
+        let old_delay = ctx.accounts.global_account.timelock_delay;
+        ctx.accounts
+            .global_account
+            .set_timelock_delay(timelock_delay);
+
+        emit!(TimelockDelayUpdated {
+            old_delay,
+            new_delay: timelock_delay,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct TimelockDelayUpdated {
+    pub old_delay: i64,
+    pub new_delay: i64,
+}
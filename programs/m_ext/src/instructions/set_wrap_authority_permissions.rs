@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::ExtError,
+    state::{ExtGlobal, WrapAuthority, EXT_GLOBAL_SEED, PERMISSION_ALL, WRAP_AUTHORITY_SEED},
+};
+
+#[derive(Accounts)]
+#[instruction(wrap_authority: Pubkey)]
+pub struct SetWrapAuthorityPermissions<'info> {
+    // The timelock authority when executing a queued change
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        constraint = global_account.admin == admin.key()
+            || global_account.is_timelock_authority(admin.key) @ ExtError::NotAuthorized,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    #[account(
+        mut,
+        seeds = [WRAP_AUTHORITY_SEED, ExtGlobal::seed_namespace(&global_account), wrap_authority.as_ref()],
+        bump = wrap_authority_account.bump,
+    )]
+    pub wrap_authority_account: Account<'info, WrapAuthority>,
+}
+
+impl SetWrapAuthorityPermissions<'_> {
+    // This instruction allows the admin to restrict a wrap authority to wrap or unwrap only,
+    // and to set the timestamp it expires at. An expiry of zero means it never expires.
+    // While the timelock is enabled, the admin can only narrow the permissions directly,
+    // widening them has to be queued.
+
+    pub fn validate(&self, permissions: u8, expires_at: i64) -> Result<()> {
+        if permissions == 0 || permissions & !PERMISSION_ALL != 0 {
+            return err!(ExtError::InvalidParam);
+        }
+
+        if expires_at < 0 {
+            return err!(ExtError::InvalidParam);
+        }
+
+        if self.global_account.timelock_authority().is_some()
+            && !self.global_account.is_timelock_authority(self.admin.key)
+        {
+            let account = &self.wrap_authority_account;
+
+            if permissions & !account.permissions != 0 {
+                return err!(ExtError::NotAuthorized);
+            }
+
+            if account.expires_at != 0 && (expires_at == 0 || expires_at > account.expires_at) {
+                return err!(ExtError::NotAuthorized);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[access_control(ctx.accounts.validate(permissions, expires_at))]
+    pub fn handler(
+        ctx: Context<Self>,
+        wrap_authority: Pubkey,
+        permissions: u8,
+        expires_at: i64,
+    ) -> Result<()> {
+        let account = &mut ctx.accounts.wrap_authority_account;
+        account.permissions = permissions;
+        account.expires_at = expires_at;
+
+        emit!(WrapAuthorityPermissionsUpdated {
+            wrap_authority,
+            permissions,
+            expires_at,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct WrapAuthorityPermissionsUpdated {
+    pub wrap_authority: Pubkey,
+    pub permissions: u8,
+    pub expires_at: i64,
+}
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::ExtError,
+    state::{ExtGlobal, WrapAuthority, EXT_GLOBAL_SEED, WRAP_AUTHORITY_SEED},
+};
+
+#[derive(Accounts)]
+#[instruction(wrap_authority: Pubkey)]
+pub struct SetWrapQuota<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        has_one = admin @ ExtError::NotAuthorized,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    #[account(
+        mut,
+        seeds = [WRAP_AUTHORITY_SEED, ExtGlobal::seed_namespace(&global_account), wrap_authority.as_ref()],
+        bump = wrap_authority_account.bump,
+    )]
+    pub wrap_authority_account: Account<'info, WrapAuthority>,
+}
+
+impl SetWrapQuota<'_> {
+    // This instruction allows the admin to set the max outstanding principal
+    // that a wrap authority can mint. A quota of zero makes it unlimited.
+    // The outstanding principal is kept, so a quota below it stops the authority from wrapping.
+
+    pub fn handler(ctx: Context<Self>, wrap_authority: Pubkey, quota: u64) -> Result<()> {
+        let wrap_quota = &mut ctx.accounts.wrap_authority_account.quota;
+        let old_quota = wrap_quota.quota;
+        wrap_quota.quota = quota;
+
+        emit!(WrapQuotaUpdated {
+            wrap_authority,
+            old_quota,
+            new_quota: quota,
+            minted: wrap_quota.minted,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct WrapQuotaUpdated {
+    pub wrap_authority: Pubkey,
+    pub old_quota: u64,
+    pub new_quota: u64,
+    pub minted: u64,
+}
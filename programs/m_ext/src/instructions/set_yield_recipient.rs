@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+use crate::{
+    errors::ExtError,
+    state::{ExtGlobal, EXT_GLOBAL_SEED},
+};
+
+#[derive(Accounts)]
+pub struct SetYieldRecipient<'info> {
+    // The timelock authority when executing a queued change
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        constraint = global_account.is_timelocked_signer(
+            admin.key,
+            global_account.admin == admin.key(),
+        ) @ ExtError::NotAuthorized,
+        has_one = ext_mint @ ExtError::InvalidMint,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    #[account(mint::token_program = ext_token_program)]
+    pub ext_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: The authority of this token account is not checked
+    /// so the yield can be directed to any ext token account
+    #[account(
+        token::mint = ext_mint,
+        token::token_program = ext_token_program,
+    )]
+    pub new_yield_recipient: InterfaceAccount<'info, TokenAccount>,
+
+    pub ext_token_program: Program<'info, Token2022>,
+}
+
+impl SetYieldRecipient<'_> {
+    // This instruction allows the admin to change the ext token account that receives the yield.
+    // Yield that has accrued but not been claimed is sent to the new recipient on the next claim,
+    // so the admin should call claim_yield beforehand if the old recipient is owed yield.
+    pub fn handler(ctx: Context<Self>) -> Result<()> {
+        let new_yield_recipient = ctx.accounts.new_yield_recipient.key();
+        let yield_config = ctx
+            .accounts
+            .global_account
+            .yield_config
+            .yield_to_one_mut()?;
+
+        let old_yield_recipient = yield_config.yield_recipient;
+        yield_config.yield_recipient = new_yield_recipient;
+
+        emit!(YieldRecipientUpdated {
+            old_yield_recipient,
+            new_yield_recipient,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct YieldRecipientUpdated {
+    pub old_yield_recipient: Pubkey,
+    pub new_yield_recipient: Pubkey,
+}
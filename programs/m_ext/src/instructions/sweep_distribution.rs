@@ -0,0 +1,123 @@
+// external dependencies
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+// local dependencies
+use crate::{
+    errors::ExtError,
+    state::{
+        Distribution, ExtGlobal, DISTRIBUTION_SEED, EXT_GLOBAL_SEED, MINT_AUTHORITY_SEED,
+        PAUSE_CLAIM,
+    },
+    utils::token::mint_tokens,
+};
+
+#[derive(Accounts)]
+pub struct SweepDistribution<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        has_one = admin @ ExtError::NotAuthorized,
+        has_one = ext_mint @ ExtError::InvalidMint,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [DISTRIBUTION_SEED, ExtGlobal::seed_namespace(&global_account), distribution_account.index.to_le_bytes().as_ref()],
+        bump = distribution_account.bump,
+    )]
+    pub distribution_account: Account<'info, Distribution>,
+
+    #[account(mut, mint::token_program = ext_token_program)]
+    pub ext_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This account is validated by the seed, it stores no data
+    #[account(
+        seeds = [MINT_AUTHORITY_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump = global_account.ext_mint_authority_bump,
+    )]
+    pub ext_mint_authority: AccountInfo<'info>,
+
+    /// CHECK: Allowing the admin to specify the recipient account is more flexible
+    /// so the authority of this token account is not checked
+    #[account(
+        mut,
+        token::mint = ext_mint,
+        token::token_program = ext_token_program,
+    )]
+    pub recipient_ext_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub ext_token_program: Program<'info, Token2022>,
+}
+
+impl SweepDistribution<'_> {
+    fn validate(&self) -> Result<()> {
+        if self.global_account.is_paused(PAUSE_CLAIM) {
+            return err!(ExtError::Paused);
+        }
+
+        // Claimants have until the expiry to claim
+        if Clock::get()?.unix_timestamp <= self.distribution_account.expires_at {
+            return err!(ExtError::Active);
+        }
+
+        Ok(())
+    }
+
+    // This instruction mints the unclaimed amount of an expired distribution
+    // to the admin's recipient and closes the distribution account.
+    #[access_control(ctx.accounts.validate())]
+    pub fn handler(ctx: Context<Self>) -> Result<()> {
+        let distribution = &ctx.accounts.distribution_account;
+        let unclaimed = distribution
+            .total_amount
+            .checked_sub(distribution.claimed_amount)
+            .ok_or(ExtError::MathUnderflow)?;
+
+        let yield_config = ctx
+            .accounts
+            .global_account
+            .yield_config
+            .merkle_distribution_mut()?;
+        yield_config.locked_amount = yield_config
+            .locked_amount
+            .checked_sub(unclaimed)
+            .ok_or(ExtError::MathUnderflow)?;
+
+        if unclaimed > 0 {
+            mint_tokens(
+                &ctx.accounts.recipient_ext_token_account,
+                unclaimed,
+                &ctx.accounts.ext_mint,
+                &ctx.accounts.ext_mint_authority,
+                &[&[
+                    MINT_AUTHORITY_SEED,
+                    ctx.accounts.global_account.seed_namespace(),
+                    &[ctx.accounts.global_account.ext_mint_authority_bump],
+                ]],
+                &ctx.accounts.ext_token_program,
+            )?;
+        }
+
+        emit!(DistributionSwept {
+            index: ctx.accounts.distribution_account.index,
+            recipient_token_account: ctx.accounts.recipient_ext_token_account.key(),
+            amount: unclaimed,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct DistributionSwept {
+    pub index: u64,
+    pub recipient_token_account: Pubkey,
+    pub amount: u64,
+}
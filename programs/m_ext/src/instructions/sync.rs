@@ -1,6 +1,8 @@
 use crate::{
     errors::ExtError,
-    state::{ExtGlobal, EXT_GLOBAL_SEED, MINT_AUTHORITY_SEED, M_VAULT_SEED},
+    state::{
+        ExtGlobal, YieldConfig, EXT_GLOBAL_SEED, MINT_AUTHORITY_SEED, M_VAULT_SEED, PAUSE_CLAIM,
+    },
     utils::conversion::sync_multiplier,
 };
 use anchor_lang::prelude::*;
@@ -14,7 +16,7 @@ use earn::{
 pub struct Sync<'info> {
     #[account(
         mut,
-        seeds = [EXT_GLOBAL_SEED],
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
         bump = global_account.bump,
         has_one = ext_mint @ ExtError::InvalidMint,
         has_one = m_earn_global_account @ ExtError::InvalidAccount
@@ -25,7 +27,7 @@ pub struct Sync<'info> {
 
     // CHECK: This account is validated by the seed, it stores no data
     #[account(
-        seeds = [M_VAULT_SEED],
+        seeds = [M_VAULT_SEED, ExtGlobal::seed_namespace(&global_account)],
         bump = global_account.m_vault_bump,
     )]
     pub m_vault: UncheckedAccount<'info>,
@@ -55,7 +57,7 @@ pub struct Sync<'info> {
 
     /// CHECK: This account is validated by the seed, it stores no data
     #[account(
-        seeds = [MINT_AUTHORITY_SEED],
+        seeds = [MINT_AUTHORITY_SEED, ExtGlobal::seed_namespace(&global_account)],
         bump = global_account.ext_mint_authority_bump,
     )]
     pub ext_mint_authority: AccountInfo<'info>,
@@ -66,17 +68,35 @@ pub struct Sync<'info> {
 }
 
 impl Sync<'_> {
+    fn validate(&self) -> Result<()> {
+        if self.global_account.is_paused(PAUSE_CLAIM) {
+            return err!(ExtError::Paused);
+        }
+
+        // Only the modes with an index on the mint need to be synced
+        if !matches!(
+            self.global_account.yield_config,
+            YieldConfig::ScaledUi(_) | YieldConfig::InterestBearing(_)
+        ) {
+            return err!(ExtError::UnsupportedYieldMode);
+        }
+
+        Ok(())
+    }
+
+    #[access_control(ctx.accounts.validate())]
     pub fn handler(ctx: Context<Self>) -> Result<()> {
         // Sync the multiplier
         // This will update the multiplier on ext_mint
         // if it doesn't match the index on m_earn_global_account
         let signer_bump = ctx.accounts.global_account.ext_mint_authority_bump;
+        let namespace = ctx.accounts.global_account.seed_namespace().to_vec();
         sync_multiplier(
             &mut ctx.accounts.ext_mint,
             &mut ctx.accounts.global_account,
             &ctx.accounts.m_earn_global_account,
             &ctx.accounts.ext_mint_authority,
-            &[&[MINT_AUTHORITY_SEED, &[signer_bump]]],
+            &[&[MINT_AUTHORITY_SEED, &namespace, &[signer_bump]]],
             &ctx.accounts.ext_token_program,
             &ctx.accounts.m_earner_account,
         )?;
@@ -0,0 +1,130 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::ExtError,
+    state::{ExtGlobal, EXT_GLOBAL_SEED},
+};
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    // The timelock authority when executing a queued change
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        constraint = global_account.is_timelocked_signer(
+            admin.key,
+            global_account.admin == admin.key(),
+        ) @ ExtError::NotAuthorized,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+}
+
+impl ProposeAdmin<'_> {
+    // This instruction allows the admin to propose a new admin for the extension.
+    // The transfer only takes effect once the proposed admin accepts it,
+    // proposing again replaces any pending proposal.
+
+    pub fn validate(&self, new_admin: Pubkey) -> Result<()> {
+        if new_admin == self.global_account.admin || new_admin == Pubkey::default() {
+            return err!(ExtError::InvalidParam);
+        }
+
+        // The admin can't also hold a role
+        if self.global_account.roles.holds_any(&new_admin) {
+            return err!(ExtError::InvalidParam);
+        }
+
+        Ok(())
+    }
+
+    #[access_control(ctx.accounts.validate(new_admin))]
+    pub fn handler(ctx: Context<Self>, new_admin: Pubkey) -> Result<()> {
+        ctx.accounts.global_account.pending_admin = Some(new_admin);
+
+        emit!(AdminProposed {
+            admin: ctx.accounts.global_account.admin,
+            pending_admin: new_admin,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    pub pending_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        constraint = global_account.pending_admin == Some(pending_admin.key()) @ ExtError::NotAuthorized,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+}
+
+impl AcceptAdmin<'_> {
+    // This instruction allows the pending admin to accept the admin role.
+
+    pub fn handler(ctx: Context<Self>) -> Result<()> {
+        let global_account = &mut ctx.accounts.global_account;
+
+        let old_admin = global_account.admin;
+        global_account.admin = ctx.accounts.pending_admin.key();
+        global_account.pending_admin = None;
+
+        emit!(AdminTransferred {
+            old_admin,
+            new_admin: global_account.admin,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct CancelAdminTransfer<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        has_one = admin @ ExtError::NotAuthorized,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+}
+
+impl CancelAdminTransfer<'_> {
+    // This instruction allows the admin to withdraw a pending admin proposal.
+
+    pub fn handler(ctx: Context<Self>) -> Result<()> {
+        let Some(pending_admin) = ctx.accounts.global_account.pending_admin.take() else {
+            return err!(ExtError::NotActive);
+        };
+
+        emit!(AdminTransferCancelled { pending_admin });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct AdminProposed {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminTransferred {
+    pub old_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminTransferCancelled {
+    pub pending_admin: Pubkey,
+}
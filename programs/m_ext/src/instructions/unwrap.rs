@@ -3,7 +3,10 @@ use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
 
 use crate::{
     errors::ExtError,
-    state::{ExtGlobal, EXT_GLOBAL_SEED, MINT_AUTHORITY_SEED, M_VAULT_SEED},
+    state::{
+        ExtGlobal, RateLimit, WrapAuthority, BLOCKLIST_SEED, EXT_GLOBAL_SEED, MINT_AUTHORITY_SEED,
+        M_VAULT_SEED, PAUSE_UNWRAP, PERMISSION_UNWRAP, RATE_LIMIT_SEED, WRAP_AUTHORITY_SEED,
+    },
     utils::{
         conversion::{amount_to_principal_up, principal_to_amount_down, sync_multiplier},
         token::{burn_tokens, transfer_tokens_from_program},
@@ -29,7 +32,7 @@ pub struct Unwrap<'info> {
 
     #[account(
         mut,
-        seeds = [EXT_GLOBAL_SEED],
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
         bump = global_account.bump,
         has_one = m_mint @ ExtError::InvalidAccount,
         has_one = ext_mint @ ExtError::InvalidAccount,
@@ -37,18 +40,26 @@ pub struct Unwrap<'info> {
     )]
     pub global_account: Account<'info, ExtGlobal>,
 
+    // Account of the wrap authority, only optional while unwrap is open to anyone
+    #[account(
+        mut,
+        seeds = [WRAP_AUTHORITY_SEED, ExtGlobal::seed_namespace(&global_account), wrap_authority_account.authority.as_ref()],
+        bump = wrap_authority_account.bump,
+    )]
+    pub wrap_authority_account: Option<Account<'info, WrapAuthority>>,
+
     pub m_earn_global_account: Account<'info, EarnGlobal>,
 
     /// CHECK: This account is validated by the seed, it stores no data
     #[account(
-        seeds = [M_VAULT_SEED],
+        seeds = [M_VAULT_SEED, ExtGlobal::seed_namespace(&global_account)],
         bump = global_account.m_vault_bump,
     )]
     pub m_vault: AccountInfo<'info>,
 
     /// CHECK: This account is validated by the seed, it stores no data
     #[account(
-        seeds = [MINT_AUTHORITY_SEED],
+        seeds = [MINT_AUTHORITY_SEED, ExtGlobal::seed_namespace(&global_account)],
         bump = global_account.ext_mint_authority_bump,
     )]
     pub ext_mint_authority: AccountInfo<'info>,
@@ -78,6 +89,21 @@ pub struct Unwrap<'info> {
     )]
     pub from_ext_token_account: InterfaceAccount<'info, TokenAccount>,
 
+    /// CHECK: This account is validated by the seed, it only exists if the owner is blocklisted
+    #[account(
+        seeds = [BLOCKLIST_SEED, ExtGlobal::seed_namespace(&global_account), from_ext_token_account.owner.as_ref()],
+        bump,
+    )]
+    pub from_blocklist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: This account is validated by the seed, it only exists if rate limits are configured
+    #[account(
+        mut,
+        seeds = [RATE_LIMIT_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump,
+    )]
+    pub rate_limit: UncheckedAccount<'info>,
+
     /// CHECK: We partially validate this account is the correct address
     /// via the seed, but we delay full validation to the handler
     /// so we can handle cases where the account has been closed.
@@ -95,15 +121,35 @@ pub struct Unwrap<'info> {
 }
 
 impl Unwrap<'_> {
+    fn auth(&self) -> Pubkey {
+        match &self.unwrap_authority {
+            Some(auth) => auth.key(),
+            None => self.token_authority.key(),
+        }
+    }
+
     pub fn validate(&self, amount: u64) -> Result<()> {
-        let auth = match &self.unwrap_authority {
-            Some(auth) => auth.key,
-            None => self.token_authority.key,
-        };
-
-        // Ensure the caller is authorized to wrap
-        if !self.global_account.wrap_authorities.contains(auth) {
-            return err!(ExtError::NotAuthorized);
+        if self.global_account.is_paused(PAUSE_UNWRAP) {
+            return err!(ExtError::Paused);
+        }
+
+        // Ensure the caller is a wrap authority allowed to unwrap,
+        // unless unwrap is open to anyone or the extension is winding down
+        let open = self.global_account.open_unwrap || self.global_account.winding_down;
+        match &self.wrap_authority_account {
+            Some(account) => {
+                if account.authority != self.auth() {
+                    return err!(ExtError::NotAuthorized);
+                }
+                account.check(PERMISSION_UNWRAP, Clock::get()?.unix_timestamp)?;
+            }
+            None if !open => return err!(ExtError::NotAuthorized),
+            None => {}
+        }
+
+        // Ensure a blocklisted wallet can't unwrap its ext tokens
+        if self.from_blocklist_entry.owner == &crate::ID {
+            return err!(ExtError::Blocklisted);
         }
 
         if amount == 0 {
@@ -115,8 +161,10 @@ impl Unwrap<'_> {
 
     #[access_control(ctx.accounts.validate(amount))]
     pub fn handler(ctx: Context<Self>, mut amount: u64) -> Result<()> {
+        let namespace = ctx.accounts.global_account.seed_namespace().to_vec();
         let authority_seeds: &[&[&[u8]]] = &[&[
             MINT_AUTHORITY_SEED,
+            &namespace,
             &[ctx.accounts.global_account.ext_mint_authority_bump],
         ]];
 
@@ -145,6 +193,19 @@ impl Unwrap<'_> {
             return err!(ExtError::InvalidAmount);
         }
 
+        // Credit the burned principal back to the quota of the unwrap authority
+        if let Some(account) = &mut ctx.accounts.wrap_authority_account {
+            account.quota.burn(principal);
+        }
+
+        // Enforce the rate limits if the admin configured them
+        if ctx.accounts.rate_limit.owner == &crate::ID {
+            let mut data = ctx.accounts.rate_limit.try_borrow_mut_data()?;
+            let mut rate_limit = RateLimit::try_deserialize(&mut &data[..])?;
+            rate_limit.record_unwrap(amount, Clock::get()?.unix_timestamp)?;
+            rate_limit.try_serialize(&mut &mut data[..])?;
+        }
+
         // Burn the amount of ext tokens from the user
         burn_tokens(
             &ctx.accounts.from_ext_token_account,            // from
@@ -161,7 +222,11 @@ impl Unwrap<'_> {
             amount,                              // amount
             &ctx.accounts.m_mint,                // mint
             &ctx.accounts.m_vault,               // authority
-            &[&[M_VAULT_SEED, &[ctx.accounts.global_account.m_vault_bump]]], // authority seeds
+            &[&[
+                M_VAULT_SEED,
+                &namespace,
+                &[ctx.accounts.global_account.m_vault_bump],
+            ]], // authority seeds
             &ctx.accounts.m_token_program,       // token program
         )?;
 
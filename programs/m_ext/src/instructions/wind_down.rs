@@ -0,0 +1,230 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+use crate::{
+    errors::ExtError,
+    state::{
+        ExtGlobal, Tombstone, YieldConfig, EXT_GLOBAL_SEED, MINT_AUTHORITY_SEED, M_VAULT_SEED,
+        TOMBSTONE_SEED,
+    },
+    utils::{
+        conversion::get_mint_close_authority,
+        token::{close_account_from_program, transfer_tokens_from_program},
+    },
+};
+
+#[derive(Accounts)]
+pub struct BeginWindDown<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        has_one = admin @ ExtError::NotAuthorized,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+}
+
+impl BeginWindDown<'_> {
+    // This instruction allows the admin to start retiring the extension.
+    // Wrapping is blocked and anyone can unwrap without a wrap authority, so holders
+    // can exit until the supply reaches zero and the extension is decommissioned.
+    // It can't be undone.
+
+    pub fn validate(&self) -> Result<()> {
+        if self.global_account.winding_down {
+            return err!(ExtError::WindingDown);
+        }
+
+        Ok(())
+    }
+
+    #[access_control(ctx.accounts.validate())]
+    pub fn handler(ctx: Context<Self>) -> Result<()> {
+        ctx.accounts.global_account.winding_down = true;
+
+        emit!(WindDownStarted {
+            ext_mint: ctx.accounts.global_account.ext_mint,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Decommission<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
+        has_one = admin @ ExtError::NotAuthorized,
+        has_one = m_mint @ ExtError::InvalidMint,
+        has_one = ext_mint @ ExtError::InvalidMint,
+        bump = global_account.bump,
+    )]
+    pub global_account: Account<'info, ExtGlobal>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = Tombstone::size(),
+        seeds = [TOMBSTONE_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump
+    )]
+    pub tombstone: Account<'info, Tombstone>,
+
+    #[account(mint::token_program = m_token_program)]
+    pub m_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, mint::token_program = ext_token_program)]
+    pub ext_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: This account is validated by the seed, it stores no data
+    #[account(
+        seeds = [MINT_AUTHORITY_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump = global_account.ext_mint_authority_bump,
+    )]
+    pub ext_mint_authority: AccountInfo<'info>,
+
+    /// CHECK: This account is validated by the seed, it stores no data
+    #[account(
+        seeds = [M_VAULT_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump = global_account.m_vault_bump,
+    )]
+    pub m_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = m_mint,
+        associated_token::authority = m_vault,
+        associated_token::token_program = m_token_program,
+    )]
+    pub vault_m_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    // Receives the residual M left in the vault
+    #[account(
+        mut,
+        token::mint = m_mint,
+        token::token_program = m_token_program,
+    )]
+    pub admin_m_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub m_token_program: Program<'info, Token2022>,
+    pub ext_token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+impl Decommission<'_> {
+    // This instruction allows the admin to close the extension once it is winding down
+    // and all ext tokens have been unwrapped. The residual M in the vault, such as fees
+    // that were never claimed, is swept to the admin and the vault token account is closed.
+    // The global account is closed and its rent is refunded to the admin. If the ext mint
+    // has the mint close authority extension set to the ext mint authority, it is closed too.
+    // A tombstone account is left in place of the global account so the extension can't be
+    // initialized again with its PDAs. Other accounts of the extension are left as is.
+
+    pub fn validate(&self) -> Result<()> {
+        if !self.global_account.winding_down {
+            return err!(ExtError::NotWindingDown);
+        }
+
+        if self.ext_mint.supply != 0 {
+            return err!(ExtError::OutstandingSupply);
+        }
+
+        // M owed to holders, such as unclaimed earner yield or locked
+        // distributions, must be claimed before the vault is swept
+        if self.global_account.yield_config.reserved_collateral()? != 0 {
+            return err!(ExtError::OutstandingClaims);
+        }
+
+        // With no ext supply left, the whole vault is the payout of claim_yield, which mints
+        // ext tokens, so the yield recipient must claim and unwrap it before the vault is swept
+        if matches!(self.global_account.yield_config, YieldConfig::YieldToOne(_))
+            && self.vault_m_token_account.amount != 0
+        {
+            return err!(ExtError::OutstandingClaims);
+        }
+
+        Ok(())
+    }
+
+    #[access_control(ctx.accounts.validate())]
+    pub fn handler(ctx: Context<Self>) -> Result<()> {
+        let namespace = ctx.accounts.global_account.seed_namespace().to_vec();
+        let vault_seeds: &[&[&[u8]]] = &[&[
+            M_VAULT_SEED,
+            &namespace,
+            &[ctx.accounts.global_account.m_vault_bump],
+        ]];
+
+        // Sweep the residual M to the admin
+        let swept_amount = ctx.accounts.vault_m_token_account.amount;
+        if swept_amount > 0 {
+            transfer_tokens_from_program(
+                &ctx.accounts.vault_m_token_account, // from
+                &ctx.accounts.admin_m_token_account, // to
+                swept_amount,                        // amount
+                &ctx.accounts.m_mint,                // mint
+                &ctx.accounts.m_vault,               // authority
+                vault_seeds,                         // authority seeds
+                &ctx.accounts.m_token_program,       // token program
+            )?;
+        }
+
+        // Close the vault token account, now that it is empty
+        close_account_from_program(
+            &ctx.accounts.vault_m_token_account.to_account_info(), // account
+            &ctx.accounts.admin.to_account_info(),                 // destination
+            &ctx.accounts.m_vault,                                 // authority
+            vault_seeds,                                           // authority seeds
+            &ctx.accounts.m_token_program,                         // token program
+        )?;
+
+        // Close the ext mint if the extension is allowed to
+        let mint_closed = get_mint_close_authority(&ctx.accounts.ext_mint)?
+            == Some(ctx.accounts.ext_mint_authority.key());
+
+        if mint_closed {
+            close_account_from_program(
+                &ctx.accounts.ext_mint.to_account_info(), // account
+                &ctx.accounts.admin.to_account_info(),    // destination
+                &ctx.accounts.ext_mint_authority,         // authority
+                &[&[
+                    MINT_AUTHORITY_SEED,
+                    &namespace,
+                    &[ctx.accounts.global_account.ext_mint_authority_bump],
+                ]], // authority seeds
+                &ctx.accounts.ext_token_program,          // token program
+            )?;
+        }
+
+        let tombstone = &mut ctx.accounts.tombstone;
+        tombstone.bump = ctx.bumps.tombstone;
+        tombstone.ext_mint = ctx.accounts.global_account.ext_mint;
+
+        emit!(Decommissioned {
+            ext_mint: ctx.accounts.global_account.ext_mint,
+            swept_amount,
+            mint_closed,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct WindDownStarted {
+    pub ext_mint: Pubkey,
+}
+
+#[event]
+pub struct Decommissioned {
+    pub ext_mint: Pubkey,
+    pub swept_amount: u64,
+    pub mint_closed: bool,
+}
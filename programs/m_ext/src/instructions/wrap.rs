@@ -3,7 +3,10 @@ use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
 
 use crate::{
     errors::ExtError,
-    state::{ExtGlobal, EXT_GLOBAL_SEED, MINT_AUTHORITY_SEED, M_VAULT_SEED},
+    state::{
+        ExtGlobal, RateLimit, WrapAuthority, BLOCKLIST_SEED, EXT_GLOBAL_SEED, MINT_AUTHORITY_SEED,
+        M_VAULT_SEED, PAUSE_WRAP, PERMISSION_WRAP, RATE_LIMIT_SEED, WRAP_AUTHORITY_SEED,
+    },
     utils::{
         conversion::{amount_to_principal_down, sync_multiplier},
         token::{mint_tokens, transfer_tokens},
@@ -29,7 +32,7 @@ pub struct Wrap<'info> {
 
     #[account(
         mut,
-        seeds = [EXT_GLOBAL_SEED],
+        seeds = [EXT_GLOBAL_SEED, ExtGlobal::seed_namespace(&global_account)],
         bump = global_account.bump,
         has_one = m_mint @ ExtError::InvalidAccount,
         has_one = ext_mint @ ExtError::InvalidAccount,
@@ -37,18 +40,26 @@ pub struct Wrap<'info> {
     )]
     pub global_account: Account<'info, ExtGlobal>,
 
+    // Account of the wrap authority, only optional while wrap is open to anyone
+    #[account(
+        mut,
+        seeds = [WRAP_AUTHORITY_SEED, ExtGlobal::seed_namespace(&global_account), wrap_authority_account.authority.as_ref()],
+        bump = wrap_authority_account.bump,
+    )]
+    pub wrap_authority_account: Option<Account<'info, WrapAuthority>>,
+
     pub m_earn_global_account: Account<'info, EarnGlobal>,
 
     /// CHECK: This account is validated by the seed, it stores no data
     #[account(
-        seeds = [M_VAULT_SEED],
+        seeds = [M_VAULT_SEED, ExtGlobal::seed_namespace(&global_account)],
         bump = global_account.m_vault_bump
     )]
     pub m_vault: AccountInfo<'info>,
 
     /// CHECK: This account is validated by the seed, it stores no data
     #[account(
-        seeds = [MINT_AUTHORITY_SEED],
+        seeds = [MINT_AUTHORITY_SEED, ExtGlobal::seed_namespace(&global_account)],
         bump = global_account.ext_mint_authority_bump,
     )]
     pub ext_mint_authority: AccountInfo<'info>,
@@ -78,6 +89,21 @@ pub struct Wrap<'info> {
     )]
     pub to_ext_token_account: InterfaceAccount<'info, TokenAccount>,
 
+    /// CHECK: This account is validated by the seed, it only exists if the owner is blocklisted
+    #[account(
+        seeds = [BLOCKLIST_SEED, ExtGlobal::seed_namespace(&global_account), to_ext_token_account.owner.as_ref()],
+        bump,
+    )]
+    pub to_blocklist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: This account is validated by the seed, it only exists if rate limits are configured
+    #[account(
+        mut,
+        seeds = [RATE_LIMIT_SEED, ExtGlobal::seed_namespace(&global_account)],
+        bump,
+    )]
+    pub rate_limit: UncheckedAccount<'info>,
+
     /// CHECK: We partially validate this account is the correct address
     /// via the seed, but we delay full validation to the handler
     /// so we can handle cases where the account has been closed.
@@ -95,15 +121,44 @@ pub struct Wrap<'info> {
 }
 
 impl Wrap<'_> {
+    fn auth(&self) -> Pubkey {
+        match &self.wrap_authority {
+            Some(auth) => auth.key(),
+            None => self.token_authority.key(),
+        }
+    }
+
     pub fn validate(&self, amount: u64) -> Result<()> {
-        let auth = match &self.wrap_authority {
-            Some(auth) => auth.key,
-            None => self.token_authority.key,
-        };
-
-        // Ensure the caller is authorized to wrap
-        if !self.global_account.wrap_authorities.contains(auth) {
-            return err!(ExtError::NotAuthorized);
+        self.validate_wrap(self.auth(), amount)
+    }
+
+    // Checks shared with wrap_with_voucher, where the authority signs a voucher instead of the transaction
+    pub fn validate_wrap(&self, authority: Pubkey, amount: u64) -> Result<()> {
+        if self.global_account.is_paused(PAUSE_WRAP) {
+            return err!(ExtError::Paused);
+        }
+
+        // No new ext tokens are minted once the extension is being retired
+        if self.global_account.winding_down {
+            return err!(ExtError::WindingDown);
+        }
+
+        // Ensure the caller is a wrap authority allowed to wrap, unless wrap is open to anyone.
+        // A wrap authority can still pass its account in open mode, its quota then applies.
+        match &self.wrap_authority_account {
+            Some(account) => {
+                if account.authority != authority {
+                    return err!(ExtError::NotAuthorized);
+                }
+                account.check(PERMISSION_WRAP, Clock::get()?.unix_timestamp)?;
+            }
+            None if !self.global_account.open_wrap => return err!(ExtError::NotAuthorized),
+            None => {}
+        }
+
+        // Ensure ext tokens are not minted to a blocklisted wallet
+        if self.to_blocklist_entry.owner == &crate::ID {
+            return err!(ExtError::Blocklisted);
         }
 
         if amount == 0 {
@@ -115,31 +170,38 @@ impl Wrap<'_> {
 
     #[access_control(ctx.accounts.validate(amount))]
     pub fn handler(ctx: Context<Self>, amount: u64) -> Result<()> {
+        ctx.accounts.process(amount)
+    }
+
+    // Moves the M into the vault and mints the ext tokens, once the caller has been validated
+    pub fn process(&mut self, amount: u64) -> Result<()> {
+        let namespace = self.global_account.seed_namespace().to_vec();
         let authority_seeds: &[&[&[u8]]] = &[&[
             MINT_AUTHORITY_SEED,
-            &[ctx.accounts.global_account.ext_mint_authority_bump],
+            &namespace,
+            &[self.global_account.ext_mint_authority_bump],
         ]];
 
         // If necessary, sync the multiplier between M and Ext tokens
         // Return the current value to use for conversions
         let multiplier: u64 = sync_multiplier(
-            &mut ctx.accounts.ext_mint,
-            &mut ctx.accounts.global_account,
-            &ctx.accounts.m_earn_global_account,
-            &ctx.accounts.ext_mint_authority,
+            &mut self.ext_mint,
+            &mut self.global_account,
+            &self.m_earn_global_account,
+            &self.ext_mint_authority,
             authority_seeds,
-            &ctx.accounts.ext_token_program,
-            &ctx.accounts.m_earner_account,
+            &self.ext_token_program,
+            &self.m_earner_account,
         )?;
 
         // Transfer the amount of m tokens from the user to the m vault
         transfer_tokens(
-            &ctx.accounts.from_m_token_account,              // from
-            &ctx.accounts.vault_m_token_account,             // to
-            amount,                                          // amount
-            &ctx.accounts.m_mint,                            // mint
-            &ctx.accounts.token_authority.to_account_info(), // authority
-            &ctx.accounts.m_token_program,                   // token program
+            &self.from_m_token_account,              // from
+            &self.vault_m_token_account,             // to
+            amount,                                  // amount
+            &self.m_mint,                            // mint
+            &self.token_authority.to_account_info(), // authority
+            &self.m_token_program,                   // token program
         )?;
 
         // Calculate the amount of ext tokens to mint based
@@ -152,14 +214,30 @@ impl Wrap<'_> {
             return err!(ExtError::InvalidAmount);
         }
 
+        // Enforce the supply cap and the quota of the wrap authority
+        self.global_account
+            .check_supply_cap(self.ext_mint.supply, principal)?;
+
+        if let Some(account) = &mut self.wrap_authority_account {
+            account.quota.mint(principal)?;
+        }
+
+        // Enforce the rate limits if the admin configured them
+        if self.rate_limit.owner == &crate::ID {
+            let mut data = self.rate_limit.try_borrow_mut_data()?;
+            let mut rate_limit = RateLimit::try_deserialize(&mut &data[..])?;
+            rate_limit.record_wrap(amount, Clock::get()?.unix_timestamp)?;
+            rate_limit.try_serialize(&mut &mut data[..])?;
+        }
+
         // Mint the amount of ext tokens to the user
         mint_tokens(
-            &ctx.accounts.to_ext_token_account, // to
-            principal,                          // amount
-            &ctx.accounts.ext_mint,             // mint
-            &ctx.accounts.ext_mint_authority,   // authority
-            authority_seeds,                    // authority seeds
-            &ctx.accounts.ext_token_program,    // token program
+            &self.to_ext_token_account, // to
+            principal,                  // amount
+            &self.ext_mint,             // mint
+            &self.ext_mint_authority,   // authority
+            authority_seeds,            // authority seeds
+            &self.ext_token_program,    // token program
         )?;
 
         Ok(())
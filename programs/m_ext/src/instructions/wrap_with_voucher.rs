@@ -0,0 +1,123 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::sysvar::instructions::{
+        load_current_index_checked, load_instruction_at_checked, ID as INSTRUCTIONS_SYSVAR_ID,
+    },
+};
+
+use crate::{
+    errors::ExtError,
+    instructions::wrap::*,
+    state::{ExtGlobal, VoucherNonce, WrapVoucher, VOUCHER_NONCE_SEED},
+    utils::ed25519::verify_ed25519_instruction,
+};
+
+#[derive(Accounts)]
+#[instruction(voucher: WrapVoucher)]
+pub struct WrapWithVoucher<'info> {
+    // Pays the rent of the nonce account, can be a relayer
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    // Accounts of wrap, the token authority is the user of the voucher
+    // and the wrap authority account is the one of the voucher signer
+    pub wrap: Wrap<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = VoucherNonce::size(),
+        seeds = [
+            VOUCHER_NONCE_SEED,
+            ExtGlobal::seed_namespace(&wrap.global_account),
+            voucher.wrap_authority.as_ref(),
+            voucher.nonce.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub voucher_nonce: Account<'info, VoucherNonce>,
+
+    /// CHECK: This account is validated by the address, it is read to find the Ed25519 instruction
+    #[account(address = INSTRUCTIONS_SYSVAR_ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl WrapWithVoucher<'_> {
+    // This instruction lets a user wrap with a voucher signed off-chain by a wrap authority,
+    // so the wrap authority doesn't have to co-sign the transaction.
+    // The instruction right before it must be an Ed25519 program instruction verifying the
+    // signature of the wrap authority over the voucher. The wrap counts against the quota
+    // of the wrap authority like a regular wrap, and the nonce account prevents replays.
+
+    pub fn validate(&self, voucher: &WrapVoucher, amount: u64) -> Result<()> {
+        // The account of the wrap authority is required even if wrap is open to anyone
+        if self.wrap.wrap_authority_account.is_none() {
+            return err!(ExtError::NotAuthorized);
+        }
+
+        let authority = voucher.wrap_authority;
+        self.wrap.validate_wrap(authority, amount)?;
+
+        if voucher.ext_mint != self.wrap.ext_mint.key() {
+            return err!(ExtError::InvalidMint);
+        }
+
+        if voucher.user != self.wrap.token_authority.key() {
+            return err!(ExtError::NotAuthorized);
+        }
+
+        if amount > voucher.max_amount {
+            return err!(ExtError::InvalidAmount);
+        }
+
+        if Clock::get()?.unix_timestamp >= voucher.expires_at {
+            return err!(ExtError::VoucherExpired);
+        }
+
+        // Ensure the wrap authority signed the voucher
+        let current_index = load_current_index_checked(&self.instructions_sysvar)?;
+        if current_index == 0 {
+            return err!(ExtError::InvalidSignature);
+        }
+
+        let ix =
+            load_instruction_at_checked(current_index as usize - 1, &self.instructions_sysvar)?;
+        if !verify_ed25519_instruction(&ix, &authority, &voucher.try_to_vec()?) {
+            return err!(ExtError::InvalidSignature);
+        }
+
+        Ok(())
+    }
+
+    #[access_control(ctx.accounts.validate(&voucher, amount))]
+    pub fn handler(ctx: Context<Self>, voucher: WrapVoucher, amount: u64) -> Result<()> {
+        let wrap_authority = voucher.wrap_authority;
+
+        ctx.accounts.voucher_nonce.set_inner(VoucherNonce {
+            bump: ctx.bumps.voucher_nonce,
+            wrap_authority,
+            nonce: voucher.nonce,
+        });
+
+        ctx.accounts.wrap.process(amount)?;
+
+        emit!(VoucherRedeemed {
+            wrap_authority,
+            user: voucher.user,
+            nonce: voucher.nonce,
+            amount,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct VoucherRedeemed {
+    pub wrap_authority: Pubkey,
+    pub user: Pubkey,
+    pub nonce: u64,
+    pub amount: u64,
+}
@@ -9,6 +9,9 @@ pub mod utils;
 use anchor_lang::prelude::*;
 
 use instructions::*;
+use state::{
+    Beneficiary, MultisigAction, RateLimitWindow, Role, TimelockAction, WrapVoucher, YieldMode,
+};
 
 #[cfg(not(feature = "no-entrypoint"))]
 solana_security_txt::security_txt! {
@@ -23,42 +26,75 @@ solana_security_txt::security_txt! {
 
 declare_id!("3C865D264L4NkAm78zfnDzQJJvXuU3fMjRUvRxyPi5da");
 
-// Validate feature combinations
-const _: () = {
-    let yield_features = { cfg!(feature = "scaled-ui") as u32 + cfg!(feature = "no-yield") as u32 };
-
-    match yield_features {
-        0 => panic!("No yield distribution feature enabled"),
-        1 => {}
-        2.. => panic!("Only one yield distribution feature can be enabled at a time"),
-    }
-};
-
 #[program]
 pub mod m_ext {
     use super::*;
 
     // Admin instructions
 
-    #[cfg(feature = "scaled-ui")]
-    pub fn initialize(
-        ctx: Context<Initialize>,
+    pub fn initialize<'info>(
+        ctx: Context<'_, '_, '_, 'info, Initialize<'info>>,
         wrap_authorities: Vec<Pubkey>,
-        fee_bps: u64,
+        yield_mode: YieldMode,
+        namespaced: bool,
+        timelock_delay: i64,
+        require_allowlist: bool,
     ) -> Result<()> {
-        Initialize::handler(ctx, wrap_authorities, fee_bps)
+        Initialize::handler(
+            ctx,
+            wrap_authorities,
+            yield_mode,
+            namespaced,
+            timelock_delay,
+            require_allowlist,
+        )
     }
 
-    #[cfg(feature = "no-yield")]
-    pub fn initialize(ctx: Context<Initialize>, wrap_authorities: Vec<Pubkey>) -> Result<()> {
-        Initialize::handler(ctx, wrap_authorities, 0)
-    }
-
-    #[cfg(feature = "scaled-ui")]
     pub fn set_fee(ctx: Context<SetFee>, fee_bps: u64) -> Result<()> {
         SetFee::handler(ctx, fee_bps)
     }
 
+    pub fn set_yield_recipient(ctx: Context<SetYieldRecipient>) -> Result<()> {
+        SetYieldRecipient::handler(ctx)
+    }
+
+    pub fn set_fee_recipient(ctx: Context<SetFeeRecipient>) -> Result<()> {
+        SetFeeRecipient::handler(ctx)
+    }
+
+    pub fn add_earner(ctx: Context<AddEarner>, fee_bps: u64) -> Result<()> {
+        AddEarner::handler(ctx, fee_bps)
+    }
+
+    pub fn remove_earner(ctx: Context<RemoveEarner>) -> Result<()> {
+        RemoveEarner::handler(ctx)
+    }
+
+    pub fn set_earner_fee(ctx: Context<SetEarnerFee>, fee_bps: u64) -> Result<()> {
+        SetEarnerFee::handler(ctx, fee_bps)
+    }
+
+    pub fn set_beneficiaries<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SetBeneficiaries<'info>>,
+        beneficiaries: Vec<Beneficiary>,
+    ) -> Result<()> {
+        SetBeneficiaries::handler(ctx, beneficiaries)
+    }
+
+    pub fn create_distribution(
+        ctx: Context<CreateDistribution>,
+        merkle_root: [u8; 32],
+        total_amount: u64,
+        num_leaves: u32,
+        expires_at: i64,
+    ) -> Result<()> {
+        CreateDistribution::handler(ctx, merkle_root, total_amount, num_leaves, expires_at)
+    }
+
+    pub fn sweep_distribution(ctx: Context<SweepDistribution>) -> Result<()> {
+        SweepDistribution::handler(ctx)
+    }
+
     pub fn add_wrap_authority(
         ctx: Context<AddWrapAuthority>,
         new_wrap_authority: Pubkey,
@@ -73,10 +109,166 @@ pub mod m_ext {
         RemoveWrapAuthority::handler(ctx, wrap_authority)
     }
 
+    pub fn update_wrap_authority(
+        ctx: Context<UpdateWrapAuthority>,
+        wrap_authority: Pubkey,
+        label: String,
+        enabled: bool,
+    ) -> Result<()> {
+        UpdateWrapAuthority::handler(ctx, wrap_authority, label, enabled)
+    }
+
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        ProposeAdmin::handler(ctx, new_admin)
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        AcceptAdmin::handler(ctx)
+    }
+
+    pub fn cancel_admin_transfer(ctx: Context<CancelAdminTransfer>) -> Result<()> {
+        CancelAdminTransfer::handler(ctx)
+    }
+
+    pub fn grant_role(ctx: Context<GrantRole>, role: Role, account: Pubkey) -> Result<()> {
+        GrantRole::handler(ctx, role, account)
+    }
+
+    pub fn revoke_role(ctx: Context<RevokeRole>, role: Role) -> Result<()> {
+        RevokeRole::handler(ctx, role)
+    }
+
+    pub fn create_multisig(
+        ctx: Context<CreateMultisig>,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        CreateMultisig::handler(ctx, signers, threshold)
+    }
+
+    pub fn update_multisig(
+        ctx: Context<UpdateMultisig>,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        UpdateMultisig::handler(ctx, signers, threshold)
+    }
+
+    pub fn set_timelock_delay(ctx: Context<SetTimelockDelay>, timelock_delay: i64) -> Result<()> {
+        SetTimelockDelay::handler(ctx, timelock_delay)
+    }
+
+    pub fn set_supply_cap(ctx: Context<SetSupplyCap>, supply_cap: u64) -> Result<()> {
+        SetSupplyCap::handler(ctx, supply_cap)
+    }
+
+    pub fn set_open_mode(
+        ctx: Context<SetOpenMode>,
+        open_wrap: bool,
+        open_unwrap: bool,
+    ) -> Result<()> {
+        SetOpenMode::handler(ctx, open_wrap, open_unwrap)
+    }
+
+    pub fn set_wrap_quota(
+        ctx: Context<SetWrapQuota>,
+        wrap_authority: Pubkey,
+        quota: u64,
+    ) -> Result<()> {
+        SetWrapQuota::handler(ctx, wrap_authority, quota)
+    }
+
+    pub fn set_wrap_authority_permissions(
+        ctx: Context<SetWrapAuthorityPermissions>,
+        wrap_authority: Pubkey,
+        permissions: u8,
+        expires_at: i64,
+    ) -> Result<()> {
+        SetWrapAuthorityPermissions::handler(ctx, wrap_authority, permissions, expires_at)
+    }
+
+    pub fn create_rate_limit(
+        ctx: Context<CreateRateLimit>,
+        wrap_windows: Vec<RateLimitWindow>,
+        unwrap_windows: Vec<RateLimitWindow>,
+    ) -> Result<()> {
+        CreateRateLimit::handler(ctx, wrap_windows, unwrap_windows)
+    }
+
+    pub fn update_rate_limit(
+        ctx: Context<UpdateRateLimit>,
+        wrap_windows: Vec<RateLimitWindow>,
+        unwrap_windows: Vec<RateLimitWindow>,
+    ) -> Result<()> {
+        UpdateRateLimit::handler(ctx, wrap_windows, unwrap_windows)
+    }
+
+    pub fn close_rate_limit(ctx: Context<CloseRateLimit>) -> Result<()> {
+        CloseRateLimit::handler(ctx)
+    }
+
+    pub fn cancel_queued_action(ctx: Context<CancelQueuedAction>) -> Result<()> {
+        CancelQueuedAction::handler(ctx)
+    }
+
+    pub fn migrate_global<'info>(
+        ctx: Context<'_, '_, '_, 'info, MigrateGlobal<'info>>,
+    ) -> Result<()> {
+        MigrateGlobal::handler(ctx)
+    }
+
+    pub fn begin_wind_down(ctx: Context<BeginWindDown>) -> Result<()> {
+        BeginWindDown::handler(ctx)
+    }
+
+    pub fn decommission(ctx: Context<Decommission>) -> Result<()> {
+        Decommission::handler(ctx)
+    }
+
     pub fn claim_fees(ctx: Context<ClaimFees>) -> Result<()> {
         ClaimFees::handler(ctx)
     }
 
+    pub fn pause(ctx: Context<Pause>, flags: u8) -> Result<()> {
+        Pause::handler(ctx, flags)
+    }
+
+    pub fn unpause(ctx: Context<Unpause>, flags: u8) -> Result<()> {
+        Unpause::handler(ctx, flags)
+    }
+
+    pub fn freeze_account(ctx: Context<FreezeAccount>) -> Result<()> {
+        FreezeAccount::handler(ctx)
+    }
+
+    pub fn thaw_account(ctx: Context<ThawAccount>) -> Result<()> {
+        ThawAccount::handler(ctx)
+    }
+
+    pub fn add_to_blocklist(ctx: Context<AddToBlocklist>, wallet: Pubkey) -> Result<()> {
+        AddToBlocklist::handler(ctx, wallet)
+    }
+
+    pub fn remove_from_blocklist(ctx: Context<RemoveFromBlocklist>) -> Result<()> {
+        RemoveFromBlocklist::handler(ctx)
+    }
+
+    pub fn add_to_allowlist(ctx: Context<AddToAllowlist>, wallet: Pubkey) -> Result<()> {
+        AddToAllowlist::handler(ctx, wallet)
+    }
+
+    pub fn remove_from_allowlist(ctx: Context<RemoveFromAllowlist>) -> Result<()> {
+        RemoveFromAllowlist::handler(ctx)
+    }
+
+    pub fn seize(ctx: Context<Seize>, amount: u64, reason: u8) -> Result<()> {
+        Seize::handler(ctx, amount, reason)
+    }
+
+    pub fn clawback(ctx: Context<Clawback>, amount: u64, reason: u8) -> Result<()> {
+        Clawback::handler(ctx, amount, reason)
+    }
+
     // Wrap authority instructions
 
     pub fn wrap(ctx: Context<Wrap>, amount: u64) -> Result<()> {
@@ -87,10 +279,90 @@ pub mod m_ext {
         Unwrap::handler(ctx, amount)
     }
 
+    pub fn wrap_with_voucher(
+        ctx: Context<WrapWithVoucher>,
+        voucher: WrapVoucher,
+        amount: u64,
+    ) -> Result<()> {
+        WrapWithVoucher::handler(ctx, voucher, amount)
+    }
+
+    // Multisig instructions
+
+    pub fn create_proposal(
+        ctx: Context<CreateProposal>,
+        action: MultisigAction,
+        expires_at: i64,
+    ) -> Result<()> {
+        CreateProposal::handler(ctx, action, expires_at)
+    }
+
+    pub fn approve_proposal(ctx: Context<ApproveProposal>) -> Result<()> {
+        ApproveProposal::handler(ctx)
+    }
+
+    pub fn execute_proposal<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteProposal<'info>>,
+    ) -> Result<()> {
+        ExecuteProposal::handler(ctx)
+    }
+
+    // Timelock instructions
+
+    pub fn queue_action(ctx: Context<QueueAction>, action: TimelockAction) -> Result<()> {
+        QueueAction::handler(ctx, action)
+    }
+
+    pub fn execute_queued_action<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteQueuedAction<'info>>,
+    ) -> Result<()> {
+        ExecuteQueuedAction::handler(ctx)
+    }
+
     // Open instructions
 
-    #[cfg(feature = "scaled-ui")]
+    pub fn approve_holder(ctx: Context<ApproveHolder>) -> Result<()> {
+        ApproveHolder::handler(ctx)
+    }
+
+    pub fn revoke_holder(ctx: Context<RevokeHolder>) -> Result<()> {
+        RevokeHolder::handler(ctx)
+    }
+
     pub fn sync(ctx: Context<Sync>) -> Result<()> {
         Sync::handler(ctx)
     }
+
+    pub fn claim_earner_yield(ctx: Context<ClaimEarnerYield>) -> Result<()> {
+        ClaimEarnerYield::handler(ctx)
+    }
+
+    pub fn create_earner(ctx: Context<CreateEarner>) -> Result<()> {
+        CreateEarner::handler(ctx)
+    }
+
+    pub fn checkpoint(ctx: Context<Checkpoint>) -> Result<()> {
+        Checkpoint::handler(ctx)
+    }
+
+    pub fn claim(ctx: Context<Claim>) -> Result<()> {
+        Claim::handler(ctx)
+    }
+
+    pub fn claim_distribution(
+        ctx: Context<ClaimDistribution>,
+        leaf_index: u32,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        ClaimDistribution::handler(ctx, leaf_index, amount, proof)
+    }
+
+    pub fn distribute<'info>(ctx: Context<'_, '_, 'info, 'info, Distribute<'info>>) -> Result<()> {
+        Distribute::handler(ctx)
+    }
+
+    pub fn claim_yield(ctx: Context<ClaimYield>) -> Result<()> {
+        ClaimYield::handler(ctx)
+    }
 }
@@ -1,11 +1,38 @@
 use anchor_lang::prelude::*;
-use cfg_if::cfg_if;
+use std::collections::HashSet;
+
+use crate::{
+    constants::{
+        MAX_BENEFICIARIES, MAX_MULTISIG_SIGNERS, MAX_RATE_LIMIT_WINDOWS,
+        MAX_WRAP_AUTHORITY_LABEL_LEN, ONE_HUNDRED_PERCENT_U64,
+    },
+    errors::ExtError,
+    utils::conversion::{get_accrued_yield, principal_to_amount_up},
+};
 
 #[constant]
 pub const EXT_GLOBAL_SEED: &[u8] = b"global";
 
+// Layout version of ExtGlobal, bumped whenever fields are added or removed.
+// The version is the first field and the yield config discriminant sits at a fixed
+// offset after the scalar fields, so clients can decode any account without knowing
+// which binary created it. New fields must be appended at the end and be valid when
+// zeroed, older accounts are upgraded in place with migrate_global.
+#[constant]
+pub const EXT_GLOBAL_VERSION: u8 = 10;
+
+// Bits of ExtGlobal::paused, each one pauses a group of instructions
+#[constant]
+pub const PAUSE_WRAP: u8 = 1;
+#[constant]
+pub const PAUSE_UNWRAP: u8 = 2;
+#[constant]
+pub const PAUSE_CLAIM: u8 = 4; // sync and every instruction that mints excess collateral
+pub const PAUSE_ALL: u8 = PAUSE_WRAP | PAUSE_UNWRAP | PAUSE_CLAIM;
+
 #[account]
 pub struct ExtGlobal {
+    pub version: u8,   // layout version of the account
     pub admin: Pubkey, // can update config values
     pub ext_mint: Pubkey,
     pub m_mint: Pubkey,
@@ -13,13 +40,25 @@ pub struct ExtGlobal {
     pub bump: u8,
     pub m_vault_bump: u8,
     pub ext_mint_authority_bump: u8,
+    pub namespaced: bool,              // PDAs have the ext mint as an extra seed
     pub yield_config: YieldConfig,     // variant specific state
-    pub wrap_authorities: Vec<Pubkey>, // accounts permissioned to wrap/unwrap the ext_mint
+    pub pending_admin: Option<Pubkey>, // proposed admin that has not accepted yet
+    pub roles: Roles,                  // keys granted each role by the admin
+    pub timelock_delay: i64, // seconds queued changes wait before execution, zero if disabled
+    pub timelock_authority_bump: u8,
+    pub queued_action_count: u64, // index of the next queued action, used in the seeds
+    pub paused: u8,               // PAUSE_* bits of the paused instructions
+    pub require_allowlist: bool,  // token accounts start frozen until their owner is allowlisted
+    pub supply_cap: u64,          // max ext supply that wrap can mint up to, zero if uncapped
+    pub open_wrap: bool,          // anyone can wrap without a wrap authority
+    pub open_unwrap: bool,        // anyone can unwrap without a wrap authority
+    pub winding_down: bool,       // wrap is blocked and anyone can unwrap until decommissioned
 }
 
 impl ExtGlobal {
-    pub fn size(wrap_authorities: usize) -> usize {
+    pub fn size() -> usize {
         8 + // discriminator
+        1 + // version
         32 + // admin
         32 + // ext_mint
         32 + // m_mint
@@ -27,9 +66,275 @@ impl ExtGlobal {
         1 + // bump
         1 + // m_vault_bump
         1 + // ext_mint_authority_bump
+        1 + // namespaced
         YieldConfig::space() + // yield_config
-        4 + // length of wrap_authorities vector
-        wrap_authorities * 32 // each Pubkey is 32 bytes
+        33 + // pending_admin
+        Roles::space() + // roles
+        8 + // timelock_delay
+        1 + // timelock_authority_bump
+        8 + // queued_action_count
+        1 + // paused
+        1 + // require_allowlist
+        8 + // supply_cap
+        1 + // open_wrap
+        1 + // open_unwrap
+        1 // winding_down
+    }
+
+    // Seed added to the PDAs of the extension, empty if the extension owns the whole program.
+    // Seeds constraints call it as `ExtGlobal::seed_namespace(&global_account)` because the IDL
+    // would mistake the method call syntax for a seed of the global account address.
+    pub fn seed_namespace(&self) -> &[u8] {
+        Self::namespace(self.namespaced, &self.ext_mint)
+    }
+
+    // Signer of the changes executed from the timelock queue, None if the timelock is disabled
+    pub fn timelock_authority(&self) -> Option<Pubkey> {
+        if self.timelock_delay == 0 {
+            return None;
+        }
+
+        Pubkey::create_program_address(
+            &[
+                TIMELOCK_AUTHORITY_SEED,
+                self.seed_namespace(),
+                &[self.timelock_authority_bump],
+            ],
+            &crate::ID,
+        )
+        .ok()
+    }
+
+    pub fn set_timelock_delay(&mut self, timelock_delay: i64) {
+        // The bump of the timelock authority is only needed once the timelock is enabled
+        if self.timelock_delay == 0 && timelock_delay > 0 {
+            let (_, bump) = Pubkey::find_program_address(
+                &[TIMELOCK_AUTHORITY_SEED, self.seed_namespace()],
+                &crate::ID,
+            );
+            self.timelock_authority_bump = bump;
+        }
+
+        self.timelock_delay = timelock_delay;
+    }
+
+    // Changes subject to the timelock can only be signed by the timelock authority while it is
+    // enabled, otherwise `authorized` tells if the signer holds the privilege for the change
+    pub fn is_timelocked_signer(&self, signer: &Pubkey, authorized: bool) -> bool {
+        match self.timelock_authority() {
+            Some(timelock_authority) => *signer == timelock_authority,
+            None => authorized,
+        }
+    }
+
+    // Changes that can be made directly only in one direction while the timelock is enabled
+    // are signed by the timelock authority when made in the other direction
+    pub fn is_timelock_authority(&self, signer: &Pubkey) -> bool {
+        self.timelock_authority() == Some(*signer)
+    }
+
+    pub fn is_paused(&self, flags: u8) -> bool {
+        self.paused & flags != 0
+    }
+
+    // Ensures minting the principal keeps the ext supply within the supply cap
+    pub fn check_supply_cap(&self, supply: u64, principal: u64) -> Result<()> {
+        let new_supply = supply
+            .checked_add(principal)
+            .ok_or(ExtError::MathOverflow)?;
+
+        if self.supply_cap != 0 && new_supply > self.supply_cap {
+            return err!(ExtError::SupplyCapExceeded);
+        }
+
+        Ok(())
+    }
+
+    pub fn namespace(namespaced: bool, ext_mint: &Pubkey) -> &[u8] {
+        if namespaced {
+            ext_mint.as_ref()
+        } else {
+            &[]
+        }
+    }
+}
+
+// Exposure limit of a wrap authority, tracked as the principal of ext tokens
+// minted through it that has not been unwrapped through it yet
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct WrapQuota {
+    pub quota: u64,  // max outstanding principal, zero if unlimited
+    pub minted: u64, // outstanding principal
+}
+
+impl WrapQuota {
+    pub fn space() -> usize {
+        2 * 8
+    }
+
+    pub fn mint(&mut self, principal: u64) -> Result<()> {
+        let minted = self
+            .minted
+            .checked_add(principal)
+            .ok_or(ExtError::MathOverflow)?;
+
+        if self.quota != 0 && minted > self.quota {
+            return err!(ExtError::QuotaExceeded);
+        }
+
+        self.minted = minted;
+
+        Ok(())
+    }
+
+    // Tokens can be unwrapped through a different authority than they were wrapped with,
+    // so the outstanding principal is floored at zero
+    pub fn burn(&mut self, principal: u64) {
+        self.minted = self.minted.saturating_sub(principal);
+    }
+}
+
+#[constant]
+pub const WRAP_AUTHORITY_SEED: &[u8] = b"wrap_authority";
+
+// Bits of WrapAuthority::permissions, each one allows an instruction
+#[constant]
+pub const PERMISSION_WRAP: u8 = 1;
+#[constant]
+pub const PERMISSION_UNWRAP: u8 = 2;
+pub const PERMISSION_ALL: u8 = PERMISSION_WRAP | PERMISSION_UNWRAP;
+
+// Key permissioned to wrap/unwrap the ext_mint, the account only exists while it is a wrap authority
+#[account]
+pub struct WrapAuthority {
+    pub bump: u8,
+    pub authority: Pubkey,
+    pub label: String,   // name of the distributor, for indexers
+    pub created_at: i64, // timestamp the authority was added
+    pub enabled: bool,   // disabled authorities can't wrap or unwrap
+    pub quota: WrapQuota,
+    pub permissions: u8, // PERMISSION_* bits of the allowed instructions
+    pub expires_at: i64, // timestamp the authority can no longer be used, zero if it never expires
+}
+
+impl WrapAuthority {
+    pub fn size() -> usize {
+        8 + // discriminator
+        1 + // bump
+        32 + // authority
+        4 + MAX_WRAP_AUTHORITY_LABEL_LEN + // label
+        8 + // created_at
+        1 + // enabled
+        WrapQuota::space() + // quota
+        1 + // permissions
+        8 // expires_at
+    }
+
+    // Ensures the authority is enabled, not expired and allowed to call the instruction
+    pub fn check(&self, permission: u8, now: i64) -> Result<()> {
+        if !self.enabled || self.permissions & permission == 0 {
+            return err!(ExtError::NotAuthorized);
+        }
+
+        if self.expires_at != 0 && now >= self.expires_at {
+            return err!(ExtError::Expired);
+        }
+
+        Ok(())
+    }
+}
+
+#[constant]
+pub const VOUCHER_NONCE_SEED: &[u8] = b"voucher_nonce";
+
+// Signed off-chain by a wrap authority to let a user wrap without the authority co-signing.
+// The borsh encoding of the voucher is the signed message.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct WrapVoucher {
+    pub ext_mint: Pubkey,       // extension the voucher can be used on
+    pub wrap_authority: Pubkey, // wrap authority that signed the voucher
+    pub user: Pubkey,           // token authority allowed to redeem the voucher
+    pub max_amount: u64,        // max amount of M that can be wrapped
+    pub expires_at: i64,        // timestamp the voucher can no longer be redeemed
+    pub nonce: u64,             // each nonce of a wrap authority can only be redeemed once
+}
+
+// Marks the nonce of a voucher as redeemed, the account is never closed so the voucher can't be replayed
+#[account]
+pub struct VoucherNonce {
+    pub bump: u8,
+    pub wrap_authority: Pubkey,
+    pub nonce: u64,
+}
+
+impl VoucherNonce {
+    pub fn size() -> usize {
+        8 + // discriminator
+        1 + // bump
+        32 + // wrap_authority
+        8 // nonce
+    }
+}
+
+// Privileges delegated by the admin, so that no single key controls every operation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    FeeManager,           // can set the fee
+    WrapAuthorityManager, // can add and remove wrap authorities
+    FeeClaimer,           // can claim fees
+    Pauser,               // can pause the extension
+    ComplianceOfficer,    // can restrict token accounts
+}
+
+// Each role is held by a single key, the default key means the role is not granted
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct Roles {
+    pub fee_manager: Pubkey,
+    pub wrap_authority_manager: Pubkey,
+    pub fee_claimer: Pubkey,
+    pub pauser: Pubkey,
+    pub compliance_officer: Pubkey,
+}
+
+impl Roles {
+    pub fn space() -> usize {
+        5 * 32
+    }
+
+    pub fn get(&self, role: Role) -> &Pubkey {
+        match role {
+            Role::FeeManager => &self.fee_manager,
+            Role::WrapAuthorityManager => &self.wrap_authority_manager,
+            Role::FeeClaimer => &self.fee_claimer,
+            Role::Pauser => &self.pauser,
+            Role::ComplianceOfficer => &self.compliance_officer,
+        }
+    }
+
+    pub fn get_mut(&mut self, role: Role) -> &mut Pubkey {
+        match role {
+            Role::FeeManager => &mut self.fee_manager,
+            Role::WrapAuthorityManager => &mut self.wrap_authority_manager,
+            Role::FeeClaimer => &mut self.fee_claimer,
+            Role::Pauser => &mut self.pauser,
+            Role::ComplianceOfficer => &mut self.compliance_officer,
+        }
+    }
+
+    pub fn has(&self, role: Role, key: &Pubkey) -> bool {
+        *key != Pubkey::default() && self.get(role) == key
+    }
+
+    pub fn holds_any(&self, key: &Pubkey) -> bool {
+        [
+            Role::FeeManager,
+            Role::WrapAuthorityManager,
+            Role::FeeClaimer,
+            Role::Pauser,
+            Role::ComplianceOfficer,
+        ]
+        .into_iter()
+        .any(|role| self.has(role, key))
     }
 }
 
@@ -39,30 +344,756 @@ pub const MINT_AUTHORITY_SEED: &[u8] = b"mint_authority";
 #[constant]
 pub const M_VAULT_SEED: &[u8] = b"m_vault";
 
-cfg_if! {
-    if #[cfg(feature = "scaled-ui")] {
-        #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-        pub struct YieldConfig {
-            pub fee_bps: u64, // fee in basis points
-            pub last_m_index: u64, // last m index
-            pub last_ext_index: u64, // last ext index
+#[constant]
+pub const EXT_EARNER_SEED: &[u8] = b"earner";
+
+#[constant]
+pub const DISTRIBUTION_SEED: &[u8] = b"distribution";
+
+// The yield mode is chosen at initialization and can't be changed afterwards.
+// Instructions that don't apply to the mode of the extension are rejected.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum YieldMode {
+    NoYield,
+    ScaledUi { fee_bps: u64 },
+    YieldToOne { yield_recipient: Pubkey },
+    EarnerManager { fee_recipient: Pubkey },
+    ClaimableYield,
+    InterestBearing { fee_bps: u64 },
+    MerkleDistribution,
+    WeightedSplit { beneficiaries: Vec<Beneficiary> },
+}
+
+// Mode specific state
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum YieldConfig {
+    NoYield,
+    ScaledUi(ScaledUiConfig),
+    YieldToOne(YieldToOneConfig),
+    EarnerManager(EarnerManagerConfig),
+    ClaimableYield(ClaimableYieldConfig),
+    InterestBearing(InterestBearingConfig),
+    MerkleDistribution(MerkleDistributionConfig),
+    WeightedSplit(WeightedSplitConfig),
+}
+
+impl YieldConfig {
+    // Space is reserved for the largest mode so the layout of the global account is the same for all of them
+    pub fn space() -> usize {
+        1 + // enum variant
+        [
+            ScaledUiConfig::space(),
+            YieldToOneConfig::space(),
+            EarnerManagerConfig::space(),
+            ClaimableYieldConfig::space(),
+            InterestBearingConfig::space(),
+            MerkleDistributionConfig::space(),
+            WeightedSplitConfig::space(),
+        ]
+        .into_iter()
+        .max()
+        .unwrap_or_default()
+    }
+
+    // Excess M in the vault that is owed to holders, e.g. yield accrued to earners
+    // or locked in distributions, and can't be claimed as fees
+    pub fn reserved_collateral(&self) -> Result<u64> {
+        match self {
+            YieldConfig::EarnerManager(config) => config.earner_yield_owed(config.last_ext_index),
+            YieldConfig::ClaimableYield(config) => config.earner_yield_owed(config.last_ext_index),
+            YieldConfig::MerkleDistribution(config) => Ok(config.locked_amount),
+            _ => Ok(0),
+        }
+    }
+
+    pub fn yield_to_one(&self) -> Result<&YieldToOneConfig> {
+        match self {
+            YieldConfig::YieldToOne(config) => Ok(config),
+            _ => err!(ExtError::UnsupportedYieldMode),
+        }
+    }
+
+    pub fn yield_to_one_mut(&mut self) -> Result<&mut YieldToOneConfig> {
+        match self {
+            YieldConfig::YieldToOne(config) => Ok(config),
+            _ => err!(ExtError::UnsupportedYieldMode),
+        }
+    }
+
+    pub fn earner_manager(&self) -> Result<&EarnerManagerConfig> {
+        match self {
+            YieldConfig::EarnerManager(config) => Ok(config),
+            _ => err!(ExtError::UnsupportedYieldMode),
+        }
+    }
+
+    pub fn earner_manager_mut(&mut self) -> Result<&mut EarnerManagerConfig> {
+        match self {
+            YieldConfig::EarnerManager(config) => Ok(config),
+            _ => err!(ExtError::UnsupportedYieldMode),
+        }
+    }
+
+    pub fn claimable_yield(&self) -> Result<&ClaimableYieldConfig> {
+        match self {
+            YieldConfig::ClaimableYield(config) => Ok(config),
+            _ => err!(ExtError::UnsupportedYieldMode),
+        }
+    }
+
+    pub fn claimable_yield_mut(&mut self) -> Result<&mut ClaimableYieldConfig> {
+        match self {
+            YieldConfig::ClaimableYield(config) => Ok(config),
+            _ => err!(ExtError::UnsupportedYieldMode),
         }
+    }
 
-        impl YieldConfig {
-            pub fn space() -> usize {
-                8 + // fee_bps
-                8 + // last_m_index
-                8 // last_ext_index
-            }
+    pub fn merkle_distribution(&self) -> Result<&MerkleDistributionConfig> {
+        match self {
+            YieldConfig::MerkleDistribution(config) => Ok(config),
+            _ => err!(ExtError::UnsupportedYieldMode),
         }
-    } else {
-        #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-        pub struct YieldConfig {}
+    }
 
-        impl YieldConfig {
-            pub fn space() -> usize {
-                0 // no space needed for yield config in no-yield mode
-            }
+    pub fn merkle_distribution_mut(&mut self) -> Result<&mut MerkleDistributionConfig> {
+        match self {
+            YieldConfig::MerkleDistribution(config) => Ok(config),
+            _ => err!(ExtError::UnsupportedYieldMode),
         }
     }
+
+    pub fn weighted_split(&self) -> Result<&WeightedSplitConfig> {
+        match self {
+            YieldConfig::WeightedSplit(config) => Ok(config),
+            _ => err!(ExtError::UnsupportedYieldMode),
+        }
+    }
+
+    pub fn weighted_split_mut(&mut self) -> Result<&mut WeightedSplitConfig> {
+        match self {
+            YieldConfig::WeightedSplit(config) => Ok(config),
+            _ => err!(ExtError::UnsupportedYieldMode),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ScaledUiConfig {
+    pub fee_bps: u64,        // fee in basis points
+    pub last_m_index: u64,   // last m index
+    pub last_ext_index: u64, // last ext index
+}
+
+impl ScaledUiConfig {
+    pub fn space() -> usize {
+        8 + // fee_bps
+        8 + // last_m_index
+        8 // last_ext_index
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct InterestBearingConfig {
+    pub fee_bps: u64,          // fee in basis points
+    pub last_m_index: u64,     // last m index
+    pub last_m_timestamp: u64, // timestamp of the last m index
+    pub last_ext_index: u64,   // last ext index
+}
+
+impl InterestBearingConfig {
+    pub fn space() -> usize {
+        8 + // fee_bps
+        8 + // last_m_index
+        8 + // last_m_timestamp
+        8 // last_ext_index
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct YieldToOneConfig {
+    pub yield_recipient: Pubkey, // ext token account that receives all yield
+}
+
+impl YieldToOneConfig {
+    pub fn space() -> usize {
+        32 // yield_recipient
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct EarnerManagerConfig {
+    pub fee_recipient: Pubkey, // ext token account that receives the earner fees
+    pub last_m_index: u64,     // last m index
+    pub last_ext_index: u64, // index used to accrue yield to earners, only increases while the vault is earning
+    pub earner_principal: u64, // sum of the principal of all earners
+    pub earner_basis: u64, // sum of the amounts each earner's principal was worth at their last update
+}
+
+impl EarnerManagerConfig {
+    pub fn space() -> usize {
+        32 + // fee_recipient
+        8 + // last_m_index
+        8 + // last_ext_index
+        8 + // earner_principal
+        8 // earner_basis
+    }
+
+    // The yield accrued by all earners that has not been claimed yet.
+    // Rounded up so the amount reserved for earners is never too small.
+    pub fn earner_yield_owed(&self, index: u64) -> Result<u64> {
+        Ok(principal_to_amount_up(self.earner_principal, index)?.saturating_sub(self.earner_basis))
+    }
+}
+
+#[account]
+pub struct ManagedEarner {
+    pub bump: u8,
+    pub token_account: Pubkey, // ext token account that earns yield
+    pub fee_bps: u64,          // fee charged on this earner's yield in basis points
+    pub principal: u64,        // balance of the token account at the last update, in principal
+    pub last_index: u64,       // index at the last update
+}
+
+impl ManagedEarner {
+    pub fn size() -> usize {
+        8 + // discriminator
+        1 + // bump
+        32 + // token_account
+        8 + // fee_bps
+        8 + // principal
+        8 // last_index
+    }
+
+    // The amount the earner's principal was worth when it was last updated
+    pub fn basis(&self) -> Result<u64> {
+        principal_to_amount_up(self.principal, self.last_index)
+    }
+
+    // The yield accrued since the last update
+    pub fn accrued_yield(&self, index: u64, balance: u64) -> Result<u64> {
+        get_accrued_yield(self.principal, self.last_index, index, balance)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ClaimableYieldConfig {
+    pub last_m_index: u64,         // last m index
+    pub last_ext_index: u64, // index used to accrue yield to earners, only increases while the vault is earning
+    pub earner_principal: u64, // sum of the principal of all earners
+    pub earner_basis: u64, // sum of the amounts each earner's principal was worth at their last update
+    pub earner_pending_yield: u64, // sum of the checkpointed yield that hasn't been claimed
+}
+
+impl ClaimableYieldConfig {
+    pub fn space() -> usize {
+        8 + // last_m_index
+        8 + // last_ext_index
+        8 + // earner_principal
+        8 + // earner_basis
+        8 // earner_pending_yield
+    }
+
+    // The yield accrued by all earners that has not been claimed yet.
+    // Rounded up so the amount reserved for earners is never too small.
+    pub fn earner_yield_owed(&self, index: u64) -> Result<u64> {
+        principal_to_amount_up(self.earner_principal, index)?
+            .saturating_sub(self.earner_basis)
+            .checked_add(self.earner_pending_yield)
+            .ok_or(ExtError::MathOverflow.into())
+    }
+}
+
+#[account]
+pub struct Earner {
+    pub bump: u8,
+    pub token_account: Pubkey, // ext token account that earns yield
+    pub principal: u64,        // balance of the token account at the last checkpoint, in principal
+    pub last_index: u64,       // index at the last checkpoint
+    pub pending_yield: u64,    // yield checkpointed but not claimed yet
+}
+
+impl Earner {
+    pub fn size() -> usize {
+        8 + // discriminator
+        1 + // bump
+        32 + // token_account
+        8 + // principal
+        8 + // last_index
+        8 // pending_yield
+    }
+
+    // The amount the earner's principal was worth when it was last checkpointed
+    pub fn basis(&self) -> Result<u64> {
+        principal_to_amount_up(self.principal, self.last_index)
+    }
+
+    // The yield accrued since the last checkpoint
+    pub fn accrued_yield(&self, index: u64, balance: u64) -> Result<u64> {
+        get_accrued_yield(self.principal, self.last_index, index, balance)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct Beneficiary {
+    pub token_account: Pubkey, // ext token account that receives the share
+    pub weight_bps: u64,       // share of the yield in bps
+}
+
+impl Beneficiary {
+    pub fn size() -> usize {
+        32 + // token_account
+        8 // weight_bps
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct WeightedSplitConfig {
+    pub beneficiaries: Vec<Beneficiary>,
+}
+
+impl WeightedSplitConfig {
+    // Space is reserved for the maximum number of beneficiaries so the set can be updated without a realloc
+    pub fn space() -> usize {
+        4 + // length of beneficiaries vector
+        MAX_BENEFICIARIES * Beneficiary::size()
+    }
+
+    pub fn validate_beneficiaries(beneficiaries: &[Beneficiary]) -> Result<()> {
+        if beneficiaries.is_empty() || beneficiaries.len() > MAX_BENEFICIARIES {
+            return err!(ExtError::InvalidParam);
+        }
+
+        // Each token account can only appear once
+        let token_accounts: HashSet<Pubkey> =
+            beneficiaries.iter().map(|b| b.token_account).collect();
+        if token_accounts.len() < beneficiaries.len() {
+            return err!(ExtError::InvalidParam);
+        }
+
+        // The weights must sum to 100%
+        let total_weight = beneficiaries
+            .iter()
+            .try_fold(0u64, |acc, b| acc.checked_add(b.weight_bps))
+            .ok_or(ExtError::MathOverflow)?;
+        if total_weight != ONE_HUNDRED_PERCENT_U64 {
+            return err!(ExtError::InvalidParam);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MerkleDistributionConfig {
+    pub distribution_count: u64, // number of distributions created, used as the index of the next one
+    pub locked_amount: u64,      // amount locked in distributions that hasn't been claimed or swept
+}
+
+impl MerkleDistributionConfig {
+    pub fn space() -> usize {
+        8 + // distribution_count
+        8 // locked_amount
+    }
+}
+
+#[account]
+pub struct Distribution {
+    pub bump: u8,
+    pub index: u64,            // index of the distribution, used in the seeds
+    pub merkle_root: [u8; 32], // root of the tree of (leaf index, claimant, amount) leaves
+    pub total_amount: u64,     // amount of excess M locked for this distribution
+    pub claimed_amount: u64,   // amount claimed so far
+    pub expires_at: i64,       // unclaimed amounts can be swept after this timestamp
+    pub num_leaves: u32,       // number of leaves in the tree
+    pub claimed: Vec<u8>,      // bitmap of claimed leaves
+}
+
+impl Distribution {
+    pub fn size(num_leaves: u32) -> usize {
+        8 + // discriminator
+        1 + // bump
+        8 + // index
+        32 + // merkle_root
+        8 + // total_amount
+        8 + // claimed_amount
+        8 + // expires_at
+        4 + // num_leaves
+        4 + // length of claimed vector
+        Self::bitmap_len(num_leaves) // one bit per leaf
+    }
+
+    pub fn bitmap_len(num_leaves: u32) -> usize {
+        (num_leaves as usize).div_ceil(8)
+    }
+
+    pub fn is_claimed(&self, leaf_index: u32) -> bool {
+        self.claimed[leaf_index as usize / 8] & (1 << (leaf_index % 8)) != 0
+    }
+
+    pub fn set_claimed(&mut self, leaf_index: u32) {
+        self.claimed[leaf_index as usize / 8] |= 1 << (leaf_index % 8);
+    }
+}
+
+#[constant]
+pub const MULTISIG_SEED: &[u8] = b"multisig";
+
+#[constant]
+pub const MULTISIG_AUTHORITY_SEED: &[u8] = b"multisig_authority";
+
+#[constant]
+pub const PROPOSAL_SEED: &[u8] = b"proposal";
+
+// M-of-N approval of admin actions. Roles are granted to the multisig authority PDA,
+// which signs the actions of proposals once enough signers have approved them.
+#[account]
+pub struct Multisig {
+    pub bump: u8,
+    pub authority_bump: u8,   // bump of the multisig authority PDA
+    pub threshold: u8,        // approvals required to execute a proposal
+    pub proposal_count: u64,  // index of the next proposal, used in the seeds
+    pub signers: Vec<Pubkey>, // accounts that can create and approve proposals
+}
+
+impl Multisig {
+    pub fn size() -> usize {
+        8 + // discriminator
+        1 + // bump
+        1 + // authority_bump
+        1 + // threshold
+        8 + // proposal_count
+        4 + // length of signers vector
+        MAX_MULTISIG_SIGNERS * 32 // each Pubkey is 32 bytes
+    }
+
+    pub fn approvals(&self, proposal: &Proposal) -> usize {
+        // Approvals of removed signers no longer count
+        proposal
+            .approvals
+            .iter()
+            .filter(|approver| self.signers.contains(approver))
+            .count()
+    }
+}
+
+// Admin actions that can be executed by the multisig, each one is executed by
+// calling the instruction of the same name with the multisig authority as signer
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum MultisigAction {
+    SetFee { fee_bps: u64 },
+    AddWrapAuthority { wrap_authority: Pubkey },
+    RemoveWrapAuthority { wrap_authority: Pubkey },
+    ClaimFees,
+}
+
+impl MultisigAction {
+    pub fn space() -> usize {
+        1 + 32 // discriminant and largest variant
+    }
+}
+
+#[account]
+pub struct Proposal {
+    pub bump: u8,
+    pub index: u64,             // index of the proposal, used in the seeds
+    pub proposer: Pubkey,       // signer that created the proposal
+    pub action: MultisigAction, // action executed once approved
+    pub accounts: Vec<Pubkey>,  // accounts the action is executed with
+    pub approvals: Vec<Pubkey>, // signers that approved the proposal
+    pub expires_at: i64,        // the proposal can't be approved or executed after this timestamp
+    pub executed: bool,
+}
+
+impl Proposal {
+    pub fn size(num_accounts: usize) -> usize {
+        8 + // discriminator
+        1 + // bump
+        8 + // index
+        32 + // proposer
+        MultisigAction::space() + // action
+        4 + num_accounts * 32 + // accounts
+        4 + MAX_MULTISIG_SIGNERS * 32 + // approvals
+        8 + // expires_at
+        1 // executed
+    }
+}
+
+#[constant]
+pub const TIMELOCK_AUTHORITY_SEED: &[u8] = b"timelock_authority";
+
+#[constant]
+pub const QUEUED_ACTION_SEED: &[u8] = b"queued_action";
+
+// Changes that are queued while the timelock is enabled, each one is executed by
+// calling the instruction of the same name with the timelock authority as signer
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum TimelockAction {
+    SetFee {
+        fee_bps: u64,
+    },
+    AddWrapAuthority {
+        wrap_authority: Pubkey,
+    },
+    ProposeAdmin {
+        new_admin: Pubkey,
+    },
+    SetEarnerFee {
+        token_account: Pubkey,
+        fee_bps: u64,
+    },
+    SetYieldRecipient {
+        yield_recipient: Pubkey,
+    },
+    SetBeneficiaries {
+        beneficiaries: Vec<Beneficiary>,
+    },
+    SetWrapAuthorityPermissions {
+        wrap_authority: Pubkey,
+        permissions: u8,
+        expires_at: i64,
+    },
+    SetOpenMode {
+        open_wrap: bool,
+        open_unwrap: bool,
+    },
+}
+
+impl TimelockAction {
+    pub fn space() -> usize {
+        1 + // discriminant
+        4 + MAX_BENEFICIARIES * Beneficiary::size() // largest variant
+    }
+
+    // Account the change applies to that isn't part of the instruction data, with its
+    // position in the accounts of the instruction, so the change can't be executed on another one
+    pub fn target_account(&self) -> Option<(usize, Pubkey)> {
+        match self {
+            // earner_token_account of SetEarnerFee, after the fee manager and 8 accounts of the claim
+            TimelockAction::SetEarnerFee { token_account, .. } => Some((9, *token_account)),
+            // new_yield_recipient of SetYieldRecipient
+            TimelockAction::SetYieldRecipient { yield_recipient } => Some((3, *yield_recipient)),
+            _ => None,
+        }
+    }
+}
+
+#[account]
+pub struct QueuedAction {
+    pub bump: u8,
+    pub index: u64,             // index of the queued action, used in the seeds
+    pub queued_by: Pubkey,      // signer that queued the action, refunded when it is closed
+    pub action: TimelockAction, // change executed after the eta
+    pub eta: i64,               // timestamp after which anyone can execute the action
+}
+
+impl QueuedAction {
+    pub fn size() -> usize {
+        8 + // discriminator
+        1 + // bump
+        8 + // index
+        32 + // queued_by
+        TimelockAction::space() + // action
+        8 // eta
+    }
+}
+
+#[constant]
+pub const FREEZE_AUTHORITY_SEED: &[u8] = b"freeze_authority";
+
+#[constant]
+pub const PERMANENT_DELEGATE_SEED: &[u8] = b"permanent_delegate";
+
+#[constant]
+pub const BLOCKLIST_SEED: &[u8] = b"blocklist";
+
+// Marks a wallet as blocklisted, the account only exists while the wallet is blocked
+#[account]
+pub struct BlocklistEntry {
+    pub bump: u8,
+    pub wallet: Pubkey,
+}
+
+impl BlocklistEntry {
+    pub fn size() -> usize {
+        8 + // discriminator
+        1 + // bump
+        32 // wallet
+    }
+}
+
+#[constant]
+pub const ALLOWLIST_SEED: &[u8] = b"allowlist";
+
+// Marks a wallet as allowed to hold ext tokens when the extension requires an allowlist,
+// the account only exists while the wallet is allowed
+#[account]
+pub struct AllowlistEntry {
+    pub bump: u8,
+    pub wallet: Pubkey,
+}
+
+impl AllowlistEntry {
+    pub fn size() -> usize {
+        8 + // discriminator
+        1 + // bump
+        32 // wallet
+    }
+}
+
+#[constant]
+pub const COMPLIANCE_FREEZE_SEED: &[u8] = b"compliance_freeze";
+
+// Marks a token account frozen by the compliance officer, the account only exists while it is frozen.
+// approve_holder can't thaw these token accounts, only thaw_account can.
+#[account]
+pub struct ComplianceFreeze {
+    pub bump: u8,
+    pub token_account: Pubkey,
+}
+
+impl ComplianceFreeze {
+    pub fn size() -> usize {
+        8 + // discriminator
+        1 + // bump
+        32 // token_account
+    }
+}
+
+#[constant]
+pub const RATE_LIMIT_SEED: &[u8] = b"rate_limit";
+
+// Rate limit window configured by the admin
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct RateLimitWindow {
+    pub capacity: u64, // max net volume of M over the window
+    pub duration: i64, // seconds to refill the full capacity
+}
+
+// Token bucket that refills linearly up to its capacity over the window duration
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct RateLimitBucket {
+    pub capacity: u64,
+    pub duration: i64,
+    pub available: u64, // volume available at last_update
+    pub last_update: i64,
+}
+
+impl RateLimitBucket {
+    pub fn space() -> usize {
+        4 * 8
+    }
+
+    fn refill(&mut self, now: i64) {
+        let elapsed = now.saturating_sub(self.last_update).max(0) as u128;
+        let refilled = elapsed * self.capacity as u128 / self.duration as u128;
+
+        self.available = (self.available as u128 + refilled).min(self.capacity as u128) as u64;
+        self.last_update = now;
+    }
+}
+
+// Limits the net volume of wrap and unwrap over rolling windows, the account
+// only exists while the admin has rate limits configured for the extension
+#[account]
+pub struct RateLimit {
+    pub bump: u8,
+    pub wrap_buckets: Vec<RateLimitBucket>,
+    pub unwrap_buckets: Vec<RateLimitBucket>,
+}
+
+impl RateLimit {
+    pub fn size() -> usize {
+        8 + // discriminator
+        1 + // bump
+        2 * (4 + MAX_RATE_LIMIT_WINDOWS * RateLimitBucket::space()) // wrap and unwrap buckets
+    }
+
+    // Replaces the windows, volume already used in a window carries over if its
+    // position is unchanged so updating the limits doesn't refill the buckets
+    pub fn set_windows(
+        &mut self,
+        wrap_windows: &[RateLimitWindow],
+        unwrap_windows: &[RateLimitWindow],
+        now: i64,
+    ) {
+        let to_buckets = |old: &[RateLimitBucket], windows: &[RateLimitWindow]| {
+            windows
+                .iter()
+                .enumerate()
+                .map(|(i, window)| {
+                    let available = old.get(i).map_or(window.capacity, |bucket| {
+                        let mut bucket = *bucket;
+                        bucket.refill(now);
+                        bucket.available.min(window.capacity)
+                    });
+
+                    RateLimitBucket {
+                        capacity: window.capacity,
+                        duration: window.duration,
+                        available,
+                        last_update: now,
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+
+        self.wrap_buckets = to_buckets(&self.wrap_buckets, wrap_windows);
+        self.unwrap_buckets = to_buckets(&self.unwrap_buckets, unwrap_windows);
+    }
+
+    // Wrapping uses up wrap capacity and frees the same amount of unwrap capacity,
+    // so the limits apply to the net volume in each direction
+    pub fn record_wrap(&mut self, amount: u64, now: i64) -> Result<()> {
+        Self::consume(&mut self.wrap_buckets, amount, now)?;
+        Self::release(&mut self.unwrap_buckets, amount, now);
+
+        Ok(())
+    }
+
+    pub fn record_unwrap(&mut self, amount: u64, now: i64) -> Result<()> {
+        Self::consume(&mut self.unwrap_buckets, amount, now)?;
+        Self::release(&mut self.wrap_buckets, amount, now);
+
+        Ok(())
+    }
+
+    fn consume(buckets: &mut [RateLimitBucket], amount: u64, now: i64) -> Result<()> {
+        for bucket in buckets.iter_mut() {
+            bucket.refill(now);
+
+            bucket.available = bucket
+                .available
+                .checked_sub(amount)
+                .ok_or(ExtError::RateLimitExceeded)?;
+        }
+
+        Ok(())
+    }
+
+    fn release(buckets: &mut [RateLimitBucket], amount: u64, now: i64) {
+        for bucket in buckets.iter_mut() {
+            bucket.refill(now);
+
+            bucket.available = bucket.available.saturating_add(amount).min(bucket.capacity);
+        }
+    }
+}
+
+#[constant]
+pub const TOMBSTONE_SEED: &[u8] = b"tombstone";
+
+// Created by decommission when the global account is closed, so the extension
+// can't be initialized again with the same PDAs
+#[account]
+pub struct Tombstone {
+    pub bump: u8,
+    pub ext_mint: Pubkey,
+}
+
+impl Tombstone {
+    pub fn size() -> usize {
+        8 + // discriminator
+        1 + // bump
+        32 // ext_mint
+    }
 }
@@ -0,0 +1,76 @@
+// external dependencies
+use anchor_lang::{
+    prelude::*,
+    system_program::{
+        allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer,
+    },
+};
+
+// Creates a rent exempt account owned by the program at a PDA, for accounts that can't
+// be declared with `init` because their number is only known from the instruction data.
+// Accounts that were already funded are topped up and allocated like `init` would.
+pub fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    space: usize,
+    account_seeds: &[&[&[u8]]],
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let lamports = account.lamports();
+
+    if lamports == 0 {
+        create_account(
+            CpiContext::new_with_signer(
+                system_program.to_account_info(),
+                CreateAccount {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+                account_seeds,
+            ),
+            rent,
+            space as u64,
+            &crate::ID,
+        )?;
+
+        return Ok(());
+    }
+
+    if rent > lamports {
+        transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            rent - lamports,
+        )?;
+    }
+
+    allocate(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            Allocate {
+                account_to_allocate: account.clone(),
+            },
+            account_seeds,
+        ),
+        space as u64,
+    )?;
+
+    assign(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            Assign {
+                account_to_assign: account.clone(),
+            },
+            account_seeds,
+        ),
+        &crate::ID,
+    )?;
+
+    Ok(())
+}
@@ -1,82 +1,226 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, solana_program::program::invoke_signed};
 use anchor_spl::token_interface::{Mint, Token2022};
-use cfg_if::cfg_if;
 use earn::state::Global as EarnGlobal;
-use spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use spl_token_2022::extension::{
+    default_account_state::DefaultAccountState,
+    interest_bearing_mint::InterestBearingConfig as InterestBearingMintConfig,
+    mint_close_authority::MintCloseAuthority, permanent_delegate::PermanentDelegate,
+    scaled_ui_amount::ScaledUiAmountConfig, BaseStateWithExtensions, StateWithExtensions,
+};
+
+use crate::{
+    constants::{
+        FIXED_POINT_BITS, FIXED_POINT_ONE, INDEX_SCALE_F64, INDEX_SCALE_U64, LN_2_FIXED,
+        ONE_HUNDRED_PERCENT_U64, SECONDS_PER_YEAR,
+    },
+    errors::ExtError,
+    state::{ExtGlobal, InterestBearingConfig, ScaledUiConfig, YieldConfig},
+};
 
-use crate::{constants::INDEX_SCALE_U64, errors::ExtError, state::ExtGlobal};
+pub fn sync_multiplier<'info>(
+    ext_mint: &mut InterfaceAccount<'info, Mint>,
+    ext_global_account: &mut Account<'info, ExtGlobal>,
+    m_earn_global_account: &Account<'info, EarnGlobal>,
+    authority: &AccountInfo<'info>,
+    authority_seeds: &[&[&[u8]]],
+    token_program: &Program<'info, Token2022>,
+    m_earner_account: &AccountInfo<'info>,
+) -> Result<u64> {
+    match &mut ext_global_account.yield_config {
+        YieldConfig::ScaledUi(config) => sync_scaled_ui(
+            config,
+            ext_mint,
+            m_earn_global_account,
+            authority,
+            authority_seeds,
+            token_program,
+            m_earner_account,
+        ),
+        YieldConfig::InterestBearing(config) => sync_interest_bearing(
+            config,
+            ext_mint,
+            m_earn_global_account,
+            authority,
+            authority_seeds,
+            token_program,
+            m_earner_account,
+        ),
+        YieldConfig::EarnerManager(_) | YieldConfig::ClaimableYield(_) => {
+            // Ext tokens are 1:1 with M tokens, but we keep the earner index in sync
+            // so the yield owed to earners is accounted for
+            sync_earner_index(ext_global_account, m_earn_global_account, m_earner_account)?;
+
+            Ok(INDEX_SCALE_U64)
+        }
+        // Ext tokens are 1:1 with M tokens and we don't need to sync this
+        _ => Ok(INDEX_SCALE_U64),
+    }
+}
+
+fn sync_scaled_ui<'info>(
+    config: &mut ScaledUiConfig,
+    ext_mint: &mut InterfaceAccount<'info, Mint>,
+    m_earn_global_account: &Account<'info, EarnGlobal>,
+    authority: &AccountInfo<'info>,
+    authority_seeds: &[&[&[u8]]],
+    token_program: &Program<'info, Token2022>,
+    m_earner_account: &AccountInfo<'info>,
+) -> Result<u64> {
+    // Get the current index and timestamp from the m_earn_global_account and cached values
+    let (index, timestamp): (u64, u64) = get_latest_index_and_timestamp(
+        config.last_m_index,
+        config.last_ext_index,
+        config.fee_bps,
+        m_earn_global_account,
+    )?;
+
+    // Compare against the current ext index, if the same, return early
+    if index == config.last_ext_index {
+        return Ok(config.last_ext_index);
+    }
 
-cfg_if! {
-    if #[cfg(feature = "scaled-ui")] {
-        use anchor_lang::solana_program::program::invoke_signed;
-        use spl_token_2022::extension::scaled_ui_amount::ScaledUiAmountConfig;
-        use crate::constants::{INDEX_SCALE_F64, ONE_HUNDRED_PERCENT_F64};
+    // Check if the extension is earning, i.e. that it has an active earner account.
+    // If it is earning, update the M index and the multiplier.
+    // If not, only update the M index. The reason is so that yield accrual can
+    // start again from a future point without issuing retroactive yield.
+    if !m_earner_account.data_is_empty() {
+        let multiplier: f64 = index as f64 / INDEX_SCALE_F64;
+
+        // Update the multiplier and timestamp in the mint account
+        invoke_signed(
+            &spl_token_2022::extension::scaled_ui_amount::instruction::update_multiplier(
+                &token_program.key(),
+                &ext_mint.key(),
+                &authority.key(),
+                &[],
+                multiplier,
+                timestamp as i64,
+            )?,
+            &[ext_mint.to_account_info(), authority.clone()],
+            authority_seeds,
+        )?;
+
+        // Reload the mint account so the new multiplier is reflected
+        ext_mint.reload()?;
+
+        // Update the last m index and last ext index in the global account
+        config.last_m_index = m_earn_global_account.index;
+        config.last_ext_index = index;
+
+        // Return the latest ext index
+        Ok(index)
+    } else {
+        // If not earning, just update the last m index
+        config.last_m_index = m_earn_global_account.index;
+
+        // Return the current ext multiplier
+        Ok(config.last_ext_index)
     }
 }
 
-#[allow(unused_variables)]
-pub fn sync_multiplier<'info>(
+fn sync_interest_bearing<'info>(
+    config: &mut InterestBearingConfig,
     ext_mint: &mut InterfaceAccount<'info, Mint>,
-    ext_global_account: &mut Account<'info, ExtGlobal>,
     m_earn_global_account: &Account<'info, EarnGlobal>,
     authority: &AccountInfo<'info>,
     authority_seeds: &[&[&[u8]]],
     token_program: &Program<'info, Token2022>,
     m_earner_account: &AccountInfo<'info>,
 ) -> Result<u64> {
-    cfg_if! {
-        if #[cfg(feature = "scaled-ui")] {
-            // Get the current index and timestamp from the m_earn_global_account and cached values
-            let (index, timestamp): (u64, u64) =
-                get_latest_index_and_timestamp(ext_global_account, m_earn_global_account)?;
-
-            // Compare against the current ext index, if the same, return early
-            if index == ext_global_account.yield_config.last_ext_index {
-                return Ok(ext_global_account.yield_config.last_ext_index);
-            }
-
-            // Check if the extension is earning, i.e. that it has an active earner account.
-            // If it is earning, update the M index and the multiplier.
-            // If not, only update the M index. The reason is so that yield accrual can
-            // start again from a future point without issuing retroactive yield.
-            if !m_earner_account.data_is_empty() {
-                let multiplier: f64 = index as f64 / INDEX_SCALE_F64;
-
-                // Update the multiplier and timestamp in the mint account
-                invoke_signed(
-                    &spl_token_2022::extension::scaled_ui_amount::instruction::update_multiplier(
-                        &token_program.key(),
-                        &ext_mint.key(),
-                        &authority.key(),
-                        &[],
-                        multiplier,
-                        timestamp as i64,
-                    )?,
-                    &[ext_mint.to_account_info(), authority.clone()],
-                    authority_seeds,
-                )?;
-
-                // Reload the mint account so the new multiplier is reflected
-                ext_mint.reload()?;
-
-                // Update the last m index and last ext index in the global account
-                ext_global_account.yield_config.last_m_index = m_earn_global_account.index;
-                ext_global_account.yield_config.last_ext_index = index;
-
-                // Return the latest ext index
-                return Ok(index);
-            } else {
-                // If not earning, just update the last m index
-                ext_global_account.yield_config.last_m_index = m_earn_global_account.index;
-
-                // Return the current ext multiplier
-                return Ok(ext_global_account.yield_config.last_ext_index);
-            }
-        } else {
-            // Ext tokens are 1:1 with M tokens and we don't need to sync this
-            return Ok(INDEX_SCALE_U64);
+    // Get the current index and timestamp from the m_earn_global_account and cached values
+    let (index, timestamp): (u64, u64) = get_latest_index_and_timestamp(
+        config.last_m_index,
+        config.last_ext_index,
+        config.fee_bps,
+        m_earn_global_account,
+    )?;
+
+    // If the M index hasn't changed, there is nothing to update
+    if m_earn_global_account.index == config.last_m_index {
+        return Ok(config.last_ext_index);
+    }
+
+    // The ext index is used for all conversions, as with the scaled-ui mode.
+    // The interest rate on the mint is only used by wallets to display the balance,
+    // so it is set to the annualized rate the ext index grew at since the last update.
+    // If the extension isn't earning, the rate is set to zero and only the M index is updated.
+    let elapsed = timestamp.saturating_sub(config.last_m_timestamp);
+    let earning = !m_earner_account.data_is_empty();
+
+    let rate: Option<i16> = if earning {
+        calculate_rate_bps(config.last_ext_index, index, elapsed)?
+    } else {
+        Some(0)
+    };
+
+    // Update the rate in the mint account if it changed
+    if let Some(rate) = rate {
+        if i16::from(get_interest_bearing_config(ext_mint)?.current_rate) != rate {
+            invoke_signed(
+                &spl_token_2022::extension::interest_bearing_mint::instruction::update_rate(
+                    &token_program.key(),
+                    &ext_mint.key(),
+                    &authority.key(),
+                    &[],
+                    rate,
+                )?,
+                &[ext_mint.to_account_info(), authority.clone()],
+                authority_seeds,
+            )?;
+
+            // Reload the mint account so the new rate is reflected
+            ext_mint.reload()?;
+        }
+    }
+
+    config.last_m_index = m_earn_global_account.index;
+    config.last_m_timestamp = timestamp;
+
+    if earning {
+        config.last_ext_index = index;
+    }
+
+    Ok(config.last_ext_index)
+}
+
+pub fn sync_earner_index<'info>(
+    ext_global_account: &mut Account<'info, ExtGlobal>,
+    m_earn_global_account: &Account<'info, EarnGlobal>,
+    m_earner_account: &AccountInfo<'info>,
+) -> Result<u64> {
+    let (last_m_index, last_ext_index) = match &mut ext_global_account.yield_config {
+        YieldConfig::EarnerManager(config) => {
+            (&mut config.last_m_index, &mut config.last_ext_index)
+        }
+        YieldConfig::ClaimableYield(config) => {
+            (&mut config.last_m_index, &mut config.last_ext_index)
         }
+        _ => return err!(ExtError::UnsupportedYieldMode),
+    };
+
+    // Get the current index from the m_earn_global_account and cached values.
+    // The earner index of the 1:1 modes is not reduced by a fee.
+    let (index, _): (u64, u64) =
+        get_latest_index_and_timestamp(*last_m_index, *last_ext_index, 0, m_earn_global_account)?;
+
+    // Same as the scaled-ui multiplier, the earner index only increases
+    // while the extension is earning so no retroactive yield is issued
+    if !m_earner_account.data_is_empty() {
+        *last_ext_index = index;
     }
+    *last_m_index = m_earn_global_account.index;
+
+    Ok(*last_ext_index)
+}
+
+// The yield accrued on an earner's principal between its last update and the provided index.
+// Only the part of the principal that is still held by the token account earns, so tokens
+// transferred out since the last update don't keep accruing yield.
+pub fn get_accrued_yield(principal: u64, last_index: u64, index: u64, balance: u64) -> Result<u64> {
+    let principal = principal.min(amount_to_principal_down(balance, last_index)?);
+
+    Ok(principal_to_amount_down(principal, index)?
+        .saturating_sub(principal_to_amount_up(principal, last_index)?))
 }
 
 pub fn amount_to_principal_down(amount: u64, index: u64) -> Result<u64> {
@@ -160,6 +304,22 @@ pub fn principal_to_amount_up(principal: u64, index: u64) -> Result<u64> {
     Ok(amount)
 }
 
+pub fn get_excess_collateral(vault_m: u64, ext_supply: u64, index: u64) -> Result<(u64, u64)> {
+    // Calculate the required collateral, rounding up to be conservative
+    // This amount will always be greater than what is required
+    // since it allows a rounding error of up to 2e-6
+    let required_m = principal_to_amount_up(ext_supply, index)?;
+
+    // Excess M is the amount of M in the vault above the amount needed to fully collateralize the extension
+    let excess = vault_m
+        .checked_sub(required_m)
+        .ok_or(ExtError::InsufficientCollateral)?; // This shouldn't underflow, but we check for safety
+
+    let excess_principal = amount_to_principal_down(excess, index)?;
+
+    Ok((excess, excess_principal))
+}
+
 pub fn get_mint_extensions<'info>(
     mint: &InterfaceAccount<'info, Mint>,
 ) -> Result<Vec<spl_token_2022::extension::ExtensionType>> {
@@ -173,331 +333,681 @@ pub fn get_mint_extensions<'info>(
     Ok(extensions)
 }
 
-cfg_if! {
-    if #[cfg(feature = "scaled-ui")] {
-        pub fn get_scaled_ui_config<'info>(
-            mint: &InterfaceAccount<'info, Mint>,
-        ) -> Result<ScaledUiAmountConfig> {
-            // Get the mint account data with extensions
-            let account_info = mint.to_account_info();
-            let mint_data = account_info.try_borrow_data()?;
-            let mint_ext_data = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+pub fn get_scaled_ui_config<'info>(
+    mint: &InterfaceAccount<'info, Mint>,
+) -> Result<ScaledUiAmountConfig> {
+    // Get the mint account data with extensions
+    let account_info = mint.to_account_info();
+    let mint_data = account_info.try_borrow_data()?;
+    let mint_ext_data = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
 
-            // Get the scaled UI config extension
-            let scaled_ui_config = mint_ext_data.get_extension::<ScaledUiAmountConfig>()?;
+    // Get the scaled UI config extension
+    let scaled_ui_config = mint_ext_data.get_extension::<ScaledUiAmountConfig>()?;
 
-            Ok(*scaled_ui_config)
-        }
+    Ok(*scaled_ui_config)
+}
+
+pub fn get_default_account_state<'info>(
+    mint: &InterfaceAccount<'info, Mint>,
+) -> Result<DefaultAccountState> {
+    // Get the mint account data with extensions
+    let account_info = mint.to_account_info();
+    let mint_data = account_info.try_borrow_data()?;
+    let mint_ext_data = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
 
+    // Get the default account state extension
+    let default_account_state = mint_ext_data.get_extension::<DefaultAccountState>()?;
 
-        fn get_latest_index_and_timestamp<'info>(
-            ext_global_account: &Account<'info, ExtGlobal>,
-            m_earn_global_account: &Account<'info, EarnGlobal>,
-        ) -> Result<(u64, u64)> {
-            let latest_m_index = m_earn_global_account.index;
-            let cached_m_index = ext_global_account.yield_config.last_m_index;
-            let latest_timestamp = m_earn_global_account.timestamp;
-            let cached_ext_index = ext_global_account.yield_config.last_ext_index;
-
-            // If no change, return early
-            if latest_m_index == cached_m_index {
-                return Ok((cached_ext_index, latest_timestamp));
-            }
-
-            // Calculate the new ext index based on the latest m index and timestamp
-            let new_ext_index = calculate_new_index(
-                cached_ext_index,
-                cached_m_index,
-                latest_m_index,
-                ext_global_account.yield_config.fee_bps
-            )?;
+    Ok(*default_account_state)
+}
 
-            Ok((new_ext_index, latest_timestamp))
-        }
+pub fn get_permanent_delegate<'info>(
+    mint: &InterfaceAccount<'info, Mint>,
+) -> Result<PermanentDelegate> {
+    // Get the mint account data with extensions
+    let account_info = mint.to_account_info();
+    let mint_data = account_info.try_borrow_data()?;
+    let mint_ext_data = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+
+    // Get the permanent delegate extension
+    let permanent_delegate = mint_ext_data.get_extension::<PermanentDelegate>()?;
+
+    Ok(*permanent_delegate)
+}
+
+// Returns the close authority of the mint, None if the mint can't be closed
+pub fn get_mint_close_authority<'info>(
+    mint: &InterfaceAccount<'info, Mint>,
+) -> Result<Option<Pubkey>> {
+    // Get the mint account data with extensions
+    let account_info = mint.to_account_info();
+    let mint_data = account_info.try_borrow_data()?;
+    let mint_ext_data = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+
+    // Get the mint close authority extension if the mint has it
+    let close_authority = mint_ext_data
+        .get_extension::<MintCloseAuthority>()
+        .ok()
+        .and_then(|ext| Option::<Pubkey>::from(ext.close_authority));
 
-        fn calculate_new_index(
-            last_ext_index: u64,
-            last_m_index: u64,
-            new_m_index: u64,
-            fee_bps: u64,
-        ) -> Result<u64> {
-            // Confirm the inputs are in the expected domain.
-            // These checks ensure that the resultant value is >= 1.0,
-            // are allowable values to set as the Token2022 Scaled UI multiplier,
-            // and the ext index is monotonically increasing.
-            // While having the last ext index <= last m index isn't strictly necessary,
-            // it arises naturally from our construction and provides a good sanity check.
-            if last_ext_index < INDEX_SCALE_U64 ||
-               last_m_index < last_ext_index ||
-               new_m_index < last_m_index ||
-               new_m_index > 100 * INDEX_SCALE_U64 || // we set a high, but finite upper bound on the index to ensure it (or the other indices) don't lead to overflow.
-               fee_bps > 10000 {
-                return err!(ExtError::InvalidInput);
-            }
-
-            // Calculate the new ext index from the formula:
-            // new_ext_index = last_ext_index * ((new_m_index / last_m_index) ^ (1 - fee_on_yield))
-            // The derivation of this formula is explained in this document: https://gist.github.com/Oighty/89dd1288a0a7fb53eb6f0314846cb746
-            let m_increase_factor: u64 = (new_m_index as u128).checked_mul(INDEX_SCALE_U64 as u128).ok_or(ExtError::MathOverflow)?.checked_div(last_m_index as u128).ok_or(ExtError::MathUnderflow)?.try_into()?;
-
-            // Calculate the increase factor for the ext index, if the fee is zero, then the increase factor is the same as M
-            let ext_increase_factor = if fee_bps == 0 {
-                m_increase_factor
-            } else {
-                // Calculate the increase factor for the ext index
-                let fee_on_yield = fee_bps as f64 / ONE_HUNDRED_PERCENT_F64;
-                // The precision of the powf operation is non-deterministic
-                // However, the margin of error is ~10^-16, which is smaller than the 10^-12 precision
-                // that we need for this use case. See: https://doc.rust-lang.org/std/primitive.f64.html#method.powf
-                (((m_increase_factor as f64) / INDEX_SCALE_F64).powf(1.0f64 - fee_on_yield) * INDEX_SCALE_F64).floor() as u64
-            };
-
-            // Calculate the new extension index
-            let new_ext_index: u64 = (last_ext_index as u128).checked_mul(ext_increase_factor as u128).ok_or(ExtError::MathOverflow)?.checked_div(INDEX_SCALE_U64 as u128).ok_or(ExtError::MathUnderflow)?.try_into()?;
-
-            Ok(new_ext_index)
+    Ok(close_authority)
+}
+
+pub fn get_interest_bearing_config<'info>(
+    mint: &InterfaceAccount<'info, Mint>,
+) -> Result<InterestBearingMintConfig> {
+    // Get the mint account data with extensions
+    let account_info = mint.to_account_info();
+    let mint_data = account_info.try_borrow_data()?;
+    let mint_ext_data = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+
+    // Get the interest bearing config extension
+    let interest_bearing_config = mint_ext_data.get_extension::<InterestBearingMintConfig>()?;
+
+    Ok(*interest_bearing_config)
+}
+
+// Calculates the continuously compounded annual rate, in basis points, that grows
+// the last ext index to the new ext index over the elapsed time.
+// Returns None if no time has elapsed, since the rate can't be derived.
+fn calculate_rate_bps(
+    last_ext_index: u64,
+    new_ext_index: u64,
+    elapsed: u64,
+) -> Result<Option<i16>> {
+    if last_ext_index == 0 || new_ext_index < last_ext_index {
+        return err!(ExtError::InvalidInput);
+    }
+
+    if elapsed == 0 {
+        return Ok(None);
+    }
+
+    // rate = ln(new_ext_index / last_ext_index) / elapsed_years
+    // Calculated in fixed-point like calculate_new_index so the result is deterministic,
+    // using ln(x) = log2(x) * ln(2)
+    let growth: u128 = (new_ext_index as u128)
+        .checked_shl(FIXED_POINT_BITS)
+        .ok_or(ExtError::MathOverflow)?
+        .checked_div(last_ext_index as u128)
+        .ok_or(ExtError::MathUnderflow)?;
+
+    // Both factors are shifted down by one bit so their product fits in a u128
+    // for any growth below 2^11, which the bounds on the indices guarantee
+    let ln_growth: u128 = (log2_fixed(growth)? >> 1)
+        .checked_mul(LN_2_FIXED >> 1)
+        .ok_or(ExtError::MathOverflow)?
+        >> (FIXED_POINT_BITS - 2);
+
+    let numerator = ln_growth
+        .checked_mul(SECONDS_PER_YEAR as u128 * ONE_HUNDRED_PERCENT_U64 as u128)
+        .ok_or(ExtError::MathOverflow)?;
+    let denominator = (elapsed as u128) << FIXED_POINT_BITS;
+
+    // Rounded to the nearest basis point
+    let rate = numerator
+        .checked_add(denominator / 2)
+        .ok_or(ExtError::MathOverflow)?
+        / denominator;
+
+    // Token2022 stores the rate as an i16, so we cap it at the max value
+    Ok(Some(rate.min(i16::MAX as u128) as i16))
+}
+
+fn get_latest_index_and_timestamp<'info>(
+    cached_m_index: u64,
+    cached_ext_index: u64,
+    fee_bps: u64,
+    m_earn_global_account: &Account<'info, EarnGlobal>,
+) -> Result<(u64, u64)> {
+    let latest_m_index = m_earn_global_account.index;
+    let latest_timestamp = m_earn_global_account.timestamp;
+
+    // If no change, return early
+    if latest_m_index == cached_m_index {
+        return Ok((cached_ext_index, latest_timestamp));
+    }
+
+    // Calculate the new ext index based on the latest m index and timestamp
+    let new_ext_index =
+        calculate_new_index(cached_ext_index, cached_m_index, latest_m_index, fee_bps)?;
+
+    Ok((new_ext_index, latest_timestamp))
+}
+
+fn calculate_new_index(
+    last_ext_index: u64,
+    last_m_index: u64,
+    new_m_index: u64,
+    fee_bps: u64,
+) -> Result<u64> {
+    // Confirm the inputs are in the expected domain.
+    // These checks ensure that the resultant value is >= 1.0,
+    // are allowable values to set as the Token2022 Scaled UI multiplier,
+    // and the ext index is monotonically increasing.
+    // The last ext index isn't required to be <= the last m index, the formula holds
+    // for any pair of indices and the overflow checks below bound the result.
+    if last_ext_index < INDEX_SCALE_U64 ||
+       new_m_index < last_m_index ||
+       new_m_index > 100 * INDEX_SCALE_U64 || // we set a high, but finite upper bound on the index to ensure it (or the other indices) don't lead to overflow.
+       fee_bps > 10000
+    {
+        return err!(ExtError::InvalidInput);
+    }
+
+    // Calculate the new ext index from the formula:
+    // new_ext_index = last_ext_index * ((new_m_index / last_m_index) ^ (1 - fee_on_yield))
+    // The derivation of this formula is explained in this document: https://gist.github.com/Oighty/89dd1288a0a7fb53eb6f0314846cb746
+    let new_ext_index: u64 = if fee_bps == 0 {
+        // If the fee is zero, the ext index increases by the same factor as M,
+        // so we can calculate it exactly and only round down once at the end
+        (last_ext_index as u128)
+            .checked_mul(new_m_index as u128)
+            .ok_or(ExtError::MathOverflow)?
+            .checked_div(last_m_index as u128)
+            .ok_or(ExtError::MathUnderflow)?
+            .try_into()?
+    } else {
+        // Calculate the increase factors in fixed-point so the result is deterministic
+        let m_increase_factor: u128 = (new_m_index as u128)
+            .checked_shl(FIXED_POINT_BITS)
+            .ok_or(ExtError::MathOverflow)?
+            .checked_div(last_m_index as u128)
+            .ok_or(ExtError::MathUnderflow)?;
+        let ext_increase_factor: u128 = pow_fixed(
+            m_increase_factor,
+            ONE_HUNDRED_PERCENT_U64 - fee_bps,
+            ONE_HUNDRED_PERCENT_U64,
+        )?;
+
+        (last_ext_index as u128)
+            .checked_mul(ext_increase_factor)
+            .ok_or(ExtError::MathOverflow)?
+            .checked_shr(FIXED_POINT_BITS)
+            .ok_or(ExtError::MathUnderflow)?
+            .try_into()?
+    };
+
+    Ok(new_ext_index)
+}
+
+// Calculates base ^ (exp_numerator / exp_denominator) for a fixed-point base >= 1.0
+// and an exponent in [0, 1] as 2 ^ (log2(base) * exponent).
+// Only integer operations are used so the result is identical on every validator and off-chain client.
+// Each step truncates, so the result is never above the exact value and, for bases up to 100,
+// is at most 2^-55 (~2.8e-17) below it in relative terms. For ext indices up to 100 this is less
+// than 0.003 of the smallest index increment, so the new index is the floor of the exact value
+// unless the exact value is within that distance above an integer, in which case it is one less.
+fn pow_fixed(base: u128, exp_numerator: u64, exp_denominator: u64) -> Result<u128> {
+    if exp_numerator > exp_denominator {
+        return err!(ExtError::InvalidInput);
+    }
+
+    let log = log2_fixed(base)?;
+    let scaled_log = log
+        .checked_mul(exp_numerator as u128)
+        .ok_or(ExtError::MathOverflow)?
+        .checked_div(exp_denominator as u128)
+        .ok_or(ExtError::MathUnderflow)?;
+
+    exp2_fixed(scaled_log)
+}
+
+// Calculates log2(x) for a fixed-point x >= 1.0, one bit of the fractional part per iteration.
+fn log2_fixed(x: u128) -> Result<u128> {
+    if x < FIXED_POINT_ONE {
+        return err!(ExtError::InvalidInput);
+    }
+
+    // The integer part is the position of the most significant bit
+    let integer_part = 127 - x.leading_zeros() - FIXED_POINT_BITS;
+    let mut result = (integer_part as u128) << FIXED_POINT_BITS;
+
+    // Normalize x to [1, 2), i.e. [2^63, 2^64) so that squaring it can't overflow a u128
+    let mut y = x >> integer_part;
+
+    // Squaring y doubles its log, so whenever it reaches 2 the next bit of the result is set
+    let mut bit = FIXED_POINT_ONE >> 1;
+    while bit > 0 {
+        y = (y * y) >> FIXED_POINT_BITS;
+        if y >= 2 * FIXED_POINT_ONE {
+            y >>= 1;
+            result += bit;
         }
+        bit >>= 1;
     }
+
+    Ok(result)
+}
+
+// Calculates 2^x for a fixed-point x >= 0.0 from the Taylor series of e^(frac(x) * ln(2)).
+fn exp2_fixed(x: u128) -> Result<u128> {
+    let integer_part = x >> FIXED_POINT_BITS;
+    let fractional_part = x & (FIXED_POINT_ONE - 1);
+
+    // The result of the series is in [1, 2), so shifting it by up to 63 bits fits in a u128
+    if integer_part >= FIXED_POINT_BITS as u128 {
+        return err!(ExtError::MathOverflow);
+    }
+
+    // z is in [0, ln(2)), so the terms shrink quickly and reach zero after ~20 iterations
+    let z = (fractional_part * LN_2_FIXED) >> FIXED_POINT_BITS;
+    let mut term = FIXED_POINT_ONE;
+    let mut sum = FIXED_POINT_ONE;
+    let mut k = 1u128;
+    while term > 0 {
+        term = ((term * z) >> FIXED_POINT_BITS) / k;
+        sum += term;
+        k += 1;
+    }
+
+    Ok(sum << integer_part)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    cfg_if! {
-        if #[cfg(feature = "scaled-ui")] {
-            #[test]
-            fn test_calculate_new_index_no_fee() {
-                // cases (starting from 1.0):
-                // no rounding
-                let expected = 1125000000000u64;
-                let result = calculate_new_index(1000000000000u64, 1000000000000u64, 1125000000000u64, 0).unwrap();
-                assert_eq!(result, expected);
-
-                // would round up -> truncates
-                let expected = 1666666666666u64;
-                let result = calculate_new_index(1000000000000u64, 1500000000000u64, 2500000000000u64, 0).unwrap();
-                assert_eq!(result, expected);
-
-                // would round down -> truncates
-                let expected = 1333333333333u64;
-                let result = calculate_new_index(1000000000000u64, 1500000000000u64, 2000000000000u64, 0).unwrap();
-                assert_eq!(result, expected);
-
-                // cases (starting from truncated value that would have rounded up):
-                // no rounding
-                let expected = 1749999999999u64; // off by one due to previous rounding
-                let result = calculate_new_index(1666666666666u64, 2000000000000u64, 2100000000000u64, 0).unwrap();
-                assert_eq!(result, expected);
-
-                // would round up -> truncates
-                let expected = 1777777777777u64;
-                let result = calculate_new_index(1666666666666u64, 3000000000000u64, 3200000000000u64, 0).unwrap();
-                assert_eq!(result, expected);
-
-                // would round down -> truncates
-                let expected = 2333333333332u64; // off by one due to previous rounding
-                let result = calculate_new_index(1666666666666u64, 5000000000000u64, 7000000000000u64, 0).unwrap();
-                assert_eq!(result, expected);
-
-                // cases (starting from truncated value that would have rounded down)
-                let expected = 1499999999999u64; // off by one due to previous rounding
-                let result = calculate_new_index(1333333333333u64, 2000000000000u64, 2250000000000u64, 0).unwrap();
-                assert_eq!(result, expected);
-
-                // would round up -> truncates
-                let expected = 1666666666666u64;
-                let result = calculate_new_index(1333333333333u64, 2000000000000u64, 2500000000000u64, 0).unwrap();
-                assert_eq!(result, expected);
-
-                // would round down -> truncates
-                let expected = 2333333333332u64;
-                let result = calculate_new_index(1333333333333u64, 4000000000000u64, 7000000000000u64, 0).unwrap();
-                assert_eq!(result, expected);
-            }
-
-            // // Helper function to trim the value to 12 decimal places after subtracting expected rounding error
-            // // This is needed to deal with imprecision in floating point arithmetic
-            // fn trim(value: f64) -> f64 {
-            //     // Truncate the value to 12 decimal places
-            //     (value * INDEX_SCALE_F64).ceil() / INDEX_SCALE_F64
-            // }
-
-            #[test]
-            fn test_calculate_new_index_with_fee() {
-                // there are three calculations here to test rounding behavior:
-                // 1. m_increase_factor = new_m_multiplier / last_m_multiplier
-                // 2. ext_increase_factor = m_increase_factor.powf(1.0 - fee_on_yield)
-                // 3. new_ext_multiplier = last_ext_multiplier * ext_increase_factor
-                // cases are listed with what the rounding behavior would be for each calculation
-                // even though the rounding only happens when converting back to u64 for the final result
-                // the basic expectation is that if there is a roundup anywhere in the sequence
-                // the final result will be off by one to the downside due to truncation
-
-                // cases:
-                // Note: we can't reliably get examples that wouldn't round either direction for the 2nd equation since it is a fractional exponent
-                // A
-                //   1. no rounding
-                //   2. rounds down
-                //   3. no rounding
-                let result = calculate_new_index(1000000000000u64, 10000000000u64, 11250000000u64, 2500).unwrap();
-                let expected_actual = 1092356486341; // wolfram alpha: 1.092356486341477...
-                let expected = expected_actual; // no error
-                assert_eq!(result, expected);
-
-                // B
-                //   1. no rounding
-                //   2. rounds down
-                //   3. rounds down
-                let result = calculate_new_index(1300000000000u64, 1500000000000u64, 1650000000000u64, 1500).unwrap();
-                let expected_actual = 1409701411824; // wolfram alpha: 1.409701411824313...
-                let expected = expected_actual; // no error
-                assert_eq!(result, expected);
-
-                // C
-                //  1. no rounding
-                //  2. rounds down
-                //  3. would round up -> truncates
-                let result = calculate_new_index(1200000000000u64, 1500000000000u64, 1650000000000u64, 1500).unwrap();
-                let expected_actual = 1301262841684; // wolfram alpha: 1.301262841683981...
-                let expected = expected_actual - 1; // off by one due to truncation
-                assert_eq!(result, expected);
-
-                // D
-                //  1. no rounding
-                //  2. would round up -> truncates
-                //  3. no rounding
-                let result = calculate_new_index(1000000000000u64, 1500000000000u64, 1650000000000u64, 1000).unwrap();
-                let expected_actual = 1089565684036; // wolfram alpha: 1.089565684035973...
-                let expected = expected_actual - 1; // off by one due to truncation
-                assert_eq!(result, expected);
-
-                // E
-                //  1. no rounding
-                //  2. would round up -> truncates
-                //  3. rounds down
-                let result = calculate_new_index(1200000000000u64, 1500000000000u64, 1650000000000u64, 1000).unwrap();
-                let expected_actual = 1307478820843; // wolfram alpha: 1.307478820843168...
-                let expected = expected_actual; // no error
-                assert_eq!(result, expected);
-
-                // F
-                //  1. no rounding
-                //  2. would round up -> truncates
-                //  3. would round up -> truncates
-                let result = calculate_new_index(1300000000000u64, 1500000000000u64, 1650000000000u64, 1000).unwrap();
-                let expected_actual = 1416435389247; // wolfram alpha: 1.41643538924676614906538927073063715743660444837662580163175093387867947...
-                let expected = expected_actual - 1; // off by one due to truncation
-                assert_eq!(result, expected);
-
-                // G
-                //  1. rounds down
-                //  2. rounds down
-                //  3. no rounding
-                let result = calculate_new_index(1000000000000u64, 1125000000000u64, 1250000000000u64, 1000).unwrap();
-                let expected_actual = 1099465842451; // wolfram alpha: 1.099465842451349...
-                let expected = expected_actual; // no error
-                assert_eq!(result, expected);
-
-                // H
-                //  1. rounds down
-                //  2. rounds down
-                //  3. rounds down
-                let result = calculate_new_index(1100000000000u64, 1125000000000u64, 1250000000000u64, 1000).unwrap();
-                let expected_actual = 1209412426696; // wolfram alpha: 1.209412426696484...
-                let expected = expected_actual; // no error
-                assert_eq!(result, expected);
-
-                // I
-                //  1. rounds down
-                //  2. rounds down
-                //  3. would round up -> truncates
-                let result = calculate_new_index(1200000000000u64, 1125000000000u64, 1250000000000u64, 1000).unwrap();
-                let expected_actual = 1319359010942; // wolfram alpha: 1.319359010941619...
-                let expected = expected_actual - 1; // off by one due to truncation
-                assert_eq!(result, expected);
-
-                // J
-                //  1. rounds down
-                //  2. would round up -> truncates
-                //  3. no rounding
-                let result = calculate_new_index(1000000000000u64, 1125000000000u64, 1250000000000u64, 2000).unwrap();
-                let expected_actual = 1087942624846; // wolfram alpha: 1.087942624845529...
-                let expected = expected_actual - 1; // off by one due to truncation
-                assert_eq!(result, expected);
-
-                // K
-                //  1. rounds down
-                //  2. would round up -> truncates
-                //  3. rounds down
-                let result = calculate_new_index(1300000000000u64, 1125000000000u64, 1250000000000u64, 2000).unwrap();
-                let expected_actual = 1414325412299; // wolfram alpha: 1.414325412299188...
-                let expected = expected_actual; // no error
-                assert_eq!(result, expected);
-
-                // L
-                //  1. rounds down
-                //  2. would round up -> truncates
-                //  3. would round up -> truncates
-                let result = calculate_new_index(1200000000000u64, 1125000000000u64, 1250000000000u64, 2000).unwrap();
-                let expected_actual = 1305531149815; // wolfram alpha: 1.305531149814635...
-                let expected = expected_actual - 1; // off by one due to truncation
-                assert_eq!(result, expected);
-
-                // M
-                //  1. would round up -> truncates
-                //  2. rounds down
-                //  3. no rounding
-                let result = calculate_new_index(1000000000000u64, 3000000000000u64, 3200000000000u64, 1000).unwrap();
-                let expected_actual = 1059804724543; // wolfram alpha: 1.059804724543068...
-                let expected = expected_actual; // no error
-                assert_eq!(result, expected);
-
-                // N
-                //  1. would round up -> truncates
-                //  2. rounds down
-                //  3. rounds down
-                let result = calculate_new_index(1400000000000u64, 3000000000000u64, 3200000000000u64, 1000).unwrap();
-                let expected_actual = 1483726614360; // wolfram alpha: 1.483726614360295...
-                let expected = expected_actual; // no error
-                assert_eq!(result, expected);
-
-                // O
-                //  1. would round up -> truncates
-                //  2. rounds down
-                //  3. would round up -> truncates
-                let result = calculate_new_index(1200000000000u64, 3000000000000u64, 3200000000000u64, 1000).unwrap();
-                let expected_actual = 1271765669452; // wolfram alpha: 1.271765669451681...
-                let expected = expected_actual - 1; // off by one due to truncation
-                assert_eq!(result, expected);
-
-                // P
-                //  1. would round up -> truncates
-                //  2. would round up -> truncates
-                //  3. no rounding
-                let result = calculate_new_index(1000000000000u64, 3000000000000u64, 3200000000000u64, 2000).unwrap();
-                let expected_actual = 1052986925779; // wolfram alpha: 1.052986925778570...
-                let expected = expected_actual - 1; // off by one due to truncation
-                assert_eq!(result, expected);
-
-                // Q
-                //  1. would round up -> truncates
-                //  2. would round up -> truncates
-                //  3. rounds down
-                let result = calculate_new_index(1200000000000u64, 3000000000000u64, 3200000000000u64, 2000).unwrap();
-                let expected_actual = 1263584310934; // wolfram alpha: 1.263584310934284...
-                let expected = expected_actual;
-                assert_eq!(result, expected);
-
-                // R
-                //  1. would round up -> truncates
-                //  2. would round up -> truncates
-                //  3. would round up -> truncates
-                let result = calculate_new_index(1400000000000u64, 3000000000000u64, 3200000000000u64, 2000).unwrap();
-                let expected_actual = 1474181696090; // wolfram alpha: 1.474181696089998...
-                let expected = expected_actual - 1; // off by one due to truncation
-                assert_eq!(result, expected);
-            }
-        }
+    #[test]
+    fn test_calculate_new_index_no_fee() {
+        // cases (starting from 1.0):
+        // no rounding
+        let expected = 1125000000000u64;
+        let result =
+            calculate_new_index(1000000000000u64, 1000000000000u64, 1125000000000u64, 0).unwrap();
+        assert_eq!(result, expected);
+
+        // would round up -> truncates
+        let expected = 1666666666666u64;
+        let result =
+            calculate_new_index(1000000000000u64, 1500000000000u64, 2500000000000u64, 0).unwrap();
+        assert_eq!(result, expected);
+
+        // would round down -> truncates
+        let expected = 1333333333333u64;
+        let result =
+            calculate_new_index(1000000000000u64, 1500000000000u64, 2000000000000u64, 0).unwrap();
+        assert_eq!(result, expected);
+
+        // cases (starting from truncated value that would have rounded up):
+        // no rounding
+        let expected = 1749999999999u64; // off by one due to previous rounding
+        let result =
+            calculate_new_index(1666666666666u64, 2000000000000u64, 2100000000000u64, 0).unwrap();
+        assert_eq!(result, expected);
+
+        // would round up -> truncates
+        let expected = 1777777777777u64;
+        let result =
+            calculate_new_index(1666666666666u64, 3000000000000u64, 3200000000000u64, 0).unwrap();
+        assert_eq!(result, expected);
+
+        // would round down -> truncates
+        let expected = 2333333333332u64; // off by one due to previous rounding
+        let result =
+            calculate_new_index(1666666666666u64, 5000000000000u64, 7000000000000u64, 0).unwrap();
+        assert_eq!(result, expected);
+
+        // cases (starting from truncated value that would have rounded down)
+        let expected = 1499999999999u64; // off by one due to previous rounding
+        let result =
+            calculate_new_index(1333333333333u64, 2000000000000u64, 2250000000000u64, 0).unwrap();
+        assert_eq!(result, expected);
+
+        // would round up -> truncates
+        let expected = 1666666666666u64;
+        let result =
+            calculate_new_index(1333333333333u64, 2000000000000u64, 2500000000000u64, 0).unwrap();
+        assert_eq!(result, expected);
+
+        // would round down -> truncates
+        let expected = 2333333333332u64;
+        let result =
+            calculate_new_index(1333333333333u64, 4000000000000u64, 7000000000000u64, 0).unwrap();
+        assert_eq!(result, expected);
+    }
+
+    // // Helper function to trim the value to 12 decimal places after subtracting expected rounding error
+    // // This is needed to deal with imprecision in floating point arithmetic
+    // fn trim(value: f64) -> f64 {
+    //     // Truncate the value to 12 decimal places
+    //     (value * INDEX_SCALE_F64).ceil() / INDEX_SCALE_F64
+    // }
+
+    #[test]
+    fn test_calculate_new_index_with_fee() {
+        // there are three calculations here to test rounding behavior:
+        // 1. m_increase_factor = new_m_multiplier / last_m_multiplier
+        // 2. ext_increase_factor = m_increase_factor ^ (1.0 - fee_on_yield)
+        // 3. new_ext_multiplier = last_ext_multiplier * ext_increase_factor
+        // cases are listed with what the rounding behavior would be for each calculation
+        // even though the rounding only happens when converting back to u64 for the final result
+        // the basic expectation is that if there is a roundup anywhere in the sequence
+        // the final result will be off by one to the downside due to truncation
+
+        // cases:
+        // Note: we can't reliably get examples that wouldn't round either direction for the 2nd equation since it is a fractional exponent
+        // A
+        //   1. no rounding
+        //   2. rounds down
+        //   3. no rounding
+        let result =
+            calculate_new_index(1000000000000u64, 10000000000u64, 11250000000u64, 2500).unwrap();
+        let expected_actual = 1092356486341; // wolfram alpha: 1.092356486341477...
+        let expected = expected_actual; // no error
+        assert_eq!(result, expected);
+
+        // B
+        //   1. no rounding
+        //   2. rounds down
+        //   3. rounds down
+        let result =
+            calculate_new_index(1300000000000u64, 1500000000000u64, 1650000000000u64, 1500)
+                .unwrap();
+        let expected_actual = 1409701411824; // wolfram alpha: 1.409701411824313...
+        let expected = expected_actual; // no error
+        assert_eq!(result, expected);
+
+        // C
+        //  1. no rounding
+        //  2. rounds down
+        //  3. would round up -> truncates
+        let result =
+            calculate_new_index(1200000000000u64, 1500000000000u64, 1650000000000u64, 1500)
+                .unwrap();
+        let expected_actual = 1301262841684; // wolfram alpha: 1.301262841683981...
+        let expected = expected_actual - 1; // off by one due to truncation
+        assert_eq!(result, expected);
+
+        // D
+        //  1. no rounding
+        //  2. would round up -> truncates
+        //  3. no rounding
+        let result =
+            calculate_new_index(1000000000000u64, 1500000000000u64, 1650000000000u64, 1000)
+                .unwrap();
+        let expected_actual = 1089565684036; // wolfram alpha: 1.089565684035973...
+        let expected = expected_actual - 1; // off by one due to truncation
+        assert_eq!(result, expected);
+
+        // E
+        //  1. no rounding
+        //  2. would round up -> truncates
+        //  3. rounds down
+        let result =
+            calculate_new_index(1200000000000u64, 1500000000000u64, 1650000000000u64, 1000)
+                .unwrap();
+        let expected_actual = 1307478820843; // wolfram alpha: 1.307478820843168...
+        let expected = expected_actual; // no error
+        assert_eq!(result, expected);
+
+        // F
+        //  1. no rounding
+        //  2. would round up -> truncates
+        //  3. would round up -> truncates
+        let result =
+            calculate_new_index(1300000000000u64, 1500000000000u64, 1650000000000u64, 1000)
+                .unwrap();
+        let expected_actual = 1416435389247; // wolfram alpha: 1.41643538924676614906538927073063715743660444837662580163175093387867947...
+        let expected = expected_actual - 1; // off by one due to truncation
+        assert_eq!(result, expected);
+
+        // G
+        //  1. rounds down
+        //  2. rounds down
+        //  3. no rounding
+        let result =
+            calculate_new_index(1000000000000u64, 1125000000000u64, 1250000000000u64, 1000)
+                .unwrap();
+        let expected_actual = 1099465842451; // wolfram alpha: 1.099465842451349...
+        let expected = expected_actual; // no error
+        assert_eq!(result, expected);
+
+        // H
+        //  1. rounds down
+        //  2. rounds down
+        //  3. rounds down
+        let result =
+            calculate_new_index(1100000000000u64, 1125000000000u64, 1250000000000u64, 1000)
+                .unwrap();
+        let expected_actual = 1209412426696; // wolfram alpha: 1.209412426696484...
+        let expected = expected_actual; // no error
+        assert_eq!(result, expected);
+
+        // I
+        //  1. rounds down
+        //  2. rounds down
+        //  3. would round up -> truncates
+        let result =
+            calculate_new_index(1200000000000u64, 1125000000000u64, 1250000000000u64, 1000)
+                .unwrap();
+        let expected_actual = 1319359010942; // wolfram alpha: 1.319359010941619...
+        let expected = expected_actual - 1; // off by one due to truncation
+        assert_eq!(result, expected);
+
+        // J
+        //  1. rounds down
+        //  2. would round up -> truncates
+        //  3. no rounding
+        let result =
+            calculate_new_index(1000000000000u64, 1125000000000u64, 1250000000000u64, 2000)
+                .unwrap();
+        let expected_actual = 1087942624846; // wolfram alpha: 1.087942624845529...
+        let expected = expected_actual - 1; // off by one due to truncation
+        assert_eq!(result, expected);
+
+        // K
+        //  1. rounds down
+        //  2. would round up -> truncates
+        //  3. rounds down
+        let result =
+            calculate_new_index(1300000000000u64, 1125000000000u64, 1250000000000u64, 2000)
+                .unwrap();
+        let expected_actual = 1414325412299; // wolfram alpha: 1.414325412299188...
+        let expected = expected_actual; // no error
+        assert_eq!(result, expected);
+
+        // L
+        //  1. rounds down
+        //  2. would round up -> truncates
+        //  3. would round up -> truncates
+        let result =
+            calculate_new_index(1200000000000u64, 1125000000000u64, 1250000000000u64, 2000)
+                .unwrap();
+        let expected_actual = 1305531149815; // wolfram alpha: 1.305531149814635...
+        let expected = expected_actual - 1; // off by one due to truncation
+        assert_eq!(result, expected);
+
+        // M
+        //  1. would round up -> truncates
+        //  2. rounds down
+        //  3. no rounding
+        let result =
+            calculate_new_index(1000000000000u64, 3000000000000u64, 3200000000000u64, 1000)
+                .unwrap();
+        let expected_actual = 1059804724543; // wolfram alpha: 1.059804724543068...
+        let expected = expected_actual; // no error
+        assert_eq!(result, expected);
+
+        // N
+        //  1. would round up -> truncates
+        //  2. rounds down
+        //  3. rounds down
+        let result =
+            calculate_new_index(1400000000000u64, 3000000000000u64, 3200000000000u64, 1000)
+                .unwrap();
+        let expected_actual = 1483726614360; // wolfram alpha: 1.483726614360295...
+        let expected = expected_actual; // no error
+        assert_eq!(result, expected);
+
+        // O
+        //  1. would round up -> truncates
+        //  2. rounds down
+        //  3. would round up -> truncates
+        let result =
+            calculate_new_index(1200000000000u64, 3000000000000u64, 3200000000000u64, 1000)
+                .unwrap();
+        let expected_actual = 1271765669452; // wolfram alpha: 1.271765669451681...
+        let expected = expected_actual - 1; // off by one due to truncation
+        assert_eq!(result, expected);
+
+        // P
+        //  1. would round up -> truncates
+        //  2. would round up -> truncates
+        //  3. no rounding
+        let result =
+            calculate_new_index(1000000000000u64, 3000000000000u64, 3200000000000u64, 2000)
+                .unwrap();
+        let expected_actual = 1052986925779; // wolfram alpha: 1.052986925778570...
+        let expected = expected_actual - 1; // off by one due to truncation
+        assert_eq!(result, expected);
+
+        // Q
+        //  1. would round up -> truncates
+        //  2. would round up -> truncates
+        //  3. rounds down
+        let result =
+            calculate_new_index(1200000000000u64, 3000000000000u64, 3200000000000u64, 2000)
+                .unwrap();
+        let expected_actual = 1263584310934; // wolfram alpha: 1.263584310934284...
+        let expected = expected_actual;
+        assert_eq!(result, expected);
+
+        // R
+        //  1. would round up -> truncates
+        //  2. would round up -> truncates
+        //  3. would round up -> truncates
+        let result =
+            calculate_new_index(1400000000000u64, 3000000000000u64, 3200000000000u64, 2000)
+                .unwrap();
+        let expected_actual = 1474181696090; // wolfram alpha: 1.474181696089998...
+        let expected = expected_actual - 1; // off by one due to truncation
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_pow_fixed() {
+        // exponent of 0 and 1
+        let base = 1_500_000_000_000u128 * FIXED_POINT_ONE / INDEX_SCALE_U64 as u128;
+        assert_eq!(pow_fixed(base, 0, 10000).unwrap(), FIXED_POINT_ONE);
+        let result = pow_fixed(base, 10000, 10000).unwrap();
+        assert!(result <= base && base - result < 1 << 8);
+
+        // exact powers of two are computed without error
+        assert_eq!(
+            pow_fixed(4 * FIXED_POINT_ONE, 5000, 10000).unwrap(),
+            2 * FIXED_POINT_ONE
+        );
+        assert_eq!(
+            pow_fixed(64 * FIXED_POINT_ONE, 5000, 10000).unwrap(),
+            8 * FIXED_POINT_ONE
+        );
+        assert_eq!(
+            pow_fixed(FIXED_POINT_ONE, 7500, 10000).unwrap(),
+            FIXED_POINT_ONE
+        );
+
+        // the result is never above the exact value, sqrt(2) = 1.41421356237309504880...
+        let result = pow_fixed(2 * FIXED_POINT_ONE, 5000, 10000).unwrap();
+        let expected = 13_043_817_825_332_782_212u128; // floor(sqrt(2) * 2^63)
+        assert!(result <= expected && expected - result < 1 << 8);
+
+        // invalid inputs
+        assert!(pow_fixed(FIXED_POINT_ONE - 1, 5000, 10000).is_err());
+        assert!(pow_fixed(2 * FIXED_POINT_ONE, 10001, 10000).is_err());
+    }
+
+    #[test]
+    fn test_get_accrued_yield() {
+        // balance unchanged since the last update, index from 1.0 to 1.1
+        let result = get_accrued_yield(
+            1_000_000u64,
+            1000000000000u64,
+            1100000000000u64,
+            1_000_000u64,
+        )
+        .unwrap();
+        assert_eq!(result, 100_000u64);
+
+        // no change in index -> no yield, even though the principal was rounded down
+        let result =
+            get_accrued_yield(909_090u64, 1100000000000u64, 1100000000000u64, 1_000_000u64)
+                .unwrap();
+        assert_eq!(result, 0u64);
+
+        // half of the balance was transferred out -> only the remaining half earns
+        let result =
+            get_accrued_yield(1_000_000u64, 1000000000000u64, 1100000000000u64, 500_000u64)
+                .unwrap();
+        assert_eq!(result, 50_000u64);
+
+        // balance increased since the last update -> only the checkpointed principal earns
+        let result = get_accrued_yield(
+            1_000_000u64,
+            1000000000000u64,
+            1100000000000u64,
+            2_000_000u64,
+        )
+        .unwrap();
+        assert_eq!(result, 100_000u64);
+
+        // index from 1.1 to 1.21, principal rounded down at the last update
+        // 999_999 * 1.21 = 1_209_998.79 -> 1_209_998, 999_999 * 1.1 = 1_099_998.9 -> 1_099_999
+        let result =
+            get_accrued_yield(999_999u64, 1100000000000u64, 1210000000000u64, 1_100_000u64)
+                .unwrap();
+        assert_eq!(result, 109_999u64);
+    }
+
+    #[test]
+    fn test_calculate_rate_bps() {
+        // 5% continuously compounded over a year: e^0.05 = 1.051271096376...
+        let result = calculate_rate_bps(1000000000000u64, 1051271096376u64, 31_556_736u64).unwrap();
+        assert_eq!(result, Some(500));
+
+        // same rate over half a year: e^0.025 = 1.025315120524...
+        let result = calculate_rate_bps(1000000000000u64, 1025315120524u64, 15_778_368u64).unwrap();
+        assert_eq!(result, Some(500));
+
+        // the rate is relative to the last index: 1.1 * e^0.04 = 1.144891180821...
+        let result = calculate_rate_bps(1100000000000u64, 1144891180821u64, 31_556_736u64).unwrap();
+        assert_eq!(result, Some(400));
+
+        // no growth -> zero rate
+        let result = calculate_rate_bps(1100000000000u64, 1100000000000u64, 86_400u64).unwrap();
+        assert_eq!(result, Some(0));
+
+        // no elapsed time -> rate can't be derived
+        let result = calculate_rate_bps(1000000000000u64, 1000000000001u64, 0u64).unwrap();
+        assert_eq!(result, None);
+
+        // rates above the i16 max are capped
+        let result = calculate_rate_bps(1000000000000u64, 100000000000000u64, 86_400u64).unwrap();
+        assert_eq!(result, Some(i16::MAX));
+
+        // decreasing index is invalid
+        assert!(calculate_rate_bps(1100000000000u64, 1000000000000u64, 86_400u64).is_err());
+
+        // known vectors, rate = round(ln(new / last) / years * 10000):
+        // doubling over a year: ln(2) = 0.693147180559...
+        let result = calculate_rate_bps(1000000000000u64, 2000000000000u64, 31_556_736u64).unwrap();
+        assert_eq!(result, Some(6931));
+
+        // e over a year: ln(2.718281828459) = 0.999999999999...
+        let result = calculate_rate_bps(1000000000000u64, 2718281828459u64, 31_556_736u64).unwrap();
+        assert_eq!(result, Some(10000));
+
+        // 5% over a year: ln(1.05) = 0.048790164169...
+        let result = calculate_rate_bps(1000000000000u64, 1050000000000u64, 31_556_736u64).unwrap();
+        assert_eq!(result, Some(488));
+
+        // 10% over ten years: ln(1.1) / 10 = 0.009531017980...
+        let result =
+            calculate_rate_bps(1000000000000u64, 1100000000000u64, 315_567_360u64).unwrap();
+        assert_eq!(result, Some(95));
+
+        // growth below half a basis point rounds to zero
+        let result = calculate_rate_bps(1000000000000u64, 1000000000001u64, 31_556_736u64).unwrap();
+        assert_eq!(result, Some(0));
     }
 }
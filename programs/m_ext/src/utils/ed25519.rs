@@ -0,0 +1,117 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{ed25519_program, instruction::Instruction},
+};
+
+// Layout of the data of an Ed25519 program instruction: the number of signatures and a padding byte,
+// followed by the offsets of each signature, public key and message
+const HEADER_SIZE: usize = 2;
+const OFFSETS_SIZE: usize = 14;
+const SIGNATURE_SIZE: usize = 64;
+const PUBKEY_SIZE: usize = 32;
+
+// Instruction index the Ed25519 program reads as the instruction being verified
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+// Returns true if the instruction makes the Ed25519 program verify a single signature of the message by the signer.
+// All offsets must point into the instruction itself, otherwise the verified data could come from another instruction.
+pub fn verify_ed25519_instruction(ix: &Instruction, signer: &Pubkey, message: &[u8]) -> bool {
+    if ix.program_id != ed25519_program::ID || !ix.accounts.is_empty() {
+        return false;
+    }
+
+    let data = &ix.data;
+    if data.len() < HEADER_SIZE + OFFSETS_SIZE || data[0] != 1 {
+        return false;
+    }
+
+    let read_u16 = |i: usize| {
+        let start = HEADER_SIZE + 2 * i;
+        u16::from_le_bytes([data[start], data[start + 1]])
+    };
+    let signature_offset = read_u16(0);
+    let signature_ix = read_u16(1);
+    let pubkey_offset = read_u16(2);
+    let pubkey_ix = read_u16(3);
+    let message_offset = read_u16(4);
+    let message_size = read_u16(5);
+    let message_ix = read_u16(6);
+
+    if [signature_ix, pubkey_ix, message_ix]
+        .iter()
+        .any(|&index| index != CURRENT_INSTRUCTION)
+    {
+        return false;
+    }
+
+    let slice = |offset: u16, size: usize| data.get(offset as usize..offset as usize + size);
+
+    slice(signature_offset, SIGNATURE_SIZE).is_some()
+        && slice(pubkey_offset, PUBKEY_SIZE) == Some(signer.as_ref())
+        && slice(message_offset, message_size as usize) == Some(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Builds the instruction the same way as the Ed25519 program helpers of the web3 sdks
+    fn build_ix(signer: &Pubkey, message: &[u8], index: u16) -> Instruction {
+        let pubkey_offset = (HEADER_SIZE + OFFSETS_SIZE) as u16;
+        let signature_offset = pubkey_offset + PUBKEY_SIZE as u16;
+        let message_offset = signature_offset + SIGNATURE_SIZE as u16;
+
+        let mut data = vec![1, 0];
+        for value in [
+            signature_offset,
+            index,
+            pubkey_offset,
+            index,
+            message_offset,
+            message.len() as u16,
+            index,
+        ] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(signer.as_ref());
+        data.extend_from_slice(&[7; SIGNATURE_SIZE]);
+        data.extend_from_slice(message);
+
+        Instruction {
+            program_id: ed25519_program::ID,
+            accounts: vec![],
+            data,
+        }
+    }
+
+    #[test]
+    fn test_verify_ed25519_instruction() {
+        let signer = Pubkey::new_unique();
+        let message = b"voucher";
+
+        let ix = build_ix(&signer, message, CURRENT_INSTRUCTION);
+        assert!(verify_ed25519_instruction(&ix, &signer, message));
+
+        // Different signer or message
+        assert!(!verify_ed25519_instruction(
+            &ix,
+            &Pubkey::new_unique(),
+            message
+        ));
+        assert!(!verify_ed25519_instruction(&ix, &signer, b"vouchers"));
+
+        // Data read from another instruction
+        let ix = build_ix(&signer, message, 0);
+        assert!(!verify_ed25519_instruction(&ix, &signer, message));
+
+        // Another program
+        let mut ix = build_ix(&signer, message, CURRENT_INSTRUCTION);
+        ix.program_id = Pubkey::new_unique();
+        assert!(!verify_ed25519_instruction(&ix, &signer, message));
+
+        // Truncated data
+        let mut ix = build_ix(&signer, message, CURRENT_INSTRUCTION);
+        ix.data.truncate(ix.data.len() - 1);
+        assert!(!verify_ed25519_instruction(&ix, &signer, message));
+    }
+}
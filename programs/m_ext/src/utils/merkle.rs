@@ -0,0 +1,74 @@
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
+
+// Domain separation prefixes so a leaf can't be passed off as an internal node
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+pub fn hash_leaf(leaf_index: u32, claimant: &Pubkey, amount: u64) -> [u8; 32] {
+    hashv(&[
+        LEAF_PREFIX,
+        &leaf_index.to_le_bytes(),
+        claimant.as_ref(),
+        &amount.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+// Pairs are hashed in sorted order so proofs don't need to encode the position of each sibling
+pub fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    if a <= b {
+        hashv(&[NODE_PREFIX, a, b]).to_bytes()
+    } else {
+        hashv(&[NODE_PREFIX, b, a]).to_bytes()
+    }
+}
+
+pub fn verify_proof(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof
+        .iter()
+        .fold(leaf, |node, sibling| hash_pair(&node, sibling));
+
+    computed == *root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_proof() {
+        let claimants: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let leaves: Vec<[u8; 32]> = claimants
+            .iter()
+            .enumerate()
+            .map(|(i, c)| hash_leaf(i as u32, c, 100 * (i as u64 + 1)))
+            .collect();
+
+        // Tree with an odd number of leaves, the last one is promoted
+        let left = hash_pair(&leaves[0], &leaves[1]);
+        let root = hash_pair(&left, &leaves[2]);
+
+        // Valid proofs
+        assert!(verify_proof(&[leaves[1], leaves[2]], &root, leaves[0]));
+        assert!(verify_proof(&[leaves[0], leaves[2]], &root, leaves[1]));
+        assert!(verify_proof(&[left], &root, leaves[2]));
+
+        // Single leaf tree, the root is the leaf
+        assert!(verify_proof(&[], &leaves[0], leaves[0]));
+
+        // Wrong amount
+        let bad_leaf = hash_leaf(0, &claimants[0], 101);
+        assert!(!verify_proof(&[leaves[1], leaves[2]], &root, bad_leaf));
+
+        // Wrong claimant
+        let bad_leaf = hash_leaf(0, &claimants[1], 100);
+        assert!(!verify_proof(&[leaves[1], leaves[2]], &root, bad_leaf));
+
+        // Wrong leaf index
+        let bad_leaf = hash_leaf(1, &claimants[0], 100);
+        assert!(!verify_proof(&[leaves[1], leaves[2]], &root, bad_leaf));
+
+        // Truncated proof
+        assert!(!verify_proof(&[leaves[1]], &root, leaves[0]));
+    }
+}
@@ -1,2 +1,5 @@
+pub mod account;
 pub mod conversion;
+pub mod ed25519;
+pub mod merkle;
 pub mod token;
@@ -1,7 +1,9 @@
 // external dependencies
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{
-    burn, mint_to, transfer_checked, Burn, Mint, MintTo, Token2022, TokenAccount, TransferChecked,
+    burn, close_account, freeze_account, mint_to, thaw_account, transfer_checked, Burn,
+    CloseAccount, FreezeAccount, Mint, MintTo, ThawAccount, Token2022, TokenAccount,
+    TransferChecked,
 };
 
 pub fn transfer_tokens_from_program<'info>(
@@ -105,3 +107,112 @@ pub fn burn_tokens<'info>(
 
     Ok(())
 }
+
+pub fn burn_tokens_from_program<'info>(
+    from: &InterfaceAccount<'info, TokenAccount>,
+    amount: u64,
+    mint: &InterfaceAccount<'info, Mint>,
+    authority: &AccountInfo<'info>,
+    authority_seeds: &[&[&[u8]]],
+    token_program: &Program<'info, Token2022>,
+) -> Result<()> {
+    // Build the arguments for the burn instruction
+    let burn_options = Burn {
+        mint: mint.to_account_info(),
+        from: from.to_account_info(),
+        authority: authority.clone(),
+    };
+
+    let cpi_context = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        burn_options,
+        authority_seeds,
+    );
+
+    // Call the burn instruction
+    burn(cpi_context, amount)?;
+
+    Ok(())
+}
+
+// Convenience functions to freeze and thaw token accounts from a program using a PDA signer
+
+pub fn freeze_token_account<'info>(
+    account: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    authority: &AccountInfo<'info>,
+    authority_seeds: &[&[&[u8]]],
+    token_program: &Program<'info, Token2022>,
+) -> Result<()> {
+    // Build the arguments for the freeze instruction
+    let freeze_options = FreezeAccount {
+        account: account.to_account_info(),
+        mint: mint.to_account_info(),
+        authority: authority.clone(),
+    };
+
+    let cpi_context = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        freeze_options,
+        authority_seeds,
+    );
+
+    // Call the freeze instruction
+    freeze_account(cpi_context)?;
+
+    Ok(())
+}
+
+pub fn thaw_token_account<'info>(
+    account: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    authority: &AccountInfo<'info>,
+    authority_seeds: &[&[&[u8]]],
+    token_program: &Program<'info, Token2022>,
+) -> Result<()> {
+    // Build the arguments for the thaw instruction
+    let thaw_options = ThawAccount {
+        account: account.to_account_info(),
+        mint: mint.to_account_info(),
+        authority: authority.clone(),
+    };
+
+    let cpi_context = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        thaw_options,
+        authority_seeds,
+    );
+
+    // Call the thaw instruction
+    thaw_account(cpi_context)?;
+
+    Ok(())
+}
+
+// Convenience function to close a token account or a mint from a program using a PDA signer
+
+pub fn close_account_from_program<'info>(
+    account: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    authority_seeds: &[&[&[u8]]],
+    token_program: &Program<'info, Token2022>,
+) -> Result<()> {
+    // Build the arguments for the close instruction
+    let close_options = CloseAccount {
+        account: account.clone(),
+        destination: destination.clone(),
+        authority: authority.clone(),
+    };
+
+    let cpi_context = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        close_options,
+        authority_seeds,
+    );
+
+    // Call the close instruction
+    close_account(cpi_context)?;
+
+    Ok(())
+}
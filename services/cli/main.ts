@@ -268,6 +268,16 @@ async function main() {
         admin,
       ];
 
+      // Initialize creates the account of each wrap authority
+      const wrapAuthorityAccounts = wrapAuthorities.map((authority) => ({
+        pubkey: PublicKey.findProgramAddressSync(
+          [Buffer.from("wrap_authority"), authority.toBuffer()],
+          program.publicKey
+        )[0],
+        isSigner: false,
+        isWritable: true,
+      }));
+
       let transaction: Transaction;
       switch (variant) {
         case "no-yield":
@@ -280,12 +290,19 @@ async function main() {
           );
 
           transaction = await noYieldProgram.methods
-            .initialize(wrapAuthorities)
+            .initialize(
+              wrapAuthorities,
+              { noYield: {} },
+              false,
+              new BN(0),
+              false
+            )
             .accounts({
               admin: admin,
               mMint: M_MINT,
               extMint: extMint.publicKey,
             })
+            .remainingAccounts(wrapAuthorityAccounts)
             .transaction();
 
           console.log("Initialized no yield extension");
@@ -301,12 +318,19 @@ async function main() {
           );
 
           transaction = await suiProgram.methods
-            .initialize(wrapAuthorities, new BN(fee))
+            .initialize(
+              wrapAuthorities,
+              { scaledUi: { feeBps: new BN(fee) } },
+              false,
+              new BN(0),
+              false
+            )
             .accounts({
               admin: admin,
               mMint: M_MINT,
               extMint: extMint.publicKey,
             })
+            .remainingAccounts(wrapAuthorityAccounts)
             .transaction();
 
           console.log("Initialized scaled UI extension");
@@ -461,7 +485,11 @@ async function main() {
   program
     .command("whitelist-extensions")
     .description("Whitelist extensions on the Swap Facility")
-    .argument("<extensions>", "Comma-separated list of extensions")
+    .argument(
+      "<extensions>",
+      "Comma-separated list of extensions as <program>:<ext mint>, " +
+        "followed by :namespaced for namespaced extensions"
+    )
     .action(async (extensions) => {
       const [payer] = keysFromEnv(["PAYER_KEYPAIR"]);
 
@@ -477,12 +505,14 @@ async function main() {
       const tx = new Transaction();
 
       for (const ext of extensions.split(",")) {
+        const [extProgram, extMint, namespaced] = ext.split(":");
         tx.add(
           await extSwap.methods
-            .whitelistExtension()
+            .whitelistExtension(namespaced === "namespaced")
             .accountsPartial({
               admin,
-              extProgram: new PublicKey(ext),
+              extProgram: new PublicKey(extProgram),
+              extMint: new PublicKey(extMint),
             })
             .instruction()
         );
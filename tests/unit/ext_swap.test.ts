@@ -26,11 +26,11 @@ import {
 import { Earn } from "../../tests/programs/earn";
 import EARN from "../../tests/programs/earn.json";
 import EXT_SWAP from "../../target/idl/ext_swap.json";
-import M_EXT from "../../target/idl/scaled_ui.json";
+import M_EXT from "../../target/idl/m_ext.json";
 import { BN, Program } from "@coral-xyz/anchor";
 import { ExtSwap } from "../../target/types/ext_swap";
 import { TransactionMetadata } from "litesvm";
-import { MExt } from "../../target/types/scaled_ui";
+import { MExt } from "../../target/types/m_ext";
 
 describe("extension swap tests", () => {
   const {
@@ -46,9 +46,13 @@ describe("extension swap tests", () => {
     mintC,
   } = loadKeypairs();
 
+  // Holds the wrap authority manager role on the extensions
+  const wrapAuthorityManager = Keypair.generate();
+
   const svm = fromWorkspace("").withSplPrograms();
   svm.airdrop(admin.publicKey, BigInt(10 * LAMPORTS_PER_SOL));
   svm.airdrop(swapper.publicKey, BigInt(10 * LAMPORTS_PER_SOL));
+  svm.airdrop(wrapAuthorityManager.publicKey, BigInt(10 * LAMPORTS_PER_SOL));
 
   // M Earn program
   svm.addProgramFromFile(EARN_PROGRAM_ID, "tests/programs/earn.so");
@@ -101,6 +105,68 @@ describe("extension swap tests", () => {
   const getVault = (p: PublicKey) =>
     PublicKey.findProgramAddressSync([Buffer.from("m_vault")], p)[0];
 
+  // The extension PDAs aren't resolved by the IDL since they can be namespaced by the ext mint
+  const getExtPdas = (p: PublicKey) => ({
+    globalAccount: PublicKey.findProgramAddressSync(
+      [Buffer.from("global")],
+      p
+    )[0],
+    mVault: getVault(p),
+    extMintAuthority: PublicKey.findProgramAddressSync(
+      [Buffer.from("mint_authority")],
+      p
+    )[0],
+    rateLimit: PublicKey.findProgramAddressSync(
+      [Buffer.from("rate_limit")],
+      p
+    )[0],
+  });
+
+  // Wrap and unwrap check the blocklist entry of the swapper, who owns the ext token accounts
+  const getBlocklistEntry = (p: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("blocklist"), swapper.publicKey.toBuffer()],
+      p
+    )[0];
+
+  // The swap program signs wrap and unwrap with its global account unless an authority is provided
+  const swapGlobal = PublicKey.findProgramAddressSync(
+    [Buffer.from("global")],
+    program.programId
+  )[0];
+
+  const getWrapAuthority = (p: PublicKey, authority: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("wrap_authority"), authority.toBuffer()],
+      p
+    )[0];
+
+  const getFromPdas = (p: PublicKey, authority = swapGlobal) => {
+    const { globalAccount, mVault, extMintAuthority, rateLimit } =
+      getExtPdas(p);
+    return {
+      fromGlobal: globalAccount,
+      fromMVaultAuth: mVault,
+      fromMintAuthority: extMintAuthority,
+      fromBlocklistEntry: getBlocklistEntry(p),
+      fromRateLimit: rateLimit,
+      fromWrapAuthorityAccount: getWrapAuthority(p, authority),
+    };
+  };
+
+  const getToPdas = (p: PublicKey, authority = swapGlobal) => {
+    const { globalAccount, mVault, extMintAuthority, rateLimit } =
+      getExtPdas(p);
+    return {
+      toGlobal: globalAccount,
+      toMVaultAuth: mVault,
+      toMintAuthority: extMintAuthority,
+      toBlocklistEntry: getBlocklistEntry(p),
+      toRateLimit: rateLimit,
+      toWrapAuthorityAccount: getWrapAuthority(p, authority),
+    };
+  };
+
   const getMEarnerAccount = (vaultMTokenAccount: PublicKey) =>
     PublicKey.findProgramAddressSync(
       [Buffer.from("earner"), vaultMTokenAccount.toBuffer()],
@@ -278,14 +344,35 @@ describe("extension swap tests", () => {
       for (const [i, p] of [extensionA, extensionB, extensionC].entries()) {
         await sendTransaction(
           p.methods
-            .initialize([], new BN(0))
+            .initialize(
+              [],
+              { scaledUi: { feeBps: new BN(0) } },
+              false,
+              new BN(0),
+              false
+            )
             .accounts({
+              ...getExtPdas(p.programId),
               mMint: mMint.publicKey,
               extMint: [mintA, mintB, mintC][i].publicKey,
             })
             .transaction(),
           [admin]
         );
+
+        await sendTransaction(
+          p.methods
+            .grantRole(
+              { wrapAuthorityManager: {} },
+              wrapAuthorityManager.publicKey
+            )
+            .accounts({
+              ...getExtPdas(p.programId),
+              admin: admin.publicKey,
+            })
+            .transaction(),
+          [admin]
+        );
       }
     });
   });
@@ -313,9 +400,10 @@ describe("extension swap tests", () => {
     it("add to ext whitelist", async () => {
       await sendTransaction(
         program.methods
-          .whitelistExtension()
+          .whitelistExtension(false)
           .accounts({
             extProgram: earn.programId,
+            extMint: mMint.publicKey,
           })
           .transaction(),
         [admin]
@@ -330,9 +418,13 @@ describe("extension swap tests", () => {
 
       // Validate the extension was added
       expect(whitelistedExtensions).toHaveLength(1);
-      expect(whitelistedExtensions[0].toBase58()).toBe(
+      expect(whitelistedExtensions[0].programId.toBase58()).toBe(
         earn.programId.toBase58()
       );
+      expect(whitelistedExtensions[0].extMint.toBase58()).toBe(
+        mMint.publicKey.toBase58()
+      );
+      expect(whitelistedExtensions[0].namespaced).toBe(false);
     });
 
     it("add to unwrap whitelist", async () => {
@@ -354,9 +446,12 @@ describe("extension swap tests", () => {
 
       // Validate whitelists
       expect(whitelistedExtensions).toHaveLength(1);
-      expect(whitelistedExtensions[0].toBase58()).toBe(
+      expect(whitelistedExtensions[0].programId.toBase58()).toBe(
         earn.programId.toBase58()
       );
+      expect(whitelistedExtensions[0].extMint.toBase58()).toBe(
+        mMint.publicKey.toBase58()
+      );
       expect(whitelistedUnwrappers).toHaveLength(1);
       expect(whitelistedUnwrappers[0].toBase58()).toBe(
         admin.publicKey.toBase58()
@@ -366,7 +461,7 @@ describe("extension swap tests", () => {
     it("remove non-existent entry", async () => {
       await sendTransaction(
         program.methods
-          .removeWhitelistedExtension(new Keypair().publicKey)
+          .removeWhitelistedExtension(new Keypair().publicKey, mMint.publicKey)
           .accounts({})
           .transaction(),
         [admin],
@@ -393,16 +488,19 @@ describe("extension swap tests", () => {
 
       // Validate whitelists
       expect(whitelistedExtensions).toHaveLength(1);
-      expect(whitelistedExtensions[0].toBase58()).toBe(
+      expect(whitelistedExtensions[0].programId.toBase58()).toBe(
         earn.programId.toBase58()
       );
+      expect(whitelistedExtensions[0].extMint.toBase58()).toBe(
+        mMint.publicKey.toBase58()
+      );
       expect(whitelistedUnwrappers).toHaveLength(0);
     });
 
     it("remove from ext whitelist", async () => {
       await sendTransaction(
         program.methods
-          .removeWhitelistedExtension(earn.programId)
+          .removeWhitelistedExtension(earn.programId, mMint.publicKey)
           .accounts({})
           .transaction(),
         [admin]
@@ -418,6 +516,72 @@ describe("extension swap tests", () => {
       // Validate the extension was removed
       expect(whitelistedExtensions).toHaveLength(0);
     });
+
+    it("migrate legacy config", async () => {
+      const current = svm.getAccount(swapGlobal)!;
+      const data = Buffer.from(current.data);
+
+      // Rewrite the config in the layout from before it was versioned, which
+      // starts with the bump and whitelists extensions by program only
+      const legacy = Buffer.alloc(8 + 1 + 32 + 4 + 4 + 32);
+      data.copy(legacy, 0, 0, 8); // discriminator
+      legacy[8] = data[9]; // bump
+      admin.publicKey.toBuffer().copy(legacy, 9);
+      legacy.writeUInt32LE(0, 41); // no unwrappers
+      legacy.writeUInt32LE(1, 45);
+      extProgramA.publicKey.toBuffer().copy(legacy, 49);
+      svm.setAccount(swapGlobal, { ...current, data: legacy });
+
+      // Each legacy program must be paired with the mint of its extension
+      await sendTransaction(
+        program.methods
+          .migrateGlobal([])
+          .accounts({ admin: admin.publicKey })
+          .transaction(),
+        [admin],
+        /Error Message: Index invalid for length of the array/
+      );
+
+      await sendTransaction(
+        program.methods
+          .migrateGlobal([mintA.publicKey])
+          .accounts({ admin: admin.publicKey })
+          .transaction(),
+        [admin]
+      );
+
+      const global = await program.account.swapGlobal.fetch(swapGlobal);
+      expect(global.version).toBe(1);
+      expect(global.admin.toBase58()).toBe(admin.publicKey.toBase58());
+      expect(global.whitelistedUnwrappers).toHaveLength(0);
+      expect(global.whitelistedExtensions).toHaveLength(1);
+      expect(global.whitelistedExtensions[0].programId.toBase58()).toBe(
+        extProgramA.publicKey.toBase58()
+      );
+      expect(global.whitelistedExtensions[0].extMint.toBase58()).toBe(
+        mintA.publicKey.toBase58()
+      );
+      expect(global.whitelistedExtensions[0].namespaced).toBe(false);
+
+      svm.expireBlockhash();
+      await sendTransaction(
+        program.methods
+          .migrateGlobal([mintA.publicKey])
+          .accounts({ admin: admin.publicKey })
+          .transaction(),
+        [admin],
+        /Error Message: Global account is already migrated/
+      );
+
+      // Restore the empty whitelist for the following tests
+      await sendTransaction(
+        program.methods
+          .removeWhitelistedExtension(extProgramA.publicKey, mintA.publicKey)
+          .accounts({})
+          .transaction(),
+        [admin]
+      );
+    });
   });
 
   describe("swapping", () => {
@@ -426,6 +590,7 @@ describe("extension swap tests", () => {
         program.methods
           .wrap(new BN(1e2))
           .accounts({
+            ...getToPdas(extProgramA.publicKey),
             signer: swapper.publicKey,
             wrapAuthority: program.programId,
             mMint: mMint.publicKey,
@@ -442,13 +607,18 @@ describe("extension swap tests", () => {
         /Error Message: Extension is not whitelisted/
       );
 
-      // Whitelist both extensions
-      for (const pid of [extProgramA, extProgramB, extProgramC]) {
+      // Whitelist the extensions
+      for (const [pid, mint] of [
+        [extProgramA, mintA],
+        [extProgramB, mintB],
+        [extProgramC, mintC],
+      ]) {
         await sendTransaction(
           program.methods
-            .whitelistExtension()
+            .whitelistExtension(false)
             .accounts({
               extProgram: pid.publicKey,
+              extMint: mint.publicKey,
             })
             .transaction(),
           [admin]
@@ -456,11 +626,60 @@ describe("extension swap tests", () => {
       }
     });
 
+    it("ext mint not whitelisted for the program", async () => {
+      // extProgramA is whitelisted, but only together with mintA
+      await sendTransaction(
+        program.methods
+          .wrap(new BN(1e2))
+          .accounts({
+            ...getToPdas(extProgramA.publicKey),
+            signer: swapper.publicKey,
+            wrapAuthority: program.programId,
+            mMint: mMint.publicKey,
+            mTokenProgram: TOKEN_2022_PROGRAM_ID,
+            toExtProgram: extProgramA.publicKey,
+            toMint: mintB.publicKey,
+            toTokenProgram: TOKEN_2022_PROGRAM_ID,
+            toMEarnerAccount: getMEarnerAccount(
+              getVaultMTokenAccount(getVault(extProgramA.publicKey))
+            ),
+          })
+          .transaction(),
+        [swapper],
+        /Error Message: Extension is not whitelisted/
+      );
+    });
+
+    it("extension PDAs of another program", async () => {
+      // The PDAs are derived from the whitelisted program and ext mint
+      await sendTransaction(
+        program.methods
+          .wrap(new BN(1e2))
+          .accounts({
+            ...getToPdas(extProgramB.publicKey),
+            signer: swapper.publicKey,
+            wrapAuthority: program.programId,
+            mMint: mMint.publicKey,
+            mTokenProgram: TOKEN_2022_PROGRAM_ID,
+            toExtProgram: extProgramA.publicKey,
+            toMint: mintA.publicKey,
+            toTokenProgram: TOKEN_2022_PROGRAM_ID,
+            toMEarnerAccount: getMEarnerAccount(
+              getVaultMTokenAccount(getVault(extProgramB.publicKey))
+            ),
+          })
+          .transaction(),
+        [swapper],
+        /Error Message: A seeds constraint was violated/
+      );
+    });
+
     it("swap program not whitelisted for wrapping", async () => {
       await sendTransaction(
         program.methods
           .wrap(new BN(1e3))
           .accounts({
+            ...getToPdas(extProgramA.publicKey),
             signer: swapper.publicKey,
             wrapAuthority: program.programId,
             mMint: mMint.publicKey,
@@ -474,7 +693,7 @@ describe("extension swap tests", () => {
           })
           .transaction(),
         [swapper],
-        /Error Message: Invalid signer/
+        /Error Message: The program expected this account to be already initialized/
       );
 
       // Whitelist swap program signer
@@ -485,8 +704,15 @@ describe("extension swap tests", () => {
         );
 
         await sendTransaction(
-          p.methods.addWrapAuthority(global).accounts({}).transaction(),
-          [admin]
+          p.methods
+            .addWrapAuthority(global)
+            .accounts({
+              ...getExtPdas(p.programId),
+              wrapAuthorityManager: wrapAuthorityManager.publicKey,
+              wrapAuthorityAccount: getWrapAuthority(p.programId, global),
+            })
+            .transaction(),
+          [wrapAuthorityManager]
         );
       }
     });
@@ -496,6 +722,7 @@ describe("extension swap tests", () => {
         program.methods
           .wrap(new BN(1e4))
           .accounts({
+            ...getToPdas(extProgramA.publicKey),
             signer: swapper.publicKey,
             wrapAuthority: program.programId,
             mMint: mMint.publicKey,
@@ -521,6 +748,7 @@ describe("extension swap tests", () => {
         program.methods
           .unwrap(new BN(1e1))
           .accounts({
+            ...getFromPdas(extProgramA.publicKey),
             signer: swapper.publicKey,
             unwrapAuthority: program.programId,
             mMint: mMint.publicKey,
@@ -552,6 +780,7 @@ describe("extension swap tests", () => {
         program.methods
           .unwrap(new BN(1e3))
           .accounts({
+            ...getFromPdas(extProgramA.publicKey),
             signer: swapper.publicKey,
             unwrapAuthority: program.programId,
             mMint: mMint.publicKey,
@@ -577,6 +806,8 @@ describe("extension swap tests", () => {
         program.methods
           .swap(new BN(1e3), 0)
           .accounts({
+            ...getFromPdas(extProgramA.publicKey),
+            ...getToPdas(extProgramB.publicKey),
             signer: swapper.publicKey,
             unwrapAuthority: program.programId,
             wrapAuthority: program.programId,
@@ -611,6 +842,8 @@ describe("extension swap tests", () => {
         program.methods
           .swap(new BN(0), 0)
           .accounts({
+            ...getFromPdas(extProgramA.publicKey),
+            ...getToPdas(extProgramB.publicKey),
             signer: swapper.publicKey,
             unwrapAuthority: program.programId,
             wrapAuthority: program.programId,
@@ -643,6 +876,8 @@ describe("extension swap tests", () => {
         program.methods
           .swap(new BN(1e2), 1)
           .accounts({
+            ...getFromPdas(extProgramA.publicKey),
+            ...getToPdas(extProgramB.publicKey),
             signer: swapper.publicKey,
             unwrapAuthority: program.programId,
             wrapAuthority: program.programId,
@@ -673,6 +908,8 @@ describe("extension swap tests", () => {
         program.methods
           .swap(new BN(1e3), 1)
           .accounts({
+            ...getFromPdas(extProgramA.publicKey),
+            ...getToPdas(extProgramB.publicKey),
             signer: swapper.publicKey,
             unwrapAuthority: program.programId,
             wrapAuthority: program.programId,
@@ -719,6 +956,8 @@ describe("extension swap tests", () => {
         program.methods
           .swap(new BN(1e3), 0)
           .accounts({
+            ...getFromPdas(extProgramA.publicKey),
+            ...getToPdas(extProgramC.publicKey),
             signer: swapper.publicKey,
             unwrapAuthority: program.programId,
             wrapAuthority: program.programId,
@@ -749,6 +988,8 @@ describe("extension swap tests", () => {
         program.methods
           .swap(new BN(1e3), 0)
           .accounts({
+            ...getFromPdas(extProgramA.publicKey),
+            ...getToPdas(extProgramC.publicKey),
             signer: swapper.publicKey,
             unwrapAuthority: program.programId,
             wrapAuthority: program.programId,
@@ -786,6 +1027,8 @@ describe("extension swap tests", () => {
         program.methods
           .swap(new BN(1e3), 0)
           .accounts({
+            ...getFromPdas(extProgramA.publicKey),
+            ...getToPdas(extProgramC.publicKey),
             signer: swapper.publicKey,
             unwrapAuthority: program.programId,
             wrapAuthority: program.programId,
@@ -822,7 +1065,7 @@ describe("extension swap tests", () => {
     it("remove from ext whitelist", async () => {
       await sendTransaction(
         program.methods
-          .removeWhitelistedExtension(extProgramC.publicKey)
+          .removeWhitelistedExtension(extProgramC.publicKey, mintC.publicKey)
           .accounts({})
           .transaction(),
         [admin]
@@ -834,6 +1077,8 @@ describe("extension swap tests", () => {
         program.methods
           .swap(new BN(1e3), 0)
           .accounts({
+            ...getFromPdas(extProgramB.publicKey),
+            ...getToPdas(extProgramC.publicKey),
             signer: swapper.publicKey,
             unwrapAuthority: program.programId,
             wrapAuthority: program.programId,
@@ -871,9 +1116,16 @@ describe("extension swap tests", () => {
       await sendTransaction(
         extensionA.methods
           .removeWrapAuthority(global)
-          .accounts({})
+          .accounts({
+            ...getExtPdas(extensionA.programId),
+            wrapAuthorityManager: wrapAuthorityManager.publicKey,
+            wrapAuthorityAccount: getWrapAuthority(
+              extensionA.programId,
+              global
+            ),
+          })
           .transaction(),
-        [admin]
+        [wrapAuthorityManager]
       );
 
       // Try to wrap
@@ -881,6 +1133,7 @@ describe("extension swap tests", () => {
         program.methods
           .wrap(new BN(1e1))
           .accounts({
+            ...getToPdas(extProgramA.publicKey),
             signer: swapper.publicKey,
             wrapAuthority: program.programId,
             mMint: mMint.publicKey,
@@ -894,7 +1147,7 @@ describe("extension swap tests", () => {
           })
           .transaction(),
         [swapper],
-        /Error Message: Invalid signer/
+        /Error Message: The program expected this account to be already initialized/
       );
     });
 
@@ -904,6 +1157,7 @@ describe("extension swap tests", () => {
         program.methods
           .wrap(new BN(1e1))
           .accounts({
+            ...getToPdas(extProgramA.publicKey, admin.publicKey),
             signer: swapper.publicKey,
             wrapAuthority: admin.publicKey,
             mMint: mMint.publicKey,
@@ -917,7 +1171,7 @@ describe("extension swap tests", () => {
           })
           .transaction(),
         [swapper, admin],
-        /Error Message: Invalid signer/
+        /Error Message: The program expected this account to be already initialized/
       );
     });
 
@@ -926,15 +1180,23 @@ describe("extension swap tests", () => {
       await sendTransaction(
         extensionA.methods
           .addWrapAuthority(admin.publicKey)
-          .accounts({})
+          .accounts({
+            ...getExtPdas(extensionA.programId),
+            wrapAuthorityManager: wrapAuthorityManager.publicKey,
+            wrapAuthorityAccount: getWrapAuthority(
+              extensionA.programId,
+              admin.publicKey
+            ),
+          })
           .transaction(),
-        [admin]
+        [wrapAuthorityManager]
       );
 
       await sendTransaction(
         program.methods
           .wrap(new BN(1e2))
           .accounts({
+            ...getToPdas(extProgramA.publicKey, admin.publicKey),
             signer: swapper.publicKey,
             wrapAuthority: admin.publicKey,
             mMint: mMint.publicKey,
@@ -956,6 +1218,8 @@ describe("extension swap tests", () => {
         program.methods
           .swap(new BN(15), 0)
           .accounts({
+            ...getFromPdas(extProgramB.publicKey),
+            ...getToPdas(extProgramA.publicKey),
             signer: swapper.publicKey,
             wrapAuthority: program.programId,
             unwrapAuthority: admin.publicKey,
@@ -986,6 +1250,8 @@ describe("extension swap tests", () => {
         program.methods
           .swap(new BN(15), 0)
           .accounts({
+            ...getFromPdas(extProgramB.publicKey),
+            ...getToPdas(extProgramA.publicKey, admin.publicKey),
             signer: swapper.publicKey,
             unwrapAuthority: program.programId,
             wrapAuthority: admin.publicKey,
@@ -1019,6 +1285,7 @@ describe("extension swap tests", () => {
         program.methods
           .unwrap(new BN(1e2))
           .accounts({
+            ...getFromPdas(extProgramA.publicKey, cosigner.publicKey),
             signer: swapper.publicKey,
             unwrapAuthority: cosigner.publicKey,
             fromExtProgram: extProgramA.publicKey,
@@ -1062,9 +1329,16 @@ describe("extension swap tests", () => {
       await sendTransaction(
         extensionA.methods
           .addWrapAuthority(cosigner.publicKey)
-          .accounts({ admin: admin.publicKey })
+          .accounts({
+            ...getExtPdas(extensionA.programId),
+            wrapAuthorityManager: wrapAuthorityManager.publicKey,
+            wrapAuthorityAccount: getWrapAuthority(
+              extensionA.programId,
+              cosigner.publicKey
+            ),
+          })
           .transaction(),
-        [admin]
+        [wrapAuthorityManager]
       );
     });
 
@@ -1073,6 +1347,7 @@ describe("extension swap tests", () => {
         program.methods
           .unwrap(new BN(1e3))
           .accounts({
+            ...getFromPdas(extProgramA.publicKey, cosigner.publicKey),
             signer: swapper.publicKey,
             unwrapAuthority: cosigner.publicKey,
             fromExtProgram: extProgramA.publicKey,
@@ -2,6 +2,7 @@ import { Program, AnchorError, BN } from "@coral-xyz/anchor";
 import { LiteSVM } from "litesvm";
 import { LiteSVMProvider } from "anchor-litesvm";
 import {
+  Ed25519Program,
   PublicKey,
   Keypair,
   LAMPORTS_PER_SOL,
@@ -15,6 +16,7 @@ import {
   TOKEN_PROGRAM_ID,
   TOKEN_2022_PROGRAM_ID,
   createInitializeMintInstruction,
+  createInitializeInterestBearingMintInstruction,
   createInitializeImmutableOwnerInstruction,
   createAssociatedTokenAccountInstruction,
   createCloseAccountInstruction,
@@ -26,10 +28,14 @@ import {
   getAssociatedTokenAddressSync,
   createInitializeAccountInstruction,
   createInitializeMultisigInstruction,
+  createInitializePermanentDelegateInstruction,
   createMintToCheckedInstruction,
   ExtensionType,
   getExtensionData,
+  getInterestBearingMintConfigState,
   createApproveCheckedInstruction,
+  createInitializeDefaultAccountStateInstruction,
+  AccountState,
 } from "@solana/spl-token";
 import {
   Earn,
@@ -44,8 +50,8 @@ import {
   ScaledUiAmountConfig,
   ScaledUiAmountConfigLayout,
 } from "../test-utils";
-import { MExt as ScaledUIExt } from "../../target/types/scaled_ui";
-import { MExt as NoYieldExt } from "../../target/types/no_yield";
+import { MExt } from "../../target/types/m_ext";
+import { createHash } from "crypto";
 
 export enum Comparison {
   Equal,
@@ -60,11 +66,17 @@ export enum Comparison {
 export enum Variant {
   ScaledUiAmount = "scaled_ui",
   NoYield = "no_yield",
+  YieldToOne = "yield_to_one",
+  EarnerManager = "earner_manager",
+  ClaimableYield = "claimable_yield",
+  InterestBearing = "interest_bearing",
+  MerkleDistribution = "merkle_distribution",
+  WeightedSplit = "weighted_split",
 }
 
-type MExt = ScaledUIExt | NoYieldExt;
-
-export type YieldConfig<V extends Variant> = V extends Variant.ScaledUiAmount
+export type YieldConfig<V extends Variant> = V extends
+  | Variant.ScaledUiAmount
+  | Variant.InterestBearing
   ? {
       feeBps?: BN;
       lastMIndex?: BN;
@@ -72,7 +84,59 @@ export type YieldConfig<V extends Variant> = V extends Variant.ScaledUiAmount
     }
   : {};
 
+export type Role =
+  | { feeManager: {} }
+  | { wrapAuthorityManager: {} }
+  | { feeClaimer: {} }
+  | { pauser: {} }
+  | { complianceOfficer: {} };
+
+// Changes that can be queued while the timelock is enabled
+export type TimelockAction =
+  | { setFee: { feeBps: BN } }
+  | { addWrapAuthority: { wrapAuthority: PublicKey } }
+  | { proposeAdmin: { newAdmin: PublicKey } }
+  | { setEarnerFee: { tokenAccount: PublicKey; feeBps: BN } }
+  | { setYieldRecipient: { yieldRecipient: PublicKey } }
+  | { setBeneficiaries: { beneficiaries: Beneficiary[] } }
+  | {
+      setWrapAuthorityPermissions: {
+        wrapAuthority: PublicKey;
+        permissions: number;
+        expiresAt: BN;
+      };
+    }
+  | { setOpenMode: { openWrap: boolean; openUnwrap: boolean } };
+
+// Bits of the pause flags on the global account
+export const PAUSE_WRAP = 1;
+export const PAUSE_UNWRAP = 2;
+export const PAUSE_CLAIM = 4;
+
+// Payout of a merkle distribution, leaves are hashed with their index in the tree
+export type DistributionLeaf = {
+  claimant: PublicKey;
+  amount: BN;
+};
+
+// Share of the yield of the weighted split mode
+export type Beneficiary = {
+  tokenAccount: PublicKey;
+  weightBps: BN;
+};
+
+// Voucher signed off-chain by a wrap authority for wrap_with_voucher
+export type WrapVoucher = {
+  extMint: PublicKey;
+  wrapAuthority: PublicKey;
+  user: PublicKey;
+  maxAmount: BN;
+  expiresAt: BN;
+  nonce: BN;
+};
+
 export type ExtGlobal<V extends Variant> = {
+  version?: number;
   admin?: PublicKey;
   extMint?: PublicKey;
   mMint?: PublicKey;
@@ -80,7 +144,8 @@ export type ExtGlobal<V extends Variant> = {
   bump?: number;
   mVaultBump?: number;
   extMintAuthorityBump?: number;
-  wrapAuthorities?: PublicKey[];
+  namespaced?: boolean;
+  pendingAdmin?: PublicKey | null;
   yieldConfig?: YieldConfig<V>;
 };
 
@@ -91,6 +156,7 @@ const PROGRAM_ID = new PublicKey(
 // Test harness for the MExt program that encapsulates all the necessary setup and helper functions to test a given program variant
 export class ExtensionTest<V extends Variant = Variant.ScaledUiAmount> {
   public variant: V;
+  public namespaced: boolean;
   public svm: LiteSVM;
   public provider: LiteSVMProvider;
   public accounts: Record<string, PublicKey | null> = {};
@@ -102,13 +168,20 @@ export class ExtensionTest<V extends Variant = Variant.ScaledUiAmount> {
   public mMintAuthority: Keypair;
   public earnAuthority: Keypair;
   public wrapAuthority: Keypair;
+  public feeManager: Keypair;
+  public wrapAuthorityManager: Keypair;
+  public feeClaimer: Keypair;
+  public pauser: Keypair;
+  public complianceOfficer: Keypair;
   public nonAdmin: Keypair;
   public nonWrapAuthority: Keypair;
+  public yieldRecipient: Keypair;
   public mEarnerList: PublicKey[] = [];
 
-  constructor(variant: V, addresses: PublicKey[]) {
+  constructor(variant: V, addresses: PublicKey[], namespaced = false) {
     this.variant = variant;
-    const M_EXT_IDL = require(`../../target/idl/${variant}.json`);
+    this.namespaced = namespaced;
+    const M_EXT_IDL = require("../../target/idl/m_ext.json");
 
     // Initialize the SVM instance with all necessary configurations
     this.svm = new LiteSVM()
@@ -128,7 +201,7 @@ export class ExtensionTest<V extends Variant = Variant.ScaledUiAmount> {
     );
 
     // Add the ext program to the SVM instance
-    this.svm.addProgramFromFile(PROGRAM_ID, `target/deploy/${variant}.so`);
+    this.svm.addProgramFromFile(PROGRAM_ID, "target/deploy/m_ext.so");
 
     // Create an anchor provider from the liteSVM instance
     this.provider = new LiteSVMProvider(this.svm);
@@ -144,15 +217,27 @@ export class ExtensionTest<V extends Variant = Variant.ScaledUiAmount> {
     this.mMintAuthority = new Keypair();
     this.earnAuthority = new Keypair();
     this.wrapAuthority = new Keypair();
+    this.feeManager = new Keypair();
+    this.wrapAuthorityManager = new Keypair();
+    this.feeClaimer = new Keypair();
+    this.pauser = new Keypair();
+    this.complianceOfficer = new Keypair();
     this.nonAdmin = new Keypair();
     this.nonWrapAuthority = new Keypair();
+    this.yieldRecipient = new Keypair();
 
     addresses = addresses.concat([
       this.admin.publicKey,
       this.earnAuthority.publicKey,
       this.wrapAuthority.publicKey,
+      this.feeManager.publicKey,
+      this.wrapAuthorityManager.publicKey,
+      this.feeClaimer.publicKey,
+      this.pauser.publicKey,
+      this.complianceOfficer.publicKey,
       this.nonAdmin.publicKey,
       this.nonWrapAuthority.publicKey,
+      this.yieldRecipient.publicKey,
     ]);
 
     for (const address of addresses) {
@@ -164,13 +249,41 @@ export class ExtensionTest<V extends Variant = Variant.ScaledUiAmount> {
     // Create the M token mint
     await this.createMintWithMultisig(this.mMint, this.mMintAuthority);
 
-    // Create the Ext token mint
+    // Create the Ext token mint, frozen by the freeze authority PDA of the extension
+    // and with its permanent delegate PDA so tokens can be seized
     switch (this.variant) {
       case Variant.ScaledUiAmount:
-        await this.createScaledUiMint(this.extMint, this.getExtMintAuthority());
+        await this.createScaledUiMint(
+          this.extMint,
+          this.getExtMintAuthority(),
+          6,
+          this.getFreezeAuthority(),
+          this.getPermanentDelegate()
+        );
+        break;
+      case Variant.InterestBearing:
+        await this.createInterestBearingMint(
+          this.extMint,
+          this.getExtMintAuthority(),
+          6,
+          this.getFreezeAuthority(),
+          this.getPermanentDelegate()
+        );
         break;
       case Variant.NoYield:
-        await this.createMint(this.extMint, this.getExtMintAuthority());
+      case Variant.YieldToOne:
+      case Variant.EarnerManager:
+      case Variant.ClaimableYield:
+      case Variant.MerkleDistribution:
+      case Variant.WeightedSplit:
+        await this.createMint(
+          this.extMint,
+          this.getExtMintAuthority(),
+          true,
+          6,
+          this.getFreezeAuthority(),
+          this.getPermanentDelegate()
+        );
         break;
       default:
         throw new Error("Unsupported variant for MExt");
@@ -456,13 +569,19 @@ export class ExtensionTest<V extends Variant = Variant.ScaledUiAmount> {
     mintAuthority: PublicKey,
     use2022: boolean = true,
     decimals = 6,
-    freezeAuthority: boolean = true
+    freezeAuthority: PublicKey | null = mintAuthority,
+    permanentDelegate: PublicKey | null = null,
+    defaultFrozen = false
   ) {
     // Create and initialize mint account
 
     const tokenProgram = use2022 ? TOKEN_2022_PROGRAM_ID : TOKEN_PROGRAM_ID;
 
-    const mintLen = getMintLen([]);
+    const extensions: ExtensionType[] = [];
+    if (permanentDelegate) extensions.push(ExtensionType.PermanentDelegate);
+    if (defaultFrozen) extensions.push(ExtensionType.DefaultAccountState);
+
+    const mintLen = getMintLen(extensions);
     const mintLamports =
       await this.provider.connection.getMinimumBalanceForRentExemption(mintLen);
     const createMintAccount = SystemProgram.createAccount({
@@ -477,12 +596,32 @@ export class ExtensionTest<V extends Variant = Variant.ScaledUiAmount> {
       mint.publicKey,
       decimals, // decimals
       mintAuthority, // mint authority
-      freezeAuthority ? mintAuthority : null, // freeze authority
+      freezeAuthority, // freeze authority
       tokenProgram
     );
 
     let tx = new Transaction();
-    tx.add(createMintAccount, initializeMint);
+    tx.add(createMintAccount);
+    if (permanentDelegate) {
+      tx.add(
+        createInitializePermanentDelegateInstruction(
+          mint.publicKey,
+          permanentDelegate,
+          tokenProgram
+        )
+      );
+    }
+    if (defaultFrozen) {
+      // New token accounts start frozen, as required by the allowlist
+      tx.add(
+        createInitializeDefaultAccountStateInstruction(
+          mint.publicKey,
+          AccountState.Frozen,
+          tokenProgram
+        )
+      );
+    }
+    tx.add(initializeMint);
 
     await this.provider.sendAndConfirm!(tx, [this.admin, mint]);
 
@@ -524,13 +663,19 @@ export class ExtensionTest<V extends Variant = Variant.ScaledUiAmount> {
   public async createScaledUiMint(
     mint: Keypair,
     mintAuthority: PublicKey,
-    decimals = 6
+    decimals = 6,
+    freezeAuthority: PublicKey = mintAuthority,
+    permanentDelegate: PublicKey | null = null
   ) {
     // Create and initialize mint account
 
     const tokenProgram = TOKEN_2022_PROGRAM_ID;
 
-    const mintLen = getMintLen([ExtensionType.ScaledUiAmountConfig]);
+    const mintLen = getMintLen(
+      permanentDelegate
+        ? [ExtensionType.ScaledUiAmountConfig, ExtensionType.PermanentDelegate]
+        : [ExtensionType.ScaledUiAmountConfig]
+    );
     const mintLamports =
       await this.provider.connection.getMinimumBalanceForRentExemption(mintLen);
     const createMintAccount = SystemProgram.createAccount({
@@ -553,12 +698,22 @@ export class ExtensionTest<V extends Variant = Variant.ScaledUiAmount> {
       mint.publicKey,
       decimals, // decimals
       mintAuthority, // mint authority
-      mintAuthority, // freeze authority
+      freezeAuthority, // freeze authority
       tokenProgram
     );
 
     let tx = new Transaction();
-    tx.add(createMintAccount, initializeScaledUiAmountConfig, initializeMint);
+    tx.add(createMintAccount, initializeScaledUiAmountConfig);
+    if (permanentDelegate) {
+      tx.add(
+        createInitializePermanentDelegateInstruction(
+          mint.publicKey,
+          permanentDelegate,
+          tokenProgram
+        )
+      );
+    }
+    tx.add(initializeMint);
 
     await this.provider.sendAndConfirm!(tx, [this.admin, mint]);
 
@@ -573,6 +728,82 @@ export class ExtensionTest<V extends Variant = Variant.ScaledUiAmount> {
     return mint.publicKey;
   }
 
+  public async createInterestBearingMint(
+    mint: Keypair,
+    mintAuthority: PublicKey,
+    decimals = 6,
+    freezeAuthority: PublicKey = mintAuthority,
+    permanentDelegate: PublicKey | null = null
+  ) {
+    // Create and initialize mint account, the mint authority is also the rate authority
+
+    const tokenProgram = TOKEN_2022_PROGRAM_ID;
+
+    const mintLen = getMintLen(
+      permanentDelegate
+        ? [ExtensionType.InterestBearingConfig, ExtensionType.PermanentDelegate]
+        : [ExtensionType.InterestBearingConfig]
+    );
+    const mintLamports =
+      await this.provider.connection.getMinimumBalanceForRentExemption(mintLen);
+    const createMintAccount = SystemProgram.createAccount({
+      fromPubkey: this.admin.publicKey,
+      newAccountPubkey: mint.publicKey,
+      space: mintLen,
+      lamports: mintLamports,
+      programId: tokenProgram,
+    });
+
+    const initializeInterestBearingConfig =
+      createInitializeInterestBearingMintInstruction(
+        mint.publicKey,
+        mintAuthority,
+        0, // rate
+        tokenProgram
+      );
+
+    const initializeMint = createInitializeMintInstruction(
+      mint.publicKey,
+      decimals, // decimals
+      mintAuthority, // mint authority
+      freezeAuthority, // freeze authority
+      tokenProgram
+    );
+
+    let tx = new Transaction();
+    tx.add(createMintAccount, initializeInterestBearingConfig);
+    if (permanentDelegate) {
+      tx.add(
+        createInitializePermanentDelegateInstruction(
+          mint.publicKey,
+          permanentDelegate,
+          tokenProgram
+        )
+      );
+    }
+    tx.add(initializeMint);
+
+    await this.provider.sendAndConfirm!(tx, [this.admin, mint]);
+
+    return mint.publicKey;
+  }
+
+  // Current rate in bps set on an interest bearing mint
+  public async getInterestRate(mint: PublicKey): Promise<number> {
+    const mintAccount = await getMint(
+      this.provider.connection,
+      mint,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const config = getInterestBearingMintConfigState(mintAccount);
+    if (config === null) {
+      throw new Error("Extension data not found");
+    }
+
+    return config.currentRate;
+  }
+
   public async getScaledUiAmountConfig(
     mint: PublicKey
   ): Promise<ScaledUiAmountConfig> {
@@ -692,6 +923,17 @@ export class ExtensionTest<V extends Variant = Variant.ScaledUiAmount> {
     return new BN(tokenAccountInfo.amount.toString());
   }
 
+  public async isFrozen(tokenAccount: PublicKey) {
+    const tokenAccountInfo = await getAccount(
+      this.provider.connection,
+      tokenAccount,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    return tokenAccountInfo.isFrozen;
+  }
+
   public async getTokenUiBalance(tokenAccount: PublicKey, multiplier?: number) {
     const tokenAccountInfo = await getAccount(
       this.provider.connection,
@@ -791,9 +1033,14 @@ export class ExtensionTest<V extends Variant = Variant.ScaledUiAmount> {
     return earnTokenAuthority;
   }
 
+  // PDAs of a namespaced extension have the ext mint as an extra seed
+  public getSeedNamespace(): Buffer[] {
+    return this.namespaced ? [this.extMint.publicKey.toBuffer()] : [];
+  }
+
   public getExtGlobalAccount(): PublicKey {
     const [globalAccount] = PublicKey.findProgramAddressSync(
-      [Buffer.from("global")],
+      [Buffer.from("global"), ...this.getSeedNamespace()],
       this.ext.programId
     );
 
@@ -802,7 +1049,7 @@ export class ExtensionTest<V extends Variant = Variant.ScaledUiAmount> {
 
   public getExtMintAuthority(): PublicKey {
     const [extMintAuthority] = PublicKey.findProgramAddressSync(
-      [Buffer.from("mint_authority")],
+      [Buffer.from("mint_authority"), ...this.getSeedNamespace()],
       this.ext.programId
     );
 
@@ -811,13 +1058,251 @@ export class ExtensionTest<V extends Variant = Variant.ScaledUiAmount> {
 
   public getMVault(): PublicKey {
     const [mVault] = PublicKey.findProgramAddressSync(
-      [Buffer.from("m_vault")],
+      [Buffer.from("m_vault"), ...this.getSeedNamespace()],
       this.ext.programId
     );
 
     return mVault;
   }
 
+  public getMultisig(): PublicKey {
+    const [multisig] = PublicKey.findProgramAddressSync(
+      [Buffer.from("multisig"), ...this.getSeedNamespace()],
+      this.ext.programId
+    );
+
+    return multisig;
+  }
+
+  public getMultisigAuthority(): PublicKey {
+    const [multisigAuthority] = PublicKey.findProgramAddressSync(
+      [Buffer.from("multisig_authority"), ...this.getSeedNamespace()],
+      this.ext.programId
+    );
+
+    return multisigAuthority;
+  }
+
+  public getProposal(index: BN): PublicKey {
+    const [proposal] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        ...this.getSeedNamespace(),
+        index.toArrayLike(Buffer, "le", 8),
+      ],
+      this.ext.programId
+    );
+
+    return proposal;
+  }
+
+  public getTimelockAuthority(): PublicKey {
+    const [timelockAuthority] = PublicKey.findProgramAddressSync(
+      [Buffer.from("timelock_authority"), ...this.getSeedNamespace()],
+      this.ext.programId
+    );
+
+    return timelockAuthority;
+  }
+
+  public getQueuedAction(index: BN): PublicKey {
+    const [queuedAction] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("queued_action"),
+        ...this.getSeedNamespace(),
+        index.toArrayLike(Buffer, "le", 8),
+      ],
+      this.ext.programId
+    );
+
+    return queuedAction;
+  }
+
+  public getFreezeAuthority(): PublicKey {
+    const [freezeAuthority] = PublicKey.findProgramAddressSync(
+      [Buffer.from("freeze_authority"), ...this.getSeedNamespace()],
+      this.ext.programId
+    );
+
+    return freezeAuthority;
+  }
+
+  public getPermanentDelegate(): PublicKey {
+    const [permanentDelegate] = PublicKey.findProgramAddressSync(
+      [Buffer.from("permanent_delegate"), ...this.getSeedNamespace()],
+      this.ext.programId
+    );
+
+    return permanentDelegate;
+  }
+
+  public getRateLimit(): PublicKey {
+    const [rateLimit] = PublicKey.findProgramAddressSync(
+      [Buffer.from("rate_limit"), ...this.getSeedNamespace()],
+      this.ext.programId
+    );
+
+    return rateLimit;
+  }
+
+  public getTombstone(): PublicKey {
+    const [tombstone] = PublicKey.findProgramAddressSync(
+      [Buffer.from("tombstone"), ...this.getSeedNamespace()],
+      this.ext.programId
+    );
+
+    return tombstone;
+  }
+
+  public getWrapAuthority(authority: PublicKey): PublicKey {
+    const [wrapAuthority] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("wrap_authority"),
+        ...this.getSeedNamespace(),
+        authority.toBuffer(),
+      ],
+      this.ext.programId
+    );
+
+    return wrapAuthority;
+  }
+
+  // Accounts of the wrap authorities created by initialize and migrate_global
+  public getWrapAuthorityMetas(authorities: PublicKey[]) {
+    return authorities.map((authority) => ({
+      pubkey: this.getWrapAuthority(authority),
+      isSigner: false,
+      isWritable: true,
+    }));
+  }
+
+  public getBlocklistEntry(wallet: PublicKey): PublicKey {
+    const [blocklistEntry] = PublicKey.findProgramAddressSync(
+      [Buffer.from("blocklist"), ...this.getSeedNamespace(), wallet.toBuffer()],
+      this.ext.programId
+    );
+
+    return blocklistEntry;
+  }
+
+  public getVoucherNonce(authority: PublicKey, nonce: BN): PublicKey {
+    const [voucherNonce] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("voucher_nonce"),
+        ...this.getSeedNamespace(),
+        authority.toBuffer(),
+        nonce.toArrayLike(Buffer, "le", 8),
+      ],
+      this.ext.programId
+    );
+
+    return voucherNonce;
+  }
+
+  public getAllowlistEntry(wallet: PublicKey): PublicKey {
+    const [allowlistEntry] = PublicKey.findProgramAddressSync(
+      [Buffer.from("allowlist"), ...this.getSeedNamespace(), wallet.toBuffer()],
+      this.ext.programId
+    );
+
+    return allowlistEntry;
+  }
+
+  public getComplianceFreeze(tokenAccount: PublicKey): PublicKey {
+    const [complianceFreeze] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("compliance_freeze"),
+        ...this.getSeedNamespace(),
+        tokenAccount.toBuffer(),
+      ],
+      this.ext.programId
+    );
+
+    return complianceFreeze;
+  }
+
+  public getDistribution(index: BN): PublicKey {
+    const [distribution] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("distribution"),
+        ...this.getSeedNamespace(),
+        index.toArrayLike(Buffer, "le", 8),
+      ],
+      this.ext.programId
+    );
+
+    return distribution;
+  }
+
+  // Wrap and unwrap check the blocklist entry of the owner of the ext token account
+  public getBlocklistEntryOf(tokenAccount: PublicKey): PublicKey {
+    const accountInfo = this.svm.getAccount(tokenAccount);
+    // The owner is stored after the mint in the token account data
+    const owner = accountInfo
+      ? new PublicKey(accountInfo.data.slice(32, 64))
+      : PublicKey.default;
+
+    return this.getBlocklistEntry(owner);
+  }
+
+  // The IDL can't resolve the PDAs of the extension
+  // since they are namespaced by the ext mint in namespaced mode
+  public getExtPdas() {
+    return {
+      globalAccount: this.getExtGlobalAccount(),
+      mVault: this.getMVault(),
+      extMintAuthority: this.getExtMintAuthority(),
+      multisig: this.getMultisig(),
+      multisigAuthority: this.getMultisigAuthority(),
+      timelockAuthority: this.getTimelockAuthority(),
+      freezeAuthority: this.getFreezeAuthority(),
+      permanentDelegate: this.getPermanentDelegate(),
+      rateLimit: this.getRateLimit(),
+      tombstone: this.getTombstone(),
+    };
+  }
+
+  // Ext token account of the yield recipient keypair, created on first use
+  public getYieldRecipient(): PublicKey {
+    return getAssociatedTokenAddressSync(
+      this.extMint.publicKey,
+      this.yieldRecipient.publicKey,
+      true,
+      TOKEN_2022_PROGRAM_ID,
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+  }
+
+  public getExtEarnerAccount(tokenAccount: PublicKey): PublicKey {
+    const [earnerAccount] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("earner"),
+        ...this.getSeedNamespace(),
+        tokenAccount.toBuffer(),
+      ],
+      this.ext.programId
+    );
+
+    return earnerAccount;
+  }
+
+  // The yield recipient gets 60% and the non-admin 40% of the weighted split
+  public getDefaultBeneficiaries(): Beneficiary[] {
+    return [
+      { tokenAccount: this.getYieldRecipient(), weightBps: new BN(6000) },
+      {
+        tokenAccount: getAssociatedTokenAddressSync(
+          this.extMint.publicKey,
+          this.nonAdmin.publicKey,
+          true,
+          TOKEN_2022_PROGRAM_ID,
+          ASSOCIATED_TOKEN_PROGRAM_ID
+        ),
+        weightBps: new BN(4000),
+      },
+    ];
+  }
+
   public getMEarnerAccount(tokenAccount: PublicKey): PublicKey {
     const [earnerAccount] = PublicKey.findProgramAddressSync(
       [Buffer.from("earner"), tokenAccount.toBuffer()],
@@ -827,14 +1312,33 @@ export class ExtensionTest<V extends Variant = Variant.ScaledUiAmount> {
     return earnerAccount;
   }
 
+  // The scaled ui and interest bearing modes convert between M and ext tokens with an index
+  public hasIndex(): boolean {
+    return (
+      this.variant === Variant.ScaledUiAmount ||
+      this.variant === Variant.InterestBearing
+    );
+  }
+
+  public async getIndexYieldConfig(): Promise<
+    YieldConfig<Variant.ScaledUiAmount | Variant.InterestBearing>
+  > {
+    const yieldConfig = (
+      await this.ext.account.extGlobal.fetch(this.getExtGlobalAccount())
+    ).yieldConfig;
+
+    return this.variant === Variant.InterestBearing
+      ? yieldConfig.interestBearing!["0"]
+      : yieldConfig.scaledUi!["0"];
+  }
+
   public async getNewMultiplier(newIndex: BN): Promise<number> {
-    if (this.variant === Variant.NoYield) {
+    // Ext tokens are 1:1 with M in the modes without an index on the mint
+    if (!this.hasIndex()) {
       return 1.0;
     }
 
-    const yieldConfig: YieldConfig<Variant.ScaledUiAmount> = (
-      await this.ext.account.extGlobal.fetch(this.getExtGlobalAccount())
-    ).yieldConfig;
+    const yieldConfig = await this.getIndexYieldConfig();
 
     return (
       (yieldConfig.lastExtIndex!.toNumber() / 1e12) *
@@ -844,13 +1348,12 @@ export class ExtensionTest<V extends Variant = Variant.ScaledUiAmount> {
   }
 
   public async getCurrentMultiplier(): Promise<number> {
-    if (this.variant === Variant.NoYield) {
+    // Ext tokens are 1:1 with M in the modes without an index on the mint
+    if (!this.hasIndex()) {
       return 1.0;
     }
 
-    const yieldConfig: YieldConfig<Variant.ScaledUiAmount> = (
-      await this.ext.account.extGlobal.fetch(this.getExtGlobalAccount())
-    ).yieldConfig;
+    const yieldConfig = await this.getIndexYieldConfig();
 
     return yieldConfig.lastExtIndex!.toNumber() / 1e12;
   }
@@ -866,7 +1369,7 @@ export class ExtensionTest<V extends Variant = Variant.ScaledUiAmount> {
     }
   }
 
-  public async expectAnchorError(txResult: Promise<string>, errCode: string) {
+  public async expectAnchorError(txResult: Promise<unknown>, errCode: string) {
     try {
       await txResult;
       throw new Error("Transaction should have reverted");
@@ -878,7 +1381,7 @@ export class ExtensionTest<V extends Variant = Variant.ScaledUiAmount> {
     }
   }
 
-  public async expectSystemError(txResult: Promise<string>) {
+  public async expectSystemError(txResult: Promise<unknown>) {
     let reverted = false;
     try {
       await txResult;
@@ -896,6 +1399,7 @@ export class ExtensionTest<V extends Variant = Variant.ScaledUiAmount> {
       this.getExtGlobalAccount()
     );
 
+    if (expected.version) expect(state.version).toEqual(expected.version);
     if (expected.admin) expect(state.admin).toEqual(expected.admin);
     if (expected.extMint) expect(state.extMint).toEqual(expected.extMint);
     if (expected.mMint) expect(state.mMint).toEqual(expected.mMint);
@@ -905,10 +1409,10 @@ export class ExtensionTest<V extends Variant = Variant.ScaledUiAmount> {
     if (expected.yieldConfig) {
       switch (this.variant) {
         case Variant.ScaledUiAmount:
-          this.expectScaledUiYieldConfig(state.yieldConfig);
+          this.expectScaledUiYieldConfig(state.yieldConfig.scaledUi!["0"]);
           break;
         case Variant.NoYield:
-          expect(state.yieldConfig).toEqual({});
+          expect(state.yieldConfig).toEqual({ noYield: {} });
           break;
         default:
           throw new Error("Unsupported variant for yield config");
@@ -919,6 +1423,10 @@ export class ExtensionTest<V extends Variant = Variant.ScaledUiAmount> {
       expect(state.mVaultBump).toEqual(expected.mVaultBump);
     if (expected.extMintAuthorityBump)
       expect(state.extMintAuthorityBump).toEqual(expected.extMintAuthorityBump);
+    if (expected.namespaced !== undefined)
+      expect(state.namespaced).toEqual(expected.namespaced);
+    if (expected.pendingAdmin !== undefined)
+      expect(state.pendingAdmin).toEqual(expected.pendingAdmin);
   }
 
   private expectScaledUiYieldConfig<V extends Variant.ScaledUiAmount>(
@@ -986,7 +1494,7 @@ export class ExtensionTest<V extends Variant = Variant.ScaledUiAmount> {
       await this.getATA(this.mMint.publicKey, this.getMVault())
     );
 
-    this.variant === Variant.ScaledUiAmount
+    this.hasIndex()
       ? expect(BigInt(mVaultBalance.toString())).toBeGreaterThan(
           BigInt(extSupply.sub(BN.min(new BN(2), extSupply)).toString())
         ) // allow for a rounding error of 2 for scaled ui due to precision issues
@@ -1186,46 +1694,115 @@ export class ExtensionTest<V extends Variant = Variant.ScaledUiAmount> {
   }
   // Helper functions for executing MExt instructions
 
-  public async initializeExt(wrapAuthorities: PublicKey[], fee_bps?: BN) {
+  // Returns the yield mode argument for initialize that matches the variant under test
+  public getYieldMode(fee_bps?: BN) {
     switch (this.variant) {
       case Variant.ScaledUiAmount:
         if (!fee_bps) {
           throw new Error("fee_bps is required for Scaled UI variant");
         }
-        // Send the transaction
-        await this.ext.methods
-          .initialize(wrapAuthorities, fee_bps)
-          .accounts({
-            admin: this.admin.publicKey,
-            mMint: this.mMint.publicKey,
-            extMint: this.extMint.publicKey,
-          })
-          .signers([this.admin])
-          .rpc();
-        break;
+        return { scaledUi: { feeBps: fee_bps } };
+      case Variant.InterestBearing:
+        if (!fee_bps) {
+          throw new Error("fee_bps is required for Interest Bearing variant");
+        }
+        return { interestBearing: { feeBps: fee_bps } };
       case Variant.NoYield:
-        // Send the transaction
-        await this.ext.methods
-          .initialize(wrapAuthorities)
-          .accounts({
-            admin: this.admin.publicKey,
-            mMint: this.mMint.publicKey,
-            extMint: this.extMint.publicKey,
-          })
-          .signers([this.admin])
-          .rpc();
-        break;
+        return { noYield: {} };
+      case Variant.YieldToOne:
+        return { yieldToOne: { yieldRecipient: this.getYieldRecipient() } };
+      case Variant.EarnerManager:
+        return { earnerManager: { feeRecipient: this.getYieldRecipient() } };
+      case Variant.ClaimableYield:
+        return { claimableYield: {} };
+      case Variant.MerkleDistribution:
+        return { merkleDistribution: {} };
+      case Variant.WeightedSplit:
+        return {
+          weightedSplit: { beneficiaries: this.getDefaultBeneficiaries() },
+        };
       default:
-        throw new Error("Unsupported variant for initializeExt");
+        throw new Error("Unsupported variant for yield mode");
     }
   }
 
+  public async initializeExt(
+    wrapAuthorities: PublicKey[],
+    fee_bps?: BN,
+    timelockDelay: BN = new BN(0),
+    requireAllowlist = false
+  ) {
+    // Send the transaction, the timelock is enabled once the roles are granted
+    await this.ext.methods
+      .initialize(
+        wrapAuthorities,
+        this.getYieldMode(fee_bps),
+        this.namespaced,
+        new BN(0),
+        requireAllowlist
+      )
+      .accounts({
+        ...this.getExtPdas(),
+        admin: this.admin.publicKey,
+        mMint: this.mMint.publicKey,
+        extMint: this.extMint.publicKey,
+      })
+      .remainingAccounts(this.getWrapAuthorityMetas(wrapAuthorities))
+      .signers([this.admin])
+      .rpc();
+
+    // Delegate the roles to separate keys
+    await this.grantRole({ feeManager: {} }, this.feeManager.publicKey);
+    await this.grantRole(
+      { wrapAuthorityManager: {} },
+      this.wrapAuthorityManager.publicKey
+    );
+    await this.grantRole({ feeClaimer: {} }, this.feeClaimer.publicKey);
+    await this.grantRole({ pauser: {} }, this.pauser.publicKey);
+    await this.grantRole(
+      { complianceOfficer: {} },
+      this.complianceOfficer.publicKey
+    );
+
+    if (timelockDelay.gtn(0)) {
+      await this.setTimelockDelay(timelockDelay);
+    }
+  }
+
+  public async setTimelockDelay(timelockDelay: BN) {
+    // Send the instruction
+    await this.ext.methods
+      .setTimelockDelay(timelockDelay)
+      .accounts({
+        ...this.getExtPdas(),
+        admin: this.admin.publicKey,
+      })
+      .signers([this.admin])
+      .rpc();
+  }
+
+  public async grantRole(role: Role, account: PublicKey) {
+    // Send the instruction
+    await this.ext.methods
+      .grantRole(role, account)
+      .accounts({
+        ...this.getExtPdas(),
+        admin: this.admin.publicKey,
+      })
+      .signers([this.admin])
+      .rpc();
+  }
+
   public async addWrapAuthority(newWrapAuthority: PublicKey) {
     // Send the instruction
     await this.ext.methods
       .addWrapAuthority(newWrapAuthority)
-      .accounts({})
-      .signers([this.admin])
+      .accounts({
+        ...this.getExtPdas(),
+        wrapAuthorityManager: this.wrapAuthorityManager.publicKey,
+        wrapAuthorityAccount: this.getWrapAuthority(newWrapAuthority),
+      })
+      .signers([this.wrapAuthorityManager])
       .rpc();
   }
 
@@ -1233,8 +1810,171 @@ export class ExtensionTest<V extends Variant = Variant.ScaledUiAmount> {
     // Send the instruction
     await this.ext.methods
       .removeWrapAuthority(oldWrapAuthority)
-      .accounts({})
-      .signers([this.admin])
+      .accounts({
+        ...this.getExtPdas(),
+        wrapAuthorityManager: this.wrapAuthorityManager.publicKey,
+        wrapAuthorityAccount: this.getWrapAuthority(oldWrapAuthority),
+      })
+      .signers([this.wrapAuthorityManager])
+      .rpc();
+  }
+
+  // Accounts of add_wrap_authority, in order, with a PDA of the program as wrap authority manager.
+  // They are passed as remaining accounts when the change is executed by the multisig or the timelock
+  public getAddWrapAuthorityAccounts(
+    authority: PublicKey,
+    newWrapAuthority: PublicKey = this.nonWrapAuthority.publicKey
+  ) {
+    return [
+      {
+        pubkey: authority,
+        isSigner: false,
+        isWritable: true,
+      },
+      {
+        pubkey: this.getExtGlobalAccount(),
+        isSigner: false,
+        isWritable: false,
+      },
+      {
+        pubkey: this.getWrapAuthority(newWrapAuthority),
+        isSigner: false,
+        isWritable: true,
+      },
+      {
+        pubkey: SystemProgram.programId,
+        isSigner: false,
+        isWritable: false,
+      },
+    ];
+  }
+
+  // Proposes adding the non wrap authority as a wrap authority through the multisig
+  public async createProposal(
+    proposer: Keypair,
+    index: number,
+    expiresAt?: BN
+  ) {
+    // Send the instruction
+    await this.ext.methods
+      .createProposal(
+        {
+          addWrapAuthority: {
+            wrapAuthority: this.nonWrapAuthority.publicKey,
+          },
+        },
+        expiresAt ?? this.currentTime().add(new BN(3600))
+      )
+      .accounts({
+        ...this.getExtPdas(),
+        proposer: proposer.publicKey,
+        proposal: this.getProposal(new BN(index)),
+      })
+      .remainingAccounts(
+        this.getAddWrapAuthorityAccounts(this.getMultisigAuthority())
+      )
+      .signers([proposer])
+      .rpc();
+  }
+
+  public async approveProposal(signer: Keypair, index: number) {
+    // Send the instruction
+    await this.ext.methods
+      .approveProposal()
+      .accounts({
+        ...this.getExtPdas(),
+        signer: signer.publicKey,
+        proposal: this.getProposal(new BN(index)),
+      })
+      .signers([signer])
+      .rpc();
+  }
+
+  public async executeProposal(
+    index: number,
+    remainingAccounts = this.getAddWrapAuthorityAccounts(
+      this.getMultisigAuthority()
+    )
+  ) {
+    // Send the instruction
+    await this.ext.methods
+      .executeProposal()
+      .accounts({
+        ...this.getExtPdas(),
+        proposal: this.getProposal(new BN(index)),
+      })
+      .remainingAccounts(remainingAccounts)
+      .rpc();
+  }
+
+  // Queues a change through the timelock, adding the non wrap authority as a wrap authority by default
+  public async queueAction(
+    signer: Keypair,
+    index: number,
+    action: TimelockAction = {
+      addWrapAuthority: { wrapAuthority: this.nonWrapAuthority.publicKey },
+    }
+  ) {
+    // Send the instruction
+    await this.ext.methods
+      .queueAction(action)
+      .accounts({
+        ...this.getExtPdas(),
+        signer: signer.publicKey,
+        queuedAction: this.getQueuedAction(new BN(index)),
+      })
+      .signers([signer])
+      .rpc();
+  }
+
+  // Accounts of an instruction built with a PDA of the program as signer, in order,
+  // to pass as remaining accounts when the change is executed by the timelock
+  public async getRemainingAccounts(
+    instruction: Promise<TransactionInstruction>
+  ) {
+    return (await instruction).keys.map((key) => ({ ...key, isSigner: false }));
+  }
+
+  public async executeQueuedAction(
+    index: number,
+    queuedBy: PublicKey,
+    remainingAccounts = this.getAddWrapAuthorityAccounts(
+      this.getTimelockAuthority()
+    )
+  ) {
+    // Send the instruction
+    await this.ext.methods
+      .executeQueuedAction()
+      .accounts({
+        ...this.getExtPdas(),
+        queuedAction: this.getQueuedAction(new BN(index)),
+        queuedBy,
+      })
+      .remainingAccounts(remainingAccounts)
+      .rpc();
+  }
+
+  public async pause(flags: number) {
+    // Send the instruction
+    await this.ext.methods
+      .pause(flags)
+      .accounts({
+        ...this.getExtPdas(),
+        pauser: this.pauser.publicKey,
+      })
+      .signers([this.pauser])
+      .rpc();
+  }
+
+  public async unpause(flags: number) {
+    // Send the instruction
+    await this.ext.methods
+      .unpause(flags)
+      .accounts({
+        ...this.getExtPdas(),
+        pauser: this.pauser.publicKey,
+      })
+      .signers([this.pauser])
       .rpc();
   }
 
@@ -1287,12 +2027,17 @@ export class ExtensionTest<V extends Variant = Variant.ScaledUiAmount> {
     await this.ext.methods
       .wrap(amount)
       .accounts({
+        ...this.getExtPdas(),
         tokenAuthority: tokenAuthority.publicKey,
         wrapAuthority: wrapAuthority
           ? wrapAuthority.publicKey
           : this.ext.programId,
+        wrapAuthorityAccount: this.getWrapAuthority(
+          (wrapAuthority ?? tokenAuthority).publicKey
+        ),
         fromMTokenAccount,
         toExtTokenAccount,
+        toBlocklistEntry: this.getBlocklistEntryOf(toExtTokenAccount),
       })
       .signers(
         wrapAuthority ? [tokenAuthority, wrapAuthority] : [tokenAuthority]
@@ -1349,12 +2094,17 @@ export class ExtensionTest<V extends Variant = Variant.ScaledUiAmount> {
     await this.ext.methods
       .unwrap(amount)
       .accounts({
+        ...this.getExtPdas(),
         tokenAuthority: tokenAuthority.publicKey,
         unwrapAuthority: wrapAuthority
           ? wrapAuthority.publicKey
           : this.ext.programId,
+        wrapAuthorityAccount: this.getWrapAuthority(
+          (wrapAuthority ?? tokenAuthority).publicKey
+        ),
         toMTokenAccount,
         fromExtTokenAccount,
+        fromBlocklistEntry: this.getBlocklistEntryOf(fromExtTokenAccount),
       })
       .signers(
         wrapAuthority ? [tokenAuthority, wrapAuthority] : [tokenAuthority]
@@ -1364,9 +2114,94 @@ export class ExtensionTest<V extends Variant = Variant.ScaledUiAmount> {
     return { vaultMTokenAccount, toMTokenAccount, fromExtTokenAccount };
   }
 
+  public async beginWindDown(signer: Keypair = this.admin) {
+    // Send the instruction
+    await this.ext.methods
+      .beginWindDown()
+      .accounts({
+        ...this.getExtPdas(),
+        admin: signer.publicKey,
+      })
+      .signers([signer])
+      .rpc();
+  }
+
+  public async decommission() {
+    // Setup the instruction, the residual M is swept to the admin
+    const vaultMTokenAccount = await this.getATA(
+      this.mMint.publicKey,
+      this.getMVault()
+    );
+    const adminMTokenAccount = await this.getATA(
+      this.mMint.publicKey,
+      this.admin.publicKey
+    );
+
+    // Send the instruction
+    await this.ext.methods
+      .decommission()
+      .accounts({
+        ...this.getExtPdas(),
+        admin: this.admin.publicKey,
+        mMint: this.mMint.publicKey,
+        extMint: this.extMint.publicKey,
+        vaultMTokenAccount,
+        adminMTokenAccount,
+      })
+      .signers([this.admin])
+      .rpc();
+  }
+
+  // Returns the Ed25519 program instruction verifying the signature
+  // of the wrap authority over the voucher
+  public signVoucher(
+    wrapAuthority: Keypair,
+    voucher: WrapVoucher
+  ): TransactionInstruction {
+    return Ed25519Program.createInstructionWithPrivateKey({
+      privateKey: wrapAuthority.secretKey,
+      message: this.ext.coder.types.encode("wrapVoucher", voucher),
+    });
+  }
+
+  public async wrapWithVoucher(
+    user: Keypair,
+    amount: BN,
+    voucher: WrapVoucher,
+    signatureIx: TransactionInstruction
+  ): Promise<string> {
+    // Setup the instruction
+    const { fromMTokenAccount, toExtTokenAccount } = await this.prepWrap(
+      user.publicKey
+    );
+
+    // Send the instruction with the signature verification right before it
+    return this.ext.methods
+      .wrapWithVoucher(voucher, amount)
+      .accounts({
+        payer: user.publicKey,
+        wrap: {
+          ...this.getExtPdas(),
+          tokenAuthority: user.publicKey,
+          wrapAuthority: this.ext.programId,
+          wrapAuthorityAccount: this.getWrapAuthority(voucher.wrapAuthority),
+          fromMTokenAccount,
+          toExtTokenAccount,
+          toBlocklistEntry: this.getBlocklistEntryOf(toExtTokenAccount),
+        },
+        voucherNonce: this.getVoucherNonce(
+          voucher.wrapAuthority,
+          voucher.nonce
+        ),
+      })
+      .preInstructions([signatureIx])
+      .signers([user])
+      .rpc();
+  }
+
   public async sync(): Promise<PublicKey> {
-    if (this.variant === Variant.NoYield) {
-      throw new Error("sync is not supported for No Yield variant");
+    if (!this.hasIndex()) {
+      throw new Error(`sync is not supported for the ${this.variant} variant`);
     }
 
     const mVault = this.getMVault();
@@ -1375,6 +2210,7 @@ export class ExtensionTest<V extends Variant = Variant.ScaledUiAmount> {
 
     // Send the instruction
     await this.ext.methods.sync().accountsPartial({
+      ...this.getExtPdas(),
       mEarnerAccount,
     }).signers([]).rpc();
 
@@ -1392,12 +2228,350 @@ export class ExtensionTest<V extends Variant = Variant.ScaledUiAmount> {
     await this.ext.methods
       .claimFees()
       .accountsPartial({
+        ...this.getExtPdas(),
+        feeClaimer: this.feeClaimer.publicKey,
+        recipientExtTokenAccount,
+      })
+      .signers([this.feeClaimer])
+      .rpc();
+
+    return { recipientExtTokenAccount };
+  }
+
+  // Propagates a new M index and claims the yield of the m vault for it,
+  // so the vault holds excess collateral for the yield modes to pay out
+  public async accrueVaultYield(newIndex: BN) {
+    // Warp ahead slightly to change the timestamp of the new index
+    this.warp(new BN(60), true);
+    await this.propagateIndex(newIndex);
+
+    await this.mClaimFor(this.getMVault());
+    await this.mCompleteClaims();
+
+    // Reset the blockhash to avoid issues with duplicate transactions from multiple claim cycles
+    this.svm.expireBlockhash();
+  }
+
+  public async claimYield(recipientExtTokenAccount?: PublicKey) {
+    const vaultMTokenAccount = await this.getATA(
+      this.mMint.publicKey,
+      this.getMVault()
+    );
+
+    // Send the instruction
+    await this.ext.methods
+      .claimYield()
+      .accountsPartial({
+        ...this.getExtPdas(),
+        recipientExtTokenAccount:
+          recipientExtTokenAccount ??
+          (await this.getATA(
+            this.extMint.publicKey,
+            this.yieldRecipient.publicKey
+          )),
+        mEarnerAccount: this.getMEarnerAccount(vaultMTokenAccount),
+      })
+      .signers([])
+      .rpc();
+  }
+
+  public async addEarner(tokenAccount: PublicKey, feeBps: BN) {
+    const vaultMTokenAccount = await this.getATA(
+      this.mMint.publicKey,
+      this.getMVault()
+    );
+
+    // Send the instruction
+    await this.ext.methods
+      .addEarner(feeBps)
+      .accountsPartial({
+        ...this.getExtPdas(),
+        feeManager: this.feeManager.publicKey,
+        mEarnerAccount: this.getMEarnerAccount(vaultMTokenAccount),
+        earnerTokenAccount: tokenAccount,
+        earnerAccount: this.getExtEarnerAccount(tokenAccount),
+      })
+      .signers([this.feeManager])
+      .rpc();
+  }
+
+  // Accounts of claim_earner_yield, also nested in set_earner_fee and remove_earner
+  public async getClaimEarnerYieldAccounts(tokenAccount: PublicKey) {
+    const vaultMTokenAccount = await this.getATA(
+      this.mMint.publicKey,
+      this.getMVault()
+    );
+
+    return {
+      ...this.getExtPdas(),
+      mEarnGlobalAccount: this.getEarnGlobalAccount(),
+      extMint: this.extMint.publicKey,
+      vaultMTokenAccount,
+      mEarnerAccount: this.getMEarnerAccount(vaultMTokenAccount),
+      earnerAccount: this.getExtEarnerAccount(tokenAccount),
+      earnerTokenAccount: tokenAccount,
+      feeRecipientTokenAccount: await this.getATA(
+        this.extMint.publicKey,
+        this.yieldRecipient.publicKey
+      ),
+      mTokenProgram: TOKEN_2022_PROGRAM_ID,
+      extTokenProgram: TOKEN_2022_PROGRAM_ID,
+    };
+  }
+
+  public async claimEarnerYield(tokenAccount: PublicKey) {
+    // Send the instruction
+    await this.ext.methods
+      .claimEarnerYield()
+      .accountsPartial(await this.getClaimEarnerYieldAccounts(tokenAccount))
+      .signers([])
+      .rpc();
+  }
+
+  public async createEarner(tokenAccount: PublicKey) {
+    const vaultMTokenAccount = await this.getATA(
+      this.mMint.publicKey,
+      this.getMVault()
+    );
+
+    // Send the instruction, anyone can pay for the earner account
+    await this.ext.methods
+      .createEarner()
+      .accountsPartial({
+        ...this.getExtPdas(),
+        payer: this.nonAdmin.publicKey,
+        mEarnGlobalAccount: this.getEarnGlobalAccount(),
+        vaultMTokenAccount,
+        mEarnerAccount: this.getMEarnerAccount(vaultMTokenAccount),
+        earnerTokenAccount: tokenAccount,
+        earnerAccount: this.getExtEarnerAccount(tokenAccount),
+      })
+      .signers([this.nonAdmin])
+      .rpc();
+  }
+
+  // Accounts of checkpoint, also nested in claim
+  public async getCheckpointAccounts(tokenAccount: PublicKey) {
+    const vaultMTokenAccount = await this.getATA(
+      this.mMint.publicKey,
+      this.getMVault()
+    );
+
+    return {
+      globalAccount: this.getExtGlobalAccount(),
+      mEarnGlobalAccount: this.getEarnGlobalAccount(),
+      mVault: this.getMVault(),
+      vaultMTokenAccount,
+      mEarnerAccount: this.getMEarnerAccount(vaultMTokenAccount),
+      earnerAccount: this.getExtEarnerAccount(tokenAccount),
+      earnerTokenAccount: tokenAccount,
+      mTokenProgram: TOKEN_2022_PROGRAM_ID,
+    };
+  }
+
+  public async checkpoint(tokenAccount: PublicKey) {
+    // Send the instruction
+    await this.ext.methods
+      .checkpoint()
+      .accountsPartial(await this.getCheckpointAccounts(tokenAccount))
+      .signers([])
+      .rpc();
+  }
+
+  public async claim(tokenAccount: PublicKey) {
+    // Send the instruction
+    await this.ext.methods
+      .claim()
+      .accountsPartial({
+        checkpoint: await this.getCheckpointAccounts(tokenAccount),
+        extMint: this.extMint.publicKey,
+        extMintAuthority: this.getExtMintAuthority(),
+        extTokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([])
+      .rpc();
+  }
+
+  // Builds the merkle tree of a distribution the same way as the program verifies it:
+  // leaves are prefixed with 0, pairs are sorted and prefixed with 1,
+  // and the last node of a layer with an odd length is promoted
+  public buildDistributionTree(leaves: DistributionLeaf[]): {
+    root: number[];
+    proofs: number[][][];
+  } {
+    const hash = (...parts: Buffer[]) =>
+      createHash("sha256").update(Buffer.concat(parts)).digest();
+
+    let layer = leaves.map((leaf, i) => {
+      const index = Buffer.alloc(4);
+      index.writeUInt32LE(i);
+      return hash(
+        Buffer.from([0]),
+        index,
+        leaf.claimant.toBuffer(),
+        leaf.amount.toArrayLike(Buffer, "le", 8)
+      );
+    });
+    const proofs: Buffer[][] = leaves.map(() => []);
+    let positions = leaves.map((_, i) => i);
+
+    while (layer.length > 1) {
+      // Record the sibling of each leaf's node on this layer, if it has one
+      positions.forEach((position, leaf) => {
+        const sibling = position ^ 1;
+        if (sibling < layer.length) proofs[leaf].push(layer[sibling]);
+      });
+
+      const next: Buffer[] = [];
+      for (let i = 0; i < layer.length; i += 2) {
+        if (i + 1 === layer.length) {
+          next.push(layer[i]);
+        } else {
+          const [a, b] =
+            Buffer.compare(layer[i], layer[i + 1]) <= 0
+              ? [layer[i], layer[i + 1]]
+              : [layer[i + 1], layer[i]];
+          next.push(hash(Buffer.from([1]), a, b));
+        }
+      }
+
+      layer = next;
+      positions = positions.map((position) => position >> 1);
+    }
+
+    return {
+      root: Array.from(layer[0]),
+      proofs: proofs.map((proof) => proof.map((node) => Array.from(node))),
+    };
+  }
+
+  public async createDistribution(
+    merkleRoot: number[],
+    totalAmount: BN,
+    numLeaves: number,
+    expiresAt: BN
+  ): Promise<PublicKey> {
+    const index = (
+      await this.ext.account.extGlobal.fetch(this.getExtGlobalAccount())
+    ).yieldConfig.merkleDistribution!["0"].distributionCount;
+    const distributionAccount = this.getDistribution(index);
+    const vaultMTokenAccount = await this.getATA(
+      this.mMint.publicKey,
+      this.getMVault()
+    );
+
+    // Send the instruction
+    await this.ext.methods
+      .createDistribution(merkleRoot, totalAmount, numLeaves, expiresAt)
+      .accountsPartial({
+        ...this.getExtPdas(),
         admin: this.admin.publicKey,
+        mEarnerAccount: this.getMEarnerAccount(vaultMTokenAccount),
+        distributionAccount,
+      })
+      .signers([this.admin])
+      .rpc();
+
+    return distributionAccount;
+  }
+
+  public async claimDistribution(
+    claimant: Keypair,
+    index: BN,
+    leafIndex: number,
+    amount: BN,
+    proof: number[][],
+    recipientExtTokenAccount?: PublicKey
+  ) {
+    // Send the instruction
+    await this.ext.methods
+      .claimDistribution(leafIndex, amount, proof)
+      .accountsPartial({
+        ...this.getExtPdas(),
+        claimant: claimant.publicKey,
+        distributionAccount: this.getDistribution(index),
+        recipientExtTokenAccount:
+          recipientExtTokenAccount ??
+          (await this.getATA(this.extMint.publicKey, claimant.publicKey)),
+      })
+      .signers([claimant])
+      .rpc();
+  }
+
+  public async sweepDistribution(
+    index: BN,
+    recipientExtTokenAccount: PublicKey
+  ) {
+    // Send the instruction
+    await this.ext.methods
+      .sweepDistribution()
+      .accountsPartial({
+        ...this.getExtPdas(),
+        admin: this.admin.publicKey,
+        distributionAccount: this.getDistribution(index),
         recipientExtTokenAccount,
       })
       .signers([this.admin])
       .rpc();
+  }
 
-    return { recipientExtTokenAccount };
+  // Accounts of distribute, also nested in set_beneficiaries
+  public async getDistributeAccounts() {
+    const vaultMTokenAccount = await this.getATA(
+      this.mMint.publicKey,
+      this.getMVault()
+    );
+
+    return {
+      globalAccount: this.getExtGlobalAccount(),
+      mEarnGlobalAccount: this.getEarnGlobalAccount(),
+      mMint: this.mMint.publicKey,
+      extMint: this.extMint.publicKey,
+      extMintAuthority: this.getExtMintAuthority(),
+      mVault: this.getMVault(),
+      vaultMTokenAccount,
+      mEarnerAccount: this.getMEarnerAccount(vaultMTokenAccount),
+      mTokenProgram: TOKEN_2022_PROGRAM_ID,
+      extTokenProgram: TOKEN_2022_PROGRAM_ID,
+    };
+  }
+
+  // The beneficiary token accounts are passed in the order they are stored in
+  public async distribute(beneficiaryTokenAccounts: PublicKey[]) {
+    // Send the instruction
+    await this.ext.methods
+      .distribute()
+      .accountsPartial(await this.getDistributeAccounts())
+      .remainingAccounts(
+        beneficiaryTokenAccounts.map((pubkey) => ({
+          pubkey,
+          isSigner: false,
+          isWritable: true,
+        }))
+      )
+      .signers([])
+      .rpc();
+  }
+
+  public async setBeneficiaries(
+    beneficiaries: Beneficiary[],
+    oldBeneficiaryTokenAccounts: PublicKey[]
+  ) {
+    // Send the instruction, the old beneficiaries are paid out first
+    await this.ext.methods
+      .setBeneficiaries(beneficiaries)
+      .accountsPartial({
+        admin: this.admin.publicKey,
+        distribute: await this.getDistributeAccounts(),
+      })
+      .remainingAccounts(
+        oldBeneficiaryTokenAccounts.map((pubkey) => ({
+          pubkey,
+          isSigner: false,
+          isWritable: true,
+        }))
+      )
+      .signers([this.admin])
+      .rpc();
   }
 }
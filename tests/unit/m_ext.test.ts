@@ -1,9 +1,19 @@
 import { BN } from "@coral-xyz/anchor";
-import { PublicKey, Keypair } from "@solana/web3.js";
+import { PublicKey, Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
 import { TOKEN_2022_PROGRAM_ID, getMint } from "@solana/spl-token";
 import { randomInt } from "crypto";
 
-import { Comparison, ExtensionTest, Variant } from "./ext_test_harness";
+import {
+  Beneficiary,
+  Comparison,
+  DistributionLeaf,
+  ExtensionTest,
+  PAUSE_CLAIM,
+  PAUSE_UNWRAP,
+  PAUSE_WRAP,
+  Variant,
+  WrapVoucher,
+} from "./ext_test_harness";
 
 // Unit tests for ext earn program
 
@@ -43,8 +53,13 @@ for (const variant of VARIANTS) {
         //   [X] it reverts with a SeedsConstraint error
         // [X] given the ext_mint does not have a freeze authority
         //   [X] it reverts with a InvalidMint error
+        // [X] given an allowlist is required, but the ext_mint does not freeze new accounts
+        //   [X] it reverts with a InvalidMint error
         // [X] given the wrap authorities are not unique
         //   [X] it reverts with an InvalidParam error
+        // [X] given the extension is initialized in namespaced mode
+        //   [X] the global account is created at the PDA namespaced by the ext mint
+        //   [X] the namespaced flag is set
 
         // given the m_mint is not owned by the token2022 program
         // it reverts with a ConstraintAddress error -> actually get AccountNotInitialized error before this
@@ -64,11 +79,16 @@ for (const variant of VARIANTS) {
           // We get an AccountNotInitialized error here because it's impossible to create
           // a m earner account that matches the vaultATA for the wrong mint
           await $.expectAnchorError(
-            (variant === Variant.NoYield
-              ? $.ext.methods.initialize([])
-              : $.ext.methods.initialize([], new BN(0))
-            )
+            $.ext.methods
+              .initialize(
+                [],
+                $.getYieldMode(new BN(0)),
+                false,
+                new BN(0),
+                false
+              )
               .accountsPartial({
+                ...$.getExtPdas(),
                 admin: $.nonAdmin.publicKey,
                 mMint: wrongMint.publicKey,
                 extMint: $.extMint.publicKey,
@@ -89,11 +109,16 @@ for (const variant of VARIANTS) {
 
           // Attempt to send the transaction
           await $.expectAnchorError(
-            (variant === Variant.NoYield
-              ? $.ext.methods.initialize([])
-              : $.ext.methods.initialize([], new BN(0))
-            )
+            $.ext.methods
+              .initialize(
+                [],
+                $.getYieldMode(new BN(0)),
+                false,
+                new BN(0),
+                false
+              )
               .accounts({
+                ...$.getExtPdas(),
                 admin: $.nonAdmin.publicKey,
                 mMint: $.mMint.publicKey,
                 extMint: wrongMint.publicKey,
@@ -113,11 +138,16 @@ for (const variant of VARIANTS) {
 
           // Attempt to send the transaction
           await $.expectAnchorError(
-            (variant === Variant.NoYield
-              ? $.ext.methods.initialize([])
-              : $.ext.methods.initialize([], new BN(0))
-            )
+            $.ext.methods
+              .initialize(
+                [],
+                $.getYieldMode(new BN(0)),
+                false,
+                new BN(0),
+                false
+              )
               .accounts({
+                ...$.getExtPdas(),
                 admin: $.nonAdmin.publicKey,
                 mMint: $.mMint.publicKey,
                 extMint: badMint.publicKey,
@@ -138,11 +168,16 @@ for (const variant of VARIANTS) {
           // Attempt to send transaction
           // Expect error (could be one of several "SeedsConstraint", "AccountOwnedByWrongProgram", "AccountNotInitialized")
           await $.expectSystemError(
-            (variant === Variant.NoYield
-              ? $.ext.methods.initialize([])
-              : $.ext.methods.initialize([], new BN(0))
-            )
+            $.ext.methods
+              .initialize(
+                [],
+                $.getYieldMode(new BN(0)),
+                false,
+                new BN(0),
+                false
+              )
               .accountsPartial({
+                ...$.getExtPdas(),
                 admin: $.nonAdmin.publicKey,
                 mMint: $.mMint.publicKey,
                 extMint: $.extMint.publicKey,
@@ -170,11 +205,16 @@ for (const variant of VARIANTS) {
           // Attempt to send transaction
           // Expect error (could be one of several "SeedsConstraint", "AccountOwnedByWrongProgram", "AccountNotInitialized")
           await $.expectSystemError(
-            (variant === Variant.NoYield
-              ? $.ext.methods.initialize([])
-              : $.ext.methods.initialize([], new BN(0))
-            )
+            $.ext.methods
+              .initialize(
+                [],
+                $.getYieldMode(new BN(0)),
+                false,
+                new BN(0),
+                false
+              )
               .accountsPartial({
+                ...$.getExtPdas(),
                 admin: $.nonAdmin.publicKey,
                 mMint: $.mMint.publicKey,
                 extMint: $.extMint.publicKey,
@@ -195,11 +235,16 @@ for (const variant of VARIANTS) {
           // Attempt to send transaction
           // Expect error (could be one of several "SeedsConstraint", "AccountOwnedByWrongProgram", "AccountNotInitialized")
           await $.expectSystemError(
-            (variant === Variant.NoYield
-              ? $.ext.methods.initialize([])
-              : $.ext.methods.initialize([], new BN(0))
-            )
+            $.ext.methods
+              .initialize(
+                [],
+                $.getYieldMode(new BN(0)),
+                false,
+                new BN(0),
+                false
+              )
               .accountsPartial({
+                ...$.getExtPdas(),
                 admin: $.nonAdmin.publicKey,
                 mMint: $.mMint.publicKey,
                 extMint: $.extMint.publicKey,
@@ -215,15 +260,20 @@ for (const variant of VARIANTS) {
         test("ext_mint does not have a freeze authority - reverts", async () => {
           // Create a mint without a freeze authority
           const wrongMint = new Keypair();
-          await $.createMint(wrongMint, $.nonAdmin.publicKey, true, 6, false);
+          await $.createMint(wrongMint, $.nonAdmin.publicKey, true, 6, null);
 
           // Attempt to send the transaction
           await $.expectAnchorError(
-            (variant === Variant.NoYield
-              ? $.ext.methods.initialize([])
-              : $.ext.methods.initialize([], new BN(0))
-            )
+            $.ext.methods
+              .initialize(
+                [],
+                $.getYieldMode(new BN(0)),
+                false,
+                new BN(0),
+                false
+              )
               .accounts({
+                ...$.getExtPdas(),
                 admin: $.nonAdmin.publicKey,
                 mMint: $.mMint.publicKey,
                 extMint: wrongMint.publicKey,
@@ -234,30 +284,89 @@ for (const variant of VARIANTS) {
           );
         });
 
+        // given an allowlist is required, but the ext_mint does not freeze new accounts
+        // it reverts with a InvalidMint error
+        test("require allowlist without default frozen state - reverts", async () => {
+          await $.expectAnchorError(
+            $.ext.methods
+              .initialize(
+                [],
+                $.getYieldMode(new BN(0)),
+                false,
+                new BN(0),
+                true
+              )
+              .accounts({
+                ...$.getExtPdas(),
+                admin: $.admin.publicKey,
+                mMint: $.mMint.publicKey,
+                extMint: $.extMint.publicKey,
+              })
+              .signers([$.admin])
+              .rpc(),
+            "InvalidMint"
+          );
+        });
+
         // given wrap authorities includes a duplicate, non-default public key
         // it reverts with an InvalidParam error
         test("wrap authorities includes a duplicate public key - reverts", async () => {
           // Change the wrap authorities
-          const wrapAuthorities: PublicKey[] = $.createUniqueKeyArray(10);
+          const wrapAuthorities: PublicKey[] = $.createUniqueKeyArray(3);
           wrapAuthorities[0] = wrapAuthorities[1];
 
           // Attempt to send transaction
           await $.expectAnchorError(
-            (variant === Variant.NoYield
-              ? $.ext.methods.initialize(wrapAuthorities)
-              : $.ext.methods.initialize(wrapAuthorities, new BN(0))
-            )
+            $.ext.methods
+              .initialize(
+                wrapAuthorities,
+                $.getYieldMode(new BN(0)),
+                false,
+                new BN(0),
+                false
+              )
               .accounts({
-                admin: $.nonAdmin.publicKey,
+                ...$.getExtPdas(),
+                admin: $.admin.publicKey,
                 mMint: $.mMint.publicKey,
                 extMint: $.extMint.publicKey,
               })
-              .signers([$.nonAdmin])
+              .remainingAccounts($.getWrapAuthorityMetas(wrapAuthorities))
+              .signers([$.admin])
               .rpc(),
             "InvalidParam"
           );
         });
 
+        // given the extension is initialized in namespaced mode
+        // it creates the global account at the PDA namespaced by the ext mint
+        // it sets the namespaced flag
+        test("initialize namespaced - success", async () => {
+          // Create a harness for an extension whose PDAs are namespaced by the ext mint
+          const $n = new ExtensionTest(variant, [], true);
+          await $n.init(initialSupply, initialIndex, claimCooldown);
+
+          const [legacyGlobalAccount] = PublicKey.findProgramAddressSync(
+            [Buffer.from("global")],
+            $n.ext.programId
+          );
+          expect($n.getExtGlobalAccount()).not.toEqual(legacyGlobalAccount);
+
+          // Send the transaction
+          await $n.initializeExt([], new BN(0));
+
+          // Check the state of the global account
+          await $n.expectExtGlobalState({
+            admin: $n.admin.publicKey,
+            extMint: $n.extMint.publicKey,
+            mMint: $n.mMint.publicKey,
+            namespaced: true,
+          });
+
+          // The global account at the fixed seeds is not created
+          $n.expectAccountEmpty(legacyGlobalAccount);
+        });
+
         // no yield test cases
         // [X] given all accounts and params are correct
         //   [X] the global account is created
@@ -266,7 +375,8 @@ for (const variant of VARIANTS) {
         //   [X] the ext_mint is set correctly
         //   [X] the m_earn_global_account is set correctly
         //   [X] the bumps are set correctly
-        //   [X] the wrap authorities are set correctly
+        //   [X] the layout version is set
+        //   [X] the accounts of the wrap authorities are created
 
         if (variant === Variant.NoYield) {
           // given accounts and params are correct
@@ -277,12 +387,13 @@ for (const variant of VARIANTS) {
           // it sets the m_earn_global_account to the provided account
           // it sets the scaled ui amount multiplier and timestamp to the values on the m earner account
           // it sets the bumps to the correct values
+          // it creates the accounts of the wrap authorities
           test("initialize - success", async () => {
-            // Get a random number of wrap authorities
-            // We use the padded array to check the stored state after the call
-            const numWrapAuthorities = randomInt(10);
-            const wrapAuthorities: PublicKey[] =
-              $.createUniqueKeyArray(numWrapAuthorities);
+            // Get a random number of wrap authorities, few enough that their
+            // accounts fit in the transaction
+            const wrapAuthorities: PublicKey[] = $.createUniqueKeyArray(
+              randomInt(5)
+            );
 
             // Derive PDA bumps
             const [, bump] = PublicKey.findProgramAddressSync(
@@ -304,17 +415,26 @@ for (const variant of VARIANTS) {
 
             // Send the transaction
             await $.ext.methods
-              .initialize(wrapAuthorities)
+              .initialize(
+                wrapAuthorities,
+                { noYield: {} },
+                false,
+                new BN(0),
+                false
+              )
               .accounts({
+                ...$.getExtPdas(),
                 admin: $.admin.publicKey,
                 mMint: $.mMint.publicKey,
                 extMint: $.extMint.publicKey,
               })
+              .remainingAccounts($.getWrapAuthorityMetas(wrapAuthorities))
               .signers([$.admin])
               .rpc();
 
             // Check the state of the global account
             await $.expectExtGlobalState({
+              version: 10,
               admin: $.admin.publicKey,
               extMint: $.extMint.publicKey,
               mMint: $.mMint.publicKey,
@@ -323,15 +443,23 @@ for (const variant of VARIANTS) {
               mVaultBump,
               extMintAuthorityBump,
               yieldConfig: {},
-              wrapAuthorities,
             });
 
-            // Confirm the size of the global account based on the number of wrap authorities
-            const expectedSize = 143 + wrapAuthorities.length * 32; // 143 bytes base size + 4 bytes for vector length + 32 bytes per wrap authority
+            // Confirm the size of the global account, wrap authorities are stored in their own accounts
+            const expectedSize = 364 + 405; // 364 bytes base size + 405 bytes reserved for the yield config
             const extGlobalSize = await $.provider.connection
               .getAccountInfo(globalAccount)
               .then((info) => info?.data.length || 0);
             expect(extGlobalSize).toEqual(expectedSize);
+
+            // Check the accounts of the wrap authorities
+            for (const authority of wrapAuthorities) {
+              const wrapAuthority = await $.ext.account.wrapAuthority.fetch(
+                $.getWrapAuthority(authority)
+              );
+              expect(wrapAuthority.authority).toEqual(authority);
+              expect(wrapAuthority.enabled).toBe(true);
+            }
           });
         }
 
@@ -347,7 +475,8 @@ for (const variant of VARIANTS) {
         //   [X] the ext_mint is set correctly
         //   [X] the m_earn_global_account is set correctly
         //   [X] the bumps are set correctly
-        //   [X] the wrap authorities are set correctly
+        //   [X] the layout version is set
+        //   [X] the accounts of the wrap authorities are created
         //   [X] the multiplier on the ext mint is initialized to m index
         //   [X] the timestamp on the ext mint is set to the m timestamp
 
@@ -362,8 +491,15 @@ for (const variant of VARIANTS) {
             // Attempt to send the transaction
             await $.expectAnchorError(
               $.ext.methods
-                .initialize([], new BN(0))
+                .initialize(
+                  [],
+                  { scaledUi: { feeBps: new BN(0) } },
+                  false,
+                  new BN(0),
+                  false
+                )
                 .accounts({
+                  ...$.getExtPdas(),
                   admin: $.nonAdmin.publicKey,
                   mMint: $.mMint.publicKey,
                   extMint: wrongMint.publicKey,
@@ -384,8 +520,15 @@ for (const variant of VARIANTS) {
             // Attempt to send the transaction
             await $.expectAnchorError(
               $.ext.methods
-                .initialize([], new BN(0))
+                .initialize(
+                  [],
+                  { scaledUi: { feeBps: new BN(0) } },
+                  false,
+                  new BN(0),
+                  false
+                )
                 .accounts({
+                  ...$.getExtPdas(),
                   admin: $.nonAdmin.publicKey,
                   mMint: $.mMint.publicKey,
                   extMint: wrongMint.publicKey,
@@ -404,12 +547,13 @@ for (const variant of VARIANTS) {
           // it sets the m_earn_global_account to the provided account
           // it sets the scalued ui amount multiplier and timestamp to the values on the m earn global account
           // it sets the bumps to the correct values
+          // it creates the accounts of the wrap authorities
           test("initialize - success", async () => {
-            // Get a random number of wrap authorities
-            // We use the padded array to check the stored state after the call
-            const numWrapAuthorities = randomInt(10);
-            const wrapAuthorities: PublicKey[] =
-              $.createUniqueKeyArray(numWrapAuthorities);
+            // Get a random number of wrap authorities, few enough that their
+            // accounts fit in the transaction
+            const wrapAuthorities: PublicKey[] = $.createUniqueKeyArray(
+              randomInt(5)
+            );
 
             // Derive PDA bumps
             const [, bump] = PublicKey.findProgramAddressSync(
@@ -434,17 +578,26 @@ for (const variant of VARIANTS) {
 
             // Send the transaction
             await $.ext.methods
-              .initialize(wrapAuthorities, feeBps)
+              .initialize(
+                wrapAuthorities,
+                { scaledUi: { feeBps } },
+                false,
+                new BN(0),
+                false
+              )
               .accounts({
+                ...$.getExtPdas(),
                 admin: $.admin.publicKey,
                 mMint: $.mMint.publicKey,
                 extMint: $.extMint.publicKey,
               })
+              .remainingAccounts($.getWrapAuthorityMetas(wrapAuthorities))
               .signers([$.admin])
               .rpc();
 
             // Check the state of the global account
             await $.expectExtGlobalState({
+              version: 10,
               admin: $.admin.publicKey,
               extMint: $.extMint.publicKey,
               mMint: $.mMint.publicKey,
@@ -452,7 +605,6 @@ for (const variant of VARIANTS) {
               bump,
               mVaultBump,
               extMintAuthorityBump,
-              wrapAuthorities,
               yieldConfig: {
                 feeBps,
                 lastMIndex: initialIndex,
@@ -460,13 +612,22 @@ for (const variant of VARIANTS) {
               },
             });
 
-            // Check the size of the global account based on the number of wrap authorities
-            const expectedSize = 143 + 24 + wrapAuthorities.length * 32; // 143 bytes base size + 24 yield config size + 32 bytes per wrap authority
+            // Check the size of the global account, wrap authorities are stored in their own accounts
+            const expectedSize = 364 + 405; // 364 bytes base size + 405 bytes reserved for the yield config
             const extGlobalSize = await $.provider.connection
               .getAccountInfo(globalAccount)
               .then((info) => info?.data.length || 0);
             expect(extGlobalSize).toEqual(expectedSize);
 
+            // Check the accounts of the wrap authorities
+            for (const authority of wrapAuthorities) {
+              const wrapAuthority = await $.ext.account.wrapAuthority.fetch(
+                $.getWrapAuthority(authority)
+              );
+              expect(wrapAuthority.authority).toEqual(authority);
+              expect(wrapAuthority.enabled).toBe(true);
+            }
+
             // Check the state of the mint
             await $.expectScaledUiAmountConfig($.extMint.publicKey, {
               authority: $.getExtMintAuthority(),
@@ -493,24 +654,28 @@ for (const variant of VARIANTS) {
         });
 
         // test cases
-        // [X] given the admin does not sign the transaction
+        // [X] given the wrap authority manager does not sign the transaction
         //   [X] it reverts with a NotAuthorized error
-        // [X] given the admin signs the transaction
-        //   [X] given the new wrap authority is already in the list
-        //     [X] it reverts with a InvalidParam error
-        //   [X] given the new wrap authority is not in the list
-        //     [X] it adds the new wrap authority to the list
-        //     [X] it resizes the ext global account to accommodate the new wrap authority
-
-        // given the admin does not sign the transaction
+        // [X] given the wrap authority manager signs the transaction
+        //   [X] given the new wrap authority already exists
+        //     [X] it reverts since its account is already in use
+        //   [X] given the new wrap authority does not exist
+        //     [X] it creates the account of the new wrap authority
+        //     [X] it does not resize the ext global account
+
+        // given the wrap authority manager does not sign the transaction
         // it reverts with a NotAuthorized error
-        test("admin does not sign - reverts", async () => {
+        test("wrap authority manager does not sign - reverts", async () => {
           // Attempt to send the transaction
           await $.expectAnchorError(
             $.ext.methods
               .addWrapAuthority($.nonWrapAuthority.publicKey)
               .accounts({
-                admin: $.nonAdmin.publicKey,
+                ...$.getExtPdas(),
+                wrapAuthorityManager: $.nonAdmin.publicKey,
+                wrapAuthorityAccount: $.getWrapAuthority(
+                  $.nonWrapAuthority.publicKey
+                ),
               })
               .signers([$.nonAdmin])
               .rpc(),
@@ -518,26 +683,31 @@ for (const variant of VARIANTS) {
           );
         });
 
-        // given the admin signs the transaction
-        // given the new wrap authority is already in the list
-        // it reverts with a InvalidParam error
-        test("new wrap authority already in the list - reverts", async () => {
+        // given the wrap authority manager signs the transaction
+        // given the new wrap authority already exists
+        // it reverts since its account is already in use
+        test("new wrap authority already exists - reverts", async () => {
           // Attempt to send the transaction
-          await $.expectAnchorError(
+          await $.expectSystemError(
             $.ext.methods
               .addWrapAuthority($.wrapAuthority.publicKey)
-              .accounts({ admin: $.admin.publicKey })
-              .signers([$.admin])
-              .rpc(),
-            "InvalidParam"
+              .accounts({
+                ...$.getExtPdas(),
+                wrapAuthorityManager: $.wrapAuthorityManager.publicKey,
+                wrapAuthorityAccount: $.getWrapAuthority(
+                  $.wrapAuthority.publicKey
+                ),
+              })
+              .signers([$.wrapAuthorityManager])
+              .rpc()
           );
         });
 
-        // given the admin signs the transaction
-        // given the new wrap authority is not in the list
-        // it adds the new wrap authority to the list
-        // it resizes the ext global account to accommodate the new wrap authority
-        test("new wrap authority is not in the list - success", async () => {
+        // given the wrap authority manager signs the transaction
+        // given the new wrap authority does not exist
+        // it creates the account of the new wrap authority
+        // it does not resize the ext global account
+        test("new wrap authority does not exist - success", async () => {
           // Cache the size of the ext global account
           const extGlobalAccount = $.getExtGlobalAccount();
           const extGlobalSize = await $.provider.connection
@@ -548,23 +718,35 @@ for (const variant of VARIANTS) {
           await $.ext.methods
             .addWrapAuthority($.nonWrapAuthority.publicKey)
             .accounts({
-              admin: $.admin.publicKey,
+              ...$.getExtPdas(),
+              wrapAuthorityManager: $.wrapAuthorityManager.publicKey,
+              wrapAuthorityAccount: $.getWrapAuthority(
+                $.nonWrapAuthority.publicKey
+              ),
             })
-            .signers([$.admin])
+            .signers([$.wrapAuthorityManager])
             .rpc();
 
           // Check that the wrap authority was added
-          wrapAuthorities.push($.nonWrapAuthority.publicKey);
-
-          await $.expectExtGlobalState({
-            wrapAuthorities,
-          });
+          const wrapAuthority = await $.ext.account.wrapAuthority.fetch(
+            $.getWrapAuthority($.nonWrapAuthority.publicKey)
+          );
+          expect(wrapAuthority.authority).toEqual($.nonWrapAuthority.publicKey);
+          expect(wrapAuthority.label).toEqual("");
+          expect(wrapAuthority.createdAt.toString()).toEqual(
+            $.currentTime().toString()
+          );
+          expect(wrapAuthority.enabled).toBe(true);
+          expect(wrapAuthority.quota.quota.toNumber()).toEqual(0);
+          expect(wrapAuthority.quota.minted.toNumber()).toEqual(0);
+          expect(wrapAuthority.permissions).toEqual(3);
+          expect(wrapAuthority.expiresAt.toNumber()).toEqual(0);
 
-          // Check that the ext global account was resized
+          // Check that the ext global account was not resized
           const newExtGlobalSize = await $.provider.connection
             .getAccountInfo(extGlobalAccount)
             .then((info) => info?.data.length || 0);
-          expect(newExtGlobalSize).toEqual(extGlobalSize + 32); // 32 bytes for the new wrap authority
+          expect(newExtGlobalSize).toEqual(extGlobalSize);
         });
       });
 
@@ -581,24 +763,28 @@ for (const variant of VARIANTS) {
         });
 
         // test cases
-        // [X] given the admin does not sign the transaction
+        // [X] given the wrap authority manager does not sign the transaction
         //   [X] it reverts with a NotAuthorized error
-        // [X] given the admin signs the transaction
-        //   [X] given the wrap authority is not in the list
-        //     [X] it reverts with a InvalidParam error
-        //   [X] given the wrap authority is in the list
-        //     [X] it removes the wrap authority from the list
-        //     [X] it resizes the ext global account down to accommodate the removed wrap authority
-
-        // given the admin does not sign the transaction
+        // [X] given the wrap authority manager signs the transaction
+        //   [X] given the wrap authority does not exist
+        //     [X] it reverts with a AccountNotInitialized error
+        //   [X] given the wrap authority exists
+        //     [X] it closes the account of the wrap authority
+        //     [X] it refunds the rent to the wrap authority manager
+
+        // given the wrap authority manager does not sign the transaction
         // it reverts with a NotAuthorized error
-        test("admin does not sign - reverts", async () => {
+        test("wrap authority manager does not sign - reverts", async () => {
           // Attempt to send the transaction
           await $.expectAnchorError(
             $.ext.methods
               .removeWrapAuthority($.wrapAuthority.publicKey)
               .accounts({
-                admin: $.nonAdmin.publicKey,
+                ...$.getExtPdas(),
+                wrapAuthorityManager: $.nonAdmin.publicKey,
+                wrapAuthorityAccount: $.getWrapAuthority(
+                  $.wrapAuthority.publicKey
+                ),
               })
               .signers([$.nonAdmin])
               .rpc(),
@@ -606,932 +792,2318 @@ for (const variant of VARIANTS) {
           );
         });
 
-        // given the admin signs the transaction
-        // given the wrap authority is not in the list
-        // it reverts with a InvalidParam error
-        test("wrap authority not in the list - reverts", async () => {
+        // given the wrap authority manager signs the transaction
+        // given the wrap authority does not exist
+        // it reverts with a AccountNotInitialized error
+        test("wrap authority does not exist - reverts", async () => {
           // Attempt to send the transaction
           await $.expectAnchorError(
             $.ext.methods
               .removeWrapAuthority($.nonWrapAuthority.publicKey)
-              .accounts({ admin: $.admin.publicKey })
-              .signers([$.admin])
+              .accounts({
+                ...$.getExtPdas(),
+                wrapAuthorityManager: $.wrapAuthorityManager.publicKey,
+                wrapAuthorityAccount: $.getWrapAuthority(
+                  $.nonWrapAuthority.publicKey
+                ),
+              })
+              .signers([$.wrapAuthorityManager])
               .rpc(),
-            "InvalidParam"
+            "AccountNotInitialized"
           );
         });
 
-        // given the admin signs the transaction
-        // given the wrap authority is in the list
-        // it removes the wrap authority from the list
-        // it resizes the ext global account down to accommodate the removed wrap authority
-        test("wrap authority is in the list - success", async () => {
-          // Cache the size of the ext global account
-          const extGlobalAccount = $.getExtGlobalAccount();
-          const extGlobalSize = await $.provider.connection
-            .getAccountInfo(extGlobalAccount)
-            .then((info) => info?.data.length || 0);
+        // given the wrap authority manager signs the transaction
+        // given the wrap authority exists
+        // it closes the account of the wrap authority
+        // it refunds the rent to the wrap authority manager
+        test("wrap authority exists - success", async () => {
+          // Cache the rent of the wrap authority account
+          const wrapAuthorityAccount = $.getWrapAuthority(
+            $.wrapAuthority.publicKey
+          );
+          const rent = $.svm.getAccount(wrapAuthorityAccount)!.lamports;
+          const managerBalance = $.svm.getAccount(
+            $.wrapAuthorityManager.publicKey
+          )!.lamports;
 
           // Send the transaction
           await $.ext.methods
             .removeWrapAuthority($.wrapAuthority.publicKey)
             .accounts({
-              admin: $.admin.publicKey,
+              ...$.getExtPdas(),
+              wrapAuthorityManager: $.wrapAuthorityManager.publicKey,
+              wrapAuthorityAccount: $.getWrapAuthority(
+                $.wrapAuthority.publicKey
+              ),
             })
-            .signers([$.admin])
+            .signers([$.wrapAuthorityManager])
             .rpc();
 
-          // Check that the wrap authority was added
-          wrapAuthorities.pop();
-
-          await $.expectExtGlobalState({
-            wrapAuthorities,
-          });
-
-          // Check that the ext global account was resized
-          const newExtGlobalSize = await $.provider.connection
-            .getAccountInfo(extGlobalAccount)
-            .then((info) => info?.data.length || 0);
-          expect(newExtGlobalSize).toEqual(extGlobalSize - 32); // remove 32 bytes
+          // Check that the wrap authority was removed and the rent refunded
+          $.expectAccountEmpty(wrapAuthorityAccount);
+          expect(
+            $.svm.getAccount($.wrapAuthorityManager.publicKey)!.lamports
+          ).toEqual(managerBalance + rent);
         });
       });
 
-      describe("claim_fees unit tests", () => {
-        // general test cases
-        // [X] given the admin does not sign the transaction
-        //   [X] it reverts with a NotAuthorized error
-        // [X] given the admin signs the transaction
-        //   [X] given the m vault is not the m vault PDA
-        //     [X] it reverts with a ConstraintSeeds error
-        //   [X] given the m vault token account is not the m vault PDA's ATA
-        //     [X] it reverts with a ConstraintAssociated error
-        //   [X] given the ext mint does not match the one on the global account
-        //     [X] it reverts with an InvalidMint error
-        //   [X] given the ext mint authority is not the ext mint authority PDA
-        //     [X] it reverts with a ConstraintSeeds error
-        //   [X] given the m earn global account does not match the derived PDA
-        //     [X] it reverts with a ConstraintSeeds error
-        //   [X] given the recipient token account is not a token account for the m mint
-        //     [X] it reverts with a ConstraintTokenMint error
-
-        const initialWrappedAmount = new BN(10_000_000); // 10 with 6 decimals
-        let wrapAuthorities: PublicKey[];
-        const feeBps = new BN(randomInt(1, 10000)); // non-zero
-        const startIndex = new BN(randomInt(initialIndex.toNumber() + 1, 2e12));
+      describe("update_wrap_authority unit tests", () => {
+        const updateWrapAuthority = (
+          signer: Keypair,
+          label: string,
+          enabled: boolean
+        ) =>
+          $.ext.methods
+            .updateWrapAuthority($.wrapAuthority.publicKey, label, enabled)
+            .accounts({
+              ...$.getExtPdas(),
+              wrapAuthorityManager: signer.publicKey,
+              wrapAuthorityAccount: $.getWrapAuthority(
+                $.wrapAuthority.publicKey
+              ),
+            })
+            .signers([signer])
+            .rpc();
 
         beforeEach(async () => {
-          wrapAuthorities = [$.admin.publicKey, $.wrapAuthority.publicKey];
+          const feeBps =
+            variant === Variant.NoYield ? new BN(0) : new BN(randomInt(10000));
           // Initialize the extension program
-          await $.initializeExt(wrapAuthorities, feeBps);
-
-          // Wrap some tokens from the admin to make the m vault's balance non-zero
-          await $.wrap($.admin, initialWrappedAmount);
-
-          // Propagate the start index
-          await $.propagateIndex(startIndex);
-
-          // Claim yield for the m vault and complete the claim cycle
-          const mVault = $.getMVault();
-          const mVaultATA = await $.getATA($.mMint.publicKey, mVault);
-          await $.mClaimFor(mVault, await $.getTokenBalance(mVaultATA));
-          await $.mCompleteClaims();
-
-          if (variant !== Variant.NoYield) {
-            // Sync the multiplier
-            await $.sync();
-          }
-          // Reset the blockhash to avoid issues with duplicate transactions
-          $.svm.expireBlockhash();
+          await $.initializeExt([$.wrapAuthority.publicKey], feeBps);
         });
 
-        // given the admin does not sign the transaction
-        // it reverts with a NotAuthorized error
-        test("admin does not sign - reverts", async () => {
-          const recipientExtTokenAccount = await $.getATA(
-            $.extMint.publicKey,
-            $.nonAdmin.publicKey
-          );
+        // test cases
+        // [X] given the wrap authority manager does not sign the transaction
+        //   [X] it reverts with a NotAuthorized error
+        // [X] given the label is longer than 32 bytes
+        //   [X] it reverts with a InvalidParam error
+        // [X] given the wrap authority manager signs the transaction
+        //   [X] it sets the label and enabled flag
+        //   [X] a disabled wrap authority can't wrap
 
-          // Attempt to send the transaction
+        // given the wrap authority manager does not sign the transaction
+        // it reverts with a NotAuthorized error
+        test("wrap authority manager does not sign - reverts", async () => {
           await $.expectAnchorError(
-            $.ext.methods
-              .claimFees()
-              .accountsPartial({
-                admin: $.nonAdmin.publicKey,
-                recipientExtTokenAccount,
-              })
-              .signers([$.nonAdmin])
-              .rpc(),
+            updateWrapAuthority($.nonAdmin, "distributor", true),
             "NotAuthorized"
           );
         });
 
-        // given the m vault is not the m vault PDA
-        // it reverts with a ConstraintSeeds error
-        test("m vault is not the m vault PDA - reverts", async () => {
-          // Change the m vault
-          const mVault = PublicKey.unique();
-          if (mVault === $.getMVault()) return;
-
-          // Create the ATA for the fake m vault so we avoid account not initialized errors
-          const mVaultATA = await $.getATA($.mMint.publicKey, mVault);
-
-          const recipientExtTokenAccount = await $.getATA(
-            $.extMint.publicKey,
-            $.admin.publicKey
-          );
-
-          // Attempt to send the transaction
+        // given the label is longer than 32 bytes
+        // it reverts with a InvalidParam error
+        test("label too long - reverts", async () => {
           await $.expectAnchorError(
-            $.ext.methods
-              .claimFees()
-              .accountsPartial({
-                admin: $.admin.publicKey,
-                mVault,
-                vaultMTokenAccount: mVaultATA,
-                recipientExtTokenAccount,
-              })
-              .signers([$.admin])
-              .rpc(),
-            "ConstraintSeeds"
+            updateWrapAuthority($.wrapAuthorityManager, "x".repeat(33), true),
+            "InvalidParam"
           );
         });
 
-        // given the m vault token account is not the m vault PDA's ATA
-        // it reverts with a ConstraintAssociated error
-        test("m vault token account is not the m vault PDA's ATA - reverts", async () => {
-          // Create a token account for the M vault that is not the ATA
-          const mVault = $.getMVault();
-          const { tokenAccount: nonAtaAccount } = await $.createTokenAccount(
-            $.mMint.publicKey,
-            mVault,
-            true,
-            true
+        // given the wrap authority manager signs the transaction
+        // it sets the label and enabled flag
+        // a disabled wrap authority can't wrap
+        test("disable wrap authority - success", async () => {
+          await updateWrapAuthority(
+            $.wrapAuthorityManager,
+            "distributor",
+            false
           );
 
-          const recipientExtTokenAccount = await $.getATA(
-            $.extMint.publicKey,
-            $.admin.publicKey
+          const wrapAuthority = await $.ext.account.wrapAuthority.fetch(
+            $.getWrapAuthority($.wrapAuthority.publicKey)
           );
+          expect(wrapAuthority.label).toEqual("distributor");
+          expect(wrapAuthority.enabled).toBe(false);
 
-          // Attempt to send the transaction
           await $.expectAnchorError(
-            $.ext.methods
-              .claimFees()
-              .accountsPartial({
-                admin: $.admin.publicKey,
-                vaultMTokenAccount: nonAtaAccount,
-                recipientExtTokenAccount,
-              })
-              .signers([$.admin])
-              .rpc(),
-            "ConstraintAssociated"
+            $.wrap($.wrapAuthority, new BN(1_000_000)),
+            "NotAuthorized"
           );
         });
+      });
 
-        // given the ext mint does not match the one on the global account
-        // it reverts with an InvalidMint error
-        test("ext mint does not match global account - reverts", async () => {
-          // Create a new mint
-          const wrongMint = new Keypair();
-          await $.createMint(wrongMint, $.nonAdmin.publicKey, true, 6);
-
-          const recipientExtTokenAccount = await $.getATA(
-            wrongMint.publicKey,
-            $.admin.publicKey
+      describe("set_wrap_authority_permissions unit tests", () => {
+        const setPermissions = (
+          signer: Keypair,
+          permissions: number,
+          expiresAt: BN
+        ) =>
+          $.ext.methods
+            .setWrapAuthorityPermissions(
+              $.wrapAuthority.publicKey,
+              permissions,
+              expiresAt
+            )
+            .accounts({
+              ...$.getExtPdas(),
+              admin: signer.publicKey,
+              wrapAuthorityAccount: $.getWrapAuthority(
+                $.wrapAuthority.publicKey
+              ),
+            })
+            .signers([signer])
+            .rpc();
+
+        const enableTimelock = () =>
+          $.ext.methods
+            .setTimelockDelay(new BN(86400))
+            .accounts({
+              ...$.getExtPdas(),
+              admin: $.admin.publicKey,
+            })
+            .signers([$.admin])
+            .rpc();
+
+        beforeEach(async () => {
+          const feeBps =
+            variant === Variant.NoYield ? new BN(0) : new BN(randomInt(10000));
+          // Initialize the extension program
+          await $.initializeExt([$.wrapAuthority.publicKey], feeBps);
+        });
+
+        // test cases
+        // [X] given the admin does not sign the transaction
+        //   [X] it reverts with a NotAuthorized error
+        // [X] given the permissions are zero or have unknown bits
+        //   [X] it reverts with a InvalidParam error
+        // [X] given the expiry is negative
+        //   [X] it reverts with a InvalidParam error
+        // [X] given the admin signs the transaction
+        //   [X] it sets the permissions and expiry
+        //   [X] an unwrap only wrap authority can't wrap
+        //   [X] a wrap only wrap authority can't unwrap
+        //   [X] an expired wrap authority can't wrap
+        // [X] given the timelock is enabled
+        //   [X] given the permissions or expiry are widened
+        //     [X] it reverts with a NotAuthorized error
+        //   [X] given the permissions and expiry are narrowed
+        //     [X] it sets the permissions and expiry
+        //   [X] given the widening is queued and executed after the delay
+        //     [X] it sets the permissions and expiry
+
+        // given the admin does not sign the transaction
+        // it reverts with a NotAuthorized error
+        test("admin does not sign - reverts", async () => {
+          await $.expectAnchorError(
+            setPermissions($.nonAdmin, 2, new BN(0)),
+            "NotAuthorized"
           );
+        });
 
-          // Attempt to send the transaction
+        // given the permissions are zero or have unknown bits
+        // it reverts with a InvalidParam error
+        test("invalid permissions - reverts", async () => {
           await $.expectAnchorError(
-            $.ext.methods
-              .claimFees()
-              .accountsPartial({
-                admin: $.admin.publicKey,
-                extMint: wrongMint.publicKey,
-                recipientExtTokenAccount,
-              })
-              .signers([$.admin])
-              .rpc(),
-            "InvalidMint"
+            setPermissions($.admin, 0, new BN(0)),
+            "InvalidParam"
+          );
+          await $.expectAnchorError(
+            setPermissions($.admin, 4, new BN(0)),
+            "InvalidParam"
           );
         });
 
-        // given the ext mint authority is not the ext mint authority PDA
-        // it reverts with a ConstraintSeeds error
-        test("ext mint authority is not the ext mint authority PDA - reverts", async () => {
-          // Change the ext mint authority
-          const extMintAuthority = PublicKey.unique();
-          if (extMintAuthority === $.getExtMintAuthority()) return;
+        // given the expiry is negative
+        // it reverts with a InvalidParam error
+        test("negative expiry - reverts", async () => {
+          await $.expectAnchorError(
+            setPermissions($.admin, 3, new BN(-1)),
+            "InvalidParam"
+          );
+        });
 
-          const recipientExtTokenAccount = await $.getATA(
-            $.extMint.publicKey,
-            $.admin.publicKey
+        // given the admin signs the transaction
+        // it sets the permissions and expiry
+        // an unwrap only wrap authority can't wrap
+        test("unwrap only - success", async () => {
+          const expiresAt = $.currentTime().add(new BN(3600));
+          await setPermissions($.admin, 2, expiresAt);
+
+          const wrapAuthority = await $.ext.account.wrapAuthority.fetch(
+            $.getWrapAuthority($.wrapAuthority.publicKey)
           );
-          // Attempt to send the transaction
+          expect(wrapAuthority.permissions).toEqual(2);
+          expect(wrapAuthority.expiresAt.toString()).toEqual(
+            expiresAt.toString()
+          );
+
           await $.expectAnchorError(
-            $.ext.methods
-              .claimFees()
-              .accountsPartial({
-                admin: $.admin.publicKey,
-                extMintAuthority,
-                recipientExtTokenAccount,
-              })
-              .signers([$.admin])
-              .rpc(),
-            "ConstraintSeeds"
+            $.wrap($.wrapAuthority, new BN(1_000_000)),
+            "NotAuthorized"
           );
         });
 
-        // given the m earner account does not match the derived one
-        // it reverts with a ConstraintSeeds / AccountNotInitialized error
-        test("m earn global account does not match derived pubkey - reverts", async () => {
-          // Change the m earn global account
-          const mEarnGlobalAccount = PublicKey.unique();
-          if (mEarnGlobalAccount.equals($.getEarnGlobalAccount())) return;
+        // given the admin signs the transaction
+        // a wrap only wrap authority can't unwrap
+        test("wrap only - success", async () => {
+          await setPermissions($.admin, 1, new BN(0));
+          await $.wrap($.wrapAuthority, new BN(1_000_000));
 
-          const recipientExtTokenAccount = await $.getATA(
-            $.extMint.publicKey,
-            $.admin.publicKey
+          await $.expectAnchorError(
+            $.unwrap($.wrapAuthority, new BN(1_000_000)),
+            "NotAuthorized"
           );
+        });
 
-          // Attempt to send the transaction
-          await $.expectSystemError(
-            $.ext.methods
-              .claimFees()
-              .accountsPartial({
-                admin: $.admin.publicKey,
-                mEarnGlobalAccount,
-                recipientExtTokenAccount,
-              })
-              .signers([$.admin])
-              .rpc()
+        // given the admin signs the transaction
+        // an expired wrap authority can't wrap
+        test("expired - reverts", async () => {
+          await setPermissions($.admin, 3, $.currentTime().add(new BN(60)));
+          $.warp(new BN(60), true);
+
+          await $.expectAnchorError(
+            $.wrap($.wrapAuthority, new BN(1_000_000)),
+            "Expired"
           );
         });
 
-        // given the recipient token account is not a token account for the ext mint
-        // it reverts with a ConstraintTokenMint error
-        test("recipient token account is not for ext mint - reverts", async () => {
-          // Create a token account for the m mint
-          const wrongTokenAccount = await $.getATA(
-            $.mMint.publicKey,
-            $.admin.publicKey
-          );
+        // given the timelock is enabled
+        // given the permissions or expiry are widened
+        // it reverts with a NotAuthorized error
+        test("timelock enabled - widen - reverts", async () => {
+          const expiresAt = $.currentTime().add(new BN(3600));
+          await setPermissions($.admin, 1, expiresAt);
+          await enableTimelock();
 
-          // Attempt to send the transaction
           await $.expectAnchorError(
-            $.ext.methods
-              .claimFees()
-              .accountsPartial({
-                admin: $.admin.publicKey,
-                recipientExtTokenAccount: wrongTokenAccount,
-              })
-              .signers([$.admin])
-              .rpc(),
-            "ConstraintTokenMint"
+            setPermissions($.admin, 3, expiresAt),
+            "NotAuthorized"
+          );
+          await $.expectAnchorError(
+            setPermissions($.admin, 1, new BN(0)),
+            "NotAuthorized"
+          );
+          await $.expectAnchorError(
+            setPermissions($.admin, 1, expiresAt.addn(1)),
+            "NotAuthorized"
           );
         });
 
-        // yield variant test cases
-        //   [X] given all the accounts are correct
-        //     [X] given the multiplier is not synced
-        //       [X] it syncs the multiplier to the current
-        //       [X] given the m vault has excess collateral
-        //         [X] it transfers the excess collateral to the recipient token account
-        //       [X] given the m vault does not have excess collateral
-        //         [X] it reverts with an InsufficientCollateral error
-        //     [X] given the multiplier is already synced
-        //       [X] given the m vault has excess collateral
-        //         [X] it transfers the excess collateral to the recipient token account
-        //       [X] given the m vault does not have excess collateral
-        //         [X] it completes but doesn't transfer any tokens
+        // given the timelock is enabled
+        // given the permissions and expiry are narrowed
+        // it sets the permissions and expiry
+        test("timelock enabled - narrow - success", async () => {
+          await enableTimelock();
 
-        if (variant === Variant.ScaledUiAmount) {
-          // given all accounts are correct
-          // given the multiplier is not synced
-          // it syncs the multiplier to the current
-          // given the m vault has excess collateral
-          // it transfers the excess collateral to the recipient token account
-          test("multiplier not synced, excess collateral exists - success", async () => {
-            // warp forward in time slightly
-            $.warp(new BN(60), true);
+          const expiresAt = $.currentTime().add(new BN(3600));
+          await setPermissions($.admin, 2, expiresAt);
+          await setPermissions($.admin, 2, expiresAt.subn(60));
 
-            // Propagate a new index to create a situation where multiplier needs sync
-            const newIndex = new BN(randomInt(startIndex.toNumber() + 1, 2e12));
-            await $.propagateIndex(newIndex);
+          const wrapAuthority = await $.ext.account.wrapAuthority.fetch(
+            $.getWrapAuthority($.wrapAuthority.publicKey)
+          );
+          expect(wrapAuthority.permissions).toEqual(2);
+          expect(wrapAuthority.expiresAt.toString()).toEqual(
+            expiresAt.subn(60).toString()
+          );
+        });
 
-            // Claim yield to ensure vault has enough collateral
-            const mVault = $.getMVault();
-            const mVaultATA = await $.getATA($.mMint.publicKey, mVault);
-            await $.mClaimFor(mVault, await $.getTokenBalance(mVaultATA));
-            await $.mCompleteClaims();
+        // given the timelock is enabled
+        // given the widening is queued and executed after the delay
+        // it sets the permissions and expiry
+        test("timelock enabled - queued widen - success", async () => {
+          await setPermissions($.admin, 1, $.currentTime().add(new BN(3600)));
+          await enableTimelock();
+
+          await $.queueAction($.admin, 0, {
+            setWrapAuthorityPermissions: {
+              wrapAuthority: $.wrapAuthority.publicKey,
+              permissions: 3,
+              expiresAt: new BN(0),
+            },
+          });
+          $.warp(new BN(86400), true);
 
-            // Cache balances before claim excess
-            const initialVaultBalance = await $.getTokenBalance(mVaultATA);
-            const recipientATA = await $.getATA(
-              $.extMint.publicKey,
-              $.admin.publicKey
-            );
+          await $.executeQueuedAction(
+            0,
+            $.admin.publicKey,
+            await $.getRemainingAccounts(
+              $.ext.methods
+                .setWrapAuthorityPermissions(
+                  $.wrapAuthority.publicKey,
+                  3,
+                  new BN(0)
+                )
+                .accounts({
+                  ...$.getExtPdas(),
+                  admin: $.getTimelockAuthority(),
+                  wrapAuthorityAccount: $.getWrapAuthority(
+                    $.wrapAuthority.publicKey
+                  ),
+                })
+                .instruction()
+            )
+          );
 
-            // Get the new multiplier calculate the expected excess
-            const multiplier = await $.getNewMultiplier(newIndex);
+          const wrapAuthority = await $.ext.account.wrapAuthority.fetch(
+            $.getWrapAuthority($.wrapAuthority.publicKey)
+          );
+          expect(wrapAuthority.permissions).toEqual(3);
+          expect(wrapAuthority.expiresAt.toString()).toEqual("0");
+        });
+      });
 
-            const initialRecipientPrincipal = await $.getTokenBalance(
-              recipientATA
-            );
-            const initialRecipientBalance = await $.getTokenUiBalance(
-              recipientATA,
-              multiplier
-            );
+      describe("migrate_global unit tests", () => {
+        let wrapAuthorities: PublicKey[];
 
-            const extSupply = await getMint(
-              $.provider.connection,
-              $.extMint.publicKey,
-              undefined,
-              TOKEN_2022_PROGRAM_ID
-            ).then((mint) => mint.supply);
+        // Layout of the global account before it was versioned, where the
+        // yield config was only present in the scaled ui build of the program
+        const writeLegacyGlobal = (
+          feeBps: BN,
+          lastMIndex: BN,
+          lastExtIndex: BN
+        ) => {
+          const globalAccount = $.getExtGlobalAccount();
+          const current = $.svm.getAccount(globalAccount)!;
+          const global = $.ext.coder.accounts.decode(
+            "extGlobal",
+            Buffer.from(current.data)
+          );
 
-            const requiredCollateral = new BN(
-              Math.ceil(Number(extSupply) * multiplier)
-            );
+          const header = Buffer.concat([
+            Buffer.from(current.data.slice(0, 8)), // discriminator
+            global.admin.toBuffer(),
+            global.extMint.toBuffer(),
+            global.mMint.toBuffer(),
+            global.mEarnGlobalAccount.toBuffer(),
+            Buffer.from([
+              global.bump,
+              global.mVaultBump,
+              global.extMintAuthorityBump,
+            ]),
+          ]);
+          const yieldConfig =
+            variant === Variant.ScaledUiAmount
+              ? Buffer.concat([
+                  feeBps.toArrayLike(Buffer, "le", 8),
+                  lastMIndex.toArrayLike(Buffer, "le", 8),
+                  lastExtIndex.toArrayLike(Buffer, "le", 8),
+                ])
+              : Buffer.alloc(0);
+          const length = Buffer.alloc(4);
+          length.writeUInt32LE(wrapAuthorities.length);
+
+          const data = Buffer.concat([
+            header,
+            yieldConfig,
+            length,
+            ...wrapAuthorities.map((a) => a.toBuffer()),
+          ]);
+
+          $.svm.setAccount(globalAccount, {
+            ...current,
+            lamports: Number(
+              $.svm.minimumBalanceForRentExemption(BigInt(data.length))
+            ),
+            data,
+          });
 
-            const expectedExcess = initialVaultBalance.sub(requiredCollateral);
-            const expectedExcessPrincipal = new BN(
-              Math.floor(Number(expectedExcess) / multiplier)
-            );
+          return data.length;
+        };
 
-            // Setup and execute the instruction
-            await $.ext.methods
-              .claimFees()
-              .accountsPartial({
-                admin: $.admin.publicKey,
-                recipientExtTokenAccount: recipientATA,
-              })
-              .signers([$.admin])
-              .rpc();
+        const migrateGlobal = (
+          wrapAuthorityAccounts: PublicKey[],
+          admin: Keypair = $.admin
+        ) =>
+          $.ext.methods
+            .migrateGlobal()
+            .accounts({
+              admin: admin.publicKey,
+              globalAccount: $.getExtGlobalAccount(),
+            })
+            .remainingAccounts(
+              wrapAuthorityAccounts.map((pubkey) => ({
+                pubkey,
+                isSigner: false,
+                isWritable: true,
+              }))
+            )
+            .signers([admin])
+            .rpc();
 
-            // Verify multiplier was updated
+        beforeEach(async () => {
+          wrapAuthorities = [$.admin.publicKey, $.wrapAuthority.publicKey];
 
-            $.expectScaledUiAmountConfig($.extMint.publicKey, {
-              authority: $.getExtMintAuthority(),
-              multiplier,
-              newMultiplier: multiplier,
-              newMultiplierEffectiveTimestamp: BigInt(
-                $.currentTime().toString()
-              ),
-            });
+          const feeBps =
+            variant === Variant.NoYield ? new BN(0) : new BN(randomInt(10000));
+          // Initialize the extension program, the wrap authorities
+          // only exist in the legacy layout written by the tests
+          await $.initializeExt([], feeBps);
+        });
 
-            // Verify excess tokens were transferred
+        // test cases
+        // [X] given the global account is at the latest version
+        //   [X] it reverts with a AlreadyMigrated error
+        // [X] given the global account has the legacy layout
+        //   [X] given the admin does not sign the transaction
+        //     [X] it reverts with a NotAuthorized error
+        //   [X] given the accounts of the wrap authorities are missing
+        //     [X] it reverts with a InvalidAccount error
+        //   [X] given the accounts of the wrap authorities are out of order
+        //     [X] it reverts with a InvalidAccount error
+        //   [X] given the admin signs the transaction
+        //     [X] it sets the layout version and keeps the existing fields
+        //     [X] it decodes the yield config of the legacy layout
+        //     [X] it creates the accounts of the wrap authorities
+        //     [X] it resizes the ext global account to the current layout
+        // [X] given the global account has an older versioned layout
+        //   [X] given bytes are left over past the end of the layout
+        //     [X] it keeps the fields of the older version
+        //     [X] it sets the fields added since to their defaults
+        //     [X] it moves the quotas of the wrap authorities to their accounts
+
+        // given the global account is at the latest version
+        // it reverts with a AlreadyMigrated error
+        test("latest version - reverts", async () => {
+          await $.expectAnchorError(migrateGlobal([]), "AlreadyMigrated");
+        });
 
-            $.expectTokenBalance(mVaultATA, initialVaultBalance);
-            $.expectTokenUiBalance(
-              recipientATA,
-              initialRecipientBalance.add(expectedExcess),
-              Comparison.LessThanOrEqual,
-              new BN(1)
-            );
-            $.expectTokenBalance(
-              recipientATA,
-              initialRecipientPrincipal.add(expectedExcessPrincipal)
-            );
-          });
+        // given the global account has the legacy layout
+        // given the admin does not sign the transaction
+        // it reverts with a NotAuthorized error
+        test("legacy layout, admin does not sign - reverts", async () => {
+          writeLegacyGlobal(new BN(0), new BN(0), new BN(0));
 
-          // given all accounts are correct
-          // given the multiplier is already synced
-          // given the m vault has excess collateral
-          // it transfers the excess collateral to the recipient token account
-          test("multiplier already synced, excess collateral exists - success", async () => {
-            // Cache balances before claim excess
-            const mVaultATA = await $.getATA($.mMint.publicKey, $.getMVault());
-            const initialVaultBalance = await $.getTokenBalance(mVaultATA);
-            const recipientATA = await $.getATA(
-              $.extMint.publicKey,
-              $.admin.publicKey
-            );
+          await $.expectAnchorError(
+            migrateGlobal(
+              wrapAuthorities.map((a) => $.getWrapAuthority(a)),
+              $.nonAdmin
+            ),
+            "NotAuthorized"
+          );
+        });
 
-            // Get the current multiplier and calculate the $.expected excess
-            const multiplier = await $.getCurrentMultiplier();
-            const initialRecipientBalance = await $.getTokenUiBalance(
-              recipientATA,
-              multiplier
-            );
-            const initialRecipientPrincipal = await $.getTokenBalance(
-              recipientATA
-            );
+        // given the global account has the legacy layout
+        // given the accounts of the wrap authorities are missing
+        // it reverts with a InvalidAccount error
+        test("legacy layout, missing wrap authority accounts - reverts", async () => {
+          writeLegacyGlobal(new BN(0), new BN(0), new BN(0));
 
-            const extSupply = await getMint(
-              $.provider.connection,
-              $.extMint.publicKey,
-              undefined,
-              TOKEN_2022_PROGRAM_ID
-            ).then((mint) => mint.supply);
+          await $.expectAnchorError(
+            migrateGlobal([$.getWrapAuthority($.admin.publicKey)]),
+            "InvalidAccount"
+          );
+        });
 
-            const requiredCollateral = new BN(
-              Math.ceil(Number(extSupply) * multiplier)
-            );
+        // given the global account has the legacy layout
+        // given the accounts of the wrap authorities are out of order
+        // it reverts with a InvalidAccount error
+        test("legacy layout, wrap authority accounts out of order - reverts", async () => {
+          writeLegacyGlobal(new BN(0), new BN(0), new BN(0));
 
-            const expectedExcess = initialVaultBalance.sub(requiredCollateral);
-            const expectedExcessPrincipal = new BN(
-              Math.floor(Number(expectedExcess) / multiplier)
-            );
+          await $.expectAnchorError(
+            migrateGlobal(
+              [...wrapAuthorities].reverse().map((a) => $.getWrapAuthority(a))
+            ),
+            "InvalidAccount"
+          );
+        });
 
-            await $.ext.methods
-              .claimFees()
-              .accountsPartial({
-                admin: $.admin.publicKey,
-                recipientExtTokenAccount: recipientATA,
-              })
-              .signers([$.admin])
-              .rpc();
+        // given the global account has the legacy layout
+        // given the admin signs the transaction
+        // it sets the layout version and keeps the existing fields
+        // it decodes the yield config of the legacy layout
+        // it creates the accounts of the wrap authorities
+        // it resizes the ext global account to the current layout
+        test("legacy layout - success", async () => {
+          const feeBps = new BN(randomInt(10000));
+          const lastMIndex = new BN(1_050_000_000_000);
+          const lastExtIndex = new BN(1_020_000_000_000);
+          const legacySize = writeLegacyGlobal(
+            feeBps,
+            lastMIndex,
+            lastExtIndex
+          );
+          expect(legacySize).toEqual(
+            (variant === Variant.ScaledUiAmount ? 167 : 143) +
+              wrapAuthorities.length * 32
+          );
 
-            // Verify excess tokens were transferred
-            $.expectTokenBalance(mVaultATA, initialVaultBalance);
-            $.expectTokenUiBalance(
-              recipientATA,
-              initialRecipientBalance.add(expectedExcess),
-              Comparison.LessThanOrEqual,
-              new BN(1)
-            );
-            $.expectTokenBalance(
-              recipientATA,
-              initialRecipientPrincipal.add(expectedExcessPrincipal)
-            );
-          });
+          await migrateGlobal(
+            wrapAuthorities.map((a) => $.getWrapAuthority(a))
+          );
 
-          // given all accounts are correct
-          // given the multiplier is already synced
-          // given the m vault does not have excess collateral
-          // it completes successfully and does not transfer any tokens
-          test("multiplier already synced, no excess collateral - success", async () => {
-            // claim the existing excess so there isn't extra
-            await $.claimFees();
-            $.svm.expireBlockhash();
+          await $.expectExtGlobalState({
+            version: 10,
+            admin: $.admin.publicKey,
+            extMint: $.extMint.publicKey,
+            mMint: $.mMint.publicKey,
+            mEarnGlobalAccount: $.getEarnGlobalAccount(),
+            namespaced: false,
+          });
 
-            // Cache balances before claim excess
-            const mVaultATA = await $.getATA($.mMint.publicKey, $.getMVault());
-            const initialVaultBalance = await $.getTokenBalance(mVaultATA);
-            const recipientATA = await $.getATA(
-              $.extMint.publicKey,
-              $.admin.publicKey
+          const state = await $.ext.account.extGlobal.fetch(
+            $.getExtGlobalAccount()
+          );
+          if (variant === Variant.ScaledUiAmount) {
+            const yieldConfig = state.yieldConfig.scaledUi!["0"];
+            expect(yieldConfig.feeBps.toString()).toEqual(feeBps.toString());
+            expect(yieldConfig.lastMIndex.toString()).toEqual(
+              lastMIndex.toString()
             );
-            const initialRecipientBalance = await $.getTokenBalance(
-              recipientATA
+            expect(yieldConfig.lastExtIndex.toString()).toEqual(
+              lastExtIndex.toString()
             );
+          } else {
+            expect(state.yieldConfig).toEqual({ noYield: {} });
+          }
+          expect(state.openWrap).toBe(false);
+          expect(state.openUnwrap).toBe(false);
+          expect(state.windingDown).toBe(false);
+
+          for (const authority of wrapAuthorities) {
+            const wrapAuthority = await $.ext.account.wrapAuthority.fetch(
+              $.getWrapAuthority(authority)
+            );
+            expect(wrapAuthority.authority).toEqual(authority);
+            expect(wrapAuthority.enabled).toBe(true);
+            expect(wrapAuthority.permissions).toEqual(3);
+            expect(wrapAuthority.quota.quota.toString()).toEqual("0");
+            expect(wrapAuthority.quota.minted.toString()).toEqual("0");
+          }
 
-            // Attempt to send the transaction
-            await $.ext.methods
-              .claimFees()
-              .accountsPartial({
-                admin: $.admin.publicKey,
-                recipientExtTokenAccount: recipientATA,
-              })
-              .signers([$.admin])
-              .rpc();
+          const extGlobalSize = await $.provider.connection
+            .getAccountInfo($.getExtGlobalAccount())
+            .then((info) => info?.data.length || 0);
+          expect(extGlobalSize).toEqual(364 + 405);
+        });
 
-            // Verify no tokens were transferred
-            $.expectTokenBalance(mVaultATA, initialVaultBalance);
-            $.expectTokenBalance(recipientATA, initialRecipientBalance);
+        // given the global account has an older versioned layout
+        // given bytes are left over past the end of the layout
+        // it keeps the fields of the older version
+        // it sets the fields added since to their defaults
+        // it moves the quotas of the wrap authorities to their accounts
+        test("older version with stale tail bytes - success", async () => {
+          const globalAccount = $.getExtGlobalAccount();
+          const current = $.svm.getAccount(globalAccount)!;
+          const global = $.ext.coder.accounts.decode(
+            "extGlobal",
+            Buffer.from(current.data)
+          );
+          global.supplyCap = new BN(1_000_000);
+
+          // Rebuild the version 7 layout, which has the wrap authorities after
+          // the yield config and their quotas after the supply cap, and leave
+          // non-zero bytes where the version 8 and 9 fields (open_wrap,
+          // open_unwrap, winding_down) would be
+          const encoded = await $.ext.coder.accounts.encode(
+            "extGlobal",
+            global
+          );
+          const yieldConfig = $.ext.coder.types.encode(
+            "yieldConfig",
+            global.yieldConfig
+          );
+          const headerSize = 141 + yieldConfig.length; // up to namespaced
+          const authoritiesLength = Buffer.alloc(4);
+          authoritiesLength.writeUInt32LE(wrapAuthorities.length);
+          const quotas = [
+            { quota: new BN(500_000), minted: new BN(200_000) },
+            { quota: new BN(0), minted: new BN(0) },
+          ];
+          const quotasLength = Buffer.alloc(4);
+          quotasLength.writeUInt32LE(quotas.length);
+
+          const data = Buffer.concat([
+            encoded.subarray(0, headerSize),
+            authoritiesLength,
+            ...wrapAuthorities.map((a) => a.toBuffer()),
+            encoded.subarray(headerSize, encoded.length - 3),
+            quotasLength,
+            ...quotas.map((q) => $.ext.coder.types.encode("wrapQuota", q)),
+            Buffer.from([1, 1, 1]),
+          ]);
+          data[8] = 7;
+
+          $.svm.setAccount(globalAccount, {
+            ...current,
+            lamports: Number(
+              $.svm.minimumBalanceForRentExemption(BigInt(data.length))
+            ),
+            data,
           });
-        }
 
-        // no yield test cases
-        //   [X] given all the accounts are correct
-        //     [X] given the m vault has excess collateral
-        //       [X] it transfers the excess collateral to the recipient token account
-        //     [X] given the m vault does not have excess collateral
-        //       [X] it completes but doesn't transfer any tokens
-        if (variant === Variant.NoYield) {
-          // given all accounts are correct
-          // given the m vault has excess collateral
-          // it transfers the excess collateral to the recipient token account
-          test("excess collateral exists - success", async () => {
-            // Cache balances before claim excess
-            const mVaultATA = await $.getATA($.mMint.publicKey, $.getMVault());
-            const initialVaultBalance = await $.getTokenBalance(mVaultATA);
-            const recipientATA = await $.getATA(
-              $.extMint.publicKey,
-              $.admin.publicKey
+          await migrateGlobal(
+            wrapAuthorities.map((a) => $.getWrapAuthority(a))
+          );
+
+          await $.expectExtGlobalState({
+            version: 10,
+            admin: $.admin.publicKey,
+            extMint: $.extMint.publicKey,
+            mMint: $.mMint.publicKey,
+          });
+
+          const state = await $.ext.account.extGlobal.fetch(globalAccount);
+          expect(state.supplyCap.toString()).toEqual("1000000");
+          expect(state.openWrap).toBe(false);
+          expect(state.openUnwrap).toBe(false);
+          expect(state.windingDown).toBe(false);
+
+          for (const [i, authority] of wrapAuthorities.entries()) {
+            const wrapAuthority = await $.ext.account.wrapAuthority.fetch(
+              $.getWrapAuthority(authority)
             );
-            const initialRecipientBalance = await $.getTokenBalance(
-              recipientATA
+            expect(wrapAuthority.quota.quota.toString()).toEqual(
+              quotas[i].quota.toString()
             );
+            expect(wrapAuthority.quota.minted.toString()).toEqual(
+              quotas[i].minted.toString()
+            );
+          }
 
-            const extSupply = await getMint(
-              $.provider.connection,
-              $.extMint.publicKey,
-              undefined,
-              TOKEN_2022_PROGRAM_ID
-            ).then((mint) => mint.supply);
+          const extGlobalSize = await $.provider.connection
+            .getAccountInfo(globalAccount)
+            .then((info) => info?.data.length || 0);
+          expect(extGlobalSize).toEqual(364 + 405);
+        });
+      });
 
-            const expectedExcess = initialVaultBalance.sub(
-              new BN(extSupply.toString())
-            );
+      describe("admin transfer unit tests", () => {
+        beforeEach(async () => {
+          const feeBps =
+            variant === Variant.NoYield ? new BN(0) : new BN(randomInt(10000));
+          // Initialize the extension program
+          await $.initializeExt([$.wrapAuthority.publicKey], feeBps);
+        });
 
-            await $.ext.methods
-              .claimFees()
-              .accountsPartial({
+        // test cases
+        // propose_admin
+        // [X] given the admin does not sign the transaction
+        //   [X] it reverts with a NotAuthorized error
+        // [X] given the new admin is the current admin
+        //   [X] it reverts with a InvalidParam error
+        // [X] given the admin signs the transaction
+        //   [X] it sets the pending admin
+        // accept_admin
+        // [X] given the signer is not the pending admin
+        //   [X] it reverts with a NotAuthorized error
+        // [X] given the pending admin signs the transaction
+        //   [X] it sets the admin and clears the pending admin
+        //   [X] the old admin can no longer propose an admin
+        // cancel_admin_transfer
+        // [X] given there is no pending admin
+        //   [X] it reverts with a NotActive error
+        // [X] given the admin signs the transaction
+        //   [X] it clears the pending admin and it can no longer be accepted
+
+        // given the admin does not sign the transaction
+        // it reverts with a NotAuthorized error
+        test("propose_admin - admin does not sign - reverts", async () => {
+          await $.expectAnchorError(
+            $.ext.methods
+              .proposeAdmin($.nonAdmin.publicKey)
+              .accounts({
+                ...$.getExtPdas(),
+                admin: $.nonAdmin.publicKey,
+              })
+              .signers([$.nonAdmin])
+              .rpc(),
+            "NotAuthorized"
+          );
+        });
+
+        // given the new admin is the current admin
+        // it reverts with a InvalidParam error
+        test("propose_admin - current admin - reverts", async () => {
+          await $.expectAnchorError(
+            $.ext.methods
+              .proposeAdmin($.admin.publicKey)
+              .accounts({
+                ...$.getExtPdas(),
                 admin: $.admin.publicKey,
-                recipientExtTokenAccount: recipientATA,
               })
               .signers([$.admin])
-              .rpc();
+              .rpc(),
+            "InvalidParam"
+          );
+        });
 
-            // Verify excess tokens were transferred
-            $.expectTokenBalance(mVaultATA, initialVaultBalance);
-            $.expectTokenBalance(
-              recipientATA,
-              initialRecipientBalance.add(expectedExcess)
-            );
+        // given the admin signs the transaction
+        // it sets the pending admin
+        test("propose_admin - success", async () => {
+          await $.ext.methods
+            .proposeAdmin($.nonAdmin.publicKey)
+            .accounts({
+              ...$.getExtPdas(),
+              admin: $.admin.publicKey,
+            })
+            .signers([$.admin])
+            .rpc();
+
+          await $.expectExtGlobalState({
+            admin: $.admin.publicKey,
+            pendingAdmin: $.nonAdmin.publicKey,
           });
+        });
 
-          // given all accounts are correct
-          // given the m vault does not have excess collateral
-          // it completes successfully and does not transfer any tokens
-          test("no excess collateral - success", async () => {
-            // claim the existing excess so there isn't extra
-            await $.claimFees();
-            $.svm.expireBlockhash();
+        // given the signer is not the pending admin
+        // it reverts with a NotAuthorized error
+        test("accept_admin - not pending admin - reverts", async () => {
+          await $.ext.methods
+            .proposeAdmin($.nonAdmin.publicKey)
+            .accounts({
+              ...$.getExtPdas(),
+              admin: $.admin.publicKey,
+            })
+            .signers([$.admin])
+            .rpc();
 
-            // Cache balances before claim excess
-            const mVaultATA = await $.getATA($.mMint.publicKey, $.getMVault());
-            const initialVaultBalance = await $.getTokenBalance(mVaultATA);
-            const recipientATA = await $.getATA(
-              $.extMint.publicKey,
-              $.admin.publicKey
-            );
-            const initialRecipientBalance = await $.getTokenBalance(
-              recipientATA
-            );
+          await $.expectAnchorError(
+            $.ext.methods
+              .acceptAdmin()
+              .accounts({
+                ...$.getExtPdas(),
+                pendingAdmin: $.wrapAuthority.publicKey,
+              })
+              .signers([$.wrapAuthority])
+              .rpc(),
+            "NotAuthorized"
+          );
+        });
 
-            // Attempt to send the transaction
-            await $.ext.methods
-              .claimFees()
-              .accountsPartial({
+        // given the pending admin signs the transaction
+        // it sets the admin and clears the pending admin
+        // the old admin can no longer propose an admin
+        test("accept_admin - success", async () => {
+          await $.ext.methods
+            .proposeAdmin($.nonAdmin.publicKey)
+            .accounts({
+              ...$.getExtPdas(),
+              admin: $.admin.publicKey,
+            })
+            .signers([$.admin])
+            .rpc();
+
+          await $.ext.methods
+            .acceptAdmin()
+            .accounts({
+              ...$.getExtPdas(),
+              pendingAdmin: $.nonAdmin.publicKey,
+            })
+            .signers([$.nonAdmin])
+            .rpc();
+
+          await $.expectExtGlobalState({
+            admin: $.nonAdmin.publicKey,
+            pendingAdmin: null,
+          });
+
+          await $.expectAnchorError(
+            $.ext.methods
+              .proposeAdmin($.wrapAuthority.publicKey)
+              .accounts({
+                ...$.getExtPdas(),
                 admin: $.admin.publicKey,
-                recipientExtTokenAccount: recipientATA,
               })
               .signers([$.admin])
-              .rpc();
+              .rpc(),
+            "NotAuthorized"
+          );
+        });
 
-            // Verify no tokens were transferred
-            $.expectTokenBalance(mVaultATA, initialVaultBalance);
-            $.expectTokenBalance(recipientATA, initialRecipientBalance);
+        // given there is no pending admin
+        // it reverts with a NotActive error
+        test("cancel_admin_transfer - no pending admin - reverts", async () => {
+          await $.expectAnchorError(
+            $.ext.methods
+              .cancelAdminTransfer()
+              .accounts({
+                ...$.getExtPdas(),
+                admin: $.admin.publicKey,
+              })
+              .signers([$.admin])
+              .rpc(),
+            "NotActive"
+          );
+        });
+
+        // given the admin signs the transaction
+        // it clears the pending admin and it can no longer be accepted
+        test("cancel_admin_transfer - success", async () => {
+          await $.ext.methods
+            .proposeAdmin($.nonAdmin.publicKey)
+            .accounts({
+              ...$.getExtPdas(),
+              admin: $.admin.publicKey,
+            })
+            .signers([$.admin])
+            .rpc();
+
+          await $.ext.methods
+            .cancelAdminTransfer()
+            .accounts({
+              ...$.getExtPdas(),
+              admin: $.admin.publicKey,
+            })
+            .signers([$.admin])
+            .rpc();
+
+          await $.expectExtGlobalState({
+            admin: $.admin.publicKey,
+            pendingAdmin: null,
           });
-        }
+
+          await $.expectAnchorError(
+            $.ext.methods
+              .acceptAdmin()
+              .accounts({
+                ...$.getExtPdas(),
+                pendingAdmin: $.nonAdmin.publicKey,
+              })
+              .signers([$.nonAdmin])
+              .rpc(),
+            "NotAuthorized"
+          );
+        });
       });
 
-      if (variant !== Variant.NoYield) {
-        describe("set_fee unit tests", () => {
-          // yield variant test cases
-          // [X] given the admin does not sign the transaction
-          //   [X] it reverts with a NotAuthorized error
-          // [X] given the admin signs the transaction
-          //   [X] given the ext mint does not match the one on the global account
-          //     [X] it reverts with an InvalidMint error
-          //   [X] given the ext mint authority is not the ext mint authority PDA
-          //     [X] it reverts with a ConstraintSeeds error
-          //   [X] given all the accounts are correct
-          //     [X] given the multiplier is not synced
-          //       [X] it syncs the multiplier to the last claimed index
-          //       [X] it updates the fee bps
-          //     [X] given the multiplier is already synced
-          //       [X] it updates the fee bps
+      describe("role unit tests", () => {
+        beforeEach(async () => {
+          const feeBps =
+            variant === Variant.NoYield ? new BN(0) : new BN(randomInt(10000));
+          // Initialize the extension program, which grants the roles to the harness keys
+          await $.initializeExt([$.wrapAuthority.publicKey], feeBps);
+        });
 
-          const initialWrappedAmount = new BN(10_000_000); // 10 with 6 decimals
-          let wrapAuthorities: PublicKey[];
-          const feeBps = new BN(randomInt(1, 10000)); // non-zero
-          const startIndex = new BN(
-            randomInt(initialIndex.toNumber() + 1, 2e12)
-          );
+        // test cases
+        // grant_role
+        // [X] given the admin does not sign the transaction
+        //   [X] it reverts with a NotAuthorized error
+        // [X] given the role is granted to the admin
+        //   [X] it reverts with a InvalidParam error
+        // [X] given the admin signs the transaction
+        //   [X] it replaces the holder of the role
+        //   [X] the previous holder can no longer use the role
+        // revoke_role
+        // [X] given the role is not granted
+        //   [X] it reverts with a NotActive error
+        // [X] given the admin signs the transaction
+        //   [X] it leaves the role unassigned
 
-          beforeEach(async () => {
-            wrapAuthorities = [$.admin.publicKey, $.wrapAuthority.publicKey];
-            // Initialize the extension program
-            await $.initializeExt(wrapAuthorities, feeBps);
+        // given the admin does not sign the transaction
+        // it reverts with a NotAuthorized error
+        test("grant_role - admin does not sign - reverts", async () => {
+          await $.expectAnchorError(
+            $.ext.methods
+              .grantRole({ feeManager: {} }, $.nonAdmin.publicKey)
+              .accounts({
+                ...$.getExtPdas(),
+                admin: $.nonAdmin.publicKey,
+              })
+              .signers([$.nonAdmin])
+              .rpc(),
+            "NotAuthorized"
+          );
+        });
 
-            // Wrap some tokens from the admin to make the m vault's balance non-zero
-            await $.wrap($.admin, initialWrappedAmount);
+        // given the role is granted to the admin
+        // it reverts with a InvalidParam error
+        test("grant_role - admin as holder - reverts", async () => {
+          await $.expectAnchorError(
+            $.ext.methods
+              .grantRole({ feeManager: {} }, $.admin.publicKey)
+              .accounts({
+                ...$.getExtPdas(),
+                admin: $.admin.publicKey,
+              })
+              .signers([$.admin])
+              .rpc(),
+            "InvalidParam"
+          );
+        });
 
-            // Propagate the start index
-            await $.propagateIndex(startIndex);
+        // given the admin signs the transaction
+        // it replaces the holder of the role
+        // the previous holder can no longer use the role
+        test("grant_role - success", async () => {
+          await $.grantRole({ wrapAuthorityManager: {} }, $.nonAdmin.publicKey);
 
-            // Claim yield for the m vault and complete the claim cycle
-            const mVault = $.getMVault();
-            const mVaultATA = await $.getATA($.mMint.publicKey, mVault);
-            await $.mClaimFor(mVault, await $.getTokenBalance(mVaultATA));
-            await $.mCompleteClaims();
+          const state = await $.ext.account.extGlobal.fetch(
+            $.getExtGlobalAccount()
+          );
+          expect(state.roles.wrapAuthorityManager).toEqual(
+            $.nonAdmin.publicKey
+          );
 
-            // Sync the multiplier
-            await $.sync();
+          await $.expectAnchorError(
+            $.ext.methods
+              .addWrapAuthority($.nonWrapAuthority.publicKey)
+              .accounts({
+                ...$.getExtPdas(),
+                wrapAuthorityManager: $.wrapAuthorityManager.publicKey,
+                wrapAuthorityAccount: $.getWrapAuthority(
+                  $.nonWrapAuthority.publicKey
+                ),
+              })
+              .signers([$.wrapAuthorityManager])
+              .rpc(),
+            "NotAuthorized"
+          );
 
-            // Reset the blockhash to avoid issues with duplicate transactions
-            $.svm.expireBlockhash();
-          });
+          await $.ext.methods
+            .addWrapAuthority($.nonWrapAuthority.publicKey)
+            .accounts({
+              ...$.getExtPdas(),
+              wrapAuthorityManager: $.nonAdmin.publicKey,
+              wrapAuthorityAccount: $.getWrapAuthority(
+                $.nonWrapAuthority.publicKey
+              ),
+            })
+            .signers([$.nonAdmin])
+            .rpc();
+        });
 
-          // given the admin does not sign the transaction
-          // it reverts with a NotAuthorized error
-          test("admin does not sign - reverts", async () => {
-            // Attempt to send the transaction
-            await $.expectAnchorError(
-              $.ext.methods
-                .setFee(new BN(randomInt(10000)))
-                .accountsPartial({
-                  admin: $.nonAdmin.publicKey,
-                })
-                .signers([$.nonAdmin])
-                .rpc(),
-              "NotAuthorized"
-            );
-          });
+        // given the role is not granted
+        // it reverts with a NotActive error
+        test("revoke_role - not granted - reverts", async () => {
+          await $.expectAnchorError(
+            $.ext.methods
+              .revokeRole({ pauser: {} })
+              .accounts({
+                ...$.getExtPdas(),
+                admin: $.admin.publicKey,
+              })
+              .signers([$.admin])
+              .rpc(),
+            "NotActive"
+          );
+        });
 
-          // given the admin signs the transaction
+        // given the admin signs the transaction
+        // it leaves the role unassigned
+        test("revoke_role - success", async () => {
+          await $.ext.methods
+            .revokeRole({ feeClaimer: {} })
+            .accounts({
+              ...$.getExtPdas(),
+              admin: $.admin.publicKey,
+            })
+            .signers([$.admin])
+            .rpc();
 
-          // given the ext mint does not match the one on the global account
-          // it reverts with an InvalidMint error
-          test("ext mint does not match global account - reverts", async () => {
-            // Create a new mint
-            const wrongMint = new Keypair();
-            await $.createMint(wrongMint, $.nonAdmin.publicKey, true, 6);
+          const state = await $.ext.account.extGlobal.fetch(
+            $.getExtGlobalAccount()
+          );
+          expect(state.roles.feeClaimer).toEqual(PublicKey.default);
+        });
+      });
 
-            // Attempt to send the transaction
-            await $.expectAnchorError(
-              $.ext.methods
-                .setFee(new BN(randomInt(10000)))
-                .accountsPartial({
-                  admin: $.admin.publicKey,
-                  extMint: wrongMint.publicKey,
-                })
-                .signers([$.admin])
-                .rpc(),
-              "InvalidMint"
-            );
-          });
+      describe("multisig unit tests", () => {
+        let signers: Keypair[];
 
-          // given the ext mint authority is not the ext mint authority PDA
-          // it reverts with a ConstraintSeeds error
-          test("ext mint authority is not the ext mint authority PDA - reverts", async () => {
-            // Change the ext mint authority
-            const extMintAuthority = PublicKey.unique();
-            if (extMintAuthority === $.getExtMintAuthority()) return;
+        beforeEach(async () => {
+          const feeBps =
+            variant === Variant.NoYield ? new BN(0) : new BN(randomInt(10000));
+          // Initialize the extension program
+          await $.initializeExt([$.wrapAuthority.publicKey], feeBps);
 
-            // Attempt to send the transaction
-            await $.expectAnchorError(
-              $.ext.methods
-                .setFee(new BN(randomInt(10000)))
-                .accountsPartial({
-                  admin: $.admin.publicKey,
-                  extMintAuthority,
-                })
-                .signers([$.admin])
-                .rpc(),
-              "ConstraintSeeds"
-            );
-          });
+          // Create a 2 of 3 multisig
+          signers = [new Keypair(), new Keypair(), new Keypair()];
+          for (const signer of signers) {
+            $.svm.airdrop(signer.publicKey, BigInt(LAMPORTS_PER_SOL));
+          }
 
-          // given all the accounts are correct
-          // given the multiplier is not synced
-          // it syncs the multiplier to the last claimed index
-          // it updates the fee bps
-          test("multiplier not synced - success", async () => {
-            // warp forward in time slightly
-            $.warp(new BN(60), true);
+          await $.ext.methods
+            .createMultisig(signers.map((s) => s.publicKey), 2)
+            .accounts({
+              ...$.getExtPdas(),
+              admin: $.admin.publicKey,
+            })
+            .signers([$.admin])
+            .rpc();
 
-            // Propagate a new index to create a situation where multiplier needs sync
-            const newIndex = new BN(randomInt(startIndex.toNumber() + 1, 2e12));
-            await $.propagateIndex(newIndex);
+          // Delegate the wrap authority manager role to the multisig and fund its rent payments
+          await $.grantRole(
+            { wrapAuthorityManager: {} },
+            $.getMultisigAuthority()
+          );
+          $.svm.airdrop($.getMultisigAuthority(), BigInt(LAMPORTS_PER_SOL));
+        });
 
-            // Claim yield to ensure vault has enough collateral
-            const mVault = $.getMVault();
-            const mVaultATA = await $.getATA($.mMint.publicKey, mVault);
-            await $.mClaimFor(mVault, await $.getTokenBalance(mVaultATA));
-            await $.mCompleteClaims();
+        // test cases
+        // create_multisig / update_multisig
+        // [X] given the admin does not sign the transaction
+        //   [X] it reverts with a NotAuthorized error
+        // [X] given the threshold is larger than the number of signers
+        //   [X] it reverts with a InvalidParam error
+        // [X] given the admin signs the transaction
+        //   [X] it sets the signers and threshold
+        // create_proposal
+        // [X] given the proposer is not a signer
+        //   [X] it reverts with a NotAuthorized error
+        // [X] given the proposer is a signer
+        //   [X] it stores the action and accounts, and counts as an approval
+        // approve_proposal
+        // [X] given the signer already approved the proposal
+        //   [X] it reverts with a InvalidParam error
+        // [X] given the proposal has expired
+        //   [X] it reverts with a NotActive error
+        // execute_proposal
+        // [X] given the threshold is not met
+        //   [X] it reverts with a InsufficientApprovals error
+        // [X] given the accounts don't match the proposal
+        //   [X] it reverts with a InvalidAccount error
+        // [X] given the threshold is met
+        //   [X] it executes the action as the multisig authority
+        //   [X] it can't be executed again
 
-            // Get the new multiplier
-            const multiplier = await $.getNewMultiplier(newIndex);
+        // given the admin does not sign the transaction
+        // it reverts with a NotAuthorized error
+        test("update_multisig - admin does not sign - reverts", async () => {
+          await $.expectAnchorError(
+            $.ext.methods
+              .updateMultisig([$.nonAdmin.publicKey], 1)
+              .accounts({
+                ...$.getExtPdas(),
+                admin: $.nonAdmin.publicKey,
+              })
+              .signers([$.nonAdmin])
+              .rpc(),
+            "NotAuthorized"
+          );
+        });
 
-            // Setup and execute the instruction
-            const newFee = new BN(randomInt(10000));
-            await $.ext.methods
-              .setFee(newFee)
-              .accountsPartial({
+        // given the threshold is larger than the number of signers
+        // it reverts with a InvalidParam error
+        test("update_multisig - threshold too large - reverts", async () => {
+          await $.expectAnchorError(
+            $.ext.methods
+              .updateMultisig([$.nonAdmin.publicKey], 2)
+              .accounts({
+                ...$.getExtPdas(),
                 admin: $.admin.publicKey,
               })
               .signers([$.admin])
-              .rpc();
+              .rpc(),
+            "InvalidParam"
+          );
+        });
 
-            // Verify multiplier was updated
-            $.expectScaledUiAmountConfig($.extMint.publicKey, {
-              authority: $.getExtMintAuthority(),
-              multiplier,
-              newMultiplier: multiplier,
-              newMultiplierEffectiveTimestamp: BigInt(
-                $.currentTime().toString()
-              ),
-            });
+        // given the admin signs the transaction
+        // it sets the signers and threshold
+        test("update_multisig - success", async () => {
+          await $.ext.methods
+            .updateMultisig([signers[0].publicKey, $.nonAdmin.publicKey], 1)
+            .accounts({
+              ...$.getExtPdas(),
+              admin: $.admin.publicKey,
+            })
+            .signers([$.admin])
+            .rpc();
 
-            // Verify fee bps was updated
-            await $.expectExtGlobalState({
-              yieldConfig: {
-                feeBps: newFee,
-                lastExtIndex: new BN(Math.floor(multiplier * 1e12)),
-                lastMIndex: newIndex,
-              },
-            });
-          });
+          const multisig = await $.ext.account.multisig.fetch($.getMultisig());
+          expect(multisig.signers).toEqual([
+            signers[0].publicKey,
+            $.nonAdmin.publicKey,
+          ]);
+          expect(multisig.threshold).toEqual(1);
+        });
 
-          // given all the accounts are correct
-          // given the multiplier is already synced
-          // it updates the fee bps
-          test("multiplier already synced - success", async () => {
-            // Cache the current multiplier
-            const multiplier = await $.getCurrentMultiplier();
+        // given the proposer is not a signer
+        // it reverts with a NotAuthorized error
+        test("create_proposal - not a signer - reverts", async () => {
+          await $.expectAnchorError(
+            $.createProposal($.nonAdmin, 0),
+            "NotAuthorized"
+          );
+        });
 
-            // Setup and execute the instruction
-            const newFee = new BN(randomInt(10000));
-            await $.ext.methods
-              .setFee(newFee)
-              .accountsPartial({
-                admin: $.admin.publicKey,
-              })
-              .signers([$.admin])
-              .rpc();
+        // given the proposer is a signer
+        // it stores the action and accounts, and counts as an approval
+        test("create_proposal - success", async () => {
+          await $.createProposal(signers[0], 0);
 
-            // Verify fee bps was updated
-            await $.expectExtGlobalState({
-              yieldConfig: {
-                feeBps: newFee,
-                lastExtIndex: new BN(Math.floor(multiplier * 1e12)),
-                lastMIndex: startIndex,
-              },
-            });
-          });
+          const proposal = await $.ext.account.proposal.fetch(
+            $.getProposal(new BN(0))
+          );
+          expect(proposal.index.toNumber()).toEqual(0);
+          expect(proposal.proposer).toEqual(signers[0].publicKey);
+          expect(proposal.accounts).toEqual(
+            $.getAddWrapAuthorityAccounts($.getMultisigAuthority()).map(
+              (a) => a.pubkey
+            )
+          );
+          expect(proposal.approvals).toEqual([signers[0].publicKey]);
+          expect(proposal.executed).toBe(false);
+
+          const multisig = await $.ext.account.multisig.fetch($.getMultisig());
+          expect(multisig.proposalCount.toNumber()).toEqual(1);
         });
-      }
-    });
 
-    describe("wrap_authority instruction tests", () => {
-      const mintAmount = new BN(100_000_000); // 100 with 6 decimals
-      const initialWrappedAmount = new BN(100_000_000); // 100 with 6 decimals
+        // given the signer already approved the proposal
+        // it reverts with a InvalidParam error
+        test("approve_proposal - already approved - reverts", async () => {
+          await $.createProposal(signers[0], 0);
 
-      let wrapAuthorities: PublicKey[];
-      const feeBps = new BN(randomInt(10000));
+          await $.expectAnchorError(
+            $.approveProposal(signers[0], 0),
+            "InvalidParam"
+          );
+        });
 
-      const startIndex = new BN(randomInt(initialIndex.toNumber() + 1, 2e12));
+        // given the proposal has expired
+        // it reverts with a NotActive error
+        test("approve_proposal - expired - reverts", async () => {
+          await $.createProposal(
+            signers[0],
+            0,
+            $.currentTime().add(new BN(60))
+          );
+          $.warp(new BN(61), true);
 
-      let vaultMTokenAccount: PublicKey;
+          await $.expectAnchorError(
+            $.approveProposal(signers[1], 0),
+            "NotActive"
+          );
+        });
 
-      // Setup accounts with M tokens so we can test wrapping and unwrapping
-      beforeEach(async () => {
-        wrapAuthorities = [$.admin.publicKey, $.wrapAuthority.publicKey];
-        vaultMTokenAccount = await $.getATA($.mMint.publicKey, $.getMVault());
+        // given the threshold is not met
+        // it reverts with a InsufficientApprovals error
+        test("execute_proposal - threshold not met - reverts", async () => {
+          await $.createProposal(signers[0], 0);
 
-        // Initialize the extension program
-        await $.initializeExt(wrapAuthorities, feeBps);
+          await $.expectAnchorError(
+            $.executeProposal(0),
+            "InsufficientApprovals"
+          );
+        });
 
-        // Mint M tokens to a wrap authority and a non-wrap authority
-        await $.mintM($.wrapAuthority.publicKey, mintAmount);
-        await $.mintM($.nonWrapAuthority.publicKey, mintAmount);
+        // given the accounts don't match the proposal
+        // it reverts with a InvalidAccount error
+        test("execute_proposal - wrong accounts - reverts", async () => {
+          await $.createProposal(signers[0], 0);
+          await $.approveProposal(signers[1], 0);
 
-        // Wrap some tokens from the admin to the make the m vault's balance non-zero
-        await $.wrap($.admin, initialWrappedAmount);
+          const accounts = $.getAddWrapAuthorityAccounts(
+            $.getMultisigAuthority()
+          );
+          accounts[0].pubkey = $.nonAdmin.publicKey;
 
-        // Propagate the start index
-        await $.propagateIndex(startIndex);
+          await $.expectAnchorError(
+            $.executeProposal(0, accounts),
+            "InvalidAccount"
+          );
+        });
 
-        // Claim yield for the m vault and complete the claim cycle
-        // so that the m vault is collateralized to start
-        await $.mClaimFor(
-          $.getMVault(),
-          await $.getTokenBalance(vaultMTokenAccount)
-        );
-        await $.mCompleteClaims();
+        // given the threshold is met
+        // it executes the action as the multisig authority
+        // it can't be executed again
+        test("execute_proposal - success", async () => {
+          await $.createProposal(signers[0], 0);
+          await $.approveProposal(signers[1], 0);
 
-        // Sync the scaled ui multiplier with the m index
-        if (variant !== Variant.NoYield) {
-          await $.sync();
-        }
+          await $.executeProposal(0);
 
-        // Claim excess tokens to make it easier to test collateral checks
-        try {
-          await $.claimFees();
-        } catch (e) {
-          // Ignore the error if there are no excess tokens
-        }
+          const wrapAuthority = await $.ext.account.wrapAuthority.fetch(
+            $.getWrapAuthority($.nonWrapAuthority.publicKey)
+          );
+          expect(wrapAuthority.authority).toEqual($.nonWrapAuthority.publicKey);
+          expect(wrapAuthority.enabled).toBe(true);
+
+          const proposal = await $.ext.account.proposal.fetch(
+            $.getProposal(new BN(0))
+          );
+          expect(proposal.executed).toBe(true);
+
+          $.svm.expireBlockhash();
+          await $.expectAnchorError($.executeProposal(0), "AlreadyExecuted");
+        });
       });
 
-      describe("wrap unit tests", () => {
-        let fromMTokenAccount: PublicKey;
-        let toExtTokenAccount: PublicKey;
+      describe("timelock unit tests", () => {
+        const delay = new BN(86400);
+        const gracePeriod = new BN(14 * 86400);
 
         beforeEach(async () => {
-          fromMTokenAccount = await $.getATA(
-            $.mMint.publicKey,
-            $.wrapAuthority.publicKey
+          const feeBps =
+            variant === Variant.NoYield ? new BN(0) : new BN(randomInt(10000));
+          // Initialize the extension program with the timelock enabled
+          await $.initializeExt([$.wrapAuthority.publicKey], feeBps, delay);
+
+          // Fund the rent payments of the timelock authority
+          $.svm.airdrop($.getTimelockAuthority(), BigInt(LAMPORTS_PER_SOL));
+        });
+
+        // test cases
+        // add_wrap_authority
+        // [X] given the timelock is enabled and the wrap authority manager signs directly
+        //   [X] it reverts with a NotAuthorized error
+        // queue_action
+        // [X] given the signer does not hold the role for the action
+        //   [X] it reverts with a NotAuthorized error
+        // [X] given the signer holds the role for the action
+        //   [X] it stores the action with an eta of now + delay
+        // execute_queued_action
+        // [X] given the eta has not passed
+        //   [X] it reverts with a Active error
+        // [X] given the grace period after the eta has passed
+        //   [X] it reverts with a QueuedActionExpired error
+        // [X] given the eta has passed
+        //   [X] it executes the action as the timelock authority
+        //   [X] it closes the queued action
+        // cancel_queued_action
+        // [X] given the admin signs the transaction
+        //   [X] it closes the queued action
+        // set_timelock_delay
+        // [X] given the new delay is lower than the current one
+        //   [X] it reverts with a InvalidParam error
+        // [X] given the new delay is higher than the current one
+        //   [X] it updates the delay
+
+        // given the timelock is enabled and the wrap authority manager signs directly
+        // it reverts with a NotAuthorized error
+        test("add_wrap_authority - timelock enabled - reverts", async () => {
+          await $.expectAnchorError(
+            $.ext.methods
+              .addWrapAuthority($.nonWrapAuthority.publicKey)
+              .accounts({
+                ...$.getExtPdas(),
+                wrapAuthorityManager: $.wrapAuthorityManager.publicKey,
+                wrapAuthorityAccount: $.getWrapAuthority(
+                  $.nonWrapAuthority.publicKey
+                ),
+              })
+              .signers([$.wrapAuthorityManager])
+              .rpc(),
+            "NotAuthorized"
           );
-          toExtTokenAccount = await $.getATA(
-            $.extMint.publicKey,
-            $.wrapAuthority.publicKey
+        });
+
+        // given the signer does not hold the role for the action
+        // it reverts with a NotAuthorized error
+        test("queue_action - missing role - reverts", async () => {
+          await $.expectAnchorError(
+            $.queueAction($.nonAdmin, 0),
+            "NotAuthorized"
           );
         });
 
-        describe("index same as start", () => {
-          // test cases
-          // [X] given the m mint account does not match the one stored in the global account
-          //   [X] it reverts with an InvalidAccount error
-          // [X] given the ext mint account does not match the one stored in the global account
-          //   [X] it reverts with an InvalidAccount error
-          // [X] given the signer is not the authority on the from m token account and is not delegated by the owner
-          //   [X] it reverts with a ConstraintTokenOwner error
-          // [X] given the vault M token account is not the M Vaults ATA for the M token mint
-          //   [X] it reverts with a ConstraintAssociated error
-          // [X] given the from m token account is for the wrong mint
-          //   [X] it reverts with a ConstraintTokenMint error
-          // [X] given the to ext token account is for the wrong mint
-          //   [X] it reverts with a ConstraintTokenMint error
-          // [X] given a wrap authority is not provided
-          //   [X] given the token authority is not in the wrap authorities list
-          //     [X] it reverts with a NotAuthorized error
-          //   [X] given the token authority is on the wrap authorities list
-          //     [X] given the user does not have enough M tokens
-          //       [X] it reverts with a ? error
-          //     [X] given the user has enough M tokens
-          //       [X] given the token authority is not the owner of the from M token account, but is delegated
-          //         [X] it transfers the amount of M tokens from the user's M token account to the M vault token account
-          //       [X] given the token authority is the owner of the from M token account
-          //         [X] it transfers the amount of M tokens from the user's M token account to the M vault token account
-          //       [X] it mints the amount of ext tokens to the user's ext token account
-          //       [X] given the user wraps and then unwraps (roundtrip)
-          //         [X] the starting balance and ending balance of the user's M token account are the same (within rounding error)
-          // [X] given a wrap authority is provided
-          //   [X] given the wrap authority is not in the wrap authorities list
-          //     [X] it reverts with a NotAuthorized error
-          //   [X] given the wrap authority is in the wrap authorities list
-          //     [X] given the user does not have enough M tokens
-          //       [X] it reverts with a ? error
-          //     [X] given the user has enough M tokens
-          //       [X] given the token authority is not the owner of the from M token account, but is delegated
-          //         [X] it transfers the amount of M tokens from the user's M token account to the M vault token account
-          //       [X] given the token authority is the owner of the from M token account
-          //         [X] it transfers the amount of M tokens from the user's M token account to the M vault token account
-          //       [X] it mints the amount of ext tokens to the user's ext token account
-          //       [X] given the user wraps and then unwraps (roundtrip)
-          //         [X] the starting balance and ending balance of the user's M token account are the same (within rounding error)
+        // given the signer holds the role for the action
+        // it stores the action with an eta of now + delay
+        test("queue_action - success", async () => {
+          await $.queueAction($.wrapAuthorityManager, 0);
 
-          // given the m mint account does not match the one stored in the global account
-          // it reverts with an InvalidAccount error
-          test("M mint account does not match global account - reverts", async () => {
-            const wrongMint = Keypair.generate();
-            await $.createMint(wrongMint, $.wrapAuthority.publicKey, true, 6);
+          const queuedAction = await $.ext.account.queuedAction.fetch(
+            $.getQueuedAction(new BN(0))
+          );
+          expect(queuedAction.index.toNumber()).toEqual(0);
+          expect(queuedAction.queuedBy).toEqual(
+            $.wrapAuthorityManager.publicKey
+          );
+          expect(queuedAction.eta.toString()).toEqual(
+            $.currentTime().add(delay).toString()
+          );
 
-            fromMTokenAccount = await $.getATA(
-              wrongMint.publicKey,
-              $.wrapAuthority.publicKey
-            );
-            vaultMTokenAccount = await $.getATA(
-              wrongMint.publicKey,
-              $.getMVault()
-            );
+          const state = await $.ext.account.extGlobal.fetch(
+            $.getExtGlobalAccount()
+          );
+          expect(state.queuedActionCount.toNumber()).toEqual(1);
+        });
 
-            // Attempt to send the transaction
-            // Expect an invalid account error
-            await $.expectAnchorError(
-              $.ext.methods
-                .wrap(mintAmount)
-                .accountsPartial({
-                  tokenAuthority: $.wrapAuthority.publicKey,
-                  wrapAuthority: $.ext.programId,
-                  mMint: wrongMint.publicKey,
-                  fromMTokenAccount,
-                  toExtTokenAccount,
-                  vaultMTokenAccount,
-                })
-                .signers([$.wrapAuthority])
-                .rpc(),
-              "InvalidAccount"
-            );
-          });
+        // given the eta has not passed
+        // it reverts with a Active error
+        test("execute_queued_action - before eta - reverts", async () => {
+          await $.queueAction($.wrapAuthorityManager, 0);
 
-          // given the ext mint account does not match the one stored in the global account
-          // it reverts with an InvalidAccount error
-          test("Ext mint account does not match global account - reverts", async () => {
-            const wrongMint = Keypair.generate();
-            await $.createMint(wrongMint, $.wrapAuthority.publicKey, true, 6);
+          await $.expectAnchorError(
+            $.executeQueuedAction(0, $.wrapAuthorityManager.publicKey),
+            "Active"
+          );
+        });
 
-            toExtTokenAccount = await $.getATA(
-              wrongMint.publicKey,
-              $.wrapAuthority.publicKey
-            );
+        // given the grace period after the eta has passed
+        // it reverts with a QueuedActionExpired error
+        test("execute_queued_action - after grace period - reverts", async () => {
+          await $.queueAction($.wrapAuthorityManager, 0);
+          $.warp(delay.add(gracePeriod).addn(1), true);
 
-            // Attempt to send the transaction
-            // Expect an invalid account error
-            await $.expectAnchorError(
-              $.ext.methods
-                .wrap(mintAmount)
-                .accountsPartial({
-                  tokenAuthority: $.wrapAuthority.publicKey,
-                  wrapAuthority: $.ext.programId,
-                  extMint: wrongMint.publicKey,
+          await $.expectAnchorError(
+            $.executeQueuedAction(0, $.wrapAuthorityManager.publicKey),
+            "QueuedActionExpired"
+          );
+        });
+
+        // given the eta has passed
+        // it executes the action as the timelock authority
+        // it closes the queued action
+        test("execute_queued_action - success", async () => {
+          await $.queueAction($.wrapAuthorityManager, 0);
+          $.warp(delay, true);
+
+          await $.executeQueuedAction(0, $.wrapAuthorityManager.publicKey);
+
+          const wrapAuthority = await $.ext.account.wrapAuthority.fetch(
+            $.getWrapAuthority($.nonWrapAuthority.publicKey)
+          );
+          expect(wrapAuthority.authority).toEqual($.nonWrapAuthority.publicKey);
+          expect(wrapAuthority.enabled).toBe(true);
+
+          const queuedAction = $.svm.getAccount($.getQueuedAction(new BN(0)));
+          expect(queuedAction?.lamports ?? 0).toEqual(0);
+        });
+
+        // given the admin signs the transaction
+        // it closes the queued action
+        test("cancel_queued_action - success", async () => {
+          await $.queueAction($.wrapAuthorityManager, 0);
+
+          await $.ext.methods
+            .cancelQueuedAction()
+            .accounts({
+              ...$.getExtPdas(),
+              admin: $.admin.publicKey,
+              queuedAction: $.getQueuedAction(new BN(0)),
+              queuedBy: $.wrapAuthorityManager.publicKey,
+            })
+            .signers([$.admin])
+            .rpc();
+
+          const queuedAction = $.svm.getAccount($.getQueuedAction(new BN(0)));
+          expect(queuedAction?.lamports ?? 0).toEqual(0);
+        });
+
+        // given the new delay is lower than the current one
+        // it reverts with a InvalidParam error
+        test("set_timelock_delay - decrease - reverts", async () => {
+          await $.expectAnchorError(
+            $.ext.methods
+              .setTimelockDelay(new BN(3600))
+              .accounts({
+                ...$.getExtPdas(),
+                admin: $.admin.publicKey,
+              })
+              .signers([$.admin])
+              .rpc(),
+            "InvalidParam"
+          );
+        });
+
+        // given the new delay is higher than the current one
+        // it updates the delay
+        test("set_timelock_delay - success", async () => {
+          await $.ext.methods
+            .setTimelockDelay(delay.muln(2))
+            .accounts({
+              ...$.getExtPdas(),
+              admin: $.admin.publicKey,
+            })
+            .signers([$.admin])
+            .rpc();
+
+          const state = await $.ext.account.extGlobal.fetch(
+            $.getExtGlobalAccount()
+          );
+          expect(state.timelockDelay.toString()).toEqual(
+            delay.muln(2).toString()
+          );
+        });
+      });
+
+      describe("claim_fees unit tests", () => {
+        // general test cases
+        // [X] given the fee claimer does not sign the transaction
+        //   [X] it reverts with a NotAuthorized error
+        // [X] given the fee claimer signs the transaction
+        //   [X] given the m vault is not the m vault PDA
+        //     [X] it reverts with a ConstraintSeeds error
+        //   [X] given the m vault token account is not the m vault PDA's ATA
+        //     [X] it reverts with a ConstraintAssociated error
+        //   [X] given the ext mint does not match the one on the global account
+        //     [X] it reverts with an InvalidMint error
+        //   [X] given the ext mint authority is not the ext mint authority PDA
+        //     [X] it reverts with a ConstraintSeeds error
+        //   [X] given the m earn global account does not match the derived PDA
+        //     [X] it reverts with a ConstraintSeeds error
+        //   [X] given the recipient token account is not a token account for the m mint
+        //     [X] it reverts with a ConstraintTokenMint error
+
+        const initialWrappedAmount = new BN(10_000_000); // 10 with 6 decimals
+        let wrapAuthorities: PublicKey[];
+        const feeBps = new BN(randomInt(1, 10000)); // non-zero
+        const startIndex = new BN(randomInt(initialIndex.toNumber() + 1, 2e12));
+
+        beforeEach(async () => {
+          wrapAuthorities = [$.admin.publicKey, $.wrapAuthority.publicKey];
+          // Initialize the extension program
+          await $.initializeExt(wrapAuthorities, feeBps);
+
+          // Wrap some tokens from the admin to make the m vault's balance non-zero
+          await $.wrap($.admin, initialWrappedAmount);
+
+          // Propagate the start index
+          await $.propagateIndex(startIndex);
+
+          // Claim yield for the m vault and complete the claim cycle
+          const mVault = $.getMVault();
+          const mVaultATA = await $.getATA($.mMint.publicKey, mVault);
+          await $.mClaimFor(mVault, await $.getTokenBalance(mVaultATA));
+          await $.mCompleteClaims();
+
+          if (variant !== Variant.NoYield) {
+            // Sync the multiplier
+            await $.sync();
+          }
+          // Reset the blockhash to avoid issues with duplicate transactions
+          $.svm.expireBlockhash();
+        });
+
+        // given the fee claimer does not sign the transaction
+        // it reverts with a NotAuthorized error
+        test("fee claimer does not sign - reverts", async () => {
+          const recipientExtTokenAccount = await $.getATA(
+            $.extMint.publicKey,
+            $.nonAdmin.publicKey
+          );
+
+          // Attempt to send the transaction
+          await $.expectAnchorError(
+            $.ext.methods
+              .claimFees()
+              .accountsPartial({
+                ...$.getExtPdas(),
+                feeClaimer: $.nonAdmin.publicKey,
+                recipientExtTokenAccount,
+              })
+              .signers([$.nonAdmin])
+              .rpc(),
+            "NotAuthorized"
+          );
+        });
+
+        // given the m vault is not the m vault PDA
+        // it reverts with a ConstraintSeeds error
+        test("m vault is not the m vault PDA - reverts", async () => {
+          // Change the m vault
+          const mVault = PublicKey.unique();
+          if (mVault === $.getMVault()) return;
+
+          // Create the ATA for the fake m vault so we avoid account not initialized errors
+          const mVaultATA = await $.getATA($.mMint.publicKey, mVault);
+
+          const recipientExtTokenAccount = await $.getATA(
+            $.extMint.publicKey,
+            $.admin.publicKey
+          );
+
+          // Attempt to send the transaction
+          await $.expectAnchorError(
+            $.ext.methods
+              .claimFees()
+              .accountsPartial({
+                ...$.getExtPdas(),
+                feeClaimer: $.feeClaimer.publicKey,
+                mVault,
+                vaultMTokenAccount: mVaultATA,
+                recipientExtTokenAccount,
+              })
+              .signers([$.feeClaimer])
+              .rpc(),
+            "ConstraintSeeds"
+          );
+        });
+
+        // given the m vault token account is not the m vault PDA's ATA
+        // it reverts with a ConstraintAssociated error
+        test("m vault token account is not the m vault PDA's ATA - reverts", async () => {
+          // Create a token account for the M vault that is not the ATA
+          const mVault = $.getMVault();
+          const { tokenAccount: nonAtaAccount } = await $.createTokenAccount(
+            $.mMint.publicKey,
+            mVault,
+            true,
+            true
+          );
+
+          const recipientExtTokenAccount = await $.getATA(
+            $.extMint.publicKey,
+            $.admin.publicKey
+          );
+
+          // Attempt to send the transaction
+          await $.expectAnchorError(
+            $.ext.methods
+              .claimFees()
+              .accountsPartial({
+                ...$.getExtPdas(),
+                feeClaimer: $.feeClaimer.publicKey,
+                vaultMTokenAccount: nonAtaAccount,
+                recipientExtTokenAccount,
+              })
+              .signers([$.feeClaimer])
+              .rpc(),
+            "ConstraintAssociated"
+          );
+        });
+
+        // given the ext mint does not match the one on the global account
+        // it reverts with an InvalidMint error
+        test("ext mint does not match global account - reverts", async () => {
+          // Create a new mint
+          const wrongMint = new Keypair();
+          await $.createMint(wrongMint, $.nonAdmin.publicKey, true, 6);
+
+          const recipientExtTokenAccount = await $.getATA(
+            wrongMint.publicKey,
+            $.admin.publicKey
+          );
+
+          // Attempt to send the transaction
+          await $.expectAnchorError(
+            $.ext.methods
+              .claimFees()
+              .accountsPartial({
+                ...$.getExtPdas(),
+                feeClaimer: $.feeClaimer.publicKey,
+                extMint: wrongMint.publicKey,
+                recipientExtTokenAccount,
+              })
+              .signers([$.feeClaimer])
+              .rpc(),
+            "InvalidMint"
+          );
+        });
+
+        // given the ext mint authority is not the ext mint authority PDA
+        // it reverts with a ConstraintSeeds error
+        test("ext mint authority is not the ext mint authority PDA - reverts", async () => {
+          // Change the ext mint authority
+          const extMintAuthority = PublicKey.unique();
+          if (extMintAuthority === $.getExtMintAuthority()) return;
+
+          const recipientExtTokenAccount = await $.getATA(
+            $.extMint.publicKey,
+            $.admin.publicKey
+          );
+          // Attempt to send the transaction
+          await $.expectAnchorError(
+            $.ext.methods
+              .claimFees()
+              .accountsPartial({
+                ...$.getExtPdas(),
+                feeClaimer: $.feeClaimer.publicKey,
+                extMintAuthority,
+                recipientExtTokenAccount,
+              })
+              .signers([$.feeClaimer])
+              .rpc(),
+            "ConstraintSeeds"
+          );
+        });
+
+        // given the m earner account does not match the derived one
+        // it reverts with a ConstraintSeeds / AccountNotInitialized error
+        test("m earn global account does not match derived pubkey - reverts", async () => {
+          // Change the m earn global account
+          const mEarnGlobalAccount = PublicKey.unique();
+          if (mEarnGlobalAccount.equals($.getEarnGlobalAccount())) return;
+
+          const recipientExtTokenAccount = await $.getATA(
+            $.extMint.publicKey,
+            $.admin.publicKey
+          );
+
+          // Attempt to send the transaction
+          await $.expectSystemError(
+            $.ext.methods
+              .claimFees()
+              .accountsPartial({
+                ...$.getExtPdas(),
+                feeClaimer: $.feeClaimer.publicKey,
+                mEarnGlobalAccount,
+                recipientExtTokenAccount,
+              })
+              .signers([$.feeClaimer])
+              .rpc()
+          );
+        });
+
+        // given the recipient token account is not a token account for the ext mint
+        // it reverts with a ConstraintTokenMint error
+        test("recipient token account is not for ext mint - reverts", async () => {
+          // Create a token account for the m mint
+          const wrongTokenAccount = await $.getATA(
+            $.mMint.publicKey,
+            $.admin.publicKey
+          );
+
+          // Attempt to send the transaction
+          await $.expectAnchorError(
+            $.ext.methods
+              .claimFees()
+              .accountsPartial({
+                ...$.getExtPdas(),
+                feeClaimer: $.feeClaimer.publicKey,
+                recipientExtTokenAccount: wrongTokenAccount,
+              })
+              .signers([$.feeClaimer])
+              .rpc(),
+            "ConstraintTokenMint"
+          );
+        });
+
+        // yield variant test cases
+        //   [X] given all the accounts are correct
+        //     [X] given the multiplier is not synced
+        //       [X] it syncs the multiplier to the current
+        //       [X] given the m vault has excess collateral
+        //         [X] it transfers the excess collateral to the recipient token account
+        //       [X] given the m vault does not have excess collateral
+        //         [X] it reverts with an InsufficientCollateral error
+        //     [X] given the multiplier is already synced
+        //       [X] given the m vault has excess collateral
+        //         [X] it transfers the excess collateral to the recipient token account
+        //       [X] given the m vault does not have excess collateral
+        //         [X] it completes but doesn't transfer any tokens
+
+        if (variant === Variant.ScaledUiAmount) {
+          // given all accounts are correct
+          // given the multiplier is not synced
+          // it syncs the multiplier to the current
+          // given the m vault has excess collateral
+          // it transfers the excess collateral to the recipient token account
+          test("multiplier not synced, excess collateral exists - success", async () => {
+            // warp forward in time slightly
+            $.warp(new BN(60), true);
+
+            // Propagate a new index to create a situation where multiplier needs sync
+            const newIndex = new BN(randomInt(startIndex.toNumber() + 1, 2e12));
+            await $.propagateIndex(newIndex);
+
+            // Claim yield to ensure vault has enough collateral
+            const mVault = $.getMVault();
+            const mVaultATA = await $.getATA($.mMint.publicKey, mVault);
+            await $.mClaimFor(mVault, await $.getTokenBalance(mVaultATA));
+            await $.mCompleteClaims();
+
+            // Cache balances before claim excess
+            const initialVaultBalance = await $.getTokenBalance(mVaultATA);
+            const recipientATA = await $.getATA(
+              $.extMint.publicKey,
+              $.admin.publicKey
+            );
+
+            // Get the new multiplier calculate the expected excess
+            const multiplier = await $.getNewMultiplier(newIndex);
+
+            const initialRecipientPrincipal = await $.getTokenBalance(
+              recipientATA
+            );
+            const initialRecipientBalance = await $.getTokenUiBalance(
+              recipientATA,
+              multiplier
+            );
+
+            const extSupply = await getMint(
+              $.provider.connection,
+              $.extMint.publicKey,
+              undefined,
+              TOKEN_2022_PROGRAM_ID
+            ).then((mint) => mint.supply);
+
+            const requiredCollateral = new BN(
+              Math.ceil(Number(extSupply) * multiplier)
+            );
+
+            const expectedExcess = initialVaultBalance.sub(requiredCollateral);
+            const expectedExcessPrincipal = new BN(
+              Math.floor(Number(expectedExcess) / multiplier)
+            );
+
+            // Setup and execute the instruction
+            await $.ext.methods
+              .claimFees()
+              .accountsPartial({
+                ...$.getExtPdas(),
+                feeClaimer: $.feeClaimer.publicKey,
+                recipientExtTokenAccount: recipientATA,
+              })
+              .signers([$.feeClaimer])
+              .rpc();
+
+            // Verify multiplier was updated
+
+            $.expectScaledUiAmountConfig($.extMint.publicKey, {
+              authority: $.getExtMintAuthority(),
+              multiplier,
+              newMultiplier: multiplier,
+              newMultiplierEffectiveTimestamp: BigInt(
+                $.currentTime().toString()
+              ),
+            });
+
+            // Verify excess tokens were transferred
+
+            $.expectTokenBalance(mVaultATA, initialVaultBalance);
+            $.expectTokenUiBalance(
+              recipientATA,
+              initialRecipientBalance.add(expectedExcess),
+              Comparison.LessThanOrEqual,
+              new BN(1)
+            );
+            $.expectTokenBalance(
+              recipientATA,
+              initialRecipientPrincipal.add(expectedExcessPrincipal)
+            );
+          });
+
+          // given all accounts are correct
+          // given the multiplier is already synced
+          // given the m vault has excess collateral
+          // it transfers the excess collateral to the recipient token account
+          test("multiplier already synced, excess collateral exists - success", async () => {
+            // Cache balances before claim excess
+            const mVaultATA = await $.getATA($.mMint.publicKey, $.getMVault());
+            const initialVaultBalance = await $.getTokenBalance(mVaultATA);
+            const recipientATA = await $.getATA(
+              $.extMint.publicKey,
+              $.admin.publicKey
+            );
+
+            // Get the current multiplier and calculate the $.expected excess
+            const multiplier = await $.getCurrentMultiplier();
+            const initialRecipientBalance = await $.getTokenUiBalance(
+              recipientATA,
+              multiplier
+            );
+            const initialRecipientPrincipal = await $.getTokenBalance(
+              recipientATA
+            );
+
+            const extSupply = await getMint(
+              $.provider.connection,
+              $.extMint.publicKey,
+              undefined,
+              TOKEN_2022_PROGRAM_ID
+            ).then((mint) => mint.supply);
+
+            const requiredCollateral = new BN(
+              Math.ceil(Number(extSupply) * multiplier)
+            );
+
+            const expectedExcess = initialVaultBalance.sub(requiredCollateral);
+            const expectedExcessPrincipal = new BN(
+              Math.floor(Number(expectedExcess) / multiplier)
+            );
+
+            await $.ext.methods
+              .claimFees()
+              .accountsPartial({
+                ...$.getExtPdas(),
+                feeClaimer: $.feeClaimer.publicKey,
+                recipientExtTokenAccount: recipientATA,
+              })
+              .signers([$.feeClaimer])
+              .rpc();
+
+            // Verify excess tokens were transferred
+            $.expectTokenBalance(mVaultATA, initialVaultBalance);
+            $.expectTokenUiBalance(
+              recipientATA,
+              initialRecipientBalance.add(expectedExcess),
+              Comparison.LessThanOrEqual,
+              new BN(1)
+            );
+            $.expectTokenBalance(
+              recipientATA,
+              initialRecipientPrincipal.add(expectedExcessPrincipal)
+            );
+          });
+
+          // given all accounts are correct
+          // given the multiplier is already synced
+          // given the m vault does not have excess collateral
+          // it completes successfully and does not transfer any tokens
+          test("multiplier already synced, no excess collateral - success", async () => {
+            // claim the existing excess so there isn't extra
+            await $.claimFees();
+            $.svm.expireBlockhash();
+
+            // Cache balances before claim excess
+            const mVaultATA = await $.getATA($.mMint.publicKey, $.getMVault());
+            const initialVaultBalance = await $.getTokenBalance(mVaultATA);
+            const recipientATA = await $.getATA(
+              $.extMint.publicKey,
+              $.admin.publicKey
+            );
+            const initialRecipientBalance = await $.getTokenBalance(
+              recipientATA
+            );
+
+            // Attempt to send the transaction
+            await $.ext.methods
+              .claimFees()
+              .accountsPartial({
+                ...$.getExtPdas(),
+                feeClaimer: $.feeClaimer.publicKey,
+                recipientExtTokenAccount: recipientATA,
+              })
+              .signers([$.feeClaimer])
+              .rpc();
+
+            // Verify no tokens were transferred
+            $.expectTokenBalance(mVaultATA, initialVaultBalance);
+            $.expectTokenBalance(recipientATA, initialRecipientBalance);
+          });
+        }
+
+        // no yield test cases
+        //   [X] given all the accounts are correct
+        //     [X] given the m vault has excess collateral
+        //       [X] it transfers the excess collateral to the recipient token account
+        //     [X] given the m vault does not have excess collateral
+        //       [X] it completes but doesn't transfer any tokens
+        if (variant === Variant.NoYield) {
+          // given all accounts are correct
+          // given the m vault has excess collateral
+          // it transfers the excess collateral to the recipient token account
+          test("excess collateral exists - success", async () => {
+            // Cache balances before claim excess
+            const mVaultATA = await $.getATA($.mMint.publicKey, $.getMVault());
+            const initialVaultBalance = await $.getTokenBalance(mVaultATA);
+            const recipientATA = await $.getATA(
+              $.extMint.publicKey,
+              $.admin.publicKey
+            );
+            const initialRecipientBalance = await $.getTokenBalance(
+              recipientATA
+            );
+
+            const extSupply = await getMint(
+              $.provider.connection,
+              $.extMint.publicKey,
+              undefined,
+              TOKEN_2022_PROGRAM_ID
+            ).then((mint) => mint.supply);
+
+            const expectedExcess = initialVaultBalance.sub(
+              new BN(extSupply.toString())
+            );
+
+            await $.ext.methods
+              .claimFees()
+              .accountsPartial({
+                ...$.getExtPdas(),
+                feeClaimer: $.feeClaimer.publicKey,
+                recipientExtTokenAccount: recipientATA,
+              })
+              .signers([$.feeClaimer])
+              .rpc();
+
+            // Verify excess tokens were transferred
+            $.expectTokenBalance(mVaultATA, initialVaultBalance);
+            $.expectTokenBalance(
+              recipientATA,
+              initialRecipientBalance.add(expectedExcess)
+            );
+          });
+
+          // given all accounts are correct
+          // given the m vault does not have excess collateral
+          // it completes successfully and does not transfer any tokens
+          test("no excess collateral - success", async () => {
+            // claim the existing excess so there isn't extra
+            await $.claimFees();
+            $.svm.expireBlockhash();
+
+            // Cache balances before claim excess
+            const mVaultATA = await $.getATA($.mMint.publicKey, $.getMVault());
+            const initialVaultBalance = await $.getTokenBalance(mVaultATA);
+            const recipientATA = await $.getATA(
+              $.extMint.publicKey,
+              $.admin.publicKey
+            );
+            const initialRecipientBalance = await $.getTokenBalance(
+              recipientATA
+            );
+
+            // Attempt to send the transaction
+            await $.ext.methods
+              .claimFees()
+              .accountsPartial({
+                ...$.getExtPdas(),
+                feeClaimer: $.feeClaimer.publicKey,
+                recipientExtTokenAccount: recipientATA,
+              })
+              .signers([$.feeClaimer])
+              .rpc();
+
+            // Verify no tokens were transferred
+            $.expectTokenBalance(mVaultATA, initialVaultBalance);
+            $.expectTokenBalance(recipientATA, initialRecipientBalance);
+          });
+        }
+      });
+
+      if (variant !== Variant.NoYield) {
+        describe("set_fee unit tests", () => {
+          // yield variant test cases
+          // [X] given the fee manager does not sign the transaction
+          //   [X] it reverts with a NotAuthorized error
+          // [X] given the fee manager signs the transaction
+          //   [X] given the ext mint does not match the one on the global account
+          //     [X] it reverts with an InvalidMint error
+          //   [X] given the ext mint authority is not the ext mint authority PDA
+          //     [X] it reverts with a ConstraintSeeds error
+          //   [X] given all the accounts are correct
+          //     [X] given the multiplier is not synced
+          //       [X] it syncs the multiplier to the last claimed index
+          //       [X] it updates the fee bps
+          //     [X] given the multiplier is already synced
+          //       [X] it updates the fee bps
+
+          const initialWrappedAmount = new BN(10_000_000); // 10 with 6 decimals
+          let wrapAuthorities: PublicKey[];
+          const feeBps = new BN(randomInt(1, 10000)); // non-zero
+          const startIndex = new BN(
+            randomInt(initialIndex.toNumber() + 1, 2e12)
+          );
+
+          beforeEach(async () => {
+            wrapAuthorities = [$.admin.publicKey, $.wrapAuthority.publicKey];
+            // Initialize the extension program
+            await $.initializeExt(wrapAuthorities, feeBps);
+
+            // Wrap some tokens from the admin to make the m vault's balance non-zero
+            await $.wrap($.admin, initialWrappedAmount);
+
+            // Propagate the start index
+            await $.propagateIndex(startIndex);
+
+            // Claim yield for the m vault and complete the claim cycle
+            const mVault = $.getMVault();
+            const mVaultATA = await $.getATA($.mMint.publicKey, mVault);
+            await $.mClaimFor(mVault, await $.getTokenBalance(mVaultATA));
+            await $.mCompleteClaims();
+
+            // Sync the multiplier
+            await $.sync();
+
+            // Reset the blockhash to avoid issues with duplicate transactions
+            $.svm.expireBlockhash();
+          });
+
+          // given the fee manager does not sign the transaction
+          // it reverts with a NotAuthorized error
+          test("fee manager does not sign - reverts", async () => {
+            // Attempt to send the transaction
+            await $.expectAnchorError(
+              $.ext.methods
+                .setFee(new BN(randomInt(10000)))
+                .accountsPartial({
+                  ...$.getExtPdas(),
+                  feeManager: $.nonAdmin.publicKey,
+                })
+                .signers([$.nonAdmin])
+                .rpc(),
+              "NotAuthorized"
+            );
+          });
+
+          // given the fee manager signs the transaction
+
+          // given the ext mint does not match the one on the global account
+          // it reverts with an InvalidMint error
+          test("ext mint does not match global account - reverts", async () => {
+            // Create a new mint
+            const wrongMint = new Keypair();
+            await $.createMint(wrongMint, $.nonAdmin.publicKey, true, 6);
+
+            // Attempt to send the transaction
+            await $.expectAnchorError(
+              $.ext.methods
+                .setFee(new BN(randomInt(10000)))
+                .accountsPartial({
+                  ...$.getExtPdas(),
+                  feeManager: $.feeManager.publicKey,
+                  extMint: wrongMint.publicKey,
+                })
+                .signers([$.feeManager])
+                .rpc(),
+              "InvalidMint"
+            );
+          });
+
+          // given the ext mint authority is not the ext mint authority PDA
+          // it reverts with a ConstraintSeeds error
+          test("ext mint authority is not the ext mint authority PDA - reverts", async () => {
+            // Change the ext mint authority
+            const extMintAuthority = PublicKey.unique();
+            if (extMintAuthority === $.getExtMintAuthority()) return;
+
+            // Attempt to send the transaction
+            await $.expectAnchorError(
+              $.ext.methods
+                .setFee(new BN(randomInt(10000)))
+                .accountsPartial({
+                  ...$.getExtPdas(),
+                  feeManager: $.feeManager.publicKey,
+                  extMintAuthority,
+                })
+                .signers([$.feeManager])
+                .rpc(),
+              "ConstraintSeeds"
+            );
+          });
+
+          // given all the accounts are correct
+          // given the multiplier is not synced
+          // it syncs the multiplier to the last claimed index
+          // it updates the fee bps
+          test("multiplier not synced - success", async () => {
+            // warp forward in time slightly
+            $.warp(new BN(60), true);
+
+            // Propagate a new index to create a situation where multiplier needs sync
+            const newIndex = new BN(randomInt(startIndex.toNumber() + 1, 2e12));
+            await $.propagateIndex(newIndex);
+
+            // Claim yield to ensure vault has enough collateral
+            const mVault = $.getMVault();
+            const mVaultATA = await $.getATA($.mMint.publicKey, mVault);
+            await $.mClaimFor(mVault, await $.getTokenBalance(mVaultATA));
+            await $.mCompleteClaims();
+
+            // Get the new multiplier
+            const multiplier = await $.getNewMultiplier(newIndex);
+
+            // Setup and execute the instruction
+            const newFee = new BN(randomInt(10000));
+            await $.ext.methods
+              .setFee(newFee)
+              .accountsPartial({
+                ...$.getExtPdas(),
+                feeManager: $.feeManager.publicKey,
+              })
+              .signers([$.feeManager])
+              .rpc();
+
+            // Verify multiplier was updated
+            $.expectScaledUiAmountConfig($.extMint.publicKey, {
+              authority: $.getExtMintAuthority(),
+              multiplier,
+              newMultiplier: multiplier,
+              newMultiplierEffectiveTimestamp: BigInt(
+                $.currentTime().toString()
+              ),
+            });
+
+            // Verify fee bps was updated
+            await $.expectExtGlobalState({
+              yieldConfig: {
+                feeBps: newFee,
+                lastExtIndex: new BN(Math.floor(multiplier * 1e12)),
+                lastMIndex: newIndex,
+              },
+            });
+          });
+
+          // given all the accounts are correct
+          // given the multiplier is already synced
+          // it updates the fee bps
+          test("multiplier already synced - success", async () => {
+            // Cache the current multiplier
+            const multiplier = await $.getCurrentMultiplier();
+
+            // Setup and execute the instruction
+            const newFee = new BN(randomInt(10000));
+            await $.ext.methods
+              .setFee(newFee)
+              .accountsPartial({
+                ...$.getExtPdas(),
+                feeManager: $.feeManager.publicKey,
+              })
+              .signers([$.feeManager])
+              .rpc();
+
+            // Verify fee bps was updated
+            await $.expectExtGlobalState({
+              yieldConfig: {
+                feeBps: newFee,
+                lastExtIndex: new BN(Math.floor(multiplier * 1e12)),
+                lastMIndex: startIndex,
+              },
+            });
+          });
+        });
+      }
+    });
+
+    describe("wrap_authority instruction tests", () => {
+      const mintAmount = new BN(100_000_000); // 100 with 6 decimals
+      const initialWrappedAmount = new BN(100_000_000); // 100 with 6 decimals
+
+      let wrapAuthorities: PublicKey[];
+      const feeBps = new BN(randomInt(10000));
+
+      const startIndex = new BN(randomInt(initialIndex.toNumber() + 1, 2e12));
+
+      let vaultMTokenAccount: PublicKey;
+
+      // Setup accounts with M tokens so we can test wrapping and unwrapping
+      beforeEach(async () => {
+        wrapAuthorities = [$.admin.publicKey, $.wrapAuthority.publicKey];
+        vaultMTokenAccount = await $.getATA($.mMint.publicKey, $.getMVault());
+
+        // Initialize the extension program
+        await $.initializeExt(wrapAuthorities, feeBps);
+
+        // Mint M tokens to a wrap authority and a non-wrap authority
+        await $.mintM($.wrapAuthority.publicKey, mintAmount);
+        await $.mintM($.nonWrapAuthority.publicKey, mintAmount);
+
+        // Wrap some tokens from the admin to the make the m vault's balance non-zero
+        await $.wrap($.admin, initialWrappedAmount);
+
+        // Propagate the start index
+        await $.propagateIndex(startIndex);
+
+        // Claim yield for the m vault and complete the claim cycle
+        // so that the m vault is collateralized to start
+        await $.mClaimFor(
+          $.getMVault(),
+          await $.getTokenBalance(vaultMTokenAccount)
+        );
+        await $.mCompleteClaims();
+
+        // Sync the scaled ui multiplier with the m index
+        if (variant !== Variant.NoYield) {
+          await $.sync();
+        }
+
+        // Claim excess tokens to make it easier to test collateral checks
+        try {
+          await $.claimFees();
+        } catch (e) {
+          // Ignore the error if there are no excess tokens
+        }
+      });
+
+      describe("wrap unit tests", () => {
+        let fromMTokenAccount: PublicKey;
+        let toExtTokenAccount: PublicKey;
+
+        beforeEach(async () => {
+          fromMTokenAccount = await $.getATA(
+            $.mMint.publicKey,
+            $.wrapAuthority.publicKey
+          );
+          toExtTokenAccount = await $.getATA(
+            $.extMint.publicKey,
+            $.wrapAuthority.publicKey
+          );
+        });
+
+        describe("index same as start", () => {
+          // test cases
+          // [X] given the m mint account does not match the one stored in the global account
+          //   [X] it reverts with an InvalidAccount error
+          // [X] given the ext mint account does not match the one stored in the global account
+          //   [X] it reverts with an InvalidAccount error
+          // [X] given the signer is not the authority on the from m token account and is not delegated by the owner
+          //   [X] it reverts with a ConstraintTokenOwner error
+          // [X] given the vault M token account is not the M Vaults ATA for the M token mint
+          //   [X] it reverts with a ConstraintAssociated error
+          // [X] given the from m token account is for the wrong mint
+          //   [X] it reverts with a ConstraintTokenMint error
+          // [X] given the to ext token account is for the wrong mint
+          //   [X] it reverts with a ConstraintTokenMint error
+          // [X] given a wrap authority is not provided
+          //   [X] given the token authority is not in the wrap authorities list
+          //     [X] it reverts with a NotAuthorized error
+          //   [X] given the token authority is on the wrap authorities list
+          //     [X] given the user does not have enough M tokens
+          //       [X] it reverts with a ? error
+          //     [X] given the user has enough M tokens
+          //       [X] given the token authority is not the owner of the from M token account, but is delegated
+          //         [X] it transfers the amount of M tokens from the user's M token account to the M vault token account
+          //       [X] given the token authority is the owner of the from M token account
+          //         [X] it transfers the amount of M tokens from the user's M token account to the M vault token account
+          //       [X] it mints the amount of ext tokens to the user's ext token account
+          //       [X] given the user wraps and then unwraps (roundtrip)
+          //         [X] the starting balance and ending balance of the user's M token account are the same (within rounding error)
+          // [X] given a wrap authority is provided
+          //   [X] given the wrap authority is not in the wrap authorities list
+          //     [X] it reverts with a NotAuthorized error
+          //   [X] given the wrap authority is in the wrap authorities list
+          //     [X] given the user does not have enough M tokens
+          //       [X] it reverts with a ? error
+          //     [X] given the user has enough M tokens
+          //       [X] given the token authority is not the owner of the from M token account, but is delegated
+          //         [X] it transfers the amount of M tokens from the user's M token account to the M vault token account
+          //       [X] given the token authority is the owner of the from M token account
+          //         [X] it transfers the amount of M tokens from the user's M token account to the M vault token account
+          //       [X] it mints the amount of ext tokens to the user's ext token account
+          //       [X] given the user wraps and then unwraps (roundtrip)
+          //         [X] the starting balance and ending balance of the user's M token account are the same (within rounding error)
+
+          // given the m mint account does not match the one stored in the global account
+          // it reverts with an InvalidAccount error
+          test("M mint account does not match global account - reverts", async () => {
+            const wrongMint = Keypair.generate();
+            await $.createMint(wrongMint, $.wrapAuthority.publicKey, true, 6);
+
+            fromMTokenAccount = await $.getATA(
+              wrongMint.publicKey,
+              $.wrapAuthority.publicKey
+            );
+            vaultMTokenAccount = await $.getATA(
+              wrongMint.publicKey,
+              $.getMVault()
+            );
+
+            // Attempt to send the transaction
+            // Expect an invalid account error
+            await $.expectAnchorError(
+              $.ext.methods
+                .wrap(mintAmount)
+                .accountsPartial({
+                  ...$.getExtPdas(),
+                  tokenAuthority: $.wrapAuthority.publicKey,
+                  wrapAuthority: $.ext.programId,
+                  wrapAuthorityAccount: $.getWrapAuthority(
+                    $.wrapAuthority.publicKey
+                  ),
+                  mMint: wrongMint.publicKey,
+                  fromMTokenAccount,
+                  toExtTokenAccount,
+                  toBlocklistEntry: $.getBlocklistEntryOf(toExtTokenAccount),
+                  vaultMTokenAccount,
+                })
+                .signers([$.wrapAuthority])
+                .rpc(),
+              "InvalidAccount"
+            );
+          });
+
+          // given the ext mint account does not match the one stored in the global account
+          // it reverts with an InvalidAccount error
+          test("Ext mint account does not match global account - reverts", async () => {
+            const wrongMint = Keypair.generate();
+            await $.createMint(wrongMint, $.wrapAuthority.publicKey, true, 6);
+
+            toExtTokenAccount = await $.getATA(
+              wrongMint.publicKey,
+              $.wrapAuthority.publicKey
+            );
+
+            // Attempt to send the transaction
+            // Expect an invalid account error
+            await $.expectAnchorError(
+              $.ext.methods
+                .wrap(mintAmount)
+                .accountsPartial({
+                  ...$.getExtPdas(),
+                  tokenAuthority: $.wrapAuthority.publicKey,
+                  wrapAuthority: $.ext.programId,
+                  wrapAuthorityAccount: $.getWrapAuthority(
+                    $.wrapAuthority.publicKey
+                  ),
+                  extMint: wrongMint.publicKey,
                   fromMTokenAccount,
                   toExtTokenAccount,
+                  toBlocklistEntry: $.getBlocklistEntryOf(toExtTokenAccount),
                 })
                 .signers([$.wrapAuthority])
                 .rpc(),
@@ -1554,10 +3126,15 @@ for (const variant of VARIANTS) {
               $.ext.methods
                 .wrap(mintAmount)
                 .accounts({
+                  ...$.getExtPdas(),
                   tokenAuthority: $.wrapAuthority.publicKey,
                   wrapAuthority: $.ext.programId,
+                  wrapAuthorityAccount: $.getWrapAuthority(
+                    $.wrapAuthority.publicKey
+                  ),
                   fromMTokenAccount,
                   toExtTokenAccount,
+                  toBlocklistEntry: $.getBlocklistEntryOf(toExtTokenAccount),
                 })
                 .signers([$.wrapAuthority])
                 .rpc()
@@ -1578,10 +3155,15 @@ for (const variant of VARIANTS) {
               $.ext.methods
                 .wrap(mintAmount)
                 .accountsPartial({
+                  ...$.getExtPdas(),
                   tokenAuthority: $.wrapAuthority.publicKey,
                   wrapAuthority: $.ext.programId,
+                  wrapAuthorityAccount: $.getWrapAuthority(
+                    $.wrapAuthority.publicKey
+                  ),
                   fromMTokenAccount,
                   toExtTokenAccount,
+                  toBlocklistEntry: $.getBlocklistEntryOf(toExtTokenAccount),
                   vaultMTokenAccount,
                 })
                 .signers([$.wrapAuthority])
@@ -1599,10 +3181,15 @@ for (const variant of VARIANTS) {
               $.ext.methods
                 .wrap(mintAmount)
                 .accounts({
+                  ...$.getExtPdas(),
                   tokenAuthority: $.wrapAuthority.publicKey,
                   wrapAuthority: $.ext.programId,
+                  wrapAuthorityAccount: $.getWrapAuthority(
+                    $.wrapAuthority.publicKey
+                  ),
                   fromMTokenAccount: toExtTokenAccount,
                   toExtTokenAccount,
+                  toBlocklistEntry: $.getBlocklistEntryOf(toExtTokenAccount),
                 })
                 .signers([$.wrapAuthority])
                 .rpc(),
@@ -1619,9 +3206,14 @@ for (const variant of VARIANTS) {
               $.ext.methods
                 .wrap(mintAmount)
                 .accounts({
+                  ...$.getExtPdas(),
                   tokenAuthority: $.wrapAuthority.publicKey,
                   wrapAuthority: $.ext.programId,
+                  wrapAuthorityAccount: $.getWrapAuthority(
+                    $.wrapAuthority.publicKey
+                  ),
                   toExtTokenAccount: fromMTokenAccount,
+                  toBlocklistEntry: $.getBlocklistEntryOf(fromMTokenAccount),
                   fromMTokenAccount,
                 })
                 .signers([$.wrapAuthority])
@@ -1649,10 +3241,15 @@ for (const variant of VARIANTS) {
               $.ext.methods
                 .wrap(mintAmount)
                 .accounts({
+                  ...$.getExtPdas(),
                   tokenAuthority: $.nonWrapAuthority.publicKey,
                   wrapAuthority: $.ext.programId,
+                  wrapAuthorityAccount: $.getWrapAuthority(
+                    $.wrapAuthority.publicKey
+                  ),
                   fromMTokenAccount,
                   toExtTokenAccount,
+                  toBlocklistEntry: $.getBlocklistEntryOf(toExtTokenAccount),
                 })
                 .signers([$.nonWrapAuthority])
                 .rpc(),
@@ -1675,10 +3272,15 @@ for (const variant of VARIANTS) {
               $.ext.methods
                 .wrap(wrapAmount)
                 .accounts({
+                  ...$.getExtPdas(),
                   tokenAuthority: $.wrapAuthority.publicKey,
                   wrapAuthority: $.ext.programId,
+                  wrapAuthorityAccount: $.getWrapAuthority(
+                    $.wrapAuthority.publicKey
+                  ),
                   fromMTokenAccount,
                   toExtTokenAccount,
+                  toBlocklistEntry: $.getBlocklistEntryOf(toExtTokenAccount),
                 })
                 .signers([$.wrapAuthority])
                 .rpc()
@@ -1690,10 +3292,15 @@ for (const variant of VARIANTS) {
               $.ext.methods
                 .wrap(new BN(0))
                 .accounts({
+                  ...$.getExtPdas(),
                   tokenAuthority: $.wrapAuthority.publicKey,
                   wrapAuthority: $.ext.programId,
+                  wrapAuthorityAccount: $.getWrapAuthority(
+                    $.wrapAuthority.publicKey
+                  ),
                   fromMTokenAccount,
                   toExtTokenAccount,
+                  toBlocklistEntry: $.getBlocklistEntryOf(toExtTokenAccount),
                 })
                 .signers([$.wrapAuthority])
                 .rpc(),
@@ -1740,10 +3347,15 @@ for (const variant of VARIANTS) {
             await $.ext.methods
               .wrap(wrapAmount)
               .accounts({
+                ...$.getExtPdas(),
                 tokenAuthority: $.wrapAuthority.publicKey,
                 wrapAuthority: $.ext.programId,
+                wrapAuthorityAccount: $.getWrapAuthority(
+                  $.wrapAuthority.publicKey
+                ),
                 fromMTokenAccount,
                 toExtTokenAccount,
+                toBlocklistEntry: $.getBlocklistEntryOf(toExtTokenAccount),
               })
               .signers([$.wrapAuthority])
               .rpc();
@@ -1795,10 +3407,15 @@ for (const variant of VARIANTS) {
             await $.ext.methods
               .wrap(wrapAmount)
               .accountsPartial({
+                ...$.getExtPdas(),
                 tokenAuthority: $.wrapAuthority.publicKey,
                 wrapAuthority: $.ext.programId,
+                wrapAuthorityAccount: $.getWrapAuthority(
+                  $.wrapAuthority.publicKey
+                ),
                 fromMTokenAccount,
                 toExtTokenAccount,
+                toBlocklistEntry: $.getBlocklistEntryOf(toExtTokenAccount),
               })
               .signers([$.wrapAuthority])
               .rpc();
@@ -1856,10 +3473,15 @@ for (const variant of VARIANTS) {
             await $.ext.methods
               .wrap(wrapAmount)
               .accountsPartial({
+                ...$.getExtPdas(),
                 tokenAuthority: $.wrapAuthority.publicKey,
                 wrapAuthority: $.ext.programId,
+                wrapAuthorityAccount: $.getWrapAuthority(
+                  $.wrapAuthority.publicKey
+                ),
                 fromMTokenAccount,
                 toExtTokenAccount,
+                toBlocklistEntry: $.getBlocklistEntryOf(toExtTokenAccount),
               })
               .signers([$.wrapAuthority])
               .rpc();
@@ -1923,10 +3545,15 @@ for (const variant of VARIANTS) {
               $.ext.methods
                 .wrap(mintAmount)
                 .accounts({
+                  ...$.getExtPdas(),
                   tokenAuthority: $.nonWrapAuthority.publicKey,
                   wrapAuthority: $.nonWrapAuthority.publicKey,
+                  wrapAuthorityAccount: $.getWrapAuthority(
+                    $.wrapAuthority.publicKey
+                  ),
                   fromMTokenAccount,
                   toExtTokenAccount,
+                  toBlocklistEntry: $.getBlocklistEntryOf(toExtTokenAccount),
                 })
                 .signers([$.nonWrapAuthority])
                 .rpc(),
@@ -1949,10 +3576,15 @@ for (const variant of VARIANTS) {
               $.ext.methods
                 .wrap(wrapAmount)
                 .accounts({
+                  ...$.getExtPdas(),
                   tokenAuthority: $.nonWrapAuthority.publicKey,
                   wrapAuthority: $.wrapAuthority.publicKey,
+                  wrapAuthorityAccount: $.getWrapAuthority(
+                    $.wrapAuthority.publicKey
+                  ),
                   fromMTokenAccount,
                   toExtTokenAccount,
+                  toBlocklistEntry: $.getBlocklistEntryOf(toExtTokenAccount),
                 })
                 .signers([$.nonWrapAuthority, $.wrapAuthority])
                 .rpc()
@@ -1998,10 +3630,15 @@ for (const variant of VARIANTS) {
             await $.ext.methods
               .wrap(wrapAmount)
               .accounts({
+                ...$.getExtPdas(),
                 tokenAuthority: $.nonAdmin.publicKey,
                 wrapAuthority: $.wrapAuthority.publicKey,
+                wrapAuthorityAccount: $.getWrapAuthority(
+                  $.wrapAuthority.publicKey
+                ),
                 fromMTokenAccount,
                 toExtTokenAccount,
+                toBlocklistEntry: $.getBlocklistEntryOf(toExtTokenAccount),
               })
               .signers([$.nonAdmin, $.wrapAuthority])
               .rpc();
@@ -2063,10 +3700,15 @@ for (const variant of VARIANTS) {
             await $.ext.methods
               .wrap(wrapAmount)
               .accountsPartial({
+                ...$.getExtPdas(),
                 tokenAuthority: $.nonWrapAuthority.publicKey,
                 wrapAuthority: $.wrapAuthority.publicKey,
+                wrapAuthorityAccount: $.getWrapAuthority(
+                  $.wrapAuthority.publicKey
+                ),
                 fromMTokenAccount,
                 toExtTokenAccount,
+                toBlocklistEntry: $.getBlocklistEntryOf(toExtTokenAccount),
               })
               .signers([$.nonWrapAuthority, $.wrapAuthority])
               .rpc();
@@ -2198,10 +3840,15 @@ for (const variant of VARIANTS) {
               await $.ext.methods
                 .wrap(wrapAmount)
                 .accounts({
+                  ...$.getExtPdas(),
                   tokenAuthority: $.wrapAuthority.publicKey,
                   wrapAuthority: $.ext.programId,
+                  wrapAuthorityAccount: $.getWrapAuthority(
+                    $.wrapAuthority.publicKey
+                  ),
                   fromMTokenAccount,
                   toExtTokenAccount,
+                  toBlocklistEntry: $.getBlocklistEntryOf(toExtTokenAccount),
                 })
                 .signers([$.wrapAuthority])
                 .rpc();
@@ -2264,10 +3911,15 @@ for (const variant of VARIANTS) {
               await $.ext.methods
                 .wrap(wrapAmount)
                 .accounts({
+                  ...$.getExtPdas(),
                   tokenAuthority: $.wrapAuthority.publicKey,
                   wrapAuthority: $.ext.programId,
+                  wrapAuthorityAccount: $.getWrapAuthority(
+                    $.wrapAuthority.publicKey
+                  ),
                   fromMTokenAccount,
                   toExtTokenAccount,
+                  toBlocklistEntry: $.getBlocklistEntryOf(toExtTokenAccount),
                 })
                 .signers([$.wrapAuthority])
                 .rpc();
@@ -2330,10 +3982,15 @@ for (const variant of VARIANTS) {
               await $.ext.methods
                 .wrap(wrapAmount)
                 .accounts({
+                  ...$.getExtPdas(),
                   tokenAuthority: $.wrapAuthority.publicKey,
                   wrapAuthority: $.ext.programId,
+                  wrapAuthorityAccount: $.getWrapAuthority(
+                    $.wrapAuthority.publicKey
+                  ),
                   fromMTokenAccount,
                   toExtTokenAccount,
+                  toBlocklistEntry: $.getBlocklistEntryOf(toExtTokenAccount),
                 })
                 .signers([$.wrapAuthority])
                 .rpc();
@@ -2405,10 +4062,15 @@ for (const variant of VARIANTS) {
               await $.ext.methods
                 .wrap(wrapAmount)
                 .accounts({
+                  ...$.getExtPdas(),
                   tokenAuthority: $.wrapAuthority.publicKey,
                   wrapAuthority: $.ext.programId,
+                  wrapAuthorityAccount: $.getWrapAuthority(
+                    $.wrapAuthority.publicKey
+                  ),
                   fromMTokenAccount,
                   toExtTokenAccount,
+                  toBlocklistEntry: $.getBlocklistEntryOf(toExtTokenAccount),
                 })
                 .signers([$.wrapAuthority])
                 .rpc();
@@ -2483,10 +4145,15 @@ for (const variant of VARIANTS) {
               await $.ext.methods
                 .wrap(wrapAmount)
                 .accounts({
+                  ...$.getExtPdas(),
                   tokenAuthority: $.wrapAuthority.publicKey,
                   wrapAuthority: $.ext.programId,
+                  wrapAuthorityAccount: $.getWrapAuthority(
+                    $.wrapAuthority.publicKey
+                  ),
                   fromMTokenAccount,
                   toExtTokenAccount,
+                  toBlocklistEntry: $.getBlocklistEntryOf(toExtTokenAccount),
                 })
                 .signers([$.wrapAuthority])
                 .rpc();
@@ -2561,10 +4228,15 @@ for (const variant of VARIANTS) {
               await $.ext.methods
                 .wrap(wrapAmount)
                 .accounts({
+                  ...$.getExtPdas(),
                   tokenAuthority: $.wrapAuthority.publicKey,
                   wrapAuthority: $.ext.programId,
+                  wrapAuthorityAccount: $.getWrapAuthority(
+                    $.wrapAuthority.publicKey
+                  ),
                   fromMTokenAccount,
                   toExtTokenAccount,
+                  toBlocklistEntry: $.getBlocklistEntryOf(toExtTokenAccount),
                 })
                 .signers([$.wrapAuthority])
                 .rpc();
@@ -2671,164 +4343,732 @@ for (const variant of VARIANTS) {
             const wrongMint = Keypair.generate();
             await $.createMint(wrongMint, $.wrapAuthority.publicKey, true, 6);
 
-            // Update the M token accounts
-            toMTokenAccount = await $.getATA(
-              wrongMint.publicKey,
-              $.wrapAuthority.publicKey
+            // Update the M token accounts
+            toMTokenAccount = await $.getATA(
+              wrongMint.publicKey,
+              $.wrapAuthority.publicKey
+            );
+            vaultMTokenAccount = await $.getATA(
+              wrongMint.publicKey,
+              $.getMVault()
+            );
+
+            // Attempt to send the transaction
+            // Expect an invalid account error
+            await $.expectAnchorError(
+              $.ext.methods
+                .unwrap(wrappedAmount)
+                .accountsPartial({
+                  ...$.getExtPdas(),
+                  tokenAuthority: $.wrapAuthority.publicKey,
+                  unwrapAuthority: $.ext.programId,
+                  wrapAuthorityAccount: $.getWrapAuthority(
+                    $.wrapAuthority.publicKey
+                  ),
+                  mMint: wrongMint.publicKey,
+                  fromExtTokenAccount,
+                  fromBlocklistEntry:
+                    $.getBlocklistEntryOf(fromExtTokenAccount),
+                  toMTokenAccount,
+                  vaultMTokenAccount,
+                })
+                .signers([$.wrapAuthority])
+                .rpc(),
+              "InvalidAccount"
+            );
+          });
+
+          // given the ext mint account does not match the one stored in the global account
+          // it reverts with an InvalidAccount error
+          test("Ext mint account does not match global account - reverts", async () => {
+            const wrongMint = Keypair.generate();
+            await $.createMint(wrongMint, $.wrapAuthority.publicKey, true, 6);
+
+            // Update the ext token accounts
+            fromExtTokenAccount = await $.getATA(
+              wrongMint.publicKey,
+              $.wrapAuthority.publicKey
+            );
+
+            // Attempt to send the transaction
+            // Expect an invalid account error
+            await $.expectAnchorError(
+              $.ext.methods
+                .unwrap(wrappedAmount)
+                .accountsPartial({
+                  ...$.getExtPdas(),
+                  tokenAuthority: $.wrapAuthority.publicKey,
+                  unwrapAuthority: $.ext.programId,
+                  wrapAuthorityAccount: $.getWrapAuthority(
+                    $.wrapAuthority.publicKey
+                  ),
+                  extMint: wrongMint.publicKey,
+                  fromExtTokenAccount,
+                  fromBlocklistEntry:
+                    $.getBlocklistEntryOf(fromExtTokenAccount),
+                  toMTokenAccount,
+                })
+                .signers([$.wrapAuthority])
+                .rpc(),
+              "InvalidAccount"
+            );
+          });
+
+          // given the token authority is not the authority on the from ext token account and not delegated
+          // it reverts with a ConstraintTokenOwner error
+          test("Token authority is not the authority on the from Ext token account and not delegated - reverts", async () => {
+            // Get the ATA for another user
+            fromExtTokenAccount = await $.getATA(
+              $.extMint.publicKey,
+              $.nonWrapAuthority.publicKey
+            );
+
+            // Attempt to send the transaction
+            // Expect revert with TokenOwner error
+            await $.expectSystemError(
+              $.ext.methods
+                .unwrap(wrappedAmount)
+                .accounts({
+                  ...$.getExtPdas(),
+                  tokenAuthority: $.wrapAuthority.publicKey,
+                  unwrapAuthority: $.ext.programId,
+                  wrapAuthorityAccount: $.getWrapAuthority(
+                    $.wrapAuthority.publicKey
+                  ),
+                  fromExtTokenAccount,
+                  fromBlocklistEntry:
+                    $.getBlocklistEntryOf(fromExtTokenAccount),
+                  toMTokenAccount,
+                })
+                .signers([$.wrapAuthority])
+                .rpc()
+            );
+          });
+
+          // given the M vault token account is not the M vault PDA's ATA
+          // it reverts with a ConstraintAssociated error
+          test("M Vault Token account is the the M Vault PDA's ATA (other token account) - reverts", async () => {
+            // Create a token account for the M vault that is not the ATA
+            const mVault = $.getMVault();
+            const { tokenAccount: vaultMTokenAccount } =
+              await $.createTokenAccount(
+                $.mMint.publicKey,
+                $.getMVault(),
+                true,
+                true
+              );
+
+            // Attempt to send the transaction
+            // Expect revert with a ConstraintAssociated error
+            await $.expectAnchorError(
+              $.ext.methods
+                .unwrap(wrappedAmount)
+                .accountsPartial({
+                  ...$.getExtPdas(),
+                  tokenAuthority: $.wrapAuthority.publicKey,
+                  unwrapAuthority: $.ext.programId,
+                  wrapAuthorityAccount: $.getWrapAuthority(
+                    $.wrapAuthority.publicKey
+                  ),
+                  fromExtTokenAccount,
+                  fromBlocklistEntry:
+                    $.getBlocklistEntryOf(fromExtTokenAccount),
+                  toMTokenAccount,
+                  vaultMTokenAccount,
+                })
+                .signers([$.wrapAuthority])
+                .rpc(),
+              "ConstraintAssociated"
+            );
+          });
+
+          // given the user m token account is for the wrong mint
+          // it reverts with a ConstraintTokenMint error
+          test("To M token account is for wrong mint - reverts", async () => {
+            // Attempt to send the transaction
+            // Expect revert with a ConstraintTokenMint error
+            await $.expectAnchorError(
+              $.ext.methods
+                .unwrap(wrappedAmount)
+                .accounts({
+                  ...$.getExtPdas(),
+                  tokenAuthority: $.wrapAuthority.publicKey,
+                  unwrapAuthority: $.ext.programId,
+                  wrapAuthorityAccount: $.getWrapAuthority(
+                    $.wrapAuthority.publicKey
+                  ),
+                  toMTokenAccount: fromExtTokenAccount,
+                  fromExtTokenAccount,
+                  fromBlocklistEntry:
+                    $.getBlocklistEntryOf(fromExtTokenAccount),
+                })
+                .signers([$.wrapAuthority])
+                .rpc(),
+              "ConstraintTokenMint"
+            );
+          });
+
+          // given the user ext token account is for the wrong mint
+          // it reverts with a ConstraintTokenMint error
+          test("From Ext token account is for the wrong mint - reverts", async () => {
+            // Attempt to send the transaction
+            // Expect revert with a ConstraintTokenMint error
+            await $.expectAnchorError(
+              $.ext.methods
+                .unwrap(wrappedAmount)
+                .accounts({
+                  ...$.getExtPdas(),
+                  tokenAuthority: $.wrapAuthority.publicKey,
+                  unwrapAuthority: $.ext.programId,
+                  wrapAuthorityAccount: $.getWrapAuthority(
+                    $.wrapAuthority.publicKey
+                  ),
+                  fromExtTokenAccount: toMTokenAccount,
+                  fromBlocklistEntry: $.getBlocklistEntryOf(toMTokenAccount),
+                  toMTokenAccount,
+                })
+                .signers([$.wrapAuthority])
+                .rpc(),
+              "ConstraintTokenMint"
+            );
+          });
+          // given a wrap authority is not provided
+          // given the token authority is not in the wrap authorities list
+          // it reverts with a NotAuthorized error
+          test("Token authority is not in the wrap authorities list - reverts", async () => {
+            fromExtTokenAccount = await $.getATA(
+              $.extMint.publicKey,
+              $.nonWrapAuthority.publicKey
+            );
+            toMTokenAccount = await $.getATA(
+              $.mMint.publicKey,
+              $.nonWrapAuthority.publicKey
+            );
+            // Attempt to send the transaction
+            // Expect revert with a NotAuthorized error
+            await $.expectAnchorError(
+              $.ext.methods
+                .unwrap(wrappedAmount)
+                .accounts({
+                  ...$.getExtPdas(),
+                  tokenAuthority: $.nonWrapAuthority.publicKey,
+                  unwrapAuthority: $.ext.programId,
+                  wrapAuthorityAccount: $.getWrapAuthority(
+                    $.wrapAuthority.publicKey
+                  ),
+                  fromExtTokenAccount,
+                  fromBlocklistEntry:
+                    $.getBlocklistEntryOf(fromExtTokenAccount),
+                  toMTokenAccount,
+                })
+                .signers([$.nonWrapAuthority])
+                .rpc(),
+              "NotAuthorized"
+            );
+          });
+
+          // given a wrap authority is not provided
+          // given the token authority is in the wrap authorities list
+          // give the from token account does not have enough ext tokens
+          // it unwraps the from token account's total balance of ext tokens
+          test("Not enough ext tokens, unwraps user's total balance - success", async () => {
+            // Get the balance of the from ext token account
+            const fromExtTokenAccountBalance =
+              variant === Variant.ScaledUiAmount
+                ? await $.getTokenUiBalance(
+                    fromExtTokenAccount,
+                    await $.getCurrentMultiplier()
+                  )
+                : await $.getTokenBalance(fromExtTokenAccount);
+
+            vaultMTokenAccount = await $.getATA(
+              $.mMint.publicKey,
+              $.getMVault()
+            );
+            const vaultMTokenAccountBalance = await $.getTokenBalance(
+              vaultMTokenAccount
+            );
+            const toMTokenAccountBalance = await $.getTokenBalance(
+              toMTokenAccount
+            );
+
+            // Create a random amount to unwrap that is greater than the balance
+            const unwrapAmount = new BN(
+              randomInt(fromExtTokenAccountBalance.toNumber() + 1, 2 ** 48 - 1)
+            );
+
+            // Send the unwrap
+            await $.ext.methods
+              .unwrap(unwrapAmount)
+              .accounts({
+                ...$.getExtPdas(),
+                tokenAuthority: $.wrapAuthority.publicKey,
+                unwrapAuthority: $.ext.programId,
+                wrapAuthorityAccount: $.getWrapAuthority(
+                  $.wrapAuthority.publicKey
+                ),
+                fromExtTokenAccount,
+                fromBlocklistEntry: $.getBlocklistEntryOf(fromExtTokenAccount),
+                toMTokenAccount,
+              })
+              .signers([$.wrapAuthority])
+              .rpc();
+
+            $.expectTokenBalance(fromExtTokenAccount, new BN(0));
+            $.expectTokenBalance(
+              vaultMTokenAccount,
+              vaultMTokenAccountBalance.sub(fromExtTokenAccountBalance),
+              Comparison.GreaterThanOrEqual,
+              new BN(2)
             );
-            vaultMTokenAccount = await $.getATA(
-              wrongMint.publicKey,
-              $.getMVault()
+            $.expectTokenBalance(
+              toMTokenAccount,
+              toMTokenAccountBalance.add(fromExtTokenAccountBalance),
+              Comparison.LessThanOrEqual,
+              new BN(2)
             );
+          });
 
-            // Attempt to send the transaction
-            // Expect an invalid account error
+          test("Invalid amount - revert", async () => {
             await $.expectAnchorError(
               $.ext.methods
-                .unwrap(wrappedAmount)
-                .accountsPartial({
+                .unwrap(new BN(0))
+                .accounts({
+                  ...$.getExtPdas(),
                   tokenAuthority: $.wrapAuthority.publicKey,
                   unwrapAuthority: $.ext.programId,
-                  mMint: wrongMint.publicKey,
+                  wrapAuthorityAccount: $.getWrapAuthority(
+                    $.wrapAuthority.publicKey
+                  ),
                   fromExtTokenAccount,
+                  fromBlocklistEntry:
+                    $.getBlocklistEntryOf(fromExtTokenAccount),
                   toMTokenAccount,
-                  vaultMTokenAccount,
                 })
                 .signers([$.wrapAuthority])
                 .rpc(),
-              "InvalidAccount"
+              "InvalidAmount"
             );
           });
 
-          // given the ext mint account does not match the one stored in the global account
-          // it reverts with an InvalidAccount error
-          test("Ext mint account does not match global account - reverts", async () => {
-            const wrongMint = Keypair.generate();
-            await $.createMint(wrongMint, $.wrapAuthority.publicKey, true, 6);
+          // given a wrap authority is not provided
+          // given the token authority is in the wrap authorities list
+          // given the from token account has enough ext tokens
+          // given the token authority is not the owner of the from ext token account, but is delegated
+          // it burns the amount of ext tokens from the from's ext token account
+          // it transfers the amount of M tokens from the M vault token account to the to's M token account
+          test("Unwrap with delegated authority - success", async () => {
+            const unwrapAmount = new BN(
+              randomInt(1, wrappedAmount.toNumber() + 1)
+            );
 
-            // Update the ext token accounts
-            fromExtTokenAccount = await $.getATA(
-              wrongMint.publicKey,
-              $.wrapAuthority.publicKey
+            // Approve (delegate) the wrap authority to spend the non-wrap authority's ext tokens
+            const { sourceATA: fromExtTokenAccount } = await $.approve(
+              $.nonWrapAuthority,
+              $.wrapAuthority.publicKey,
+              $.extMint.publicKey,
+              unwrapAmount
+            );
+            toMTokenAccount = await $.getATA(
+              $.mMint.publicKey,
+              $.nonWrapAuthority.publicKey
             );
 
-            // Attempt to send the transaction
-            // Expect an invalid account error
-            await $.expectAnchorError(
-              $.ext.methods
-                .unwrap(wrappedAmount)
-                .accountsPartial({
-                  tokenAuthority: $.wrapAuthority.publicKey,
-                  unwrapAuthority: $.ext.programId,
-                  extMint: wrongMint.publicKey,
+            // Cache initial balances
+            const fromExtTokenAccountBalance =
+              variant === Variant.ScaledUiAmount
+                ? await $.getTokenUiBalance(fromExtTokenAccount)
+                : await $.getTokenBalance(fromExtTokenAccount);
+            const vaultMTokenAccountBalance = await $.getTokenBalance(
+              vaultMTokenAccount
+            );
+            const toMTokenAccountBalance = await $.getTokenBalance(
+              toMTokenAccount
+            );
+
+            // Send the instruction
+            await $.ext.methods
+              .unwrap(unwrapAmount)
+              .accounts({
+                ...$.getExtPdas(),
+                tokenAuthority: $.wrapAuthority.publicKey,
+                unwrapAuthority: $.ext.programId,
+                wrapAuthorityAccount: $.getWrapAuthority(
+                  $.wrapAuthority.publicKey
+                ),
+                fromExtTokenAccount,
+                fromBlocklistEntry: $.getBlocklistEntryOf(fromExtTokenAccount),
+                toMTokenAccount,
+              })
+              .signers([$.wrapAuthority])
+              .rpc();
+
+            // Confirm updated balances
+            variant === Variant.ScaledUiAmount
+              ? await $.expectTokenUiBalance(
                   fromExtTokenAccount,
-                  toMTokenAccount,
-                })
-                .signers([$.wrapAuthority])
-                .rpc(),
-              "InvalidAccount"
+                  fromExtTokenAccountBalance.sub(unwrapAmount),
+                  Comparison.LessThanOrEqual,
+                  new BN(2)
+                )
+              : await $.expectTokenBalance(
+                  fromExtTokenAccount,
+                  fromExtTokenAccountBalance.sub(unwrapAmount)
+                );
+            await $.expectTokenBalance(
+              vaultMTokenAccount,
+              vaultMTokenAccountBalance.sub(unwrapAmount),
+              Comparison.GreaterThanOrEqual,
+              new BN(2)
+            );
+            await $.expectTokenBalance(
+              toMTokenAccount,
+              toMTokenAccountBalance.add(unwrapAmount),
+              Comparison.LessThanOrEqual,
+              new BN(2)
             );
           });
 
-          // given the token authority is not the authority on the from ext token account and not delegated
-          // it reverts with a ConstraintTokenOwner error
-          test("Token authority is not the authority on the from Ext token account and not delegated - reverts", async () => {
-            // Get the ATA for another user
+          // given a wrap authority is not provided
+          // given the token authority is in the wrap authorities list
+          // given the from token account has enough ext tokens
+          // it transfers the amount of M tokens from the M vault token account to the user's M token account
+          // it burns the amount of ext tokens from the user's ext token account
+          test("Unwrap to wrap authority account - success", async () => {
+            // Cache initial balances
+            const fromExtTokenAccountBalance =
+              variant === Variant.ScaledUiAmount
+                ? await $.getTokenUiBalance(fromExtTokenAccount)
+                : await $.getTokenBalance(fromExtTokenAccount);
+            const vaultMTokenAccountBalance = await $.getTokenBalance(
+              vaultMTokenAccount
+            );
+            const toMTokenAccountBalance = await $.getTokenBalance(
+              toMTokenAccount
+            );
+
+            const unwrapAmount = new BN(
+              randomInt(1, wrappedAmount.toNumber() + 1)
+            );
+
+            // Send the instruction
+            await $.ext.methods
+              .unwrap(unwrapAmount)
+              .accountsPartial({
+                ...$.getExtPdas(),
+                tokenAuthority: $.wrapAuthority.publicKey,
+                unwrapAuthority: $.ext.programId,
+                wrapAuthorityAccount: $.getWrapAuthority(
+                  $.wrapAuthority.publicKey
+                ),
+                fromExtTokenAccount,
+                fromBlocklistEntry: $.getBlocklistEntryOf(fromExtTokenAccount),
+                toMTokenAccount,
+              })
+              .signers([$.wrapAuthority])
+              .rpc();
+
+            // Confirm updated balances
+            await $.expectTokenBalance(
+              toMTokenAccount,
+              toMTokenAccountBalance.add(unwrapAmount),
+              Comparison.LessThanOrEqual,
+              new BN(2)
+            );
+            await $.expectTokenBalance(
+              vaultMTokenAccount,
+              vaultMTokenAccountBalance.sub(unwrapAmount),
+              Comparison.GreaterThanOrEqual,
+              new BN(2)
+            );
+            variant === Variant.ScaledUiAmount
+              ? await $.expectTokenUiBalance(
+                  fromExtTokenAccount,
+                  fromExtTokenAccountBalance.sub(unwrapAmount),
+                  Comparison.LessThanOrEqual,
+                  new BN(2)
+                )
+              : await $.expectTokenBalance(
+                  fromExtTokenAccount,
+                  fromExtTokenAccountBalance.sub(unwrapAmount)
+                );
+          });
+
+          // given a wrap authority is not provided
+          // given the token authority is in the wrap authorities list
+          // given the from token account has enough ext tokens
+          // it transfers the amount of M tokens from the M vault token account to the to M token account
+          // it burns the amount of ext tokens from the from ext token account
+          test("Unwrap to different account - success", async () => {
+            toMTokenAccount = await $.getATA(
+              $.mMint.publicKey,
+              $.nonWrapAuthority.publicKey
+            );
+
+            // Cache initial balances
+            const fromExtTokenAccountBalance =
+              variant === Variant.ScaledUiAmount
+                ? await $.getTokenUiBalance(fromExtTokenAccount)
+                : await $.getTokenBalance(fromExtTokenAccount);
+            const vaultMTokenAccountBalance = await $.getTokenBalance(
+              vaultMTokenAccount
+            );
+            const toMTokenAccountBalance = await $.getTokenBalance(
+              toMTokenAccount
+            );
+
+            const unwrapAmount = new BN(
+              randomInt(1, wrappedAmount.toNumber() + 1)
+            );
+
+            // Send the instruction
+            await $.ext.methods
+              .unwrap(unwrapAmount)
+              .accounts({
+                ...$.getExtPdas(),
+                tokenAuthority: $.wrapAuthority.publicKey,
+                unwrapAuthority: $.ext.programId,
+                wrapAuthorityAccount: $.getWrapAuthority(
+                  $.wrapAuthority.publicKey
+                ),
+                fromExtTokenAccount,
+                fromBlocklistEntry: $.getBlocklistEntryOf(fromExtTokenAccount),
+                toMTokenAccount,
+              })
+              .signers([$.wrapAuthority])
+              .rpc();
+
+            // Confirm updated balances
+            await $.expectTokenBalance(
+              toMTokenAccount,
+              toMTokenAccountBalance.add(unwrapAmount),
+              Comparison.LessThanOrEqual,
+              new BN(2)
+            );
+            await $.expectTokenBalance(
+              vaultMTokenAccount,
+              vaultMTokenAccountBalance.sub(unwrapAmount),
+              Comparison.GreaterThanOrEqual,
+              new BN(2)
+            );
+            variant === Variant.ScaledUiAmount
+              ? await $.expectTokenUiBalance(
+                  fromExtTokenAccount,
+                  fromExtTokenAccountBalance.sub(unwrapAmount),
+                  Comparison.LessThanOrEqual,
+                  new BN(2)
+                )
+              : await $.expectTokenBalance(
+                  fromExtTokenAccount,
+                  fromExtTokenAccountBalance.sub(unwrapAmount)
+                );
+          });
+
+          // given a wrap authority is provided
+          // given the wrap authority does not sign the transaction
+          // it reverts
+          test("Wrap authority does not sign - reverts", async () => {
             fromExtTokenAccount = await $.getATA(
               $.extMint.publicKey,
               $.nonWrapAuthority.publicKey
             );
+            toMTokenAccount = await $.getATA(
+              $.mMint.publicKey,
+              $.nonWrapAuthority.publicKey
+            );
 
             // Attempt to send the transaction
-            // Expect revert with TokenOwner error
+            // Expect revert
             await $.expectSystemError(
               $.ext.methods
                 .unwrap(wrappedAmount)
                 .accounts({
-                  tokenAuthority: $.wrapAuthority.publicKey,
-                  unwrapAuthority: $.ext.programId,
+                  ...$.getExtPdas(),
+                  tokenAuthority: $.nonWrapAuthority.publicKey,
+                  unwrapAuthority: $.wrapAuthority.publicKey,
+                  wrapAuthorityAccount: $.getWrapAuthority(
+                    $.wrapAuthority.publicKey
+                  ),
                   fromExtTokenAccount,
+                  fromBlocklistEntry:
+                    $.getBlocklistEntryOf(fromExtTokenAccount),
                   toMTokenAccount,
                 })
-                .signers([$.wrapAuthority])
+                .signers([$.nonWrapAuthority])
                 .rpc()
             );
           });
 
-          // given the M vault token account is not the M vault PDA's ATA
-          // it reverts with a ConstraintAssociated error
-          test("M Vault Token account is the the M Vault PDA's ATA (other token account) - reverts", async () => {
-            // Create a token account for the M vault that is not the ATA
-            const mVault = $.getMVault();
-            const { tokenAccount: vaultMTokenAccount } =
-              await $.createTokenAccount(
-                $.mMint.publicKey,
-                $.getMVault(),
-                true,
-                true
-              );
+          // given a wrap authority is provided
+          // given the wrap authority is not on the wrap authorities list
+          // it reverts with a NotAuthorized error
+          test("Wrap authority not on wrap authorities list - reverts", async () => {
+            fromExtTokenAccount = await $.getATA(
+              $.extMint.publicKey,
+              $.nonWrapAuthority.publicKey
+            );
+            toMTokenAccount = await $.getATA(
+              $.mMint.publicKey,
+              $.nonWrapAuthority.publicKey
+            );
 
             // Attempt to send the transaction
-            // Expect revert with a ConstraintAssociated error
+            // Expect revert with a NotAuthorized error
             await $.expectAnchorError(
               $.ext.methods
                 .unwrap(wrappedAmount)
-                .accountsPartial({
-                  tokenAuthority: $.wrapAuthority.publicKey,
-                  unwrapAuthority: $.ext.programId,
+                .accounts({
+                  ...$.getExtPdas(),
+                  tokenAuthority: $.nonWrapAuthority.publicKey,
+                  unwrapAuthority: $.nonAdmin.publicKey,
+                  wrapAuthorityAccount: $.getWrapAuthority(
+                    $.wrapAuthority.publicKey
+                  ),
                   fromExtTokenAccount,
+                  fromBlocklistEntry:
+                    $.getBlocklistEntryOf(fromExtTokenAccount),
                   toMTokenAccount,
-                  vaultMTokenAccount,
                 })
-                .signers([$.wrapAuthority])
+                .signers([$.nonWrapAuthority, $.nonAdmin])
                 .rpc(),
-              "ConstraintAssociated"
+              "NotAuthorized"
             );
           });
 
-          // given the user m token account is for the wrong mint
-          // it reverts with a ConstraintTokenMint error
-          test("To M token account is for wrong mint - reverts", async () => {
-            // Attempt to send the transaction
-            // Expect revert with a ConstraintTokenMint error
-            await $.expectAnchorError(
-              $.ext.methods
-                .unwrap(wrappedAmount)
-                .accounts({
-                  tokenAuthority: $.wrapAuthority.publicKey,
-                  unwrapAuthority: $.ext.programId,
-                  toMTokenAccount: fromExtTokenAccount,
-                  fromExtTokenAccount,
-                })
-                .signers([$.wrapAuthority])
-                .rpc(),
-              "ConstraintTokenMint"
+          // given a wrap authority is provided
+          // given the wrap authority is on the wrap authorities list
+          // given the from token account does not have enough ext tokens
+          // it unwraps the from token account's total balance of ext tokens
+          test("Not enough ext tokens, unwraps user's total balance - wrap authority - success", async () => {
+            fromExtTokenAccount = await $.getATA(
+              $.extMint.publicKey,
+              $.nonWrapAuthority.publicKey
+            );
+            toMTokenAccount = await $.getATA(
+              $.mMint.publicKey,
+              $.nonWrapAuthority.publicKey
+            );
+            vaultMTokenAccount = await $.getATA(
+              $.mMint.publicKey,
+              $.getMVault()
+            );
+
+            // Get the balance of the from ext token account
+            const fromExtTokenAccountBalance =
+              variant === Variant.ScaledUiAmount
+                ? await $.getTokenUiBalance(
+                    fromExtTokenAccount,
+                    await $.getCurrentMultiplier()
+                  )
+                : await $.getTokenBalance(fromExtTokenAccount);
+            const vaultMTokenAccountBalance = await $.getTokenBalance(
+              vaultMTokenAccount
+            );
+            const toMTokenAccountBalance = await $.getTokenBalance(
+              toMTokenAccount
+            );
+
+            const unwrapAmount = new BN(
+              randomInt(fromExtTokenAccountBalance.toNumber() + 1, 2 ** 48 - 1)
+            );
+
+            // Send the unwrap
+            await $.ext.methods
+              .unwrap(unwrapAmount)
+              .accounts({
+                ...$.getExtPdas(),
+                tokenAuthority: $.nonWrapAuthority.publicKey,
+                unwrapAuthority: $.wrapAuthority.publicKey,
+                wrapAuthorityAccount: $.getWrapAuthority(
+                  $.wrapAuthority.publicKey
+                ),
+                fromExtTokenAccount,
+                fromBlocklistEntry: $.getBlocklistEntryOf(fromExtTokenAccount),
+                toMTokenAccount,
+              })
+              .signers([$.nonWrapAuthority, $.wrapAuthority])
+              .rpc();
+
+            $.expectTokenBalance(fromExtTokenAccount, new BN(0));
+            $.expectTokenBalance(
+              vaultMTokenAccount,
+              vaultMTokenAccountBalance.sub(fromExtTokenAccountBalance),
+              Comparison.GreaterThanOrEqual,
+              new BN(2)
+            );
+            $.expectTokenBalance(
+              toMTokenAccount,
+              toMTokenAccountBalance.add(fromExtTokenAccountBalance),
+              Comparison.LessThanOrEqual,
+              new BN(2)
             );
           });
 
-          // given the user ext token account is for the wrong mint
-          // it reverts with a ConstraintTokenMint error
-          test("From Ext token account is for the wrong mint - reverts", async () => {
-            // Attempt to send the transaction
-            // Expect revert with a ConstraintTokenMint error
-            await $.expectAnchorError(
-              $.ext.methods
-                .unwrap(wrappedAmount)
-                .accounts({
-                  tokenAuthority: $.wrapAuthority.publicKey,
-                  unwrapAuthority: $.ext.programId,
-                  fromExtTokenAccount: toMTokenAccount,
-                  toMTokenAccount,
-                })
-                .signers([$.wrapAuthority])
-                .rpc(),
-              "ConstraintTokenMint"
+          // given a wrap authority is provided
+          // given the wrap authority is on the wrap authorities list
+          // given the from token account has enough ext tokens
+          // given the token authority is not the owner of the from ext token account, but is delegated
+          // it burns the amount of ext tokens from the from's ext token account
+          // it transfers the amount of M tokens from the M vault token account to the to's M token account
+          test("Unwrap with delegated authority - wrap authority - success", async () => {
+            vaultMTokenAccount = await $.getATA(
+              $.mMint.publicKey,
+              $.getMVault()
             );
+            toMTokenAccount = await $.getATA(
+              $.mMint.publicKey,
+              $.nonWrapAuthority.publicKey
+            );
+
+            // Approve (delegate) the nonAdmin to spend the non-wrap authority's ext tokens
+            const { sourceATA: fromExtTokenAccount } = await $.approve(
+              $.nonWrapAuthority,
+              $.nonAdmin.publicKey,
+              $.extMint.publicKey,
+              wrappedAmount
+            );
+
+            // Cache initial balances
+            const fromExtTokenAccountBalance =
+              variant === Variant.ScaledUiAmount
+                ? await $.getTokenUiBalance(fromExtTokenAccount)
+                : await $.getTokenBalance(fromExtTokenAccount);
+            const vaultMTokenAccountBalance = await $.getTokenBalance(
+              vaultMTokenAccount
+            );
+            const toMTokenAccountBalance = await $.getTokenBalance(
+              toMTokenAccount
+            );
+
+            const unwrapAmount = new BN(
+              randomInt(1, wrappedAmount.toNumber() + 1)
+            );
+
+            // Send the instruction
+            await $.ext.methods
+              .unwrap(unwrapAmount)
+              .accounts({
+                ...$.getExtPdas(),
+                tokenAuthority: $.nonAdmin.publicKey,
+                unwrapAuthority: $.wrapAuthority.publicKey,
+                wrapAuthorityAccount: $.getWrapAuthority(
+                  $.wrapAuthority.publicKey
+                ),
+                fromExtTokenAccount,
+                fromBlocklistEntry: $.getBlocklistEntryOf(fromExtTokenAccount),
+                toMTokenAccount,
+              })
+              .signers([$.nonAdmin, $.wrapAuthority])
+              .rpc();
           });
-          // given a wrap authority is not provided
-          // given the token authority is not in the wrap authorities list
-          // it reverts with a NotAuthorized error
-          test("Token authority is not in the wrap authorities list - reverts", async () => {
+
+          // given a wrap authority is provided
+          // given the wrap authority is on the wrap authorities list
+          // given the from token account has enough ext tokens
+          // given the token authority is the owner of the from ext token account
+          // it burns the amount of ext tokens from the from's ext token account
+          // it transfers the amount of M tokens from the M vault token account to the to's M token account
+          test("Unwrap with owner authority - wrap authority - success", async () => {
             fromExtTokenAccount = await $.getATA(
               $.extMint.publicKey,
               $.nonWrapAuthority.publicKey
@@ -2837,144 +5077,318 @@ for (const variant of VARIANTS) {
               $.mMint.publicKey,
               $.nonWrapAuthority.publicKey
             );
-            // Attempt to send the transaction
-            // Expect revert with a NotAuthorized error
-            await $.expectAnchorError(
-              $.ext.methods
-                .unwrap(wrappedAmount)
-                .accounts({
-                  tokenAuthority: $.nonWrapAuthority.publicKey,
-                  unwrapAuthority: $.ext.programId,
+            vaultMTokenAccount = await $.getATA(
+              $.mMint.publicKey,
+              $.getMVault()
+            );
+
+            // Cache initial balances
+            const fromExtTokenAccountBalance =
+              variant === Variant.ScaledUiAmount
+                ? await $.getTokenUiBalance(fromExtTokenAccount)
+                : await $.getTokenBalance(fromExtTokenAccount);
+            const vaultMTokenAccountBalance = await $.getTokenBalance(
+              vaultMTokenAccount
+            );
+            const toMTokenAccountBalance = await $.getTokenBalance(
+              toMTokenAccount
+            );
+
+            const unwrapAmount = new BN(
+              randomInt(1, wrappedAmount.toNumber() + 1)
+            );
+
+            // Send the instruction
+            await $.ext.methods
+              .unwrap(unwrapAmount)
+              .accounts({
+                ...$.getExtPdas(),
+                tokenAuthority: $.nonWrapAuthority.publicKey,
+                unwrapAuthority: $.wrapAuthority.publicKey,
+                wrapAuthorityAccount: $.getWrapAuthority(
+                  $.wrapAuthority.publicKey
+                ),
+                fromExtTokenAccount,
+                fromBlocklistEntry: $.getBlocklistEntryOf(fromExtTokenAccount),
+                toMTokenAccount,
+              })
+              .signers([$.nonWrapAuthority, $.wrapAuthority])
+              .rpc();
+
+            // Confirm updated balances
+            await $.expectTokenBalance(
+              toMTokenAccount,
+              toMTokenAccountBalance.add(unwrapAmount),
+              Comparison.LessThanOrEqual,
+              new BN(2)
+            );
+            await $.expectTokenBalance(
+              vaultMTokenAccount,
+              vaultMTokenAccountBalance.sub(unwrapAmount),
+              Comparison.GreaterThanOrEqual,
+              new BN(2)
+            );
+            variant === Variant.ScaledUiAmount
+              ? await $.expectTokenUiBalance(
                   fromExtTokenAccount,
-                  toMTokenAccount,
-                })
-                .signers([$.nonWrapAuthority])
-                .rpc(),
-              "NotAuthorized"
-            );
+                  fromExtTokenAccountBalance.sub(unwrapAmount),
+                  Comparison.LessThanOrEqual,
+                  new BN(2)
+                )
+              : await $.expectTokenBalance(
+                  fromExtTokenAccount,
+                  fromExtTokenAccountBalance.sub(unwrapAmount)
+                );
           });
+        });
 
-          // given a wrap authority is not provided
-          // given the token authority is in the wrap authorities list
-          // give the from token account does not have enough ext tokens
-          // it unwraps the from token account's total balance of ext tokens
-          test("Not enough ext tokens, unwraps user's total balance - success", async () => {
-            // Get the balance of the from ext token account
-            const fromExtTokenAccountBalance =
-              variant === Variant.ScaledUiAmount
-                ? await $.getTokenUiBalance(
-                    fromExtTokenAccount,
-                    await $.getCurrentMultiplier()
-                  )
-                : await $.getTokenBalance(fromExtTokenAccount);
+        describe("index different from start", () => {
+          const newIndex = new BN(
+            randomInt(startIndex.toNumber() + 1, 2e12 + 1)
+          );
+          let newMultiplier: number = 1.0;
+          let vaultBalanceAtNewIndex: BN;
 
-            vaultMTokenAccount = await $.getATA(
-              $.mMint.publicKey,
-              $.getMVault()
+          beforeEach(async () => {
+            // Reset the blockhash to avoid issues with duplicate transactions from multiple claim cycles
+            $.svm.expireBlockhash();
+
+            // Propagate the new index
+            await $.propagateIndex(newIndex);
+
+            // Get the vault balance at the new index
+            vaultBalanceAtNewIndex = await $.getTokenBalance(
+              vaultMTokenAccount
             );
+
+            // Calculate the expected multipler after the new index push
+            if (variant === Variant.ScaledUiAmount) {
+              newMultiplier = await $.getNewMultiplier(newIndex);
+            }
+          });
+
+          // test cases
+          // [X] given there are no flows before the yield is distributed
+          //   [X] it unwraps the amount of M tokens from the M vault token account to the user's M token account
+          //   [X] it burns the correct amount of ext tokens from the user's ext token account
+          //   [X] the extension is solvent
+          // [X] given there are net inflows before the yield is distributed
+          //   [X] it unwraps the amount of M tokens from the M vault token account to the user's M token account
+          //   [X] it unwraps the correct amount of ext tokens from the user's ext token account
+          //   [X] the extension is solvent
+          // [X] given there are net outflows before the yield is distributed
+          //   [X] it unwraps the amount of M tokens from the M vault token account to the user's M token account
+          //   [X] it unwraps the correct amount of ext tokens from the user's ext token account
+          //   [X] the extension is solvent
+
+          // given yield has been minted to the m vault for the new index
+          // it unwraps the amount of M tokens from the M vault token account to the user's M token account
+          test("Unwrap with new index - no flows - success", async () => {
+            // Mint yield to the m vault for the new index
+            await $.mClaimFor($.getMVault(), vaultBalanceAtNewIndex);
+            await $.mCompleteClaims();
+
+            // Cache initial balances
             const vaultMTokenAccountBalance = await $.getTokenBalance(
               vaultMTokenAccount
             );
             const toMTokenAccountBalance = await $.getTokenBalance(
               toMTokenAccount
             );
+            const fromExtTokenAccountBalance =
+              variant === Variant.ScaledUiAmount
+                ? await $.getTokenUiBalance(fromExtTokenAccount, newMultiplier)
+                : await $.getTokenBalance(fromExtTokenAccount);
 
-            // Create a random amount to unwrap that is greater than the balance
             const unwrapAmount = new BN(
-              randomInt(fromExtTokenAccountBalance.toNumber() + 1, 2 ** 48 - 1)
+              randomInt(1, fromExtTokenAccountBalance.toNumber() + 1)
             );
 
-            // Send the unwrap
+            // Send the instruction
             await $.ext.methods
               .unwrap(unwrapAmount)
               .accounts({
+                ...$.getExtPdas(),
                 tokenAuthority: $.wrapAuthority.publicKey,
                 unwrapAuthority: $.ext.programId,
+                wrapAuthorityAccount: $.getWrapAuthority(
+                  $.wrapAuthority.publicKey
+                ),
                 fromExtTokenAccount,
+                fromBlocklistEntry: $.getBlocklistEntryOf(fromExtTokenAccount),
                 toMTokenAccount,
               })
               .signers([$.wrapAuthority])
               .rpc();
 
-            $.expectTokenBalance(fromExtTokenAccount, new BN(0));
-            $.expectTokenBalance(
-              vaultMTokenAccount,
-              vaultMTokenAccountBalance.sub(fromExtTokenAccountBalance),
-              Comparison.GreaterThanOrEqual,
-              new BN(2)
-            );
-            $.expectTokenBalance(
+            // Confirm updated balances
+            await $.expectTokenBalance(
               toMTokenAccount,
-              toMTokenAccountBalance.add(fromExtTokenAccountBalance),
+              toMTokenAccountBalance.add(unwrapAmount),
               Comparison.LessThanOrEqual,
               new BN(2)
             );
+            await $.expectTokenBalance(
+              vaultMTokenAccount,
+              vaultMTokenAccountBalance.sub(unwrapAmount),
+              Comparison.GreaterThanOrEqual,
+              new BN(2)
+            );
+            variant === Variant.ScaledUiAmount
+              ? await $.expectTokenUiBalance(
+                  fromExtTokenAccount,
+                  fromExtTokenAccountBalance.sub(unwrapAmount),
+                  Comparison.LessThanOrEqual,
+                  new BN(2)
+                )
+              : await $.expectTokenBalance(
+                  fromExtTokenAccount,
+                  fromExtTokenAccountBalance.sub(unwrapAmount)
+                );
+
+            // Confirm the extension is solvent
+            await $.expectExtSolvent();
           });
 
-          test("Invalid amount - revert", async () => {
-            await $.expectAnchorError(
-              $.ext.methods
-                .unwrap(new BN(0))
-                .accounts({
-                  tokenAuthority: $.wrapAuthority.publicKey,
-                  unwrapAuthority: $.ext.programId,
-                  fromExtTokenAccount,
-                  toMTokenAccount,
-                })
-                .signers([$.wrapAuthority])
-                .rpc(),
-              "InvalidAmount"
+          // given there are net inflows before the yield is distributed
+          // it unwraps the amount of M tokens from the M vault token account to the user's M token account
+          // it unwraps the correct amount of ext tokens from the user's ext token account
+          // the extension is solvent
+          test("Unwrap - extension has inflows - success", async () => {
+            // Mint and wrap additional tokens prior to claim
+            const inflows = new BN(
+              randomInt(100, wrappedAmount.toNumber() + 1)
             );
-          });
+            await $.mintM($.admin.publicKey, inflows);
+            await $.wrap($.admin, inflows);
+
+            // Mint yield to the m vault for the new index
+            await $.mClaimFor($.getMVault(), vaultBalanceAtNewIndex);
+            await $.mCompleteClaims();
+
+            // Cache initial balances
+            const vaultMTokenAccountBalance = await $.getTokenBalance(
+              vaultMTokenAccount
+            );
+            const toMTokenAccountBalance = await $.getTokenBalance(
+              toMTokenAccount
+            );
+            const fromExtTokenAccountBalance =
+              variant === Variant.ScaledUiAmount
+                ? await $.getTokenUiBalance(fromExtTokenAccount, newMultiplier)
+                : await $.getTokenBalance(fromExtTokenAccount);
 
-          // given a wrap authority is not provided
-          // given the token authority is in the wrap authorities list
-          // given the from token account has enough ext tokens
-          // given the token authority is not the owner of the from ext token account, but is delegated
-          // it burns the amount of ext tokens from the from's ext token account
-          // it transfers the amount of M tokens from the M vault token account to the to's M token account
-          test("Unwrap with delegated authority - success", async () => {
             const unwrapAmount = new BN(
-              randomInt(1, wrappedAmount.toNumber() + 1)
+              randomInt(1, fromExtTokenAccountBalance.toNumber() + 1)
             );
 
-            // Approve (delegate) the wrap authority to spend the non-wrap authority's ext tokens
-            const { sourceATA: fromExtTokenAccount } = await $.approve(
-              $.nonWrapAuthority,
-              $.wrapAuthority.publicKey,
-              $.extMint.publicKey,
-              unwrapAmount
+            // Send the instruction
+            await $.ext.methods
+              .unwrap(unwrapAmount)
+              .accounts({
+                ...$.getExtPdas(),
+                tokenAuthority: $.wrapAuthority.publicKey,
+                unwrapAuthority: $.ext.programId,
+                wrapAuthorityAccount: $.getWrapAuthority(
+                  $.wrapAuthority.publicKey
+                ),
+                fromExtTokenAccount,
+                fromBlocklistEntry: $.getBlocklistEntryOf(fromExtTokenAccount),
+                toMTokenAccount,
+              })
+              .signers([$.wrapAuthority])
+              .rpc();
+
+            // Confirm updated balances
+            await $.expectTokenBalance(
+              toMTokenAccount,
+              toMTokenAccountBalance.add(unwrapAmount),
+              Comparison.LessThanOrEqual,
+              new BN(2)
             );
-            toMTokenAccount = await $.getATA(
-              $.mMint.publicKey,
-              $.nonWrapAuthority.publicKey
+            await $.expectTokenBalance(
+              vaultMTokenAccount,
+              vaultMTokenAccountBalance.sub(unwrapAmount),
+              Comparison.GreaterThanOrEqual,
+              new BN(2)
+            );
+            variant === Variant.ScaledUiAmount
+              ? await $.expectTokenUiBalance(
+                  fromExtTokenAccount,
+                  fromExtTokenAccountBalance.sub(unwrapAmount),
+                  Comparison.LessThanOrEqual,
+                  new BN(2)
+                )
+              : await $.expectTokenBalance(
+                  fromExtTokenAccount,
+                  fromExtTokenAccountBalance.sub(unwrapAmount)
+                );
+
+            // Confirm the extension is solvent
+            await $.expectExtSolvent();
+          });
+
+          // given there are net outflows before the yield is distributed
+          // it unwraps the amount of M tokens from the M vault token account to the user's M token account
+          // it unwraps the correct amount of ext tokens from the user's ext token account
+          // the extension is solvent
+          test("Unwrap - extension has outflows - success", async () => {
+            // Unwrap tokens prior to claim
+            const outflows = new BN(
+              randomInt(100, initialWrappedAmount.toNumber() + 1)
             );
+            await $.unwrap($.admin, outflows);
+
+            // Mint yield to the m vault for the new index
+            await $.mClaimFor($.getMVault(), vaultBalanceAtNewIndex);
+            await $.mCompleteClaims();
 
             // Cache initial balances
-            const fromExtTokenAccountBalance =
-              variant === Variant.ScaledUiAmount
-                ? await $.getTokenUiBalance(fromExtTokenAccount)
-                : await $.getTokenBalance(fromExtTokenAccount);
             const vaultMTokenAccountBalance = await $.getTokenBalance(
               vaultMTokenAccount
             );
             const toMTokenAccountBalance = await $.getTokenBalance(
               toMTokenAccount
             );
+            const fromExtTokenAccountBalance =
+              variant === Variant.ScaledUiAmount
+                ? await $.getTokenUiBalance(fromExtTokenAccount, newMultiplier)
+                : await $.getTokenBalance(fromExtTokenAccount);
+
+            const unwrapAmount = new BN(
+              randomInt(1, fromExtTokenAccountBalance.toNumber() + 1)
+            );
 
             // Send the instruction
             await $.ext.methods
               .unwrap(unwrapAmount)
               .accounts({
+                ...$.getExtPdas(),
                 tokenAuthority: $.wrapAuthority.publicKey,
                 unwrapAuthority: $.ext.programId,
+                wrapAuthorityAccount: $.getWrapAuthority(
+                  $.wrapAuthority.publicKey
+                ),
                 fromExtTokenAccount,
+                fromBlocklistEntry: $.getBlocklistEntryOf(fromExtTokenAccount),
                 toMTokenAccount,
               })
               .signers([$.wrapAuthority])
               .rpc();
 
             // Confirm updated balances
+            await $.expectTokenBalance(
+              toMTokenAccount,
+              toMTokenAccountBalance.add(unwrapAmount),
+              Comparison.LessThanOrEqual,
+              new BN(2)
+            );
+            await $.expectTokenBalance(
+              vaultMTokenAccount,
+              vaultMTokenAccountBalance.sub(unwrapAmount),
+              Comparison.GreaterThanOrEqual,
+              new BN(2)
+            );
             variant === Variant.ScaledUiAmount
               ? await $.expectTokenUiBalance(
                   fromExtTokenAccount,
@@ -2986,643 +5400,1278 @@ for (const variant of VARIANTS) {
                   fromExtTokenAccount,
                   fromExtTokenAccountBalance.sub(unwrapAmount)
                 );
-            await $.expectTokenBalance(
-              vaultMTokenAccount,
-              vaultMTokenAccountBalance.sub(unwrapAmount),
-              Comparison.GreaterThanOrEqual,
-              new BN(2)
-            );
-            await $.expectTokenBalance(
+
+            // Confirm the extension is solvent
+            await $.expectExtSolvent();
+          });
+        });
+      });
+
+      describe("supply cap and quota unit tests", () => {
+        const amount = new BN(1_000_000); // 1 with 6 decimals
+
+        const setWrapQuota = (wrapAuthority: PublicKey, quota: BN) =>
+          $.ext.methods
+            .setWrapQuota(wrapAuthority, quota)
+            .accounts({
+              ...$.getExtPdas(),
+              admin: $.admin.publicKey,
+              wrapAuthorityAccount: $.getWrapAuthority(wrapAuthority),
+            })
+            .signers([$.admin])
+            .rpc();
+
+        const getWrapQuota = async (wrapAuthority: PublicKey) => {
+          const account = await $.ext.account.wrapAuthority.fetch(
+            $.getWrapAuthority(wrapAuthority)
+          );
+
+          return account.quota;
+        };
+
+        // test cases
+        // set_supply_cap / set_wrap_quota
+        // [X] given the admin does not sign the transaction
+        //   [X] it reverts with a NotAuthorized error
+        // [X] given the key is not a wrap authority
+        //   [X] it reverts with an AccountNotInitialized error
+        // wrap / unwrap
+        // [X] given the wrap would exceed the supply cap
+        //   [X] it reverts with a SupplyCapExceeded error
+        // [X] given the wrap would exceed the quota of the wrap authority
+        //   [X] it reverts with a QuotaExceeded error
+        // [X] given the wrap is within the quota of the wrap authority
+        //   [X] wrap adds the minted principal to the quota
+        //   [X] unwrap credits the burned principal back
+
+        // given the admin does not sign the transaction
+        // it reverts with a NotAuthorized error
+        test("set_supply_cap - admin does not sign - reverts", async () => {
+          await $.expectAnchorError(
+            $.ext.methods
+              .setSupplyCap(new BN(1))
+              .accounts({
+                ...$.getExtPdas(),
+                admin: $.nonAdmin.publicKey,
+              })
+              .signers([$.nonAdmin])
+              .rpc(),
+            "NotAuthorized"
+          );
+        });
+
+        // given the key is not a wrap authority
+        // it reverts with an AccountNotInitialized error
+        test("set_wrap_quota - not a wrap authority - reverts", async () => {
+          await $.expectAnchorError(
+            setWrapQuota($.nonWrapAuthority.publicKey, new BN(1)),
+            "AccountNotInitialized"
+          );
+        });
+
+        // given the wrap would exceed the supply cap
+        // it reverts with a SupplyCapExceeded error
+        test("wrap - exceeds supply cap - reverts", async () => {
+          // The cap applies to the raw supply of the ext mint
+          const { supply } = await getMint(
+            $.provider.connection,
+            $.extMint.publicKey,
+            undefined,
+            TOKEN_2022_PROGRAM_ID
+          );
+
+          await $.ext.methods
+            .setSupplyCap(new BN(supply.toString()))
+            .accounts({
+              ...$.getExtPdas(),
+              admin: $.admin.publicKey,
+            })
+            .signers([$.admin])
+            .rpc();
+
+          await $.expectAnchorError(
+            $.wrap($.wrapAuthority, amount),
+            "SupplyCapExceeded"
+          );
+        });
+
+        // given the wrap would exceed the quota of the wrap authority
+        // it reverts with a QuotaExceeded error
+        test("wrap - exceeds quota - reverts", async () => {
+          await setWrapQuota($.wrapAuthority.publicKey, new BN(1));
+
+          await $.expectAnchorError(
+            $.wrap($.wrapAuthority, amount),
+            "QuotaExceeded"
+          );
+        });
+
+        // given the wrap is within the quota of the wrap authority
+        // wrap adds the minted principal to the quota
+        // unwrap credits the burned principal back
+        test("wrap / unwrap - within quota - success", async () => {
+          await setWrapQuota($.wrapAuthority.publicKey, mintAmount);
+
+          const { toExtTokenAccount } = await $.wrap($.wrapAuthority, amount);
+          const principal = await $.getTokenBalance(toExtTokenAccount);
+
+          let wrapQuota = await getWrapQuota($.wrapAuthority.publicKey);
+          expect(wrapQuota.quota.toString()).toEqual(mintAmount.toString());
+          expect(wrapQuota.minted.toString()).toEqual(principal.toString());
+
+          await $.unwrap($.wrapAuthority, amount);
+
+          wrapQuota = await getWrapQuota($.wrapAuthority.publicKey);
+          expect(wrapQuota.minted.toString()).toEqual("0");
+        });
+      });
+
+      describe("wrap_with_voucher unit tests", () => {
+        const amount = new BN(1_000_000); // 1 with 6 decimals
+
+        const newVoucher = (overrides: Partial<WrapVoucher> = {}) => ({
+          extMint: $.extMint.publicKey,
+          wrapAuthority: $.wrapAuthority.publicKey,
+          user: $.nonWrapAuthority.publicKey,
+          maxAmount: amount,
+          expiresAt: $.currentTime().add(new BN(3600)),
+          nonce: new BN(randomInt(1_000_000)),
+          ...overrides,
+        });
+
+        const wrapWithVoucher = (
+          voucher: WrapVoucher,
+          signer = $.wrapAuthority
+        ) =>
+          $.wrapWithVoucher(
+            $.nonWrapAuthority,
+            amount,
+            voucher,
+            $.signVoucher(signer, voucher)
+          );
+
+        // test cases
+        // [X] given the voucher is not signed by the wrap authority
+        //   [X] it reverts with a InvalidSignature error
+        // [X] given the voucher is for another user
+        //   [X] it reverts with a NotAuthorized error
+        // [X] given the amount is above the max amount of the voucher
+        //   [X] it reverts with a InvalidAmount error
+        // [X] given the voucher has expired
+        //   [X] it reverts with a VoucherExpired error
+        // [X] given a valid voucher
+        //   [X] it wraps the amount for the user
+        //   [X] it adds the minted principal to the quota of the wrap authority
+        //   [X] the voucher can't be redeemed again
+
+        // given the voucher is not signed by the wrap authority
+        // it reverts with a InvalidSignature error
+        test("wrong signer - reverts", async () => {
+          await $.expectAnchorError(
+            wrapWithVoucher(newVoucher(), $.nonWrapAuthority),
+            "InvalidSignature"
+          );
+        });
+
+        // given the voucher is for another user
+        // it reverts with a NotAuthorized error
+        test("another user - reverts", async () => {
+          await $.expectAnchorError(
+            wrapWithVoucher(newVoucher({ user: $.nonAdmin.publicKey })),
+            "NotAuthorized"
+          );
+        });
+
+        // given the amount is above the max amount of the voucher
+        // it reverts with a InvalidAmount error
+        test("amount above max amount - reverts", async () => {
+          await $.expectAnchorError(
+            wrapWithVoucher(newVoucher({ maxAmount: amount.subn(1) })),
+            "InvalidAmount"
+          );
+        });
+
+        // given the voucher has expired
+        // it reverts with a VoucherExpired error
+        test("expired - reverts", async () => {
+          await $.expectAnchorError(
+            wrapWithVoucher(newVoucher({ expiresAt: $.currentTime() })),
+            "VoucherExpired"
+          );
+        });
+
+        // given a valid voucher
+        // it wraps the amount for the user
+        // it adds the minted principal to the quota of the wrap authority
+        // the voucher can't be redeemed again
+        test("valid voucher - success", async () => {
+          const voucher = newVoucher();
+          const toExtTokenAccount = await $.getATA(
+            $.extMint.publicKey,
+            $.nonWrapAuthority.publicKey
+          );
+          const balance = await $.getTokenBalance(toExtTokenAccount);
+
+          await wrapWithVoucher(voucher);
+
+          const principal = (await $.getTokenBalance(toExtTokenAccount)).sub(
+            balance
+          );
+          expect(principal.gtn(0)).toBe(true);
+
+          const wrapAuthority = await $.ext.account.wrapAuthority.fetch(
+            $.getWrapAuthority($.wrapAuthority.publicKey)
+          );
+          expect(wrapAuthority.quota.minted.toString()).toEqual(
+            principal.toString()
+          );
+
+          const voucherNonce = await $.ext.account.voucherNonce.fetch(
+            $.getVoucherNonce($.wrapAuthority.publicKey, voucher.nonce)
+          );
+          expect(voucherNonce.wrapAuthority).toEqual($.wrapAuthority.publicKey);
+          expect(voucherNonce.nonce.toString()).toEqual(
+            voucher.nonce.toString()
+          );
+
+          // The nonce account already exists
+          $.svm.expireBlockhash();
+          await $.expectSystemError(wrapWithVoucher(voucher));
+        });
+      });
+
+      describe("set_open_mode unit tests", () => {
+        const amount = new BN(1_000_000); // 1 with 6 decimals
+
+        const setOpenMode = (
+          signer: Keypair,
+          openWrap: boolean,
+          openUnwrap: boolean
+        ) =>
+          $.ext.methods
+            .setOpenMode(openWrap, openUnwrap)
+            .accounts({
+              ...$.getExtPdas(),
+              admin: signer.publicKey,
+            })
+            .signers([signer])
+            .rpc();
+
+        // Wrap and unwrap without the account of a wrap authority
+        const openWrap = async (user: Keypair) => {
+          const { fromMTokenAccount, toExtTokenAccount } = await $.prepWrap(
+            user.publicKey
+          );
+
+          return $.ext.methods
+            .wrap(amount)
+            .accounts({
+              ...$.getExtPdas(),
+              tokenAuthority: user.publicKey,
+              wrapAuthority: null,
+              wrapAuthorityAccount: null,
+              fromMTokenAccount,
+              toExtTokenAccount,
+              toBlocklistEntry: $.getBlocklistEntryOf(toExtTokenAccount),
+            })
+            .signers([user])
+            .rpc();
+        };
+
+        const openUnwrap = async (user: Keypair) => {
+          const { toMTokenAccount, fromExtTokenAccount } = await $.prepUnwrap(
+            user.publicKey
+          );
+
+          return $.ext.methods
+            .unwrap(amount)
+            .accounts({
+              ...$.getExtPdas(),
+              tokenAuthority: user.publicKey,
+              unwrapAuthority: null,
+              wrapAuthorityAccount: null,
               toMTokenAccount,
-              toMTokenAccountBalance.add(unwrapAmount),
-              Comparison.LessThanOrEqual,
-              new BN(2)
-            );
+              fromExtTokenAccount,
+              fromBlocklistEntry: $.getBlocklistEntryOf(fromExtTokenAccount),
+            })
+            .signers([user])
+            .rpc();
+        };
+
+        // test cases
+        // [X] given the admin does not sign the transaction
+        //   [X] it reverts with a NotAuthorized error
+        // [X] given wrap and unwrap are not open
+        //   [X] wrap without a wrap authority reverts with a NotAuthorized error
+        // [X] given the admin signs the transaction
+        //   [X] it sets the open mode of each direction
+        //   [X] anyone can wrap when wrap is open
+        //   [X] unwrap still requires a wrap authority when only wrap is open
+        //   [X] anyone can unwrap when unwrap is open
+        // [X] given the timelock is enabled
+        //   [X] opening wrap reverts with a NotAuthorized error
+        //   [X] unwrap can still be opened
+        //   [X] opening wrap can be queued and executed after the delay
+
+        // given the admin does not sign the transaction
+        // it reverts with a NotAuthorized error
+        test("admin does not sign - reverts", async () => {
+          await $.expectAnchorError(
+            setOpenMode($.nonAdmin, true, true),
+            "NotAuthorized"
+          );
+        });
+
+        // given wrap and unwrap are not open
+        // wrap without a wrap authority reverts with a NotAuthorized error
+        test("not open - reverts", async () => {
+          await $.expectAnchorError(
+            openWrap($.nonWrapAuthority),
+            "NotAuthorized"
+          );
+        });
+
+        // given the admin signs the transaction
+        // it sets the open mode of each direction
+        // anyone can wrap when wrap is open
+        // unwrap still requires a wrap authority when only wrap is open
+        // anyone can unwrap when unwrap is open
+        test("open wrap and unwrap - success", async () => {
+          await setOpenMode($.admin, true, false);
+
+          let state = await $.ext.account.extGlobal.fetch(
+            $.getExtGlobalAccount()
+          );
+          expect(state.openWrap).toBe(true);
+          expect(state.openUnwrap).toBe(false);
+
+          const toExtTokenAccount = await $.getATA(
+            $.extMint.publicKey,
+            $.nonWrapAuthority.publicKey
+          );
+          const balance = await $.getTokenBalance(toExtTokenAccount);
+
+          await openWrap($.nonWrapAuthority);
+
+          const wrapped = await $.getTokenBalance(toExtTokenAccount);
+          expect(wrapped.gt(balance)).toBe(true);
+
+          await $.expectAnchorError(
+            openUnwrap($.nonWrapAuthority),
+            "NotAuthorized"
+          );
+
+          await setOpenMode($.admin, true, true);
+
+          state = await $.ext.account.extGlobal.fetch($.getExtGlobalAccount());
+          expect(state.openUnwrap).toBe(true);
+
+          $.svm.expireBlockhash();
+          await openUnwrap($.nonWrapAuthority);
+
+          const unwrapped = await $.getTokenBalance(toExtTokenAccount);
+          expect(unwrapped.lt(wrapped)).toBe(true);
+        });
+
+        // given the timelock is enabled
+        // opening wrap reverts with a NotAuthorized error
+        // unwrap can still be opened
+        test("open wrap - timelock enabled - reverts", async () => {
+          await $.setTimelockDelay(new BN(86400));
+
+          await $.expectAnchorError(
+            setOpenMode($.admin, true, false),
+            "NotAuthorized"
+          );
+
+          await setOpenMode($.admin, false, true);
+
+          const state = await $.ext.account.extGlobal.fetch(
+            $.getExtGlobalAccount()
+          );
+          expect(state.openWrap).toBe(false);
+          expect(state.openUnwrap).toBe(true);
+        });
+
+        // given the timelock is enabled
+        // opening wrap can be queued and executed after the delay
+        test("open wrap - timelock enabled - queued", async () => {
+          const delay = new BN(86400);
+          await $.setTimelockDelay(delay);
+
+          await $.queueAction($.admin, 0, {
+            setOpenMode: { openWrap: true, openUnwrap: false },
           });
+          $.warp(delay, true);
 
-          // given a wrap authority is not provided
-          // given the token authority is in the wrap authorities list
-          // given the from token account has enough ext tokens
-          // it transfers the amount of M tokens from the M vault token account to the user's M token account
-          // it burns the amount of ext tokens from the user's ext token account
-          test("Unwrap to wrap authority account - success", async () => {
-            // Cache initial balances
-            const fromExtTokenAccountBalance =
-              variant === Variant.ScaledUiAmount
-                ? await $.getTokenUiBalance(fromExtTokenAccount)
-                : await $.getTokenBalance(fromExtTokenAccount);
-            const vaultMTokenAccountBalance = await $.getTokenBalance(
-              vaultMTokenAccount
-            );
-            const toMTokenAccountBalance = await $.getTokenBalance(
-              toMTokenAccount
-            );
+          await $.executeQueuedAction(
+            0,
+            $.admin.publicKey,
+            await $.getRemainingAccounts(
+              $.ext.methods
+                .setOpenMode(true, false)
+                .accounts({
+                  ...$.getExtPdas(),
+                  admin: $.getTimelockAuthority(),
+                })
+                .instruction()
+            )
+          );
+
+          const state = await $.ext.account.extGlobal.fetch(
+            $.getExtGlobalAccount()
+          );
+          expect(state.openWrap).toBe(true);
+          expect(state.openUnwrap).toBe(false);
+
+          await openWrap($.nonWrapAuthority);
+        });
+      });
+
+      describe("wind down unit tests", () => {
+        const amount = new BN(1_000_000); // 1 with 6 decimals
+
+        // Unwrap without the account of a wrap authority
+        const windDownUnwrap = async (user: Keypair, unwrapAmount: BN) => {
+          const { toMTokenAccount, fromExtTokenAccount } = await $.prepUnwrap(
+            user.publicKey
+          );
+
+          return $.ext.methods
+            .unwrap(unwrapAmount)
+            .accounts({
+              ...$.getExtPdas(),
+              tokenAuthority: user.publicKey,
+              unwrapAuthority: null,
+              wrapAuthorityAccount: null,
+              toMTokenAccount,
+              fromExtTokenAccount,
+              fromBlocklistEntry: $.getBlocklistEntryOf(fromExtTokenAccount),
+            })
+            .signers([user])
+            .rpc();
+        };
+
+        // test cases
+        // [X] given the admin does not sign the transaction
+        //   [X] it reverts with a NotAuthorized error
+        // [X] given the extension is already winding down
+        //   [X] it reverts with a WindingDown error
+        // [X] given the extension is winding down
+        //   [X] it sets the winding down flag
+        //   [X] wrap reverts with a WindingDown error
+        //   [X] anyone can unwrap without a wrap authority
+        // [X] given the extension is not winding down
+        //   [X] decommission reverts with a NotWindingDown error
+        // [X] given ext tokens are still outstanding
+        //   [X] decommission reverts with an OutstandingSupply error
+        // [X] given all ext tokens have been unwrapped
+        //   [X] it sweeps the residual M to the admin
+        //   [X] it closes the vault token account and the global account
+        //   [X] it creates the tombstone account
+        //   [X] the extension can't be initialized again
+
+        // given the admin does not sign the transaction
+        // it reverts with a NotAuthorized error
+        test("admin does not sign - reverts", async () => {
+          await $.expectAnchorError(
+            $.beginWindDown($.nonAdmin),
+            "NotAuthorized"
+          );
+        });
+
+        // given the extension is already winding down
+        // it reverts with a WindingDown error
+        test("already winding down - reverts", async () => {
+          await $.beginWindDown();
+
+          $.svm.expireBlockhash();
+          await $.expectAnchorError($.beginWindDown(), "WindingDown");
+        });
+
+        // given the extension is winding down
+        // it sets the winding down flag
+        // wrap reverts with a WindingDown error
+        // anyone can unwrap without a wrap authority
+        test("begin wind down - success", async () => {
+          await $.beginWindDown();
+
+          const state = await $.ext.account.extGlobal.fetch(
+            $.getExtGlobalAccount()
+          );
+          expect(state.windingDown).toBe(true);
+
+          await $.expectAnchorError(
+            $.wrap($.wrapAuthority, amount),
+            "WindingDown"
+          );
+
+          const fromExtTokenAccount = await $.getATA(
+            $.extMint.publicKey,
+            $.admin.publicKey
+          );
+          const balance = await $.getTokenBalance(fromExtTokenAccount);
+
+          await windDownUnwrap($.admin, amount);
+
+          const unwrapped = await $.getTokenBalance(fromExtTokenAccount);
+          expect(unwrapped.lt(balance)).toBe(true);
+        });
 
-            const unwrapAmount = new BN(
-              randomInt(1, wrappedAmount.toNumber() + 1)
-            );
+        // given the extension is not winding down
+        // decommission reverts with a NotWindingDown error
+        test("not winding down - reverts", async () => {
+          await $.expectAnchorError($.decommission(), "NotWindingDown");
+        });
 
-            // Send the instruction
-            await $.ext.methods
-              .unwrap(unwrapAmount)
-              .accountsPartial({
-                tokenAuthority: $.wrapAuthority.publicKey,
-                unwrapAuthority: $.ext.programId,
-                fromExtTokenAccount,
-                toMTokenAccount,
-              })
-              .signers([$.wrapAuthority])
-              .rpc();
+        // given ext tokens are still outstanding
+        // decommission reverts with an OutstandingSupply error
+        test("outstanding supply - reverts", async () => {
+          await $.beginWindDown();
 
-            // Confirm updated balances
-            await $.expectTokenBalance(
-              toMTokenAccount,
-              toMTokenAccountBalance.add(unwrapAmount),
-              Comparison.LessThanOrEqual,
-              new BN(2)
-            );
-            await $.expectTokenBalance(
-              vaultMTokenAccount,
-              vaultMTokenAccountBalance.sub(unwrapAmount),
-              Comparison.GreaterThanOrEqual,
-              new BN(2)
-            );
-            variant === Variant.ScaledUiAmount
-              ? await $.expectTokenUiBalance(
-                  fromExtTokenAccount,
-                  fromExtTokenAccountBalance.sub(unwrapAmount),
-                  Comparison.LessThanOrEqual,
-                  new BN(2)
-                )
-              : await $.expectTokenBalance(
-                  fromExtTokenAccount,
-                  fromExtTokenAccountBalance.sub(unwrapAmount)
-                );
-          });
+          await $.expectAnchorError($.decommission(), "OutstandingSupply");
+        });
 
-          // given a wrap authority is not provided
-          // given the token authority is in the wrap authorities list
-          // given the from token account has enough ext tokens
-          // it transfers the amount of M tokens from the M vault token account to the to M token account
-          // it burns the amount of ext tokens from the from ext token account
-          test("Unwrap to different account - success", async () => {
-            toMTokenAccount = await $.getATA(
-              $.mMint.publicKey,
-              $.nonWrapAuthority.publicKey
-            );
+        // given all ext tokens have been unwrapped
+        // it sweeps the residual M to the admin
+        // it closes the vault token account and the global account
+        // it creates the tombstone account
+        // the extension can't be initialized again
+        test("decommission - success", async () => {
+          await $.beginWindDown();
+
+          // The admin holds all the ext tokens, unwrapping the whole
+          // vault balance burns all of them
+          await windDownUnwrap(
+            $.admin,
+            await $.getTokenBalance(vaultMTokenAccount)
+          );
+          const supply = await $.getTokenSupply($.extMint.publicKey);
+          expect(supply.isZero()).toBe(true);
 
-            // Cache initial balances
-            const fromExtTokenAccountBalance =
-              variant === Variant.ScaledUiAmount
-                ? await $.getTokenUiBalance(fromExtTokenAccount)
-                : await $.getTokenBalance(fromExtTokenAccount);
-            const vaultMTokenAccountBalance = await $.getTokenBalance(
-              vaultMTokenAccount
-            );
-            const toMTokenAccountBalance = await $.getTokenBalance(
-              toMTokenAccount
-            );
+          const adminMTokenAccount = await $.getATA(
+            $.mMint.publicKey,
+            $.admin.publicKey
+          );
+          const residual = await $.getTokenBalance(vaultMTokenAccount);
+          const adminBalance = await $.getTokenBalance(adminMTokenAccount);
 
-            const unwrapAmount = new BN(
-              randomInt(1, wrappedAmount.toNumber() + 1)
-            );
+          await $.decommission();
 
-            // Send the instruction
-            await $.ext.methods
-              .unwrap(unwrapAmount)
+          expect(await $.getTokenBalance(adminMTokenAccount)).toEqual(
+            adminBalance.add(residual)
+          );
+          $.expectAccountEmpty(vaultMTokenAccount);
+          $.expectAccountEmpty($.getExtGlobalAccount());
+
+          const tombstone = await $.ext.account.tombstone.fetch(
+            $.getTombstone()
+          );
+          expect(tombstone.extMint).toEqual($.extMint.publicKey);
+
+          $.svm.expireBlockhash();
+          await $.expectAnchorError(
+            $.initializeExt([$.wrapAuthority.publicKey]),
+            "Decommissioned"
+          );
+        });
+      });
+
+      describe("rate limit unit tests", () => {
+        const amount = new BN(1_000_000); // 1 with 6 decimals
+        const hour = new BN(3600);
+
+        const createRateLimit = (wrapCapacity: BN, unwrapCapacity: BN) =>
+          $.ext.methods
+            .createRateLimit(
+              [{ capacity: wrapCapacity, duration: hour }],
+              [{ capacity: unwrapCapacity, duration: hour }]
+            )
+            .accounts({
+              ...$.getExtPdas(),
+              admin: $.admin.publicKey,
+            })
+            .signers([$.admin])
+            .rpc();
+
+        // test cases
+        // create_rate_limit / close_rate_limit
+        // [X] given the admin does not sign the transaction
+        //   [X] it reverts with a NotAuthorized error
+        // [X] given a window has a zero duration
+        //   [X] it reverts with an InvalidParam error
+        // wrap / unwrap
+        // [X] given the wrap exceeds the available wrap volume
+        //   [X] it reverts with a RateLimitExceeded error
+        // [X] given the unwrap exceeds the available unwrap volume
+        //   [X] it reverts with a RateLimitExceeded error
+        // [X] given tokens are unwrapped
+        //   [X] the unwrapped volume can be wrapped again
+        // [X] given the window has elapsed
+        //   [X] the wrap volume is refilled
+        // [X] given the rate limits are closed
+        //   [X] wrap is no longer limited
+
+        // given the admin does not sign the transaction
+        // it reverts with a NotAuthorized error
+        test("create_rate_limit - admin does not sign - reverts", async () => {
+          await $.expectAnchorError(
+            $.ext.methods
+              .createRateLimit([{ capacity: amount, duration: hour }], [])
               .accounts({
-                tokenAuthority: $.wrapAuthority.publicKey,
-                unwrapAuthority: $.ext.programId,
-                fromExtTokenAccount,
-                toMTokenAccount,
+                ...$.getExtPdas(),
+                admin: $.nonAdmin.publicKey,
               })
-              .signers([$.wrapAuthority])
-              .rpc();
+              .signers([$.nonAdmin])
+              .rpc(),
+            "NotAuthorized"
+          );
+        });
 
-            // Confirm updated balances
-            await $.expectTokenBalance(
-              toMTokenAccount,
-              toMTokenAccountBalance.add(unwrapAmount),
-              Comparison.LessThanOrEqual,
-              new BN(2)
-            );
-            await $.expectTokenBalance(
-              vaultMTokenAccount,
-              vaultMTokenAccountBalance.sub(unwrapAmount),
-              Comparison.GreaterThanOrEqual,
-              new BN(2)
-            );
-            variant === Variant.ScaledUiAmount
-              ? await $.expectTokenUiBalance(
-                  fromExtTokenAccount,
-                  fromExtTokenAccountBalance.sub(unwrapAmount),
-                  Comparison.LessThanOrEqual,
-                  new BN(2)
-                )
-              : await $.expectTokenBalance(
-                  fromExtTokenAccount,
-                  fromExtTokenAccountBalance.sub(unwrapAmount)
-                );
-          });
+        // given a window has a zero duration
+        // it reverts with an InvalidParam error
+        test("create_rate_limit - zero duration - reverts", async () => {
+          await $.expectAnchorError(
+            $.ext.methods
+              .createRateLimit([{ capacity: amount, duration: new BN(0) }], [])
+              .accounts({
+                ...$.getExtPdas(),
+                admin: $.admin.publicKey,
+              })
+              .signers([$.admin])
+              .rpc(),
+            "InvalidParam"
+          );
+        });
 
-          // given a wrap authority is provided
-          // given the wrap authority does not sign the transaction
-          // it reverts
-          test("Wrap authority does not sign - reverts", async () => {
-            fromExtTokenAccount = await $.getATA(
-              $.extMint.publicKey,
-              $.nonWrapAuthority.publicKey
-            );
-            toMTokenAccount = await $.getATA(
-              $.mMint.publicKey,
-              $.nonWrapAuthority.publicKey
-            );
+        // given the wrap exceeds the available wrap volume
+        // it reverts with a RateLimitExceeded error
+        test("wrap - exceeds rate limit - reverts", async () => {
+          await createRateLimit(amount.subn(1), amount);
 
-            // Attempt to send the transaction
-            // Expect revert
-            await $.expectSystemError(
-              $.ext.methods
-                .unwrap(wrappedAmount)
-                .accounts({
-                  tokenAuthority: $.nonWrapAuthority.publicKey,
-                  unwrapAuthority: $.wrapAuthority.publicKey,
-                  fromExtTokenAccount,
-                  toMTokenAccount,
-                })
-                .signers([$.nonWrapAuthority])
-                .rpc()
-            );
-          });
+          await $.expectAnchorError(
+            $.wrap($.wrapAuthority, amount),
+            "RateLimitExceeded"
+          );
+        });
 
-          // given a wrap authority is provided
-          // given the wrap authority is not on the wrap authorities list
-          // it reverts with a NotAuthorized error
-          test("Wrap authority not on wrap authorities list - reverts", async () => {
-            fromExtTokenAccount = await $.getATA(
-              $.extMint.publicKey,
-              $.nonWrapAuthority.publicKey
-            );
-            toMTokenAccount = await $.getATA(
-              $.mMint.publicKey,
-              $.nonWrapAuthority.publicKey
-            );
+        // given the unwrap exceeds the available unwrap volume
+        // it reverts with a RateLimitExceeded error
+        test("unwrap - exceeds rate limit - reverts", async () => {
+          await $.wrap($.wrapAuthority, amount);
+          await createRateLimit(amount, new BN(1));
 
-            // Attempt to send the transaction
-            // Expect revert with a NotAuthorized error
-            await $.expectAnchorError(
-              $.ext.methods
-                .unwrap(wrappedAmount)
-                .accounts({
-                  tokenAuthority: $.nonWrapAuthority.publicKey,
-                  unwrapAuthority: $.nonAdmin.publicKey,
-                  fromExtTokenAccount,
-                  toMTokenAccount,
-                })
-                .signers([$.nonWrapAuthority, $.nonAdmin])
-                .rpc(),
-              "NotAuthorized"
-            );
-          });
+          await $.expectAnchorError(
+            $.unwrap($.wrapAuthority, amount),
+            "RateLimitExceeded"
+          );
+        });
 
-          // given a wrap authority is provided
-          // given the wrap authority is on the wrap authorities list
-          // given the from token account does not have enough ext tokens
-          // it unwraps the from token account's total balance of ext tokens
-          test("Not enough ext tokens, unwraps user's total balance - wrap authority - success", async () => {
-            fromExtTokenAccount = await $.getATA(
-              $.extMint.publicKey,
-              $.nonWrapAuthority.publicKey
-            );
-            toMTokenAccount = await $.getATA(
-              $.mMint.publicKey,
-              $.nonWrapAuthority.publicKey
-            );
-            vaultMTokenAccount = await $.getATA(
-              $.mMint.publicKey,
-              $.getMVault()
-            );
+        // given tokens are unwrapped
+        // the unwrapped volume can be wrapped again
+        test("unwrap - frees wrap volume - success", async () => {
+          await createRateLimit(amount.muln(2), amount.muln(2));
 
-            // Get the balance of the from ext token account
-            const fromExtTokenAccountBalance =
-              variant === Variant.ScaledUiAmount
-                ? await $.getTokenUiBalance(
-                    fromExtTokenAccount,
-                    await $.getCurrentMultiplier()
-                  )
-                : await $.getTokenBalance(fromExtTokenAccount);
-            const vaultMTokenAccountBalance = await $.getTokenBalance(
-              vaultMTokenAccount
-            );
-            const toMTokenAccountBalance = await $.getTokenBalance(
-              toMTokenAccount
-            );
+          await $.wrap($.wrapAuthority, amount);
+          await $.expectAnchorError(
+            $.wrap($.wrapAuthority, amount.addn(1)),
+            "RateLimitExceeded"
+          );
+
+          await $.unwrap($.wrapAuthority, amount);
+          await $.wrap($.wrapAuthority, amount.addn(1));
+        });
+
+        // given the window has elapsed
+        // the wrap volume is refilled
+        test("wrap - after window - success", async () => {
+          await createRateLimit(amount, amount);
+
+          await $.wrap($.wrapAuthority, amount);
+          $.warp(hour, true);
+          $.svm.expireBlockhash();
+
+          await $.wrap($.wrapAuthority, amount);
+        });
+
+        // given the rate limits are closed
+        // wrap is no longer limited
+        test("close_rate_limit - success", async () => {
+          await createRateLimit(new BN(1), new BN(1));
+
+          await $.ext.methods
+            .closeRateLimit()
+            .accounts({
+              ...$.getExtPdas(),
+              admin: $.admin.publicKey,
+            })
+            .signers([$.admin])
+            .rpc();
 
-            const unwrapAmount = new BN(
-              randomInt(fromExtTokenAccountBalance.toNumber() + 1, 2 ** 48 - 1)
-            );
+          $.expectAccountEmpty($.getRateLimit());
 
-            // Send the unwrap
-            await $.ext.methods
-              .unwrap(unwrapAmount)
+          await $.wrap($.wrapAuthority, amount);
+        });
+      });
+
+      describe("pause unit tests", () => {
+        const amount = new BN(1_000_000); // 1 with 6 decimals
+
+        // test cases
+        // pause / unpause
+        // [X] given the pauser does not sign the transaction
+        //   [X] it reverts with a NotAuthorized error
+        // [X] given the flags are empty or include unknown bits
+        //   [X] it reverts with a InvalidParam error
+        // [X] given the pauser signs the transaction
+        //   [X] it sets the flags on the global account
+        // wrap / unwrap / claim_fees
+        // [X] given wrapping is paused
+        //   [X] wrap reverts with a Paused error
+        //   [X] unwrap is not affected
+        // [X] given unwrapping is paused
+        //   [X] unwrap reverts with a Paused error
+        // [X] given claiming is paused
+        //   [X] claim_fees reverts with a Paused error
+        // [X] given wrapping is unpaused
+        //   [X] wrap succeeds again
+
+        // given the pauser does not sign the transaction
+        // it reverts with a NotAuthorized error
+        test("pause - pauser does not sign - reverts", async () => {
+          await $.expectAnchorError(
+            $.ext.methods
+              .pause(PAUSE_WRAP)
               .accounts({
-                tokenAuthority: $.nonWrapAuthority.publicKey,
-                unwrapAuthority: $.wrapAuthority.publicKey,
-                fromExtTokenAccount,
-                toMTokenAccount,
+                ...$.getExtPdas(),
+                pauser: $.nonAdmin.publicKey,
               })
-              .signers([$.nonWrapAuthority, $.wrapAuthority])
-              .rpc();
+              .signers([$.nonAdmin])
+              .rpc(),
+            "NotAuthorized"
+          );
+        });
 
-            $.expectTokenBalance(fromExtTokenAccount, new BN(0));
-            $.expectTokenBalance(
-              vaultMTokenAccount,
-              vaultMTokenAccountBalance.sub(fromExtTokenAccountBalance),
-              Comparison.GreaterThanOrEqual,
-              new BN(2)
-            );
-            $.expectTokenBalance(
-              toMTokenAccount,
-              toMTokenAccountBalance.add(fromExtTokenAccountBalance),
-              Comparison.LessThanOrEqual,
-              new BN(2)
-            );
-          });
+        // given the flags are empty or include unknown bits
+        // it reverts with a InvalidParam error
+        test("pause - invalid flags - reverts", async () => {
+          await $.expectAnchorError($.pause(0), "InvalidParam");
+          await $.expectAnchorError($.pause(8), "InvalidParam");
+        });
 
-          // given a wrap authority is provided
-          // given the wrap authority is on the wrap authorities list
-          // given the from token account has enough ext tokens
-          // given the token authority is not the owner of the from ext token account, but is delegated
-          // it burns the amount of ext tokens from the from's ext token account
-          // it transfers the amount of M tokens from the M vault token account to the to's M token account
-          test("Unwrap with delegated authority - wrap authority - success", async () => {
-            vaultMTokenAccount = await $.getATA(
-              $.mMint.publicKey,
-              $.getMVault()
-            );
-            toMTokenAccount = await $.getATA(
-              $.mMint.publicKey,
-              $.nonWrapAuthority.publicKey
-            );
+        // given the pauser signs the transaction
+        // it sets the flags on the global account
+        test("pause - success", async () => {
+          await $.pause(PAUSE_WRAP | PAUSE_CLAIM);
 
-            // Approve (delegate) the nonAdmin to spend the non-wrap authority's ext tokens
-            const { sourceATA: fromExtTokenAccount } = await $.approve(
-              $.nonWrapAuthority,
-              $.nonAdmin.publicKey,
-              $.extMint.publicKey,
-              wrappedAmount
-            );
+          const state = await $.ext.account.extGlobal.fetch(
+            $.getExtGlobalAccount()
+          );
+          expect(state.paused).toEqual(PAUSE_WRAP | PAUSE_CLAIM);
+        });
 
-            // Cache initial balances
-            const fromExtTokenAccountBalance =
-              variant === Variant.ScaledUiAmount
-                ? await $.getTokenUiBalance(fromExtTokenAccount)
-                : await $.getTokenBalance(fromExtTokenAccount);
-            const vaultMTokenAccountBalance = await $.getTokenBalance(
-              vaultMTokenAccount
-            );
-            const toMTokenAccountBalance = await $.getTokenBalance(
-              toMTokenAccount
-            );
+        // given wrapping is paused
+        // wrap reverts with a Paused error
+        // unwrap is not affected
+        test("wrap - paused - reverts", async () => {
+          await $.pause(PAUSE_WRAP);
 
-            const unwrapAmount = new BN(
-              randomInt(1, wrappedAmount.toNumber() + 1)
-            );
+          await $.expectAnchorError($.wrap($.wrapAuthority, amount), "Paused");
 
-            // Send the instruction
-            await $.ext.methods
-              .unwrap(unwrapAmount)
+          await $.unwrap($.admin, amount);
+        });
+
+        // given unwrapping is paused
+        // unwrap reverts with a Paused error
+        test("unwrap - paused - reverts", async () => {
+          await $.pause(PAUSE_UNWRAP);
+
+          await $.expectAnchorError($.unwrap($.admin, amount), "Paused");
+        });
+
+        // given claiming is paused
+        // claim_fees reverts with a Paused error
+        test("claim_fees - paused - reverts", async () => {
+          await $.pause(PAUSE_CLAIM);
+
+          await $.expectAnchorError($.claimFees(), "Paused");
+        });
+
+        // given wrapping is unpaused
+        // wrap succeeds again
+        test("unpause - success", async () => {
+          await $.pause(PAUSE_WRAP | PAUSE_UNWRAP);
+          await $.unpause(PAUSE_WRAP);
+
+          const state = await $.ext.account.extGlobal.fetch(
+            $.getExtGlobalAccount()
+          );
+          expect(state.paused).toEqual(PAUSE_UNWRAP);
+
+          await $.wrap($.wrapAuthority, amount);
+        });
+      });
+
+      describe("compliance unit tests", () => {
+        const amount = new BN(1_000_000); // 1 with 6 decimals
+
+        let toExtTokenAccount: PublicKey;
+
+        const addToBlocklist = (wallet: PublicKey) =>
+          $.ext.methods
+            .addToBlocklist(wallet)
+            .accounts({
+              ...$.getExtPdas(),
+              complianceOfficer: $.complianceOfficer.publicKey,
+              blocklistEntry: $.getBlocklistEntry(wallet),
+            })
+            .signers([$.complianceOfficer])
+            .rpc();
+
+        beforeEach(async () => {
+          toExtTokenAccount = await $.getATA(
+            $.extMint.publicKey,
+            $.wrapAuthority.publicKey
+          );
+        });
+
+        // test cases
+        // freeze_account / thaw_account
+        // [X] given the compliance officer does not sign the transaction
+        //   [X] it reverts with a NotAuthorized error
+        // [X] given the compliance officer signs the transaction
+        //   [X] it freezes the token account and records the freeze
+        //   [X] it thaws the token account and closes the record
+        // add_to_blocklist / remove_from_blocklist
+        // [X] given the compliance officer does not sign the transaction
+        //   [X] it reverts with a NotAuthorized error
+        // [X] given the owner of the to ext token account is blocklisted
+        //   [X] wrap reverts with a Blocklisted error
+        // [X] given the owner of the from ext token account is blocklisted
+        //   [X] unwrap reverts with a Blocklisted error
+        // [X] given the wallet is removed from the blocklist
+        //   [X] wrap succeeds again
+
+        // given the compliance officer does not sign the transaction
+        // it reverts with a NotAuthorized error
+        test("freeze_account - unauthorized - reverts", async () => {
+          await $.expectAnchorError(
+            $.ext.methods
+              .freezeAccount()
               .accounts({
-                tokenAuthority: $.nonAdmin.publicKey,
-                unwrapAuthority: $.wrapAuthority.publicKey,
-                fromExtTokenAccount,
-                toMTokenAccount,
+                ...$.getExtPdas(),
+                complianceOfficer: $.nonAdmin.publicKey,
+                tokenAccount: toExtTokenAccount,
+                complianceFreeze: $.getComplianceFreeze(toExtTokenAccount),
               })
-              .signers([$.nonAdmin, $.wrapAuthority])
-              .rpc();
-          });
+              .signers([$.nonAdmin])
+              .rpc(),
+            "NotAuthorized"
+          );
+        });
 
-          // given a wrap authority is provided
-          // given the wrap authority is on the wrap authorities list
-          // given the from token account has enough ext tokens
-          // given the token authority is the owner of the from ext token account
-          // it burns the amount of ext tokens from the from's ext token account
-          // it transfers the amount of M tokens from the M vault token account to the to's M token account
-          test("Unwrap with owner authority - wrap authority - success", async () => {
-            fromExtTokenAccount = await $.getATA(
-              $.extMint.publicKey,
-              $.nonWrapAuthority.publicKey
-            );
-            toMTokenAccount = await $.getATA(
-              $.mMint.publicKey,
-              $.nonWrapAuthority.publicKey
-            );
-            vaultMTokenAccount = await $.getATA(
-              $.mMint.publicKey,
-              $.getMVault()
-            );
+        // given the compliance officer signs the transaction
+        // it freezes the token account and records the freeze
+        // it thaws the token account and closes the record
+        test("freeze_account / thaw_account - success", async () => {
+          const complianceFreeze = $.getComplianceFreeze(toExtTokenAccount);
 
-            // Cache initial balances
-            const fromExtTokenAccountBalance =
-              variant === Variant.ScaledUiAmount
-                ? await $.getTokenUiBalance(fromExtTokenAccount)
-                : await $.getTokenBalance(fromExtTokenAccount);
-            const vaultMTokenAccountBalance = await $.getTokenBalance(
-              vaultMTokenAccount
-            );
-            const toMTokenAccountBalance = await $.getTokenBalance(
-              toMTokenAccount
-            );
+          await $.ext.methods
+            .freezeAccount()
+            .accounts({
+              ...$.getExtPdas(),
+              complianceOfficer: $.complianceOfficer.publicKey,
+              tokenAccount: toExtTokenAccount,
+              complianceFreeze,
+            })
+            .signers([$.complianceOfficer])
+            .rpc();
 
-            const unwrapAmount = new BN(
-              randomInt(1, wrappedAmount.toNumber() + 1)
-            );
+          expect(await $.isFrozen(toExtTokenAccount)).toBe(true);
+          const record = await $.ext.account.complianceFreeze.fetch(
+            complianceFreeze
+          );
+          expect(record.tokenAccount).toEqual(toExtTokenAccount);
 
-            // Send the instruction
-            await $.ext.methods
-              .unwrap(unwrapAmount)
+          await $.ext.methods
+            .thawAccount()
+            .accounts({
+              ...$.getExtPdas(),
+              complianceOfficer: $.complianceOfficer.publicKey,
+              tokenAccount: toExtTokenAccount,
+              complianceFreeze,
+            })
+            .signers([$.complianceOfficer])
+            .rpc();
+
+          expect(await $.isFrozen(toExtTokenAccount)).toBe(false);
+          $.expectAccountEmpty(complianceFreeze);
+        });
+
+        // given the compliance officer does not sign the transaction
+        // it reverts with a NotAuthorized error
+        test("add_to_blocklist - unauthorized - reverts", async () => {
+          await $.expectAnchorError(
+            $.ext.methods
+              .addToBlocklist($.nonWrapAuthority.publicKey)
               .accounts({
-                tokenAuthority: $.nonWrapAuthority.publicKey,
-                unwrapAuthority: $.wrapAuthority.publicKey,
-                fromExtTokenAccount,
-                toMTokenAccount,
+                ...$.getExtPdas(),
+                complianceOfficer: $.nonAdmin.publicKey,
+                blocklistEntry: $.getBlocklistEntry(
+                  $.nonWrapAuthority.publicKey
+                ),
               })
-              .signers([$.nonWrapAuthority, $.wrapAuthority])
-              .rpc();
+              .signers([$.nonAdmin])
+              .rpc(),
+            "NotAuthorized"
+          );
+        });
 
-            // Confirm updated balances
-            await $.expectTokenBalance(
-              toMTokenAccount,
-              toMTokenAccountBalance.add(unwrapAmount),
-              Comparison.LessThanOrEqual,
-              new BN(2)
-            );
-            await $.expectTokenBalance(
-              vaultMTokenAccount,
-              vaultMTokenAccountBalance.sub(unwrapAmount),
-              Comparison.GreaterThanOrEqual,
-              new BN(2)
-            );
-            variant === Variant.ScaledUiAmount
-              ? await $.expectTokenUiBalance(
-                  fromExtTokenAccount,
-                  fromExtTokenAccountBalance.sub(unwrapAmount),
-                  Comparison.LessThanOrEqual,
-                  new BN(2)
-                )
-              : await $.expectTokenBalance(
-                  fromExtTokenAccount,
-                  fromExtTokenAccountBalance.sub(unwrapAmount)
-                );
-          });
+        // given the owner of the to ext token account is blocklisted
+        // wrap reverts with a Blocklisted error
+        test("wrap - to blocklisted wallet - reverts", async () => {
+          await addToBlocklist($.nonWrapAuthority.publicKey);
+
+          await $.expectAnchorError(
+            $.wrap(
+              $.wrapAuthority,
+              amount,
+              null,
+              undefined,
+              $.nonWrapAuthority.publicKey
+            ),
+            "Blocklisted"
+          );
         });
 
-        describe("index different from start", () => {
-          const newIndex = new BN(
-            randomInt(startIndex.toNumber() + 1, 2e12 + 1)
+        // given the owner of the from ext token account is blocklisted
+        // unwrap reverts with a Blocklisted error
+        test("unwrap - from blocklisted wallet - reverts", async () => {
+          await $.wrap($.wrapAuthority, amount);
+          await addToBlocklist($.wrapAuthority.publicKey);
+
+          await $.expectAnchorError(
+            $.unwrap($.wrapAuthority, amount),
+            "Blocklisted"
           );
-          let newMultiplier: number = 1.0;
-          let vaultBalanceAtNewIndex: BN;
+        });
 
-          beforeEach(async () => {
-            // Reset the blockhash to avoid issues with duplicate transactions from multiple claim cycles
-            $.svm.expireBlockhash();
+        // given the wallet is removed from the blocklist
+        // wrap succeeds again
+        test("remove_from_blocklist - success", async () => {
+          await addToBlocklist($.wrapAuthority.publicKey);
 
-            // Propagate the new index
-            await $.propagateIndex(newIndex);
+          await $.ext.methods
+            .removeFromBlocklist()
+            .accounts({
+              ...$.getExtPdas(),
+              complianceOfficer: $.complianceOfficer.publicKey,
+              blocklistEntry: $.getBlocklistEntry($.wrapAuthority.publicKey),
+            })
+            .signers([$.complianceOfficer])
+            .rpc();
 
-            // Get the vault balance at the new index
-            vaultBalanceAtNewIndex = await $.getTokenBalance(
-              vaultMTokenAccount
-            );
+          $.expectAccountEmpty($.getBlocklistEntry($.wrapAuthority.publicKey));
+
+          await $.wrap($.wrapAuthority, amount);
+        });
+      });
+
+      describe("allowlist unit tests", () => {
+        let tokenAccount: PublicKey;
+
+        beforeEach(async () => {
+          tokenAccount = await $.getATA(
+            $.extMint.publicKey,
+            $.wrapAuthority.publicKey
+          );
+        });
+
+        // test cases
+        // add_to_allowlist / remove_from_allowlist
+        // [X] given the compliance officer does not sign the transaction
+        //   [X] it reverts with a NotAuthorized error
+        // [X] given the compliance officer signs the transaction
+        //   [X] it creates the allowlist entry
+        //   [X] it closes the allowlist entry
+        // approve_holder / revoke_holder
+        // [X] given the extension does not require an allowlist
+        //   [X] it reverts with a NotActive error
+
+        // given the compliance officer does not sign the transaction
+        // it reverts with a NotAuthorized error
+        test("add_to_allowlist - unauthorized - reverts", async () => {
+          await $.expectAnchorError(
+            $.ext.methods
+              .addToAllowlist($.wrapAuthority.publicKey)
+              .accounts({
+                ...$.getExtPdas(),
+                complianceOfficer: $.nonAdmin.publicKey,
+                allowlistEntry: $.getAllowlistEntry($.wrapAuthority.publicKey),
+              })
+              .signers([$.nonAdmin])
+              .rpc(),
+            "NotAuthorized"
+          );
+        });
 
-            // Calculate the expected multipler after the new index push
-            if (variant === Variant.ScaledUiAmount) {
-              newMultiplier = await $.getNewMultiplier(newIndex);
-            }
-          });
+        // given the compliance officer signs the transaction
+        // it creates the allowlist entry
+        // it closes the allowlist entry
+        test("add_to_allowlist / remove_from_allowlist - success", async () => {
+          const allowlistEntry = $.getAllowlistEntry($.wrapAuthority.publicKey);
 
-          // test cases
-          // [X] given there are no flows before the yield is distributed
-          //   [X] it unwraps the amount of M tokens from the M vault token account to the user's M token account
-          //   [X] it burns the correct amount of ext tokens from the user's ext token account
-          //   [X] the extension is solvent
-          // [X] given there are net inflows before the yield is distributed
-          //   [X] it unwraps the amount of M tokens from the M vault token account to the user's M token account
-          //   [X] it unwraps the correct amount of ext tokens from the user's ext token account
-          //   [X] the extension is solvent
-          // [X] given there are net outflows before the yield is distributed
-          //   [X] it unwraps the amount of M tokens from the M vault token account to the user's M token account
-          //   [X] it unwraps the correct amount of ext tokens from the user's ext token account
-          //   [X] the extension is solvent
+          await $.ext.methods
+            .addToAllowlist($.wrapAuthority.publicKey)
+            .accounts({
+              ...$.getExtPdas(),
+              complianceOfficer: $.complianceOfficer.publicKey,
+              allowlistEntry,
+            })
+            .signers([$.complianceOfficer])
+            .rpc();
 
-          // given yield has been minted to the m vault for the new index
-          // it unwraps the amount of M tokens from the M vault token account to the user's M token account
-          test("Unwrap with new index - no flows - success", async () => {
-            // Mint yield to the m vault for the new index
-            await $.mClaimFor($.getMVault(), vaultBalanceAtNewIndex);
-            await $.mCompleteClaims();
+          const entry = await $.ext.account.allowlistEntry.fetch(
+            allowlistEntry
+          );
+          expect(entry.wallet).toEqual($.wrapAuthority.publicKey);
 
-            // Cache initial balances
-            const vaultMTokenAccountBalance = await $.getTokenBalance(
-              vaultMTokenAccount
-            );
-            const toMTokenAccountBalance = await $.getTokenBalance(
-              toMTokenAccount
-            );
-            const fromExtTokenAccountBalance =
-              variant === Variant.ScaledUiAmount
-                ? await $.getTokenUiBalance(fromExtTokenAccount, newMultiplier)
-                : await $.getTokenBalance(fromExtTokenAccount);
+          await $.ext.methods
+            .removeFromAllowlist()
+            .accounts({
+              ...$.getExtPdas(),
+              complianceOfficer: $.complianceOfficer.publicKey,
+              allowlistEntry,
+            })
+            .signers([$.complianceOfficer])
+            .rpc();
 
-            const unwrapAmount = new BN(
-              randomInt(1, fromExtTokenAccountBalance.toNumber() + 1)
-            );
+          $.expectAccountEmpty(allowlistEntry);
+        });
 
-            // Send the instruction
-            await $.ext.methods
-              .unwrap(unwrapAmount)
+        // given the extension does not require an allowlist
+        // it reverts with a NotActive error
+        test("approve_holder - allowlist not required - reverts", async () => {
+          await $.expectAnchorError(
+            $.ext.methods
+              .approveHolder()
               .accounts({
-                tokenAuthority: $.wrapAuthority.publicKey,
-                unwrapAuthority: $.ext.programId,
-                fromExtTokenAccount,
-                toMTokenAccount,
+                ...$.getExtPdas(),
+                tokenAccount,
+                allowlistEntry: $.getAllowlistEntry($.wrapAuthority.publicKey),
+                complianceFreeze: $.getComplianceFreeze(tokenAccount),
               })
-              .signers([$.wrapAuthority])
-              .rpc();
-
-            // Confirm updated balances
-            await $.expectTokenBalance(
-              toMTokenAccount,
-              toMTokenAccountBalance.add(unwrapAmount),
-              Comparison.LessThanOrEqual,
-              new BN(2)
-            );
-            await $.expectTokenBalance(
-              vaultMTokenAccount,
-              vaultMTokenAccountBalance.sub(unwrapAmount),
-              Comparison.GreaterThanOrEqual,
-              new BN(2)
-            );
-            variant === Variant.ScaledUiAmount
-              ? await $.expectTokenUiBalance(
-                  fromExtTokenAccount,
-                  fromExtTokenAccountBalance.sub(unwrapAmount),
-                  Comparison.LessThanOrEqual,
-                  new BN(2)
-                )
-              : await $.expectTokenBalance(
-                  fromExtTokenAccount,
-                  fromExtTokenAccountBalance.sub(unwrapAmount)
-                );
+              .rpc(),
+            "NotActive"
+          );
+        });
 
-            // Confirm the extension is solvent
-            await $.expectExtSolvent();
-          });
+        // given the extension does not require an allowlist
+        // it reverts with a NotActive error
+        test("revoke_holder - allowlist not required - reverts", async () => {
+          await $.expectAnchorError(
+            $.ext.methods
+              .revokeHolder()
+              .accounts({
+                ...$.getExtPdas(),
+                tokenAccount,
+                allowlistEntry: $.getAllowlistEntry($.wrapAuthority.publicKey),
+              })
+              .rpc(),
+            "NotActive"
+          );
+        });
+      });
 
-          // given there are net inflows before the yield is distributed
-          // it unwraps the amount of M tokens from the M vault token account to the user's M token account
-          // it unwraps the correct amount of ext tokens from the user's ext token account
-          // the extension is solvent
-          test("Unwrap - extension has inflows - success", async () => {
-            // Mint and wrap additional tokens prior to claim
-            const inflows = new BN(
-              randomInt(100, wrappedAmount.toNumber() + 1)
-            );
-            await $.mintM($.admin.publicKey, inflows);
-            await $.wrap($.admin, inflows);
+      describe("seize unit tests", () => {
+        const amount = new BN(1_000_000); // 1 with 6 decimals
 
-            // Mint yield to the m vault for the new index
-            await $.mClaimFor($.getMVault(), vaultBalanceAtNewIndex);
-            await $.mCompleteClaims();
+        let fromExtTokenAccount: PublicKey;
 
-            // Cache initial balances
-            const vaultMTokenAccountBalance = await $.getTokenBalance(
-              vaultMTokenAccount
-            );
-            const toMTokenAccountBalance = await $.getTokenBalance(
-              toMTokenAccount
-            );
-            const fromExtTokenAccountBalance =
-              variant === Variant.ScaledUiAmount
-                ? await $.getTokenUiBalance(fromExtTokenAccount, newMultiplier)
-                : await $.getTokenBalance(fromExtTokenAccount);
+        beforeEach(async () => {
+          ({ toExtTokenAccount: fromExtTokenAccount } = await $.wrap(
+            $.wrapAuthority,
+            amount
+          ));
+        });
 
-            const unwrapAmount = new BN(
-              randomInt(1, fromExtTokenAccountBalance.toNumber() + 1)
-            );
+        // test cases
+        // seize / clawback
+        // [X] given the compliance officer does not sign the transaction
+        //   [X] it reverts with a NotAuthorized error
+        // [X] given the compliance officer seizes the ext tokens
+        //   [X] the ext tokens are moved to the recovery token account
+        // [X] given the compliance officer claws back the ext tokens
+        //   [X] the ext tokens are burned
+        //   [X] the backing M is sent to the recovery M token account
+        //   [X] the extension remains solvent
+        // [X] given the token account is frozen by the compliance officer
+        //   [X] it seizes or claws back the ext tokens
+        //   [X] the token account is frozen again
+
+        // given the compliance officer does not sign the transaction
+        // it reverts with a NotAuthorized error
+        test("seize - unauthorized - reverts", async () => {
+          const recoveryTokenAccount = await $.getATA(
+            $.extMint.publicKey,
+            $.admin.publicKey
+          );
 
-            // Send the instruction
-            await $.ext.methods
-              .unwrap(unwrapAmount)
+          await $.expectAnchorError(
+            $.ext.methods
+              .seize(amount, 1)
               .accounts({
-                tokenAuthority: $.wrapAuthority.publicKey,
-                unwrapAuthority: $.ext.programId,
-                fromExtTokenAccount,
-                toMTokenAccount,
+                ...$.getExtPdas(),
+                complianceOfficer: $.nonAdmin.publicKey,
+                fromTokenAccount: fromExtTokenAccount,
+                recoveryTokenAccount,
               })
-              .signers([$.wrapAuthority])
-              .rpc();
+              .signers([$.nonAdmin])
+              .rpc(),
+            "NotAuthorized"
+          );
+        });
 
-            // Confirm updated balances
-            await $.expectTokenBalance(
-              toMTokenAccount,
-              toMTokenAccountBalance.add(unwrapAmount),
-              Comparison.LessThanOrEqual,
-              new BN(2)
-            );
-            await $.expectTokenBalance(
-              vaultMTokenAccount,
-              vaultMTokenAccountBalance.sub(unwrapAmount),
-              Comparison.GreaterThanOrEqual,
-              new BN(2)
-            );
-            variant === Variant.ScaledUiAmount
-              ? await $.expectTokenUiBalance(
-                  fromExtTokenAccount,
-                  fromExtTokenAccountBalance.sub(unwrapAmount),
-                  Comparison.LessThanOrEqual,
-                  new BN(2)
-                )
-              : await $.expectTokenBalance(
-                  fromExtTokenAccount,
-                  fromExtTokenAccountBalance.sub(unwrapAmount)
-                );
+        // given the compliance officer seizes the ext tokens
+        // the ext tokens are moved to the recovery token account
+        test("seize - success", async () => {
+          const recoveryTokenAccount = await $.getATA(
+            $.extMint.publicKey,
+            $.admin.publicKey
+          );
+          const balance = await $.getTokenBalance(fromExtTokenAccount);
+          const recoveryBalance = await $.getTokenBalance(recoveryTokenAccount);
 
-            // Confirm the extension is solvent
-            await $.expectExtSolvent();
-          });
+          await $.ext.methods
+            .seize(balance, 1)
+            .accounts({
+              ...$.getExtPdas(),
+              complianceOfficer: $.complianceOfficer.publicKey,
+              fromTokenAccount: fromExtTokenAccount,
+              recoveryTokenAccount,
+            })
+            .signers([$.complianceOfficer])
+            .rpc();
 
-          // given there are net outflows before the yield is distributed
-          // it unwraps the amount of M tokens from the M vault token account to the user's M token account
-          // it unwraps the correct amount of ext tokens from the user's ext token account
-          // the extension is solvent
-          test("Unwrap - extension has outflows - success", async () => {
-            // Unwrap tokens prior to claim
-            const outflows = new BN(
-              randomInt(100, initialWrappedAmount.toNumber() + 1)
-            );
-            await $.unwrap($.admin, outflows);
+          await $.expectTokenBalance(fromExtTokenAccount, new BN(0));
+          await $.expectTokenBalance(
+            recoveryTokenAccount,
+            recoveryBalance.add(balance)
+          );
+        });
 
-            // Mint yield to the m vault for the new index
-            await $.mClaimFor($.getMVault(), vaultBalanceAtNewIndex);
-            await $.mCompleteClaims();
+        // given the compliance officer claws back the ext tokens
+        // the ext tokens are burned
+        // the backing M is sent to the recovery M token account
+        // the extension remains solvent
+        test("clawback - success", async () => {
+          const recoveryMTokenAccount = await $.getATA(
+            $.mMint.publicKey,
+            $.admin.publicKey
+          );
+          const recoveryBalance = await $.getTokenBalance(
+            recoveryMTokenAccount
+          );
 
-            // Cache initial balances
-            const vaultMTokenAccountBalance = await $.getTokenBalance(
-              vaultMTokenAccount
-            );
-            const toMTokenAccountBalance = await $.getTokenBalance(
-              toMTokenAccount
-            );
-            const fromExtTokenAccountBalance =
-              variant === Variant.ScaledUiAmount
-                ? await $.getTokenUiBalance(fromExtTokenAccount, newMultiplier)
-                : await $.getTokenBalance(fromExtTokenAccount);
+          await $.ext.methods
+            .clawback(amount, 2)
+            .accounts({
+              ...$.getExtPdas(),
+              complianceOfficer: $.complianceOfficer.publicKey,
+              recoveryMTokenAccount,
+              fromExtTokenAccount,
+            })
+            .signers([$.complianceOfficer])
+            .rpc();
 
-            const unwrapAmount = new BN(
-              randomInt(1, fromExtTokenAccountBalance.toNumber() + 1)
-            );
+          await $.expectTokenBalance(fromExtTokenAccount, new BN(0));
+          await $.expectTokenBalance(
+            recoveryMTokenAccount,
+            recoveryBalance.add(amount),
+            Comparison.LessThanOrEqual,
+            new BN(2)
+          );
+          await $.expectExtSolvent();
+        });
 
-            // Send the instruction
-            await $.ext.methods
-              .unwrap(unwrapAmount)
-              .accounts({
-                tokenAuthority: $.wrapAuthority.publicKey,
-                unwrapAuthority: $.ext.programId,
-                fromExtTokenAccount,
-                toMTokenAccount,
-              })
-              .signers([$.wrapAuthority])
-              .rpc();
+        const freezeFromAccount = () =>
+          $.ext.methods
+            .freezeAccount()
+            .accounts({
+              ...$.getExtPdas(),
+              complianceOfficer: $.complianceOfficer.publicKey,
+              tokenAccount: fromExtTokenAccount,
+              complianceFreeze: $.getComplianceFreeze(fromExtTokenAccount),
+            })
+            .signers([$.complianceOfficer])
+            .rpc();
+
+        // given the token account is frozen by the compliance officer
+        // it seizes the ext tokens
+        // the token account is frozen again
+        test("seize - frozen account - success", async () => {
+          await freezeFromAccount();
+
+          const recoveryTokenAccount = await $.getATA(
+            $.extMint.publicKey,
+            $.admin.publicKey
+          );
+          const balance = await $.getTokenBalance(fromExtTokenAccount);
+          const recoveryBalance = await $.getTokenBalance(recoveryTokenAccount);
+
+          await $.ext.methods
+            .seize(balance, 1)
+            .accounts({
+              ...$.getExtPdas(),
+              complianceOfficer: $.complianceOfficer.publicKey,
+              fromTokenAccount: fromExtTokenAccount,
+              recoveryTokenAccount,
+            })
+            .signers([$.complianceOfficer])
+            .rpc();
 
-            // Confirm updated balances
-            await $.expectTokenBalance(
-              toMTokenAccount,
-              toMTokenAccountBalance.add(unwrapAmount),
-              Comparison.LessThanOrEqual,
-              new BN(2)
-            );
-            await $.expectTokenBalance(
-              vaultMTokenAccount,
-              vaultMTokenAccountBalance.sub(unwrapAmount),
-              Comparison.GreaterThanOrEqual,
-              new BN(2)
-            );
-            variant === Variant.ScaledUiAmount
-              ? await $.expectTokenUiBalance(
-                  fromExtTokenAccount,
-                  fromExtTokenAccountBalance.sub(unwrapAmount),
-                  Comparison.LessThanOrEqual,
-                  new BN(2)
-                )
-              : await $.expectTokenBalance(
-                  fromExtTokenAccount,
-                  fromExtTokenAccountBalance.sub(unwrapAmount)
-                );
+          await $.expectTokenBalance(fromExtTokenAccount, new BN(0));
+          await $.expectTokenBalance(
+            recoveryTokenAccount,
+            recoveryBalance.add(balance)
+          );
+          expect(await $.isFrozen(fromExtTokenAccount)).toBe(true);
+        });
 
-            // Confirm the extension is solvent
-            await $.expectExtSolvent();
-          });
+        // given the token account is frozen by the compliance officer
+        // it claws back the ext tokens
+        // the token account is frozen again
+        test("clawback - frozen account - success", async () => {
+          await freezeFromAccount();
+
+          const recoveryMTokenAccount = await $.getATA(
+            $.mMint.publicKey,
+            $.admin.publicKey
+          );
+          const recoveryBalance = await $.getTokenBalance(
+            recoveryMTokenAccount
+          );
+
+          await $.ext.methods
+            .clawback(amount, 2)
+            .accounts({
+              ...$.getExtPdas(),
+              complianceOfficer: $.complianceOfficer.publicKey,
+              recoveryMTokenAccount,
+              fromExtTokenAccount,
+            })
+            .signers([$.complianceOfficer])
+            .rpc();
+
+          await $.expectTokenBalance(fromExtTokenAccount, new BN(0));
+          await $.expectTokenBalance(
+            recoveryMTokenAccount,
+            recoveryBalance.add(amount),
+            Comparison.LessThanOrEqual,
+            new BN(2)
+          );
+          expect(await $.isFrozen(fromExtTokenAccount)).toBe(true);
+          await $.expectExtSolvent();
         });
       });
     });
@@ -3702,6 +6751,7 @@ for (const variant of VARIANTS) {
               $.ext.methods
                 .sync()
                 .accountsPartial({
+                  ...$.getExtPdas(),
                   mEarnGlobalAccount,
                 })
                 .signers([])
@@ -3722,6 +6772,7 @@ for (const variant of VARIANTS) {
               $.ext.methods
                 .sync()
                 .accountsPartial({
+                  ...$.getExtPdas(),
                   extMint: newMint.publicKey,
                 })
                 .signers([])
@@ -3745,6 +6796,7 @@ for (const variant of VARIANTS) {
               $.ext.methods
                 .sync()
                 .accountsPartial({
+                  ...$.getExtPdas(),
                   extMintAuthority,
                 })
                 .signers([])
@@ -3789,7 +6841,11 @@ for (const variant of VARIANTS) {
             );
 
             // Send the instruction
-            await $.ext.methods.sync().accounts({}).signers([]).rpc();
+            await $.ext.methods
+              .sync()
+              .accounts({ ...$.getExtPdas() })
+              .signers([])
+              .rpc();
 
             // Confirm the scaled ui config on the ext mint matches the m index
             const multiplier = await $.getCurrentMultiplier();
@@ -3823,7 +6879,11 @@ for (const variant of VARIANTS) {
             );
 
             // Send the instruction
-            await $.ext.methods.sync().accounts({}).signers([]).rpc();
+            await $.ext.methods
+              .sync()
+              .accounts({ ...$.getExtPdas() })
+              .signers([])
+              .rpc();
 
             // Confirm the scaled ui config on the ext mint matches the m index
             await $.expectScaledUiAmountConfig($.extMint.publicKey, {
@@ -3860,7 +6920,11 @@ for (const variant of VARIANTS) {
               );
 
               // Send the sync instruction
-              await $.ext.methods.sync().accounts({}).signers([]).rpc();
+              await $.ext.methods
+                .sync()
+                .accounts({ ...$.getExtPdas() })
+                .signers([])
+                .rpc();
 
               // Check that last_m_index was updated
               await $.expectExtGlobalState({
@@ -3895,7 +6959,11 @@ for (const variant of VARIANTS) {
               );
 
               // Send the sync instruction while the extension is not earning
-              await $.ext.methods.sync().accounts({}).signers([]).rpc();
+              await $.ext.methods
+                .sync()
+                .accounts({ ...$.getExtPdas() })
+                .signers([])
+                .rpc();
 
               // Expect only last_m_index to be updated
               await $.expectExtGlobalState({
@@ -3923,7 +6991,11 @@ for (const variant of VARIANTS) {
               await $.propagateIndex(laterIndex);
 
               // Sync the extension again now that it's earning
-              await $.ext.methods.sync().accounts({}).signers([]).rpc();
+              await $.ext.methods
+                .sync()
+                .accounts({ ...$.getExtPdas() })
+                .signers([])
+                .rpc();
 
               // Expect the last ext_index and multipliers to be updated correctly on sync
               const expectedNewMultiplier = await $.getNewMultiplier(
@@ -3955,3 +7027,1461 @@ for (const variant of VARIANTS) {
     }
   });
 }
+
+describe("allowlist required unit tests", () => {
+  let $: ExtensionTest<Variant.NoYield>;
+  let tokenAccount: PublicKey;
+
+  const approveHolder = () =>
+    $.ext.methods
+      .approveHolder()
+      .accounts({
+        ...$.getExtPdas(),
+        tokenAccount,
+        allowlistEntry: $.getAllowlistEntry($.nonAdmin.publicKey),
+        complianceFreeze: $.getComplianceFreeze(tokenAccount),
+      })
+      .rpc();
+
+  beforeEach(async () => {
+    $ = new ExtensionTest(Variant.NoYield, []);
+    await $.init(initialSupply, initialIndex, claimCooldown);
+
+    // Replace the ext mint with one whose token accounts start frozen
+    $.extMint = new Keypair();
+    await $.createMint(
+      $.extMint,
+      $.getExtMintAuthority(),
+      true,
+      6,
+      $.getFreezeAuthority(),
+      $.getPermanentDelegate(),
+      true
+    );
+    await $.initializeExt([$.admin.publicKey], undefined, new BN(0), true);
+
+    // Allowlist the owner of the token account, which starts frozen
+    await $.ext.methods
+      .addToAllowlist($.nonAdmin.publicKey)
+      .accounts({
+        ...$.getExtPdas(),
+        complianceOfficer: $.complianceOfficer.publicKey,
+        allowlistEntry: $.getAllowlistEntry($.nonAdmin.publicKey),
+      })
+      .signers([$.complianceOfficer])
+      .rpc();
+    tokenAccount = await $.getATA($.extMint.publicKey, $.nonAdmin.publicKey);
+  });
+
+  // test cases
+  // [X] given the owner of the token account is allowlisted
+  //   [X] approve_holder thaws the token account
+  //   [X] given the compliance officer froze the token account
+  //     [X] approve_holder reverts with a FrozenByComplianceOfficer error
+  //     [X] thaw_account thaws it and closes the record
+
+  test("approve_holder - success", async () => {
+    expect(await $.isFrozen(tokenAccount)).toBe(true);
+
+    await approveHolder();
+
+    expect(await $.isFrozen(tokenAccount)).toBe(false);
+  });
+
+  test("approve_holder - frozen by the compliance officer - reverts", async () => {
+    await approveHolder();
+
+    const complianceFreeze = $.getComplianceFreeze(tokenAccount);
+    await $.ext.methods
+      .freezeAccount()
+      .accounts({
+        ...$.getExtPdas(),
+        complianceOfficer: $.complianceOfficer.publicKey,
+        tokenAccount,
+        complianceFreeze,
+      })
+      .signers([$.complianceOfficer])
+      .rpc();
+
+    // The owner is still allowlisted, but the freeze can't be lifted by anyone
+    $.svm.expireBlockhash();
+    await $.expectAnchorError(approveHolder(), "FrozenByComplianceOfficer");
+    expect(await $.isFrozen(tokenAccount)).toBe(true);
+
+    await $.ext.methods
+      .thawAccount()
+      .accounts({
+        ...$.getExtPdas(),
+        complianceOfficer: $.complianceOfficer.publicKey,
+        tokenAccount,
+        complianceFreeze,
+      })
+      .signers([$.complianceOfficer])
+      .rpc();
+
+    expect(await $.isFrozen(tokenAccount)).toBe(false);
+    $.expectAccountEmpty(complianceFreeze);
+  });
+});
+
+describe("yield_to_one unit tests", () => {
+  let $: ExtensionTest<Variant.YieldToOne>;
+  let vaultMTokenAccount: PublicKey;
+  let recipientExtTokenAccount: PublicKey;
+  const initialWrappedAmount = new BN(10_000_000); // 10 with 6 decimals
+
+  beforeEach(async () => {
+    $ = new ExtensionTest(Variant.YieldToOne, []);
+    await $.init(initialSupply, initialIndex, claimCooldown);
+
+    // Initialize the extension with the yield recipient keypair's ATA as the recipient
+    await $.initializeExt([$.admin.publicKey, $.wrapAuthority.publicKey]);
+    recipientExtTokenAccount = await $.getATA(
+      $.extMint.publicKey,
+      $.yieldRecipient.publicKey
+    );
+
+    // Wrap some tokens from the admin to make the m vault's balance non-zero
+    ({ vaultMTokenAccount } = await $.wrap($.admin, initialWrappedAmount));
+  });
+
+  // test cases
+  // [X] given the m vault has no excess collateral
+  //   [X] it completes but doesn't mint any tokens
+  // [X] given the m vault has received yield
+  //   [X] it mints all the excess collateral to the yield recipient 1:1
+  //   [X] given the yield has already been claimed
+  //     [X] it completes but doesn't mint any tokens
+  // [X] given the recipient token account is not the yield recipient
+  //   [X] it reverts with an InvalidAccount error
+  // [X] given claiming is paused
+  //   [X] it reverts with a Paused error
+  // [X] given the admin changes the yield recipient
+  //   [X] it mints the next claim to the new recipient
+  // [X] given a non-admin changes the yield recipient
+  //   [X] it reverts with a NotAuthorized error
+  // [X] given the timelock is enabled
+  //   [X] the admin can't change the yield recipient directly
+  //   [X] the queued change can't be executed with another recipient
+  //   [X] the queued change sets the yield recipient
+  // [X] given the extension uses another yield mode
+  //   [X] set_yield_recipient reverts with an UnsupportedYieldMode error
+  // [X] given the extension is winding down with unclaimed yield
+  //   [X] decommission reverts with an OutstandingClaims error
+  //   [X] it decommissions once the yield is claimed and unwrapped
+
+  test("claim_yield - no excess", async () => {
+    await $.claimYield();
+
+    await $.expectTokenBalance(recipientExtTokenAccount, new BN(0));
+    expect((await $.getTokenSupply($.extMint.publicKey)).toString()).toEqual(
+      initialWrappedAmount.toString()
+    );
+  });
+
+  test("claim_yield - mints the excess to the recipient", async () => {
+    // Accrue ~10% of yield to the vault
+    await $.accrueVaultYield(new BN(1_210_000_000_000));
+
+    const vaultBalance = await $.getTokenBalance(vaultMTokenAccount);
+    const supply = await $.getTokenSupply($.extMint.publicKey);
+    expect(vaultBalance.gt(supply)).toBe(true);
+
+    await $.claimYield();
+
+    // Ext tokens are 1:1 with M, so the recipient receives the whole excess
+    await $.expectTokenBalance(
+      recipientExtTokenAccount,
+      vaultBalance.sub(supply)
+    );
+    expect((await $.getTokenSupply($.extMint.publicKey)).toString()).toEqual(
+      vaultBalance.toString()
+    );
+
+    // The ext tokens of the holders are unchanged
+    await $.expectTokenBalance(
+      await $.getATA($.extMint.publicKey, $.admin.publicKey),
+      initialWrappedAmount
+    );
+    await $.expectExtSolvent();
+  });
+
+  test("claim_yield - already claimed", async () => {
+    await $.accrueVaultYield(new BN(1_210_000_000_000));
+    await $.claimYield();
+
+    const recipientBalance = await $.getTokenBalance(recipientExtTokenAccount);
+    $.svm.expireBlockhash();
+
+    // Nothing accrued since the last claim
+    await $.claimYield();
+
+    await $.expectTokenBalance(recipientExtTokenAccount, recipientBalance);
+  });
+
+  test("claim_yield - wrong recipient - reverts", async () => {
+    await $.accrueVaultYield(new BN(1_210_000_000_000));
+
+    await $.expectAnchorError(
+      $.claimYield(await $.getATA($.extMint.publicKey, $.nonAdmin.publicKey)),
+      "InvalidAccount"
+    );
+  });
+
+  test("claim_yield - paused - reverts", async () => {
+    await $.accrueVaultYield(new BN(1_210_000_000_000));
+    await $.pause(PAUSE_CLAIM);
+
+    await $.expectAnchorError($.claimYield(), "Paused");
+    await $.expectTokenBalance(recipientExtTokenAccount, new BN(0));
+  });
+
+  test("set_yield_recipient - success", async () => {
+    const newRecipient = await $.getATA(
+      $.extMint.publicKey,
+      $.nonAdmin.publicKey
+    );
+
+    await $.ext.methods
+      .setYieldRecipient()
+      .accounts({
+        ...$.getExtPdas(),
+        admin: $.admin.publicKey,
+        extMint: $.extMint.publicKey,
+        newYieldRecipient: newRecipient,
+      })
+      .signers([$.admin])
+      .rpc();
+
+    const state = await $.ext.account.extGlobal.fetch($.getExtGlobalAccount());
+    expect(state.yieldConfig.yieldToOne!["0"].yieldRecipient).toEqual(
+      newRecipient
+    );
+
+    // The next claim goes to the new recipient
+    await $.accrueVaultYield(new BN(1_210_000_000_000));
+    const vaultBalance = await $.getTokenBalance(vaultMTokenAccount);
+    await $.claimYield(newRecipient);
+
+    await $.expectTokenBalance(
+      newRecipient,
+      vaultBalance.sub(initialWrappedAmount)
+    );
+    await $.expectTokenBalance(recipientExtTokenAccount, new BN(0));
+  });
+
+  test("set_yield_recipient - non-admin - reverts", async () => {
+    await $.expectAnchorError(
+      $.ext.methods
+        .setYieldRecipient()
+        .accounts({
+          ...$.getExtPdas(),
+          admin: $.nonAdmin.publicKey,
+          extMint: $.extMint.publicKey,
+          newYieldRecipient: await $.getATA(
+            $.extMint.publicKey,
+            $.nonAdmin.publicKey
+          ),
+        })
+        .signers([$.nonAdmin])
+        .rpc(),
+      "NotAuthorized"
+    );
+  });
+
+  test("set_yield_recipient - timelock enabled - queued", async () => {
+    const delay = new BN(86400);
+    await $.setTimelockDelay(delay);
+
+    const newRecipient = await $.getATA(
+      $.extMint.publicKey,
+      $.nonAdmin.publicKey
+    );
+    const setYieldRecipient = (
+      admin: PublicKey,
+      newYieldRecipient: PublicKey
+    ) =>
+      $.ext.methods.setYieldRecipient().accountsPartial({
+        ...$.getExtPdas(),
+        admin,
+        extMint: $.extMint.publicKey,
+        newYieldRecipient,
+      });
+
+    await $.expectAnchorError(
+      setYieldRecipient($.admin.publicKey, newRecipient)
+        .signers([$.admin])
+        .rpc(),
+      "NotAuthorized"
+    );
+
+    await $.queueAction($.admin, 0, {
+      setYieldRecipient: { yieldRecipient: newRecipient },
+    });
+    $.warp(delay, true);
+
+    // The change only applies to the recipient it was queued for
+    await $.expectAnchorError(
+      $.executeQueuedAction(
+        0,
+        $.admin.publicKey,
+        await $.getRemainingAccounts(
+          setYieldRecipient(
+            $.getTimelockAuthority(),
+            recipientExtTokenAccount
+          ).instruction()
+        )
+      ),
+      "InvalidAccount"
+    );
+
+    await $.executeQueuedAction(
+      0,
+      $.admin.publicKey,
+      await $.getRemainingAccounts(
+        setYieldRecipient($.getTimelockAuthority(), newRecipient).instruction()
+      )
+    );
+
+    const state = await $.ext.account.extGlobal.fetch($.getExtGlobalAccount());
+    expect(state.yieldConfig.yieldToOne!["0"].yieldRecipient).toEqual(
+      newRecipient
+    );
+  });
+
+  test("set_yield_recipient - other yield mode - reverts", async () => {
+    // Start over with an extension that has no yield
+    const other = new ExtensionTest(Variant.NoYield, []);
+    await other.init(initialSupply, initialIndex, claimCooldown);
+    await other.initializeExt([other.admin.publicKey]);
+
+    await other.expectAnchorError(
+      other.ext.methods
+        .setYieldRecipient()
+        .accounts({
+          ...other.getExtPdas(),
+          admin: other.admin.publicKey,
+          extMint: other.extMint.publicKey,
+          newYieldRecipient: await other.getATA(
+            other.extMint.publicKey,
+            other.nonAdmin.publicKey
+          ),
+        })
+        .signers([other.admin])
+        .rpc(),
+      "UnsupportedYieldMode"
+    );
+  });
+
+  test("decommission - unclaimed yield - reverts", async () => {
+    await $.accrueVaultYield(new BN(1_210_000_000_000));
+    await $.beginWindDown();
+
+    // Unwrapping all ext tokens leaves only the yield in the vault
+    await $.unwrap($.admin, initialWrappedAmount);
+    expect((await $.getTokenSupply($.extMint.publicKey)).isZero()).toBe(true);
+    expect((await $.getTokenBalance(vaultMTokenAccount)).gtn(0)).toBe(true);
+
+    await $.expectAnchorError($.decommission(), "OutstandingClaims");
+
+    // The recipient claims the yield and unwraps it
+    await $.claimYield();
+    await $.unwrap(
+      $.yieldRecipient,
+      await $.getTokenBalance(recipientExtTokenAccount),
+      $.admin
+    );
+    await $.expectTokenBalance(vaultMTokenAccount, new BN(0));
+
+    $.svm.expireBlockhash();
+    await $.decommission();
+
+    $.expectAccountEmpty(vaultMTokenAccount);
+    $.expectAccountEmpty($.getExtGlobalAccount());
+  });
+});
+
+describe("earner_manager unit tests", () => {
+  let $: ExtensionTest<Variant.EarnerManager>;
+  let earnerTokenAccount: PublicKey;
+  let holderTokenAccount: PublicKey;
+  let feeRecipientTokenAccount: PublicKey;
+  const wrappedAmount = new BN(5_000_000); // 5 with 6 decimals
+  const earnerFeeBps = new BN(2000); // 20%
+
+  beforeEach(async () => {
+    $ = new ExtensionTest(Variant.EarnerManager, []);
+    await $.init(initialSupply, initialIndex, claimCooldown);
+
+    // The yield recipient keypair's ATA receives the earner fees
+    await $.initializeExt([$.admin.publicKey, $.wrapAuthority.publicKey]);
+    feeRecipientTokenAccount = await $.getATA(
+      $.extMint.publicKey,
+      $.yieldRecipient.publicKey
+    );
+
+    // Wrap to an earner and to a holder that doesn't earn
+    ({ toExtTokenAccount: earnerTokenAccount } = await $.wrap(
+      $.admin,
+      wrappedAmount
+    ));
+    ({ toExtTokenAccount: holderTokenAccount } = await $.wrap(
+      $.admin,
+      wrappedAmount,
+      null,
+      undefined,
+      $.nonAdmin.publicKey
+    ));
+
+    await $.addEarner(earnerTokenAccount, earnerFeeBps);
+  });
+
+  // test cases
+  // [X] given the vault has received 10% of yield
+  //   [X] it mints the earner's yield net of its fee, and the fee to the fee recipient
+  //   [X] it doesn't accrue yield to holders that aren't earners
+  //   [X] given the yield has already been claimed
+  //     [X] it doesn't mint anything
+  //   [X] given the fees are claimed first
+  //     [X] it leaves the yield owed to the earner in the vault
+  // [X] given claiming is paused
+  //   [X] claim_earner_yield reverts with a Paused error
+  //   [X] set_earner_fee reverts with a Paused error
+  // [X] given an earner is added after the index increased
+  //   [X] it doesn't receive the yield from before it was added
+  // [X] given the fee manager changes the fee of an earner
+  //   [X] it settles the yield accrued at the old fee first
+  // [X] given the timelock is enabled
+  //   [X] the fee manager can't change the fee of an earner directly
+  //   [X] the queued change can't be executed on another earner
+  //   [X] the queued change sets the fee of the earner
+  // [X] given the fee manager removes an earner
+  //   [X] it can't claim anymore
+  //   [X] it pays out the yield accrued before it was removed
+  // [X] given the fee is above 100%
+  //   [X] add_earner reverts with an InvalidParam error
+  // [X] given the admin adds an earner
+  //   [X] it reverts with a NotAuthorized error
+  // [X] given the fee claimer sets the fee recipient
+  //   [X] the earner fees are minted to the new recipient
+  // [X] given the admin sets the fee recipient
+  //   [X] it reverts with a NotAuthorized error
+
+  test("claim_earner_yield - splits the yield with the fee recipient", async () => {
+    // The earner index grows from 1.0 to 1.1
+    await $.accrueVaultYield(new BN(1_210_000_000_000));
+
+    await $.claimEarnerYield(earnerTokenAccount);
+
+    // 10% of 5 = 0.5, 20% of which is the fee
+    await $.expectTokenBalance(earnerTokenAccount, new BN(5_400_000));
+    await $.expectTokenBalance(feeRecipientTokenAccount, new BN(100_000));
+    await $.expectTokenBalance(holderTokenAccount, wrappedAmount);
+
+    const earner = await $.ext.account.managedEarner.fetch(
+      $.getExtEarnerAccount(earnerTokenAccount)
+    );
+    expect(earner.lastIndex.toString()).toEqual("1100000000000");
+    expect(earner.principal.toString()).toEqual("4909090");
+    await $.expectExtSolvent();
+  });
+
+  test("claim_earner_yield - already claimed", async () => {
+    await $.accrueVaultYield(new BN(1_210_000_000_000));
+    await $.claimEarnerYield(earnerTokenAccount);
+    $.svm.expireBlockhash();
+
+    await $.claimEarnerYield(earnerTokenAccount);
+
+    await $.expectTokenBalance(earnerTokenAccount, new BN(5_400_000));
+    await $.expectTokenBalance(feeRecipientTokenAccount, new BN(100_000));
+  });
+
+  test("claim_fees - leaves the earner yield in the vault", async () => {
+    await $.accrueVaultYield(new BN(1_210_000_000_000));
+
+    // The vault received 1, half of which is owed to the earner
+    const { recipientExtTokenAccount } = await $.claimFees(
+      await $.getATA($.extMint.publicKey, $.nonWrapAuthority.publicKey)
+    );
+    await $.expectTokenBalance(recipientExtTokenAccount, new BN(500_000));
+
+    // The earner can still claim its yield in full
+    await $.claimEarnerYield(earnerTokenAccount);
+    await $.expectTokenBalance(earnerTokenAccount, new BN(5_400_000));
+    await $.expectTokenBalance(feeRecipientTokenAccount, new BN(100_000));
+    await $.expectExtSolvent();
+  });
+
+  test("claim_earner_yield - paused - reverts", async () => {
+    await $.accrueVaultYield(new BN(1_210_000_000_000));
+    await $.pause(PAUSE_CLAIM);
+
+    await $.expectAnchorError($.claimEarnerYield(earnerTokenAccount), "Paused");
+    await $.expectTokenBalance(earnerTokenAccount, wrappedAmount);
+  });
+
+  test("set_earner_fee - paused - reverts", async () => {
+    await $.accrueVaultYield(new BN(1_210_000_000_000));
+    await $.pause(PAUSE_CLAIM);
+
+    // The yield accrued at the old fee can't be settled
+    await $.expectAnchorError(
+      $.ext.methods
+        .setEarnerFee(new BN(0))
+        .accountsPartial({
+          feeManager: $.feeManager.publicKey,
+          claim: await $.getClaimEarnerYieldAccounts(earnerTokenAccount),
+        })
+        .signers([$.feeManager])
+        .rpc(),
+      "Paused"
+    );
+  });
+
+  test("add_earner - no yield from before it was added", async () => {
+    await $.accrueVaultYield(new BN(1_210_000_000_000));
+
+    await $.addEarner(holderTokenAccount, new BN(0));
+    await $.claimEarnerYield(holderTokenAccount);
+
+    await $.expectTokenBalance(holderTokenAccount, wrappedAmount);
+  });
+
+  test("set_earner_fee - settles at the old fee", async () => {
+    await $.accrueVaultYield(new BN(1_210_000_000_000));
+
+    await $.ext.methods
+      .setEarnerFee(new BN(0))
+      .accountsPartial({
+        feeManager: $.feeManager.publicKey,
+        claim: await $.getClaimEarnerYieldAccounts(earnerTokenAccount),
+      })
+      .signers([$.feeManager])
+      .rpc();
+
+    // The yield up to now was charged the old fee
+    await $.expectTokenBalance(earnerTokenAccount, new BN(5_400_000));
+    await $.expectTokenBalance(feeRecipientTokenAccount, new BN(100_000));
+
+    // The yield from now on is not charged a fee
+    await $.accrueVaultYield(new BN(1_331_000_000_000));
+    await $.claimEarnerYield(earnerTokenAccount);
+
+    // 4909090 principal at an index of 1.21 less its basis at 1.1
+    await $.expectTokenBalance(earnerTokenAccount, new BN(5_939_999));
+    await $.expectTokenBalance(feeRecipientTokenAccount, new BN(100_000));
+    await $.expectExtSolvent();
+  });
+
+  test("set_earner_fee - timelock enabled - queued", async () => {
+    const delay = new BN(86400);
+    await $.setTimelockDelay(delay);
+    await $.addEarner(holderTokenAccount, earnerFeeBps);
+
+    const setEarnerFee = async (
+      feeManager: PublicKey,
+      tokenAccount: PublicKey
+    ) =>
+      $.ext.methods.setEarnerFee(new BN(0)).accountsPartial({
+        feeManager,
+        claim: await $.getClaimEarnerYieldAccounts(tokenAccount),
+      });
+
+    await $.expectAnchorError(
+      (await setEarnerFee($.feeManager.publicKey, earnerTokenAccount))
+        .signers([$.feeManager])
+        .rpc(),
+      "NotAuthorized"
+    );
+
+    await $.queueAction($.feeManager, 0, {
+      setEarnerFee: { tokenAccount: earnerTokenAccount, feeBps: new BN(0) },
+    });
+    $.warp(delay, true);
+
+    // The change only applies to the earner it was queued for
+    await $.expectAnchorError(
+      $.executeQueuedAction(
+        0,
+        $.feeManager.publicKey,
+        await $.getRemainingAccounts(
+          (
+            await setEarnerFee($.getTimelockAuthority(), holderTokenAccount)
+          ).instruction()
+        )
+      ),
+      "InvalidAccount"
+    );
+
+    await $.executeQueuedAction(
+      0,
+      $.feeManager.publicKey,
+      await $.getRemainingAccounts(
+        (
+          await setEarnerFee($.getTimelockAuthority(), earnerTokenAccount)
+        ).instruction()
+      )
+    );
+
+    const earner = await $.ext.account.managedEarner.fetch(
+      $.getExtEarnerAccount(earnerTokenAccount)
+    );
+    expect(earner.feeBps.toString()).toEqual("0");
+  });
+
+  test("remove_earner - can't claim anymore", async () => {
+    await $.ext.methods
+      .removeEarner()
+      .accountsPartial({
+        feeManager: $.feeManager.publicKey,
+        claim: await $.getClaimEarnerYieldAccounts(earnerTokenAccount),
+      })
+      .signers([$.feeManager])
+      .rpc();
+
+    $.expectAccountEmpty($.getExtEarnerAccount(earnerTokenAccount));
+
+    await $.accrueVaultYield(new BN(1_210_000_000_000));
+    await $.expectAnchorError(
+      $.claimEarnerYield(earnerTokenAccount),
+      "AccountNotInitialized"
+    );
+  });
+
+  test("remove_earner - pays out the accrued yield", async () => {
+    await $.accrueVaultYield(new BN(1_210_000_000_000));
+
+    await $.ext.methods
+      .removeEarner()
+      .accountsPartial({
+        feeManager: $.feeManager.publicKey,
+        claim: await $.getClaimEarnerYieldAccounts(earnerTokenAccount),
+      })
+      .signers([$.feeManager])
+      .rpc();
+
+    // The yield up to the removal is settled at the earner's fee
+    await $.expectTokenBalance(earnerTokenAccount, new BN(5_400_000));
+    await $.expectTokenBalance(feeRecipientTokenAccount, new BN(100_000));
+    $.expectAccountEmpty($.getExtEarnerAccount(earnerTokenAccount));
+    await $.expectExtSolvent();
+  });
+
+  test("add_earner - fee above 100% - reverts", async () => {
+    await $.expectAnchorError(
+      $.addEarner(holderTokenAccount, new BN(10_001)),
+      "InvalidParam"
+    );
+  });
+
+  test("add_earner - admin - reverts", async () => {
+    await $.expectAnchorError(
+      $.ext.methods
+        .addEarner(new BN(0))
+        .accountsPartial({
+          ...$.getExtPdas(),
+          feeManager: $.admin.publicKey,
+          earnerTokenAccount: holderTokenAccount,
+          earnerAccount: $.getExtEarnerAccount(holderTokenAccount),
+        })
+        .signers([$.admin])
+        .rpc(),
+      "NotAuthorized"
+    );
+  });
+
+  test("set_fee_recipient - mints the fees to the new recipient", async () => {
+    await $.ext.methods
+      .setFeeRecipient()
+      .accountsPartial({
+        ...$.getExtPdas(),
+        feeClaimer: $.feeClaimer.publicKey,
+        newFeeRecipient: holderTokenAccount,
+      })
+      .signers([$.feeClaimer])
+      .rpc();
+
+    await $.accrueVaultYield(new BN(1_210_000_000_000));
+    await $.ext.methods
+      .claimEarnerYield()
+      .accountsPartial({
+        ...(await $.getClaimEarnerYieldAccounts(earnerTokenAccount)),
+        feeRecipientTokenAccount: holderTokenAccount,
+      })
+      .rpc();
+
+    await $.expectTokenBalance(earnerTokenAccount, new BN(5_400_000));
+    await $.expectTokenBalance(holderTokenAccount, new BN(5_100_000));
+  });
+
+  test("set_fee_recipient - admin - reverts", async () => {
+    await $.expectAnchorError(
+      $.ext.methods
+        .setFeeRecipient()
+        .accountsPartial({
+          ...$.getExtPdas(),
+          feeClaimer: $.admin.publicKey,
+          newFeeRecipient: holderTokenAccount,
+        })
+        .signers([$.admin])
+        .rpc(),
+      "NotAuthorized"
+    );
+  });
+});
+
+describe("claimable_yield unit tests", () => {
+  let $: ExtensionTest<Variant.ClaimableYield>;
+  let earnerTokenAccount: PublicKey;
+  let holderTokenAccount: PublicKey;
+  const wrappedAmount = new BN(5_000_000); // 5 with 6 decimals
+
+  beforeEach(async () => {
+    $ = new ExtensionTest(Variant.ClaimableYield, []);
+    await $.init(initialSupply, initialIndex, claimCooldown);
+    await $.initializeExt([$.admin.publicKey, $.wrapAuthority.publicKey]);
+
+    // Wrap to an earner and to a holder without an earner account
+    ({ toExtTokenAccount: earnerTokenAccount } = await $.wrap(
+      $.admin,
+      wrappedAmount
+    ));
+    ({ toExtTokenAccount: holderTokenAccount } = await $.wrap(
+      $.admin,
+      wrappedAmount,
+      null,
+      undefined,
+      $.nonAdmin.publicKey
+    ));
+
+    await $.createEarner(earnerTokenAccount);
+  });
+
+  // test cases
+  // [X] given the vault has received 10% of yield
+  //   [X] claim mints the earner's yield without rebasing other balances
+  //   [X] given the earner is checkpointed first
+  //     [X] it records the yield as pending without minting it
+  //     [X] claim mints the pending yield
+  //   [X] given the yield has already been claimed
+  //     [X] it doesn't mint anything
+  //   [X] given the fees are claimed first
+  //     [X] it leaves the yield owed to the earner in the vault
+  //   [X] given claiming is paused
+  //     [X] claim reverts with a Paused error
+  // [X] given an earner account is created after the index increased
+  //   [X] it doesn't receive the yield from before it was created
+  // [X] given the earner account already exists
+  //   [X] create_earner reverts
+
+  test("claim - mints the accrued yield", async () => {
+    await $.accrueVaultYield(new BN(1_210_000_000_000));
+
+    await $.claim(earnerTokenAccount);
+
+    // 10% of 5
+    await $.expectTokenBalance(earnerTokenAccount, new BN(5_500_000));
+    await $.expectTokenBalance(holderTokenAccount, wrappedAmount);
+
+    const earner = await $.ext.account.earner.fetch(
+      $.getExtEarnerAccount(earnerTokenAccount)
+    );
+    expect(earner.pendingYield.toString()).toEqual("0");
+    expect(earner.principal.toString()).toEqual("5000000");
+    await $.expectExtSolvent();
+  });
+
+  test("checkpoint - records the yield as pending", async () => {
+    await $.accrueVaultYield(new BN(1_210_000_000_000));
+
+    await $.checkpoint(earnerTokenAccount);
+
+    // Nothing is minted until the earner claims
+    await $.expectTokenBalance(earnerTokenAccount, wrappedAmount);
+    const earner = await $.ext.account.earner.fetch(
+      $.getExtEarnerAccount(earnerTokenAccount)
+    );
+    expect(earner.pendingYield.toString()).toEqual("500000");
+    expect(earner.lastIndex.toString()).toEqual("1100000000000");
+
+    const state = await $.ext.account.extGlobal.fetch($.getExtGlobalAccount());
+    expect(
+      state.yieldConfig.claimableYield!["0"].earnerPendingYield.toString()
+    ).toEqual("500000");
+
+    await $.claim(earnerTokenAccount);
+    await $.expectTokenBalance(earnerTokenAccount, new BN(5_500_000));
+  });
+
+  test("claim - already claimed", async () => {
+    await $.accrueVaultYield(new BN(1_210_000_000_000));
+    await $.claim(earnerTokenAccount);
+    $.svm.expireBlockhash();
+
+    await $.claim(earnerTokenAccount);
+
+    await $.expectTokenBalance(earnerTokenAccount, new BN(5_500_000));
+  });
+
+  test("claim - paused - reverts", async () => {
+    await $.accrueVaultYield(new BN(1_210_000_000_000));
+    await $.pause(PAUSE_CLAIM);
+
+    await $.expectAnchorError($.claim(earnerTokenAccount), "Paused");
+    await $.expectTokenBalance(earnerTokenAccount, wrappedAmount);
+  });
+
+  test("claim_fees - leaves the earner yield in the vault", async () => {
+    await $.accrueVaultYield(new BN(1_210_000_000_000));
+    await $.checkpoint(earnerTokenAccount);
+
+    // The vault received 1, half of which is owed to the earner
+    const { recipientExtTokenAccount } = await $.claimFees(
+      await $.getATA($.extMint.publicKey, $.nonWrapAuthority.publicKey)
+    );
+    await $.expectTokenBalance(recipientExtTokenAccount, new BN(500_000));
+
+    await $.claim(earnerTokenAccount);
+    await $.expectTokenBalance(earnerTokenAccount, new BN(5_500_000));
+    await $.expectExtSolvent();
+  });
+
+  test("create_earner - no yield from before it was created", async () => {
+    await $.accrueVaultYield(new BN(1_210_000_000_000));
+
+    await $.createEarner(holderTokenAccount);
+    await $.claim(holderTokenAccount);
+
+    await $.expectTokenBalance(holderTokenAccount, wrappedAmount);
+  });
+
+  test("create_earner - already exists - reverts", async () => {
+    $.svm.expireBlockhash();
+
+    await $.expectSystemError($.createEarner(earnerTokenAccount));
+  });
+});
+
+describe("interest_bearing unit tests", () => {
+  let $: ExtensionTest<Variant.InterestBearing>;
+  let vaultMTokenAccount: PublicKey;
+  const initialWrappedAmount = new BN(10_000_000); // 10 with 6 decimals
+  const feeBps = new BN(2000); // 20%
+  const secondsPerYear = new BN(31_556_736);
+
+  beforeEach(async () => {
+    $ = new ExtensionTest(Variant.InterestBearing, []);
+    await $.init(initialSupply, initialIndex, claimCooldown);
+    await $.initializeExt(
+      [$.admin.publicKey, $.wrapAuthority.publicKey],
+      feeBps
+    );
+
+    ({ vaultMTokenAccount } = await $.wrap($.admin, initialWrappedAmount));
+  });
+
+  // test cases
+  // [X] given the ext mint doesn't have the interest bearing extension
+  //   [X] initialize reverts with an InvalidMint error
+  // [X] given M grew 5% over a year
+  //   [X] sync sets the rate on the mint to the annualized growth of the ext index
+  //   [X] claim_fees mints the excess collateral above the ext index
+  //   [X] wrap converts the amount to principal at the ext index
+  // [X] given the index hasn't changed
+  //   [X] sync leaves the rate at zero
+
+  test("initialize - mint without the extension - reverts", async () => {
+    const other = new ExtensionTest(Variant.NoYield, []);
+    await other.init(initialSupply, initialIndex, claimCooldown);
+
+    await other.expectAnchorError(
+      other.ext.methods
+        .initialize(
+          [],
+          { interestBearing: { feeBps } },
+          false,
+          new BN(0),
+          false
+        )
+        .accounts({
+          ...other.getExtPdas(),
+          admin: other.admin.publicKey,
+          mMint: other.mMint.publicKey,
+          extMint: other.extMint.publicKey,
+        })
+        .signers([other.admin])
+        .rpc(),
+      "InvalidMint"
+    );
+  });
+
+  test("sync - no index change - rate stays zero", async () => {
+    $.warp(new BN(60), true);
+    await $.sync();
+
+    expect(await $.getInterestRate($.extMint.publicKey)).toEqual(0);
+  });
+
+  describe("M grew 5% over a year", () => {
+    const newIndex = new BN(1_155_000_000_000);
+
+    beforeEach(async () => {
+      // The index is propagated a year after the extension was initialized
+      $.warp(secondsPerYear.subn(60), true);
+      await $.accrueVaultYield(newIndex);
+    });
+
+    test("sync - sets the annualized rate", async () => {
+      const expectedMultiplier = await $.getNewMultiplier(newIndex);
+
+      await $.sync();
+
+      // ln(1.05) * 80% = 3.9% after the fee
+      expect(await $.getInterestRate($.extMint.publicKey)).toEqual(390);
+
+      const yieldConfig = await $.getIndexYieldConfig();
+      expect(yieldConfig.lastMIndex!.toString()).toEqual(newIndex.toString());
+      expect(yieldConfig.lastExtIndex!.toNumber() / 1e12).toBeCloseTo(
+        expectedMultiplier,
+        10
+      );
+    });
+
+    test("claim_fees - mints the excess above the ext index", async () => {
+      const { recipientExtTokenAccount } = await $.claimFees(
+        await $.getATA($.extMint.publicKey, $.nonWrapAuthority.publicKey)
+      );
+
+      // The required collateral is rounded up and the excess principal down
+      const extIndex = (await $.getIndexYieldConfig()).lastExtIndex!;
+      const scale = new BN(1e12);
+      const vaultBalance = await $.getTokenBalance(vaultMTokenAccount);
+      const requiredM = initialWrappedAmount
+        .mul(extIndex)
+        .add(scale.subn(1))
+        .div(scale);
+      const expectedPrincipal = vaultBalance
+        .sub(requiredM)
+        .mul(scale)
+        .div(extIndex);
+
+      expect(expectedPrincipal.gtn(0)).toBe(true);
+      await $.expectTokenBalance(recipientExtTokenAccount, expectedPrincipal);
+      await $.expectExtSolvent();
+    });
+
+    test("wrap - converts at the ext index", async () => {
+      const amount = new BN(1_000_000);
+      const { toExtTokenAccount } = await $.wrap(
+        $.admin,
+        amount,
+        null,
+        undefined,
+        $.nonAdmin.publicKey
+      );
+
+      const extIndex = (await $.getIndexYieldConfig()).lastExtIndex!;
+      await $.expectTokenBalance(
+        toExtTokenAccount,
+        amount.mul(new BN(1e12)).div(extIndex)
+      );
+    });
+  });
+});
+
+describe("merkle_distribution unit tests", () => {
+  let $: ExtensionTest<Variant.MerkleDistribution>;
+  let leaves: DistributionLeaf[];
+  let root: number[];
+  let proofs: number[][][];
+  let expiresAt: BN;
+  const index = new BN(0);
+  const totalAmount = new BN(600_000);
+
+  beforeEach(async () => {
+    $ = new ExtensionTest(Variant.MerkleDistribution, []);
+    await $.init(initialSupply, initialIndex, claimCooldown);
+    await $.initializeExt([$.admin.publicKey, $.wrapAuthority.publicKey]);
+
+    // The vault receives 1 of excess collateral
+    await $.wrap($.admin, new BN(10_000_000));
+    await $.accrueVaultYield(new BN(1_210_000_000_000));
+
+    leaves = [
+      { claimant: $.nonAdmin.publicKey, amount: new BN(300_000) },
+      { claimant: $.wrapAuthority.publicKey, amount: new BN(200_000) },
+      { claimant: $.nonWrapAuthority.publicKey, amount: new BN(100_000) },
+    ];
+    ({ root, proofs } = $.buildDistributionTree(leaves));
+    expiresAt = $.currentTime().addn(86_400);
+
+    await $.createDistribution(root, totalAmount, leaves.length, expiresAt);
+  });
+
+  // test cases
+  // [X] given a distribution is created
+  //   [X] it locks the total amount of excess collateral
+  //   [X] claim_fees can't claim the locked amount
+  // [X] given the total amount is more than the excess collateral
+  //   [X] it reverts with an InsufficientCollateral error
+  // [X] given a claimant provides a valid proof
+  //   [X] it mints the amount of its leaf
+  //   [X] given the leaf has already been claimed
+  //     [X] it reverts with an AlreadyClaimed error
+  // [X] given a claimant provides an invalid proof
+  //   [X] it reverts with an InvalidProof error
+  // [X] given the distribution has expired
+  //   [X] claim reverts with a NotActive error
+  //   [X] sweep mints the unclaimed amount to the admin's recipient and closes the distribution
+  // [X] given the distribution hasn't expired
+  //   [X] sweep reverts with an Active error
+  // [X] given claiming is paused
+  //   [X] claim reverts with a Paused error
+  //   [X] sweep reverts with a Paused error
+  // [X] given the extension is winding down with a distribution outstanding
+  //   [X] decommission reverts with an OutstandingClaims error
+
+  test("create_distribution - locks the total amount", async () => {
+    const config = (
+      await $.ext.account.extGlobal.fetch($.getExtGlobalAccount())
+    ).yieldConfig.merkleDistribution!["0"];
+    expect(config.lockedAmount.toString()).toEqual(totalAmount.toString());
+    expect(config.distributionCount.toString()).toEqual("1");
+
+    const distribution = await $.ext.account.distribution.fetch(
+      $.getDistribution(index)
+    );
+    expect(distribution.merkleRoot).toEqual(root);
+    expect(distribution.numLeaves).toEqual(leaves.length);
+
+    // Only the rest of the excess can be claimed as fees
+    const { recipientExtTokenAccount } = await $.claimFees(
+      await $.getATA($.extMint.publicKey, $.feeClaimer.publicKey)
+    );
+    await $.expectTokenBalance(recipientExtTokenAccount, new BN(400_000));
+  });
+
+  test("create_distribution - more than the excess - reverts", async () => {
+    // 0.4 of excess is left after the first distribution
+    await $.expectAnchorError(
+      $.createDistribution(root, new BN(400_001), leaves.length, expiresAt),
+      "InsufficientCollateral"
+    );
+  });
+
+  test("claim_distribution - success", async () => {
+    await $.claimDistribution(
+      $.nonAdmin,
+      index,
+      0,
+      leaves[0].amount,
+      proofs[0]
+    );
+
+    await $.expectTokenBalance(
+      await $.getATA($.extMint.publicKey, $.nonAdmin.publicKey),
+      leaves[0].amount
+    );
+
+    // The last leaf was promoted, so its proof is shorter
+    await $.claimDistribution(
+      $.nonWrapAuthority,
+      index,
+      2,
+      leaves[2].amount,
+      proofs[2]
+    );
+    await $.expectTokenBalance(
+      await $.getATA($.extMint.publicKey, $.nonWrapAuthority.publicKey),
+      leaves[2].amount
+    );
+
+    const distribution = await $.ext.account.distribution.fetch(
+      $.getDistribution(index)
+    );
+    expect(distribution.claimedAmount.toString()).toEqual("400000");
+    await $.expectExtSolvent();
+  });
+
+  test("claim_distribution - already claimed - reverts", async () => {
+    await $.claimDistribution(
+      $.nonAdmin,
+      index,
+      0,
+      leaves[0].amount,
+      proofs[0]
+    );
+    $.svm.expireBlockhash();
+
+    await $.expectAnchorError(
+      $.claimDistribution(
+        $.nonAdmin,
+        index,
+        0,
+        leaves[0].amount,
+        proofs[0]
+      ),
+      "AlreadyClaimed"
+    );
+  });
+
+  test("claim_distribution - invalid proof - reverts", async () => {
+    // Wrong amount
+    await $.expectAnchorError(
+      $.claimDistribution(
+        $.nonAdmin,
+        index,
+        0,
+        leaves[0].amount.addn(1),
+        proofs[0]
+      ),
+      "InvalidProof"
+    );
+
+    // Wrong claimant
+    await $.expectAnchorError(
+      $.claimDistribution(
+        $.wrapAuthority,
+        index,
+        0,
+        leaves[0].amount,
+        proofs[0]
+      ),
+      "InvalidProof"
+    );
+  });
+
+  test("claim_distribution - expired - reverts", async () => {
+    $.warp(expiresAt.addn(1), false);
+
+    await $.expectAnchorError(
+      $.claimDistribution(
+        $.nonAdmin,
+        index,
+        0,
+        leaves[0].amount,
+        proofs[0]
+      ),
+      "NotActive"
+    );
+  });
+
+  test("sweep_distribution - not expired - reverts", async () => {
+    await $.expectAnchorError(
+      $.sweepDistribution(
+        index,
+        await $.getATA($.extMint.publicKey, $.admin.publicKey)
+      ),
+      "Active"
+    );
+  });
+
+  test("sweep_distribution - success", async () => {
+    await $.claimDistribution(
+      $.nonAdmin,
+      index,
+      0,
+      leaves[0].amount,
+      proofs[0]
+    );
+
+    $.warp(expiresAt.addn(1), false);
+
+    const recipient = await $.getATA($.extMint.publicKey, $.admin.publicKey);
+    const recipientBalance = await $.getTokenBalance(recipient);
+    await $.sweepDistribution(index, recipient);
+
+    // The unclaimed amount goes to the admin's recipient
+    await $.expectTokenBalance(
+      recipient,
+      recipientBalance.add(totalAmount).sub(leaves[0].amount)
+    );
+    $.expectAccountEmpty($.getDistribution(index));
+
+    const config = (
+      await $.ext.account.extGlobal.fetch($.getExtGlobalAccount())
+    ).yieldConfig.merkleDistribution!["0"];
+    expect(config.lockedAmount.toString()).toEqual("0");
+    await $.expectExtSolvent();
+  });
+
+  test("claim_distribution - paused - reverts", async () => {
+    await $.pause(PAUSE_CLAIM);
+
+    await $.expectAnchorError(
+      $.claimDistribution(
+        $.nonAdmin,
+        index,
+        0,
+        leaves[0].amount,
+        proofs[0]
+      ),
+      "Paused"
+    );
+  });
+
+  test("sweep_distribution - paused - reverts", async () => {
+    $.warp(expiresAt.addn(1), false);
+    await $.pause(PAUSE_CLAIM);
+
+    await $.expectAnchorError(
+      $.sweepDistribution(
+        index,
+        await $.getATA($.extMint.publicKey, $.admin.publicKey)
+      ),
+      "Paused"
+    );
+  });
+
+  test("decommission - outstanding distribution - reverts", async () => {
+    await $.beginWindDown();
+
+    // Unwrap every ext token so only the locked amount is left in the vault
+    const balance = await $.getTokenBalance(
+      await $.getATA($.extMint.publicKey, $.admin.publicKey)
+    );
+    await $.unwrap($.admin, balance);
+    expect((await $.getTokenSupply($.extMint.publicKey)).isZero()).toBe(true);
+
+    await $.expectAnchorError($.decommission(), "OutstandingClaims");
+  });
+});
+
+describe("weighted_split unit tests", () => {
+  let $: ExtensionTest<Variant.WeightedSplit>;
+  let beneficiaries: Beneficiary[];
+  let beneficiaryTokenAccounts: PublicKey[];
+
+  beforeEach(async () => {
+    $ = new ExtensionTest(Variant.WeightedSplit, []);
+    await $.init(initialSupply, initialIndex, claimCooldown);
+    await $.initializeExt([$.admin.publicKey, $.wrapAuthority.publicKey]);
+
+    // Create the token accounts of the default beneficiaries
+    beneficiaries = $.getDefaultBeneficiaries();
+    beneficiaryTokenAccounts = [
+      await $.getATA($.extMint.publicKey, $.yieldRecipient.publicKey),
+      await $.getATA($.extMint.publicKey, $.nonAdmin.publicKey),
+    ];
+
+    // The vault receives 1 of excess collateral
+    await $.wrap($.admin, new BN(10_000_000));
+    await $.accrueVaultYield(new BN(1_210_000_000_000));
+  });
+
+  // test cases
+  // [X] given the weights of the beneficiaries don't sum to 100%
+  //   [X] initialize reverts with an InvalidParam error
+  //   [X] set_beneficiaries reverts with an InvalidParam error
+  // [X] given a token account appears twice
+  //   [X] set_beneficiaries reverts with an InvalidParam error
+  // [X] given the vault has excess collateral
+  //   [X] distribute mints the excess to the beneficiaries by weight
+  //   [X] given the accounts are not in the stored order
+  //     [X] it reverts with an InvalidAccount error
+  //   [X] given a beneficiary token account has been closed
+  //     [X] its share stays in the vault
+  //   [X] set_beneficiaries pays out the old beneficiaries first
+  //   [X] claim_fees reverts with an UnsupportedYieldMode error
+  //   [X] given claiming is paused
+  //     [X] distribute reverts with a Paused error
+  //     [X] set_beneficiaries reverts with a Paused error
+  //   [X] given the timelock is enabled
+  //     [X] the admin can't set the beneficiaries directly
+  //     [X] the queued change pays out the old beneficiaries and sets the new ones
+
+  test("initialize - weights don't sum to 100% - reverts", async () => {
+    const other = new ExtensionTest(Variant.WeightedSplit, []);
+    await other.init(initialSupply, initialIndex, claimCooldown);
+
+    const [first, second] = other.getDefaultBeneficiaries();
+    await other.expectAnchorError(
+      other.ext.methods
+        .initialize(
+          [],
+          {
+            weightedSplit: {
+              beneficiaries: [first, { ...second, weightBps: new BN(3999) }],
+            },
+          },
+          false,
+          new BN(0),
+          false
+        )
+        .accounts({
+          ...other.getExtPdas(),
+          admin: other.admin.publicKey,
+          mMint: other.mMint.publicKey,
+          extMint: other.extMint.publicKey,
+        })
+        .signers([other.admin])
+        .rpc(),
+      "InvalidParam"
+    );
+  });
+
+  test("set_beneficiaries - weights don't sum to 100% - reverts", async () => {
+    await $.expectAnchorError(
+      $.setBeneficiaries(
+        [beneficiaries[0], { ...beneficiaries[1], weightBps: new BN(4001) }],
+        beneficiaryTokenAccounts
+      ),
+      "InvalidParam"
+    );
+  });
+
+  test("set_beneficiaries - duplicate token account - reverts", async () => {
+    await $.expectAnchorError(
+      $.setBeneficiaries(
+        [
+          { ...beneficiaries[0], weightBps: new BN(5000) },
+          { ...beneficiaries[0], weightBps: new BN(5000) },
+        ],
+        beneficiaryTokenAccounts
+      ),
+      "InvalidParam"
+    );
+  });
+
+  test("distribute - splits the excess by weight", async () => {
+    await $.distribute(beneficiaryTokenAccounts);
+
+    await $.expectTokenBalance(beneficiaryTokenAccounts[0], new BN(600_000));
+    await $.expectTokenBalance(beneficiaryTokenAccounts[1], new BN(400_000));
+    await $.expectExtSolvent();
+  });
+
+  test("distribute - wrong order - reverts", async () => {
+    await $.expectAnchorError(
+      $.distribute([...beneficiaryTokenAccounts].reverse()),
+      "InvalidAccount"
+    );
+  });
+
+  test("distribute - closed beneficiary - share stays in the vault", async () => {
+    await $.closeTokenAccount($.nonAdmin, beneficiaryTokenAccounts[1]);
+
+    await $.distribute(beneficiaryTokenAccounts);
+
+    await $.expectTokenBalance(beneficiaryTokenAccounts[0], new BN(600_000));
+
+    // The 0.4 left in the vault is split again when the beneficiary is replaced
+    const newTokenAccount = await $.getATA(
+      $.extMint.publicKey,
+      $.nonWrapAuthority.publicKey
+    );
+    await $.setBeneficiaries(
+      [
+        { tokenAccount: beneficiaryTokenAccounts[0], weightBps: new BN(0) },
+        { tokenAccount: newTokenAccount, weightBps: new BN(10000) },
+      ],
+      beneficiaryTokenAccounts
+    );
+    await $.expectTokenBalance(beneficiaryTokenAccounts[0], new BN(840_000));
+
+    $.svm.expireBlockhash();
+    await $.distribute([beneficiaryTokenAccounts[0], newTokenAccount]);
+
+    await $.expectTokenBalance(newTokenAccount, new BN(160_000));
+    await $.expectExtSolvent();
+  });
+
+  test("set_beneficiaries - pays out the old beneficiaries first", async () => {
+    const newTokenAccount = await $.getATA(
+      $.extMint.publicKey,
+      $.nonWrapAuthority.publicKey
+    );
+
+    await $.setBeneficiaries(
+      [{ tokenAccount: newTokenAccount, weightBps: new BN(10000) }],
+      beneficiaryTokenAccounts
+    );
+
+    await $.expectTokenBalance(beneficiaryTokenAccounts[0], new BN(600_000));
+    await $.expectTokenBalance(beneficiaryTokenAccounts[1], new BN(400_000));
+    await $.expectTokenBalance(newTokenAccount, new BN(0));
+
+    // The next yield goes to the new beneficiary
+    await $.accrueVaultYield(new BN(1_331_000_000_000));
+    await $.distribute([newTokenAccount]);
+
+    // 10% of the 11 in the vault
+    await $.expectTokenBalance(newTokenAccount, new BN(1_100_000));
+  });
+
+  test("claim_fees - reverts", async () => {
+    await $.expectAnchorError($.claimFees(), "UnsupportedYieldMode");
+  });
+
+  test("distribute - paused - reverts", async () => {
+    await $.pause(PAUSE_CLAIM);
+
+    await $.expectAnchorError($.distribute(beneficiaryTokenAccounts), "Paused");
+  });
+
+  test("set_beneficiaries - paused - reverts", async () => {
+    await $.pause(PAUSE_CLAIM);
+
+    await $.expectAnchorError(
+      $.setBeneficiaries(
+        [
+          {
+            tokenAccount: beneficiaryTokenAccounts[0],
+            weightBps: new BN(10000),
+          },
+        ],
+        beneficiaryTokenAccounts
+      ),
+      "Paused"
+    );
+  });
+
+  test("set_beneficiaries - timelock enabled - queued", async () => {
+    const delay = new BN(86400);
+    await $.setTimelockDelay(delay);
+
+    const newBeneficiaries = [
+      { tokenAccount: beneficiaryTokenAccounts[0], weightBps: new BN(10000) },
+    ];
+    await $.expectAnchorError(
+      $.setBeneficiaries(newBeneficiaries, beneficiaryTokenAccounts),
+      "NotAuthorized"
+    );
+
+    await $.queueAction($.admin, 0, {
+      setBeneficiaries: { beneficiaries: newBeneficiaries },
+    });
+    $.warp(delay, true);
+
+    await $.executeQueuedAction(
+      0,
+      $.admin.publicKey,
+      await $.getRemainingAccounts(
+        $.ext.methods
+          .setBeneficiaries(newBeneficiaries)
+          .accountsPartial({
+            admin: $.getTimelockAuthority(),
+            distribute: await $.getDistributeAccounts(),
+          })
+          .remainingAccounts(
+            beneficiaryTokenAccounts.map((pubkey) => ({
+              pubkey,
+              isSigner: false,
+              isWritable: true,
+            }))
+          )
+          .instruction()
+      )
+    );
+
+    // The old beneficiaries are paid out before the change
+    await $.expectTokenBalance(beneficiaryTokenAccounts[0], new BN(600_000));
+    await $.expectTokenBalance(beneficiaryTokenAccounts[1], new BN(400_000));
+
+    const state = await $.ext.account.extGlobal.fetch($.getExtGlobalAccount());
+    expect(state.yieldConfig.weightedSplit!["0"].beneficiaries.length).toEqual(
+      1
+    );
+  });
+});